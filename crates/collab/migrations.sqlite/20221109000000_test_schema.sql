@@ -112,6 +112,7 @@ CREATE TABLE "project_repositories" (
     "remote_upstream_url" VARCHAR,
     "remote_origin_url" VARCHAR,
     "linked_worktrees" VARCHAR,
+    "rebase" VARCHAR,
     PRIMARY KEY (project_id, id)
 );
 
@@ -308,7 +308,8 @@ CREATE TABLE public.project_repositories (
     merge_message character varying,
     remote_upstream_url character varying,
     remote_origin_url character varying,
-    linked_worktrees text
+    linked_worktrees text,
+    rebase character varying
 );
 
 CREATE TABLE public.project_repository_statuses (
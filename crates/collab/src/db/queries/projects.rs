@@ -382,6 +382,12 @@ impl Database {
                 linked_worktrees: ActiveValue::Set(Some(
                     serde_json::to_string(&update.linked_worktrees).unwrap(),
                 )),
+                rebase: ActiveValue::Set(
+                    update
+                        .rebase
+                        .as_ref()
+                        .map(|rebase| serde_json::to_string(rebase).unwrap()),
+                ),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -397,6 +403,7 @@ impl Database {
                     project_repository::Column::HeadCommitDetails,
                     project_repository::Column::MergeMessage,
                     project_repository::Column::LinkedWorktrees,
+                    project_repository::Column::Rebase,
                 ])
                 .to_owned(),
             )
@@ -898,6 +905,10 @@ impl Database {
                             .as_deref()
                             .and_then(|s| serde_json::from_str(s).ok())
                             .unwrap_or_default(),
+                        rebase: db_repository_entry
+                            .rebase
+                            .as_deref()
+                            .and_then(|s| serde_json::from_str(s).ok()),
                     });
                 }
             }
@@ -805,6 +805,10 @@ impl Database {
                                 .as_deref()
                                 .and_then(|s| serde_json::from_str(s).ok())
                                 .unwrap_or_default(),
+                            rebase: db_repository
+                                .rebase
+                                .as_deref()
+                                .and_then(|s| serde_json::from_str(s).ok()),
                         });
                     }
                 }
@@ -26,6 +26,8 @@ pub struct Model {
     pub remote_origin_url: Option<String>,
     // JSON array of linked worktree objects
     pub linked_worktrees: Option<String>,
+    // A JSON object representing the rebase in progress
+    pub rebase: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -857,3 +857,89 @@ async fn test_diff_stat_sync_between_host_and_downstream_client(
         "remote diff stats should be restored from the database after rejoining the call"
     );
 }
+
+#[gpui::test]
+async fn test_rebase_in_progress_sync(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a" }))
+        .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    client_a.fs().set_commit_history_for_repo(
+        dot_git,
+        &[
+            ("base", "Initial commit"),
+            ("aaa", "Add a"),
+            ("bbb", "Edit a"),
+        ],
+    );
+    client_a
+        .fs()
+        .with_git_state(dot_git, false, |state| {
+            state
+                .rebase_conflicts
+                .insert("bbb".into(), vec![RepoPath::new("a.txt").unwrap()]);
+        })
+        .unwrap();
+
+    let (project_a, _) = client_a.build_local_project(path!("/project"), cx_a).await;
+    executor.run_until_parked();
+
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    // Start a rebase on the host that stops at the conflicting commit.
+    let repository_a =
+        project_a.read_with(cx_a, |project, cx| project.active_repository(cx).unwrap());
+    let todo = repository_a
+        .update(cx_a, |repository, _| repository.rebase_todo("base".into()))
+        .await
+        .unwrap()
+        .unwrap();
+    repository_a
+        .update(cx_a, |repository, _| {
+            repository.rebase_start("base".into(), todo)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    executor.run_until_parked();
+
+    let host_rebase = repository_a.read_with(cx_a, |repository, _| repository.rebase.clone());
+    let host_rebase = host_rebase.expect("host should see the rebase in progress");
+    assert_eq!(host_rebase.stopped_sha.as_deref(), Some("bbb"));
+
+    // A guest that was already in the project receives the rebase as an update.
+    let guest_rebase = project_b.read_with(cx_b, |project, cx| {
+        let repository = project.active_repository(cx).unwrap();
+        repository.read(cx).rebase.clone()
+    });
+    assert_eq!(guest_rebase.as_ref(), Some(&host_rebase));
+
+    // A guest that joins later loads the rebase from the database.
+    let project_c = client_c.join_remote_project(project_id, cx_c).await;
+    executor.run_until_parked();
+    let late_guest_rebase = project_c.read_with(cx_c, |project, cx| {
+        let repository = project.active_repository(cx).unwrap();
+        repository.read(cx).rebase.clone()
+    });
+    assert_eq!(late_guest_rebase.as_ref(), Some(&host_rebase));
+}
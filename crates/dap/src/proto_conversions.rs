@@ -576,3 +576,32 @@ impl ProtoConversion for dap_types::Thread {
         }
     }
 }
+
+impl ProtoConversion for dap_types::GotoTarget {
+    type ProtoType = proto::DapGotoTarget;
+    type Output = Self;
+
+    fn to_proto(self) -> Self::ProtoType {
+        proto::DapGotoTarget {
+            id: self.id,
+            label: self.label,
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            instruction_pointer_reference: self.instruction_pointer_reference,
+        }
+    }
+
+    fn from_proto(payload: Self::ProtoType) -> Self {
+        Self {
+            id: payload.id,
+            label: payload.label,
+            line: payload.line,
+            column: payload.column,
+            end_line: payload.end_line,
+            end_column: payload.end_column,
+            instruction_pointer_reference: payload.instruction_pointer_reference,
+        }
+    }
+}
@@ -49,6 +49,8 @@ actions!(
         StepOut,
         /// Steps back to the previous statement.
         StepBack,
+        /// Runs the program backwards until it reaches a breakpoint.
+        ReverseContinue,
         /// Stops the debugging session.
         Stop,
         /// Toggles whether to ignore all breakpoints.
@@ -192,8 +194,16 @@ pub fn init(cx: &mut App) {
                     })
                     .when(supports_step_back, |div| {
                         let active_item = active_item.clone();
-                        div.on_action(move |_: &StepBack, _, cx| {
-                            active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                        div.on_action({
+                            let active_item = active_item.clone();
+                            move |_: &StepBack, _, cx| {
+                                active_item.update(cx, |item, cx| item.step_back(cx)).ok();
+                            }
+                        })
+                        .on_action(move |_: &ReverseContinue, _, cx| {
+                            active_item
+                                .update(cx, |item, cx| item.reverse_continue(cx))
+                                .ok();
                         })
                     })
                     .on_action({
@@ -347,6 +357,61 @@ pub fn init(cx: &mut App) {
                         },
                     );
 
+                    window.on_action_when(
+                        session.any_stopped_thread()
+                            && session
+                                .capabilities()
+                                .supports_goto_targets_request
+                                .unwrap_or_default(),
+                        TypeId::of::<editor::actions::SetNextStatement>(),
+                        {
+                            let editor = editor.clone();
+                            let active_session = active_session.clone();
+                            move |_, phase, _, cx| {
+                                if phase != DispatchPhase::Bubble {
+                                    return;
+                                }
+                                maybe!({
+                                    let (buffer, position) = editor
+                                        .update(cx, |editor, cx| {
+                                            let cursor_point: language::Point = editor
+                                                .selections
+                                                .newest(&editor.display_snapshot(cx))
+                                                .head();
+
+                                            editor
+                                                .buffer()
+                                                .read(cx)
+                                                .point_to_buffer_point(cursor_point, cx)
+                                        })
+                                        .ok()??;
+
+                                    let path =
+                                debugger::breakpoint_store::BreakpointStore::abs_path_from_buffer(
+                                    &buffer, cx,
+                                )?;
+
+                                    active_session.update(cx, |session, cx| {
+                                        session.running_state().update(cx, |state, cx| {
+                                            if let Some(thread_id) = state.selected_thread_id() {
+                                                state.session().update(cx, |session, cx| {
+                                                    session.go_to_position(
+                                                        path,
+                                                        position.row,
+                                                        thread_id,
+                                                        cx,
+                                                    );
+                                                })
+                                            }
+                                        });
+                                    });
+
+                                    Some(())
+                                });
+                            }
+                        },
+                    );
+
                     window.on_action(
                         TypeId::of::<editor::actions::EvaluateSelectedText>(),
                         move |_, _, window, cx| {
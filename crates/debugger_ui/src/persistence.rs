@@ -11,8 +11,9 @@ use workspace::{Member, Pane, PaneAxis, Workspace};
 
 use crate::session::running::{
     self, DebugTerminal, RunningState, SubView, breakpoint_list::BreakpointList, console::Console,
-    loaded_source_list::LoadedSourceList, memory_view::MemoryView, module_list::ModuleList,
-    stack_frame_list::StackFrameList, variable_list::VariableList,
+    disassembly_view::DisassemblyView, loaded_source_list::LoadedSourceList,
+    memory_view::MemoryView, module_list::ModuleList, stack_frame_list::StackFrameList,
+    variable_list::VariableList,
 };
 
 #[derive(Clone, Hash, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -25,6 +26,7 @@ pub(crate) enum DebuggerPaneItem {
     LoadedSources,
     Terminal,
     MemoryView,
+    Disassembly,
 }
 
 impl DebuggerPaneItem {
@@ -38,6 +40,7 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources,
             DebuggerPaneItem::Terminal,
             DebuggerPaneItem::MemoryView,
+            DebuggerPaneItem::Disassembly,
         ];
         VARIANTS
     }
@@ -51,6 +54,9 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources => capabilities
                 .supports_loaded_sources_request
                 .unwrap_or_default(),
+            DebuggerPaneItem::Disassembly => capabilities
+                .supports_disassemble_request
+                .unwrap_or_default(),
             _ => true,
         }
     }
@@ -65,6 +71,7 @@ impl DebuggerPaneItem {
             DebuggerPaneItem::LoadedSources => SharedString::new_static("Sources"),
             DebuggerPaneItem::Terminal => SharedString::new_static("Terminal"),
             DebuggerPaneItem::MemoryView => SharedString::new_static("Memory View"),
+            DebuggerPaneItem::Disassembly => SharedString::new_static("Disassembly"),
         }
     }
     pub(crate) fn tab_tooltip(self) -> SharedString {
@@ -87,6 +94,9 @@ impl DebuggerPaneItem {
                 "Provides an interactive terminal session within the debugging environment."
             }
             DebuggerPaneItem::MemoryView => "Allows inspection of memory contents.",
+            DebuggerPaneItem::Disassembly => {
+                "Shows the machine instructions around the current instruction pointer."
+            }
         };
         SharedString::new_static(tooltip)
     }
@@ -212,6 +222,7 @@ pub(crate) fn deserialize_pane_layout(
     loaded_sources: &Entity<LoadedSourceList>,
     terminal: &Entity<DebugTerminal>,
     memory_view: &Entity<MemoryView>,
+    disassembly_view: &Entity<DisassemblyView>,
     subscriptions: &mut HashMap<EntityId, Subscription>,
     window: &mut Window,
     cx: &mut Context<RunningState>,
@@ -237,6 +248,7 @@ pub(crate) fn deserialize_pane_layout(
                     loaded_sources,
                     terminal,
                     memory_view,
+                    disassembly_view,
                     subscriptions,
                     window,
                     cx,
@@ -333,6 +345,14 @@ pub(crate) fn deserialize_pane_layout(
                         pane_handle.clone(),
                         cx,
                     )),
+                    DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                        disassembly_view.focus_handle(cx),
+                        disassembly_view.clone().into(),
+                        DebuggerPaneItem::Disassembly,
+                        running_state.clone(),
+                        pane_handle.clone(),
+                        cx,
+                    )),
                 })
                 .collect();
 
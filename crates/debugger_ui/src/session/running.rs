@@ -1,5 +1,6 @@
 pub(crate) mod breakpoint_list;
 pub(crate) mod console;
+pub(crate) mod disassembly_view;
 pub(crate) mod loaded_source_list;
 pub(crate) mod memory_view;
 pub(crate) mod module_list;
@@ -17,7 +18,7 @@ use crate::{
     attach_modal::{AttachModal, ModalIntent},
     new_process_modal::resolve_path,
     persistence::{self, DebuggerPaneItem, SerializedLayout},
-    session::running::memory_view::MemoryView,
+    session::running::{disassembly_view::DisassemblyView, memory_view::MemoryView},
 };
 
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -25,7 +26,7 @@ use breakpoint_list::BreakpointList;
 use collections::{HashMap, IndexMap};
 use console::Console;
 use dap::{
-    Capabilities, DapRegistry, RunInTerminalRequestArguments, Thread,
+    Capabilities, DapRegistry, RunInTerminalRequestArguments, SteppingGranularity, Thread,
     adapters::{DebugAdapterName, DebugTaskDefinition},
     client::SessionId,
     debugger_settings::DebuggerSettings,
@@ -89,6 +90,7 @@ pub struct RunningState {
     pub(crate) scenario: Option<DebugScenario>,
     pub(crate) scenario_context: Option<DebugScenarioContext>,
     memory_view: Entity<MemoryView>,
+    disassembly_view: Entity<DisassemblyView>,
 }
 
 impl RunningState {
@@ -808,6 +810,15 @@ impl RunningState {
 
         let module_list = cx.new(|cx| ModuleList::new(session.clone(), workspace.clone(), cx));
 
+        let disassembly_view = cx.new(|cx| {
+            DisassemblyView::new(
+                session.clone(),
+                workspace.clone(),
+                stack_frame_list.clone(),
+                cx,
+            )
+        });
+
         let loaded_source_list = cx.new(|cx| LoadedSourceList::new(session.clone(), cx));
 
         let console = cx.new(|cx| {
@@ -879,6 +890,9 @@ impl RunningState {
                         {
                             this.remove_pane_item(DebuggerPaneItem::LoadedSources, window, cx);
                         }
+                        if !capabilities.supports_disassemble_request.unwrap_or(false) {
+                            this.remove_pane_item(DebuggerPaneItem::Disassembly, window, cx);
+                        }
                     }
                     SessionEvent::RunInTerminal { request, sender } => this
                         .handle_run_in_terminal(request, sender.clone(), window, cx)
@@ -917,6 +931,7 @@ impl RunningState {
                 &loaded_source_list,
                 &debug_terminal,
                 &memory_view,
+                &disassembly_view,
                 &mut pane_close_subscriptions,
                 window,
                 cx,
@@ -946,6 +961,7 @@ impl RunningState {
 
         Self {
             memory_view,
+            disassembly_view,
             session,
             workspace,
             project: weak_project,
@@ -1314,6 +1330,7 @@ impl RunningState {
             show_command: false,
             show_rerun: false,
             save: task::SaveStrategy::default(),
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -1417,6 +1434,14 @@ impl RunningState {
                 host_pane,
                 cx,
             )),
+            DebuggerPaneItem::Disassembly => Box::new(SubView::new(
+                self.disassembly_view.focus_handle(cx),
+                self.disassembly_view.clone().into(),
+                item_kind,
+                running_state,
+                host_pane,
+                cx,
+            )),
         }
     }
 
@@ -1638,6 +1663,11 @@ impl RunningState {
         });
     }
 
+    #[cfg(test)]
+    pub(crate) fn disassembly_view(&self) -> &Entity<DisassemblyView> {
+        &self.disassembly_view
+    }
+
     #[cfg(test)]
     pub(crate) fn variable_list(&self) -> &Entity<VariableList> {
         &self.variable_list
@@ -1707,12 +1737,27 @@ impl RunningState {
         });
     }
 
+    /// Steps by instruction while the disassembly is shown, and by the configured granularity otherwise.
+    fn stepping_granularity(&self, cx: &App) -> SteppingGranularity {
+        let disassembly_visible = self.panes.panes().into_iter().any(|pane| {
+            pane.read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<SubView>())
+                .is_some_and(|view| view.read(cx).view_kind() == DebuggerPaneItem::Disassembly)
+        });
+        if disassembly_visible {
+            SteppingGranularity::Instruction
+        } else {
+            DebuggerSettings::get_global(cx).stepping_granularity
+        }
+    }
+
     pub fn step_over(&mut self, cx: &mut Context<Self>) {
         let Some(thread_id) = self.thread_id else {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        let granularity = self.stepping_granularity(cx);
 
         self.session().update(cx, |state, cx| {
             state.step_over(thread_id, granularity, cx);
@@ -1724,7 +1769,7 @@ impl RunningState {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        let granularity = self.stepping_granularity(cx);
 
         self.session().update(cx, |state, cx| {
             state.step_in(thread_id, granularity, cx);
@@ -1736,7 +1781,7 @@ impl RunningState {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        let granularity = self.stepping_granularity(cx);
 
         self.session().update(cx, |state, cx| {
             state.step_out(thread_id, granularity, cx);
@@ -1748,13 +1793,23 @@ impl RunningState {
             return;
         };
 
-        let granularity = DebuggerSettings::get_global(cx).stepping_granularity;
+        let granularity = self.stepping_granularity(cx);
 
         self.session().update(cx, |state, cx| {
             state.step_back(thread_id, granularity, cx);
         });
     }
 
+    pub(crate) fn reverse_continue(&mut self, cx: &mut Context<Self>) {
+        let Some(thread_id) = self.thread_id else {
+            return;
+        };
+
+        self.session().update(cx, |state, cx| {
+            state.reverse_continue(thread_id, cx);
+        });
+    }
+
     pub fn rerun_session(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if let Some((scenario, context)) = self.scenario.take().zip(self.scenario_context.take())
             && scenario.build.is_some()
@@ -0,0 +1,425 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{
+    AnyElement, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{BufferSnapshot, Point};
+use project::debugger::session::{Session, SessionEvent};
+use ui::{Tooltip, WithScrollbar, prelude::*};
+use workspace::Workspace;
+
+use crate::session::running::stack_frame_list::StackFrameList;
+
+/// How far before the instruction pointer the disassembly starts, so that the instructions leading up to it are shown too.
+const BYTES_BEFORE_INSTRUCTION_POINTER: u64 = 64;
+const INSTRUCTION_COUNT: u64 = 96;
+
+enum DisassemblyEntry {
+    Source {
+        location: SharedString,
+        text: Option<SharedString>,
+    },
+    Instruction {
+        address: Option<u64>,
+        instruction: dap::DisassembledInstruction,
+    },
+}
+
+pub(crate) struct DisassemblyView {
+    session: Entity<Session>,
+    workspace: WeakEntity<Workspace>,
+    stack_frame_list: Entity<StackFrameList>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    entries: Vec<DisassemblyEntry>,
+    /// The instruction pointer of the selected stack frame, as the disassembly was last requested for.
+    instruction_pointer_reference: Option<String>,
+    _refresh_task: Task<Result<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DisassemblyView {
+    pub(crate) fn new(
+        session: Entity<Session>,
+        workspace: WeakEntity<Workspace>,
+        stack_frame_list: Entity<StackFrameList>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let _subscriptions = vec![
+            cx.subscribe(&session, |this, _, event, cx| match event {
+                SessionEvent::Stopped(_) | SessionEvent::HistoricSnapshotSelected => {
+                    this.instruction_pointer_reference = None;
+                    cx.notify();
+                }
+                _ => {}
+            }),
+            cx.observe(&session, |_, _, cx| cx.notify()),
+            cx.observe(&stack_frame_list, |_, _, cx| cx.notify()),
+        ];
+
+        Self {
+            session,
+            workspace,
+            stack_frame_list,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            entries: Vec::new(),
+            instruction_pointer_reference: None,
+            _refresh_task: Task::ready(Ok(())),
+            _subscriptions,
+        }
+    }
+
+    fn selected_instruction_pointer_reference(&self, cx: &App) -> Option<String> {
+        let stack_frame_id = self.stack_frame_list.read(cx).opened_stack_frame_id()?;
+        self.session
+            .read(cx)
+            .stack_frame(stack_frame_id)?
+            .dap
+            .instruction_pointer_reference
+            .clone()
+    }
+
+    fn instruction_pointer(&self) -> Option<u64> {
+        parse_address(self.instruction_pointer_reference.as_deref()?)
+    }
+
+    fn refresh_if_needed(&mut self, cx: &mut Context<Self>) {
+        let instruction_pointer_reference = self.selected_instruction_pointer_reference(cx);
+        if instruction_pointer_reference == self.instruction_pointer_reference {
+            return;
+        }
+        self.instruction_pointer_reference = instruction_pointer_reference.clone();
+        let Some(instruction_pointer_reference) = instruction_pointer_reference else {
+            self.entries.clear();
+            self._refresh_task = Task::ready(Ok(()));
+            return;
+        };
+
+        let instruction_pointer = parse_address(&instruction_pointer_reference);
+        let project = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.project().clone())
+            .ok();
+        self._refresh_task = cx.spawn(async move |this, cx| {
+            let mut instructions = None;
+            if let Some(instruction_pointer) = instruction_pointer {
+                let start = instruction_pointer.saturating_sub(BYTES_BEFORE_INSTRUCTION_POINTER);
+                let preceding = this.update(cx, |this, cx| {
+                    this.session.update(cx, |session, cx| {
+                        session.disassemble(format!("0x{start:X}"), INSTRUCTION_COUNT, cx)
+                    })
+                })?;
+                // On architectures with variable-length instructions, decoding from an arbitrary address can go astray,
+                // so the result is only trusted when one of its instructions starts at the instruction pointer.
+                instructions = preceding.await.filter(|instructions| {
+                    instructions.iter().any(|instruction| {
+                        parse_address(&instruction.address) == Some(instruction_pointer)
+                    })
+                });
+            }
+            let instructions = match instructions {
+                Some(instructions) => instructions,
+                None => this
+                    .update(cx, |this, cx| {
+                        this.session.update(cx, |session, cx| {
+                            session.disassemble(
+                                instruction_pointer_reference.clone(),
+                                INSTRUCTION_COUNT,
+                                cx,
+                            )
+                        })
+                    })?
+                    .await
+                    .unwrap_or_default(),
+            };
+
+            let mut sources = HashMap::default();
+            if let Some(project) = project {
+                for path in source_paths(&instructions) {
+                    let buffer = project
+                        .update(cx, |project, cx| project.open_local_buffer(&path, cx))
+                        .await;
+                    if let Ok(buffer) = buffer {
+                        sources.insert(path, buffer.read_with(cx, |buffer, _| buffer.snapshot()));
+                    }
+                }
+            }
+
+            this.update(cx, |this, cx| {
+                this.entries = build_entries(instructions, &sources);
+                let instruction_pointer = this.instruction_pointer();
+                let current_ix = this.entries.iter().position(|entry| match entry {
+                    DisassemblyEntry::Instruction { address, .. } => {
+                        address.is_some() && *address == instruction_pointer
+                    }
+                    DisassemblyEntry::Source { .. } => false,
+                });
+                if let Some(ix) = current_ix {
+                    this.scroll_handle
+                        .scroll_to_item(ix, ScrollStrategy::Center);
+                }
+                cx.notify();
+            })
+        });
+    }
+
+    fn toggle_breakpoint(&mut self, instruction_reference: String, cx: &mut Context<Self>) {
+        self.session.update(cx, |session, cx| {
+            session.toggle_instruction_breakpoint(instruction_reference, cx);
+        });
+    }
+
+    #[cfg(test)]
+    pub(crate) fn entries_text(&self, cx: &App) -> Vec<String> {
+        let instruction_pointer = self.instruction_pointer();
+        let breakpoints = self
+            .session
+            .read(cx)
+            .instruction_breakpoints()
+            .map(|state| state.dap.instruction_reference.clone())
+            .collect::<Vec<_>>();
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                DisassemblyEntry::Source { location, text } => {
+                    format!("{location}: {}", text.as_deref().unwrap_or_default())
+                }
+                DisassemblyEntry::Instruction {
+                    address,
+                    instruction,
+                } => {
+                    let marker = if *address == instruction_pointer && address.is_some() {
+                        ">"
+                    } else if breakpoints.contains(&instruction.address) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    format!(
+                        "{marker} {} {}",
+                        instruction.address, instruction.instruction
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        match &self.entries[ix] {
+            DisassemblyEntry::Source { location, text } => h_flex()
+                .id(("disassembly-source", ix))
+                .w_full()
+                .pl_6()
+                .gap_2()
+                .child(
+                    Label::new(location.clone())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .when_some(text.clone(), |this, text| {
+                    this.child(
+                        Label::new(text)
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                })
+                .into_any_element(),
+            DisassemblyEntry::Instruction {
+                address,
+                instruction,
+            } => {
+                let is_current = address.is_some() && *address == self.instruction_pointer();
+                let supports_breakpoints = self
+                    .session
+                    .read(cx)
+                    .capabilities()
+                    .supports_instruction_breakpoints
+                    .unwrap_or_default();
+                let breakpoint = self
+                    .session
+                    .read(cx)
+                    .instruction_breakpoints()
+                    .find(|state| state.dap.instruction_reference == instruction.address)
+                    .cloned();
+
+                let gutter = div()
+                    .id(("disassembly-breakpoint", ix))
+                    .size_4()
+                    .flex_none()
+                    .when_some(breakpoint, |this, breakpoint| {
+                        let icon = if breakpoint.verified {
+                            IconName::DebugBreakpoint
+                        } else {
+                            IconName::DebugDisabledBreakpoint
+                        };
+                        this.child(
+                            Icon::new(icon)
+                                .size(IconSize::XSmall)
+                                .color(Color::Debugger),
+                        )
+                        .when_some(breakpoint.message, |this, message| {
+                            this.tooltip(Tooltip::text(message))
+                        })
+                    })
+                    .when(supports_breakpoints, |this| {
+                        let instruction_reference = instruction.address.clone();
+                        this.cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_breakpoint(instruction_reference.clone(), cx);
+                            }))
+                    });
+
+                h_flex()
+                    .id(("disassembly-instruction", ix))
+                    .w_full()
+                    .gap_2()
+                    .when(is_current, |this| {
+                        this.bg(cx.theme().colors().editor_debugger_active_line_background)
+                    })
+                    .child(gutter)
+                    .child(div().size_4().flex_none().when(is_current, |this| {
+                        this.child(
+                            Icon::new(IconName::ArrowRight)
+                                .size(IconSize::XSmall)
+                                .color(Color::Warning),
+                        )
+                    }))
+                    .child(
+                        Label::new(instruction.address.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(instruction.instruction.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::Small),
+                    )
+                    .when_some(instruction.symbol.clone(), |this, symbol| {
+                        this.child(
+                            Label::new(format!("<{symbol}>"))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .into_any_element()
+            }
+        }
+    }
+
+    fn render_list(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        uniform_list(
+            "disassembly-view",
+            self.entries.len(),
+            cx.processor(|this, range: Range<usize>, _window, cx| {
+                range.map(|ix| this.render_entry(ix, cx)).collect()
+            }),
+        )
+        .track_scroll(&self.scroll_handle)
+        .size_full()
+    }
+}
+
+/// Parses an address the way adapters format memory references: hexadecimal with a `0x` prefix, or decimal.
+fn parse_address(reference: &str) -> Option<u64> {
+    match reference
+        .strip_prefix("0x")
+        .or_else(|| reference.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => reference.parse().ok(),
+    }
+}
+
+fn source_paths(instructions: &[dap::DisassembledInstruction]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for path in instructions
+        .iter()
+        .filter_map(|instruction| instruction.location.as_ref()?.path.clone())
+    {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Interleaves the instructions with the source lines they were compiled from.
+///
+/// Adapters may omit an instruction's location when it is the same as the previous one's.
+fn build_entries(
+    instructions: Vec<dap::DisassembledInstruction>,
+    sources: &HashMap<String, BufferSnapshot>,
+) -> Vec<DisassemblyEntry> {
+    let mut entries = Vec::with_capacity(instructions.len());
+    let mut current_source = None;
+    let mut current_location = None;
+    for instruction in instructions {
+        if let Some(source) = &instruction.location {
+            current_source = Some(source.clone());
+        }
+        let location = current_source.as_ref().zip(instruction.line);
+        if let Some((source, line)) = location {
+            let name = source
+                .name
+                .clone()
+                .or_else(|| source.path.clone())
+                .unwrap_or_default();
+            let location = format!("{name}:{line}");
+            if current_location.as_ref() != Some(&location) {
+                let text = source
+                    .path
+                    .as_ref()
+                    .and_then(|path| sources.get(path))
+                    .and_then(|snapshot| source_line(snapshot, line));
+                entries.push(DisassemblyEntry::Source {
+                    location: location.clone().into(),
+                    text,
+                });
+                current_location = Some(location);
+            }
+        }
+        entries.push(DisassemblyEntry::Instruction {
+            address: parse_address(&instruction.address),
+            instruction,
+        });
+    }
+    entries
+}
+
+fn source_line(snapshot: &BufferSnapshot, line: u64) -> Option<SharedString> {
+    let row = u32::try_from(line.checked_sub(1)?).ok()?;
+    if row > snapshot.max_point().row {
+        return None;
+    }
+    let text = snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>();
+    Some(text.trim().to_string().into())
+}
+
+impl Focusable for DisassemblyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for DisassemblyView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.refresh_if_needed(cx);
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .child(self.render_list(cx))
+            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+    }
+}
@@ -7,7 +7,7 @@ use dap::{
 };
 use editor::Editor;
 use gpui::{
-    Action, AnyElement, ClickEvent, ClipboardItem, Context, DismissEvent, Empty, Entity,
+    Action, AnyElement, App, ClickEvent, ClipboardItem, Context, DismissEvent, Empty, Entity,
     FocusHandle, Focusable, Hsla, MouseDownEvent, Point, Subscription, TextStyleRefinement,
     UniformListScrollHandle, WeakEntity, actions, anchored, deferred, uniform_list,
 };
@@ -538,6 +538,9 @@ impl VariableList {
             let Some(state) = self.entry_states.get(&var_path) else {
                 return;
             };
+            let Some(entry) = self.entries.iter().find(|entry| entry.path == var_path) else {
+                return;
+            };
 
             let variables_reference = state.parent_reference;
             let Some(name) = var_path.leaf_name else {
@@ -549,19 +552,55 @@ impl VariableList {
             };
 
             let value = editor.read(cx).text(cx);
+            let (supports_set_variable, supports_set_expression) = self.editing_capabilities(cx);
+
+            // Watchers have no variables reference to assign through, so they are
+            // assigned by expression, as are variables when only that is supported.
+            let expression = match &entry.entry {
+                DapEntry::Watcher(watcher) => Some(watcher.expression.to_string()),
+                DapEntry::Variable(variable) if !supports_set_variable => {
+                    variable.evaluate_name.clone()
+                }
+                DapEntry::Variable(_) => None,
+                DapEntry::Scope(_) => return,
+            };
 
-            self.session.update(cx, |session, cx| {
-                session.set_variable_value(
+            self.session.update(cx, |session, cx| match expression {
+                Some(expression) if supports_set_expression => {
+                    session.set_expression(stack_frame_id, expression, value, cx)
+                }
+                Some(_) => {}
+                None => session.set_variable_value(
                     stack_frame_id,
                     variables_reference,
                     name.into(),
                     value,
                     cx,
-                )
+                ),
             });
         }
     }
 
+    fn editing_capabilities(&self, cx: &App) -> (bool, bool) {
+        let capabilities = self.session.read(cx).capabilities();
+        (
+            capabilities.supports_set_variable.unwrap_or_default(),
+            capabilities.supports_set_expression.unwrap_or_default(),
+        )
+    }
+
+    fn can_edit(&self, entry: &DapEntry, cx: &App) -> bool {
+        let (supports_set_variable, supports_set_expression) = self.editing_capabilities(cx);
+        match entry {
+            DapEntry::Watcher(_) => supports_set_expression,
+            DapEntry::Variable(variable) => {
+                supports_set_variable
+                    || (supports_set_expression && variable.evaluate_name.is_some())
+            }
+            DapEntry::Scope(_) => false,
+        }
+    }
+
     fn collapse_selected_entry(
         &mut self,
         _: &CollapseSelectedEntry,
@@ -656,13 +695,10 @@ impl VariableList {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let (supports_set_variable, supports_data_breakpoints, supports_go_to_memory) =
+        let can_edit = self.can_edit(&entry.entry, cx);
+        let (supports_data_breakpoints, supports_go_to_memory) =
             self.session.read_with(cx, |session, _| {
                 (
-                    session
-                        .capabilities()
-                        .supports_set_variable
-                        .unwrap_or_default(),
                     session
                         .capabilities()
                         .supports_data_breakpoints
@@ -706,7 +742,7 @@ impl VariableList {
                     menu.when_some(entry.as_variable(), |menu, _| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .when(can_edit, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .when(supports_go_to_memory, |menu| {
@@ -747,7 +783,7 @@ impl VariableList {
                     .when(entry.as_watcher().is_some(), |menu| {
                         menu.action("Copy Name", CopyVariableName.boxed_clone())
                             .action("Copy Value", CopyVariableValue.boxed_clone())
-                            .when(supports_set_variable, |menu| {
+                            .when(can_edit, |menu| {
                                 menu.action("Edit Value", EditVariable.boxed_clone())
                             })
                             .action("Remove Watch", RemoveWatch.boxed_clone())
@@ -971,6 +1007,11 @@ impl VariableList {
         self.build_entries(cx);
     }
 
+    #[cfg(test)]
+    pub(crate) fn edited_value_editor(&self) -> Option<Entity<Editor>> {
+        self.edited_path.as_ref().map(|(_, editor)| editor.clone())
+    }
+
     #[track_caller]
     #[cfg(test)]
     pub(crate) fn assert_visual_entries(&self, expected: Vec<&str>) {
@@ -1126,34 +1167,25 @@ impl VariableList {
                         this.child(div().size_full().px_2().child(editor.clone()))
                     } else {
                         this.text_color(cx.theme().colors().text_muted)
-                            .when(
-                                !self.disabled
-                                    && self
-                                        .session
-                                        .read(cx)
-                                        .capabilities()
-                                        .supports_set_variable
-                                        .unwrap_or_default(),
-                                |this| {
-                                    let path = entry.path.clone();
-                                    let variable_value = value.clone();
-                                    this.on_click(cx.listener(
-                                        move |this, click: &ClickEvent, window, cx| {
-                                            if click.click_count() < 2 {
-                                                return;
-                                            }
-                                            let editor = Self::create_variable_editor(
-                                                &variable_value,
-                                                window,
-                                                cx,
-                                            );
-                                            this.edited_path = Some((path.clone(), editor));
-
-                                            cx.notify();
-                                        },
-                                    ))
-                                },
-                            )
+                            .when(!self.disabled && self.can_edit(&entry.entry, cx), |this| {
+                                let path = entry.path.clone();
+                                let variable_value = value.clone();
+                                this.on_click(cx.listener(
+                                    move |this, click: &ClickEvent, window, cx| {
+                                        if click.click_count() < 2 {
+                                            return;
+                                        }
+                                        let editor = Self::create_variable_editor(
+                                            &variable_value,
+                                            window,
+                                            cx,
+                                        );
+                                        this.edited_path = Some((path.clone(), editor));
+
+                                        cx.notify();
+                                    },
+                                ))
+                            })
                             .child(
                                 Label::new(format!("=  {}", &value))
                                     .single_line()
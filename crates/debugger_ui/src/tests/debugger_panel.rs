@@ -10,8 +10,8 @@ use dap::{
     adapters::DebugTaskDefinition,
     client::SessionId,
     requests::{
-        Continue, Disconnect, Launch, Next, RunInTerminal, SetBreakpoints, StackTrace,
-        StartDebugging, StepBack, StepIn, StepOut, Threads,
+        Continue, Disconnect, Goto, GotoTargets, Launch, Next, ReverseContinue, RunInTerminal,
+        SetBreakpoints, StackTrace, StartDebugging, StepBack, StepIn, StepOut, Threads,
     },
 };
 use editor::{
@@ -1013,6 +1013,20 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
         })
     });
 
+    client.on_request::<ReverseContinue, _>(move |_, _| {
+        Err(ErrorResponse {
+            error: Some(dap::Message {
+                id: 1,
+                format: "error".into(),
+                variables: None,
+                send_telemetry: None,
+                show_user: None,
+                url: None,
+                url_label: None,
+            }),
+        })
+    });
+
     client
         .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
             reason: dap::StoppedEventReason::Pause,
@@ -1037,6 +1051,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
         "step_over",
         "continue_thread",
         "step_back",
+        "reverse_continue",
         "step_in",
         "step_out",
     ] {
@@ -1044,6 +1059,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
             "step_over" => running_state.step_over(cx),
             "continue_thread" => running_state.continue_thread(cx),
             "step_back" => running_state.step_back(cx),
+            "reverse_continue" => running_state.reverse_continue(cx),
             "step_in" => running_state.step_in(cx),
             "step_out" => running_state.step_out(cx),
             _ => unreachable!(),
@@ -1056,7 +1072,7 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
                     .thread_status(cx)
                     .expect("There should be an active thread selected"),
                 match *operation {
-                    "continue_thread" => ThreadStatus::Running,
+                    "continue_thread" | "reverse_continue" => ThreadStatus::Running,
                     _ => ThreadStatus::Stepping,
                 },
                 "Thread status was not set to correct intermediate state after {} request",
@@ -1084,6 +1100,121 @@ async fn test_debug_panel_item_thread_status_reset_on_failure(
     }
 }
 
+#[gpui::test]
+async fn test_go_to_position_jumps_to_first_goto_target(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<dap::requests::Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_goto_targets_request: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<GotoTargets, _>(move |_, args| {
+        assert_eq!(Some(path!("/project/main.rs").into()), args.source.path);
+        assert_eq!(3, args.line);
+
+        Ok(dap::GotoTargetsResponse {
+            targets: vec![
+                dap::GotoTarget {
+                    id: 7,
+                    label: "Third line".into(),
+                    line: 3,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: None,
+                },
+                dap::GotoTarget {
+                    id: 8,
+                    label: "Third line, again".into(),
+                    line: 3,
+                    column: Some(5),
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: None,
+                },
+            ],
+        })
+    });
+
+    let goto_target = Arc::new(AtomicUsize::new(0));
+    client.on_request::<Goto, _>({
+        let goto_target = goto_target.clone();
+        move |_, args| {
+            assert_eq!(1, args.thread_id);
+            goto_target.store(args.target_id as usize, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    session.update(cx, |session, cx| {
+        session.go_to_position(
+            Arc::from(Path::new(path!("/project/main.rs"))),
+            2,
+            ThreadId(1),
+            cx,
+        );
+    });
+
+    cx.run_until_parked();
+
+    assert_eq!(7, goto_target.load(Ordering::SeqCst));
+}
+
 #[gpui::test]
 async fn test_send_breakpoints_when_editor_has_been_saved(
     executor: BackgroundExecutor,
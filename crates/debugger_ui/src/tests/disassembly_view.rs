@@ -0,0 +1,250 @@
+#![expect(clippy::result_large_err)]
+use crate::{
+    debugger_panel::DebugPanel,
+    persistence::DebuggerPaneItem,
+    tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
+};
+use dap::{
+    StackFrame, SteppingGranularity, StoppedEvent,
+    requests::{
+        Disassemble, Initialize, Next, Scopes, SetInstructionBreakpoints, StackTrace, Threads,
+    },
+};
+use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use project::{FakeFs, Project};
+use serde_json::json;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use unindent::Unindent as _;
+use util::path;
+
+fn source() -> dap::Source {
+    dap::Source {
+        name: Some("main.c".into()),
+        path: Some(path!("/project/main.c").into()),
+        source_reference: None,
+        presentation_hint: None,
+        origin: None,
+        sources: None,
+        adapter_data: None,
+        checksums: None,
+    }
+}
+
+fn instruction(
+    address: &str,
+    instruction: &str,
+    line: u64,
+    location: Option<dap::Source>,
+) -> dap::DisassembledInstruction {
+    dap::DisassembledInstruction {
+        address: address.into(),
+        instruction_bytes: None,
+        instruction: instruction.into(),
+        symbol: None,
+        location,
+        line: Some(line),
+        column: None,
+        end_line: None,
+        end_column: None,
+        presentation_hint: None,
+    }
+}
+
+#[gpui::test]
+async fn test_disassembly_view(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.c": r#"
+                int main() {
+                    int x = 1;
+                    return x;
+                }
+            "#
+            .unindent(),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_disassemble_request: Some(true),
+                supports_instruction_breakpoints: Some(true),
+                supports_stepping_granularity: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(source()),
+                line: 3,
+                column: 5,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: Some("0x1008".into()),
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+    client.on_request::<Disassemble, _>(move |_, args| {
+        // The disassembly starts before the instruction pointer, to show what led up to it.
+        assert_eq!(args.memory_reference, "0xFC8");
+        Ok(dap::DisassembleResponse {
+            instructions: vec![
+                instruction("0x1000", "push rbp", 1, Some(source())),
+                instruction("0x1004", "mov dword ptr [rbp - 4], 1", 2, None),
+                instruction("0x1008", "mov eax, dword ptr [rbp - 4]", 3, None),
+                instruction("0x100C", "pop rbp", 3, None),
+                instruction("0x1010", "ret", 4, None),
+            ],
+        })
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            item.running_state().clone()
+        });
+
+    running_state.update_in(cx, |this, window, cx| {
+        this.activate_item(DebuggerPaneItem::Disassembly, window, cx);
+        cx.refresh_windows();
+    });
+
+    cx.run_until_parked();
+
+    let disassembly_view = running_state.update(cx, |state, _| state.disassembly_view().clone());
+    disassembly_view.update(cx, |view, cx| {
+        assert_eq!(
+            view.entries_text(cx),
+            [
+                "main.c:1: int main() {",
+                "  0x1000 push rbp",
+                "main.c:2: int x = 1;",
+                "  0x1004 mov dword ptr [rbp - 4], 1",
+                "main.c:3: return x;",
+                "> 0x1008 mov eax, dword ptr [rbp - 4]",
+                "  0x100C pop rbp",
+                "main.c:4: }",
+                "  0x1010 ret",
+            ]
+        );
+    });
+
+    let instruction_breakpoints = Arc::new(Mutex::new(Vec::new()));
+    client.on_request::<SetInstructionBreakpoints, _>({
+        let instruction_breakpoints = instruction_breakpoints.clone();
+        move |_, args| {
+            *instruction_breakpoints.lock().unwrap() = args
+                .breakpoints
+                .iter()
+                .map(|breakpoint| breakpoint.instruction_reference.clone())
+                .collect::<Vec<_>>();
+            Ok(dap::SetInstructionBreakpointsResponse {
+                breakpoints: args
+                    .breakpoints
+                    .iter()
+                    .map(|_| dap::Breakpoint {
+                        id: None,
+                        verified: true,
+                        message: None,
+                        source: None,
+                        line: None,
+                        column: None,
+                        end_line: None,
+                        end_column: None,
+                        instruction_reference: None,
+                        offset: None,
+                        reason: None,
+                    })
+                    .collect(),
+            })
+        }
+    });
+
+    session.update(cx, |session, cx| {
+        session.toggle_instruction_breakpoint("0x100C".into(), cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(*instruction_breakpoints.lock().unwrap(), ["0x100C"]);
+    session.update(cx, |session, _| {
+        assert!(
+            session
+                .instruction_breakpoints()
+                .all(|breakpoint| breakpoint.verified)
+        );
+    });
+    disassembly_view.update(cx, |view, cx| {
+        assert_eq!(view.entries_text(cx)[6], "* 0x100C pop rbp");
+    });
+
+    let stepped_by_instruction = Arc::new(AtomicBool::new(false));
+    client.on_request::<Next, _>({
+        let stepped_by_instruction = stepped_by_instruction.clone();
+        move |_, args| {
+            stepped_by_instruction.store(
+                matches!(args.granularity, Some(SteppingGranularity::Instruction)),
+                Ordering::SeqCst,
+            );
+            Ok(())
+        }
+    });
+
+    running_state.update(cx, |state, cx| state.step_over(cx));
+    cx.run_until_parked();
+
+    assert!(
+        stepped_by_instruction.load(Ordering::SeqCst),
+        "Steps should be by instruction while the disassembly is shown"
+    );
+}
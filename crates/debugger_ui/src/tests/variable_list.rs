@@ -1,6 +1,6 @@
 #![expect(clippy::result_large_err)]
 use std::sync::{
-    Arc,
+    Arc, Mutex,
     atomic::{AtomicBool, Ordering},
 };
 
@@ -8,14 +8,14 @@ use crate::{
     DebugPanel,
     persistence::DebuggerPaneItem,
     session::running::variable_list::{
-        AddWatch, CollapseSelectedEntry, ExpandSelectedEntry, RemoveWatch,
+        AddWatch, CollapseSelectedEntry, EditVariable, ExpandSelectedEntry, RemoveWatch,
     },
     tests::{active_debug_session_panel, init_test, init_test_workspace, start_debug_session},
 };
 use collections::HashMap;
 use dap::{
     Scope, StackFrame, Variable,
-    requests::{Evaluate, Initialize, Launch, Scopes, StackTrace, Variables},
+    requests::{Evaluate, Initialize, Launch, Scopes, SetExpression, StackTrace, Variables},
 };
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
 use menu::{SelectFirst, SelectNext, SelectPrevious};
@@ -2341,3 +2341,182 @@ async fn test_refresh_watchers(executor: BackgroundExecutor, cx: &mut TestAppCon
         assert_eq!(3, watcher.variables_reference);
     });
 }
+
+#[gpui::test]
+async fn test_edit_watcher_value_with_set_expression(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+           "src": {
+               "test.js": "let count = 1;",
+           }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let session = start_debug_session(&workspace, cx, |client| {
+        client.on_request::<Initialize, _>(move |_, _| {
+            Ok(dap::Capabilities {
+                supports_set_expression: Some(true),
+                ..Default::default()
+            })
+        });
+    })
+    .unwrap();
+    let client = session.update(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<dap::requests::Threads, _>(move |_, _| {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "Thread 1".into(),
+            }],
+        })
+    });
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![StackFrame {
+                id: 1,
+                name: "Stack Frame 1".into(),
+                source: Some(dap::Source {
+                    name: Some("test.js".into()),
+                    path: Some(path!("/project/src/test.js").into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: None,
+        })
+    });
+
+    client.on_request::<Scopes, _>(move |_, _| Ok(dap::ScopesResponse { scopes: vec![] }));
+
+    let current_value = Arc::new(Mutex::new("1".to_string()));
+    client.on_request::<Evaluate, _>({
+        let current_value = current_value.clone();
+        move |_, args| {
+            assert_eq!("count", args.expression);
+
+            Ok(dap::EvaluateResponse {
+                result: current_value.lock().unwrap().clone(),
+                type_: None,
+                presentation_hint: None,
+                variables_reference: 0,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    client.on_request::<SetExpression, _>({
+        let current_value = current_value.clone();
+        move |_, args| {
+            assert_eq!("count", args.expression);
+            assert_eq!(Some(1), args.frame_id);
+            *current_value.lock().unwrap() = args.value.clone();
+
+            Ok(dap::SetExpressionResponse {
+                value: args.value,
+                type_: None,
+                presentation_hint: None,
+                variables_reference: None,
+                named_variables: None,
+                indexed_variables: None,
+                memory_reference: None,
+                value_location_reference: None,
+            })
+        }
+    });
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let running_state =
+        active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+            cx.focus_self(window);
+            let running = item.running_state().clone();
+
+            let variable_list = running.update(cx, |state, cx| {
+                state.activate_item(DebuggerPaneItem::Variables, window, cx);
+                state.variable_list().clone()
+            });
+            variable_list.update(cx, |_, cx| cx.focus_self(window));
+            running
+        });
+    cx.run_until_parked();
+
+    session
+        .update(cx, |session, cx| session.add_watcher("count".into(), 1, cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let variable_list = running_state.update(cx, |state, _| state.variable_list().clone());
+    variable_list.update(cx, |list, cx| {
+        list.assert_visual_entries(vec!["> count"]);
+        cx.dispatch_action(&SelectFirst);
+        cx.dispatch_action(&EditVariable);
+    });
+    cx.run_until_parked();
+
+    let editor = variable_list
+        .read_with(cx, |list, _| list.edited_value_editor())
+        .expect("editing a watcher should be possible with setExpression");
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_text("42", window, cx);
+    });
+    variable_list.update(cx, |_, cx| {
+        cx.dispatch_action(&menu::Confirm);
+    });
+    cx.run_until_parked();
+
+    session.update(cx, |session, _| {
+        let watcher = session
+            .watchers()
+            .get(&SharedString::from("count"))
+            .unwrap();
+
+        assert_eq!("42", watcher.value.to_string());
+    });
+}
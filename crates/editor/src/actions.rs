@@ -375,6 +375,8 @@ actions!(
     [
         /// Runs program execution to the current cursor position.
         RunToCursor,
+        /// Moves the program's next statement to the current cursor position.
+        SetNextStatement,
         /// Evaluates the selected text in the debugger context.
         EvaluateSelectedText
     ]
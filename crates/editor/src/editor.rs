@@ -38,8 +38,10 @@ mod persistence;
 mod runnables;
 mod rust_analyzer_ext;
 pub mod scroll;
+mod selection_ranges;
 mod selections_collection;
 pub mod semantic_tokens;
+mod snippet_variables;
 mod split;
 pub mod split_editor_view;
 
@@ -169,8 +171,8 @@ use project::{
     },
     git_store::GitStoreEvent,
     lsp_store::{
-        BufferSemanticTokens, CacheInlayHints, CompletionDocumentation, FormatTrigger,
-        LspFormatTarget, OpenLspBufferHandle, RefreshForServer,
+        BufferSemanticTokens, CacheInlayHints, CompletionDocumentation, DocumentLink,
+        DocumentLinkTarget, FormatTrigger, LspFormatTarget, OpenLspBufferHandle, RefreshForServer,
     },
     project_settings::{DiagnosticSeverity, GoToDiagnosticSeverityFilter, ProjectSettings},
 };
@@ -186,6 +188,7 @@ use settings::{
 };
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
+use snippet_variables::SnippetVariables;
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -1334,6 +1337,7 @@ pub struct Editor {
     refresh_colors_task: Task<()>,
     use_document_folding_ranges: bool,
     refresh_folding_ranges_task: Task<()>,
+    selection_ranges_task: Task<()>,
     inlay_hints: Option<LspInlayHintData>,
     folding_newlines: Task<()>,
     select_next_is_case_sensitive: Option<bool>,
@@ -1696,6 +1700,16 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    transforms: Vec<Vec<SnippetTransform>>,
+}
+
+/// A transformed occurrence of a snippet tabstop, rewritten from the text of the
+/// tabstop's first occurrence when the tabstop is left.
+#[derive(Debug)]
+struct SnippetTransform {
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: snippet::Transform,
 }
 
 #[doc(hidden)]
@@ -1814,6 +1828,44 @@ impl SelectSyntaxNodeHistory {
     }
 }
 
+/// Returns the range of the closest named syntax node enclosing `old_range` that does not
+/// start or end inside a fold, or the surrounding word when inside a string.
+fn larger_syntax_node_range(
+    buffer: &MultiBufferSnapshot,
+    display_map: &DisplaySnapshot,
+    old_range: Range<MultiBufferOffset>,
+) -> Range<MultiBufferOffset> {
+    if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
+        // manually select word at selection
+        if ["string_content", "inline"].contains(&node.kind()) {
+            let (word_range, _) = buffer.surrounding_word(old_range.start, None);
+            // ignore if word is already selected
+            if !word_range.is_empty() && old_range != word_range {
+                let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
+                // only select word if start and end point belongs to same word
+                if word_range == last_word_range {
+                    return word_range;
+                }
+            }
+        }
+    }
+
+    let mut new_range = old_range.clone();
+    while let Some((node, range)) = buffer.syntax_ancestor(new_range.clone()) {
+        new_range = range;
+        if !node.is_named() {
+            continue;
+        }
+        if !display_map.intersects_fold(new_range.start)
+            && !display_map.intersects_fold(new_range.end)
+        {
+            break;
+        }
+    }
+
+    new_range
+}
+
 enum SelectSyntaxNodeScrollBehavior {
     CursorTop,
     FitSelection,
@@ -2561,6 +2613,7 @@ impl Editor {
             refresh_colors_task: Task::ready(()),
             use_document_folding_ranges: false,
             refresh_folding_ranges_task: Task::ready(()),
+            selection_ranges_task: Task::ready(()),
             inlay_hints: None,
             next_color_inlay_id: 0,
             post_scroll_update: Task::ready(()),
@@ -6626,11 +6679,21 @@ impl Editor {
         let (initial_position, _) =
             multibuffer_snapshot.anchor_to_buffer_anchor(completions_menu.initial_position)?;
 
+        let snippet_variables = completion
+            .is_snippet()
+            .then(|| SnippetVariables::new(self, cx));
         let CompletionEdit {
             new_text,
             snippet,
             replace_range,
-        } = process_completion_for_edit(&completion, intent, &buffer_handle, &initial_position, cx);
+        } = process_completion_for_edit(
+            &completion,
+            intent,
+            &buffer_handle,
+            &initial_position,
+            snippet_variables.as_ref(),
+            cx,
+        );
 
         let buffer = buffer_handle.read(cx).snapshot();
         let newest_selection = self.selections.newest_anchor();
@@ -9085,6 +9148,7 @@ impl Editor {
         };
 
         let run_to_cursor = window.is_action_available(&RunToCursor, cx);
+        let set_next_statement = window.is_action_available(&SetNextStatement, cx);
 
         let toggle_state_msg = breakpoint.as_ref().map_or(None, |bp| match bp.1.state {
             BreakpointState::Enabled => Some("Disable"),
@@ -9113,8 +9177,25 @@ impl Editor {
 
                         window.dispatch_action(Box::new(RunToCursor), cx);
                     })
-                    .separator()
                 })
+                .when(set_next_statement, |this| {
+                    let weak_editor = weak_editor.clone();
+                    this.entry("Set Next Statement", None, move |window, cx| {
+                        weak_editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(
+                                    SelectionEffects::no_scroll(),
+                                    window,
+                                    cx,
+                                    |s| s.select_ranges([Point::new(row, 0)..Point::new(row, 0)]),
+                                );
+                            })
+                            .ok();
+
+                        window.dispatch_action(Box::new(SetNextStatement), cx);
+                    })
+                })
+                .when(run_to_cursor || set_next_statement, |this| this.separator())
                 .when_some(toggle_state_msg, |this, msg| {
                     this.entry(msg, None, {
                         let weak_editor = weak_editor.clone();
@@ -10645,6 +10726,7 @@ impl Editor {
             is_end_tabstop: bool,
             ranges: Vec<Range<T>>,
             choices: Option<Vec<String>>,
+            transforms: Vec<SnippetTransform>,
         }
 
         let tabstops = self.buffer.update(cx, |buffer, cx| {
@@ -10686,10 +10768,35 @@ impl Editor {
                         .collect::<Vec<_>>();
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
 
+                    // Each insertion gets its own copy of the transforms, reading from the
+                    // first occurrence of the tabstop within that same insertion.
+                    let mut transforms = Vec::new();
+                    if let Some(source_range) = tabstop.ranges.first() {
+                        let mut delta = 0_isize;
+                        for insertion_range in insertion_ranges {
+                            let insertion_start = insertion_range.start + delta;
+                            delta += snippet.text.len() as isize
+                                - (insertion_range.end - insertion_range.start) as isize;
+                            let anchor_range = |range: &Range<isize>| {
+                                let start = (insertion_start + range.start).min(snapshot.len());
+                                let end = (insertion_start + range.end).min(snapshot.len());
+                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                            };
+                            transforms.extend(tabstop.transforms.iter().map(|transform| {
+                                SnippetTransform {
+                                    source: anchor_range(source_range),
+                                    target: anchor_range(&transform.range),
+                                    transform: transform.transform.clone(),
+                                }
+                            }));
+                        }
+                    }
+
                     Tabstop {
                         is_end_tabstop,
                         ranges: tabstop_ranges,
                         choices: tabstop.choices.clone(),
+                        transforms,
                     }
                 })
                 .collect::<Vec<_>>()
@@ -10715,15 +10822,16 @@ impl Editor {
                     .map(|tabstop| tabstop.choices.clone())
                     .collect();
 
-                let ranges = tabstops
+                let (ranges, transforms) = tabstops
                     .into_iter()
-                    .map(|tabstop| tabstop.ranges)
-                    .collect::<Vec<_>>();
+                    .map(|tabstop| (tabstop.ranges, tabstop.transforms))
+                    .unzip();
 
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
                     choices,
+                    transforms,
                 });
             }
 
@@ -10812,6 +10920,14 @@ impl Editor {
         cx: &mut Context<Self>,
     ) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            let can_move = match bias {
+                Bias::Left => snippet.active_index > 0,
+                Bias::Right => snippet.active_index + 1 < snippet.ranges.len(),
+            };
+            if can_move && let Some(transforms) = snippet.transforms.get(snippet.active_index) {
+                self.apply_snippet_transforms(transforms, cx);
+            }
+
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -10854,6 +10970,28 @@ impl Editor {
         false
     }
 
+    fn apply_snippet_transforms(
+        &mut self,
+        transforms: &[SnippetTransform],
+        cx: &mut Context<Self>,
+    ) {
+        if transforms.is_empty() {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = transforms
+            .iter()
+            .map(|transform| {
+                let text = snapshot
+                    .text_for_range(transform.source.clone())
+                    .collect::<String>();
+                (transform.target.clone(), transform.transform.apply(&text))
+            })
+            .collect::<Vec<_>>();
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    }
+
     pub fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.transact(window, cx, |this, window, cx| {
             this.select_all(&SelectAll, window, cx);
@@ -15960,9 +16098,10 @@ impl Editor {
             .map(|selection| selection.range())
             .collect_vec();
 
+        let variables = SnippetVariables::new(self, cx);
         let snippet = if let Some(snippet_body) = &action.snippet {
             if action.language.is_none() && action.name.is_none() {
-                Snippet::parse(snippet_body)?
+                Snippet::parse_with_variables(snippet_body, |name| variables.resolve(name))?
             } else {
                 bail!("`snippet` is mutually exclusive with `language` and `name`")
             }
@@ -15974,7 +16113,7 @@ impl Editor {
                 .into_iter()
                 .find(|snippet| snippet.name == *name)
                 .context("snippet not found")?;
-            Snippet::parse(&snippet.body)?
+            Snippet::parse_with_variables(&snippet.body, |name| variables.resolve(name))?
         } else {
             // todo(andrew): open modal to select snippet
             bail!("`name` or `snippet` is required")
@@ -16839,51 +16978,17 @@ impl Editor {
 
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
 
+        if self.select_larger_lsp_ranges(old_selections.clone(), visible_row_count, window, cx) {
+            return;
+        }
+
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
-        let mut selected_larger_node = false;
-        let mut new_selections = old_selections
+        let new_selections = old_selections
             .iter()
             .map(|selection| {
-                let old_range = selection.start..selection.end;
-
-                if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
-                    // manually select word at selection
-                    if ["string_content", "inline"].contains(&node.kind()) {
-                        let (word_range, _) = buffer.surrounding_word(old_range.start, None);
-                        // ignore if word is already selected
-                        if !word_range.is_empty() && old_range != word_range {
-                            let (last_word_range, _) = buffer.surrounding_word(old_range.end, None);
-                            // only select word if start and end point belongs to same word
-                            if word_range == last_word_range {
-                                selected_larger_node = true;
-                                return Selection {
-                                    id: selection.id,
-                                    start: word_range.start,
-                                    end: word_range.end,
-                                    goal: SelectionGoal::None,
-                                    reversed: selection.reversed,
-                                };
-                            }
-                        }
-                    }
-                }
-
-                let mut new_range = old_range.clone();
-                while let Some((node, range)) = buffer.syntax_ancestor(new_range.clone()) {
-                    new_range = range;
-                    if !node.is_named() {
-                        continue;
-                    }
-                    if !display_map.intersects_fold(new_range.start)
-                        && !display_map.intersects_fold(new_range.end)
-                    {
-                        break;
-                    }
-                }
-
-                selected_larger_node |= new_range != old_range;
+                let new_range = larger_syntax_node_range(&buffer, &display_map, selection.range());
                 Selection {
                     id: selection.id,
                     start: new_range.start,
@@ -16894,10 +16999,36 @@ impl Editor {
             })
             .collect::<Vec<_>>();
 
+        self.select_larger_ranges(
+            old_selections,
+            new_selections,
+            visible_row_count,
+            window,
+            cx,
+        );
+    }
+
+    /// Selects the given enlarged ranges, recording the previous selections so that
+    /// `SelectSmallerSyntaxNode` can restore them.
+    fn select_larger_ranges(
+        &mut self,
+        old_selections: Box<[Selection<MultiBufferOffset>]>,
+        mut new_selections: Vec<Selection<MultiBufferOffset>>,
+        visible_row_count: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selected_larger_node = old_selections
+            .iter()
+            .zip(&new_selections)
+            .any(|(old, new)| old.range() != new.range());
         if !selected_larger_node {
             return; // don't put this call in the history
         }
 
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
         // scroll based on transformation done to the last selection created by the user
         let (last_old, last_new) = old_selections
             .last()
@@ -16912,13 +17043,11 @@ impl Editor {
             last_new.reversed
         };
 
-        if selected_larger_node {
-            self.select_syntax_node_history.disable_clearing = true;
-            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
-                s.select(new_selections.clone());
-            });
-            self.select_syntax_node_history.disable_clearing = false;
-        }
+        self.select_syntax_node_history.disable_clearing = true;
+        self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select(new_selections.clone());
+        });
+        self.select_syntax_node_history.disable_clearing = false;
 
         let start_row = last_new.start.to_display_point(&display_map).row().0;
         let end_row = last_new.end.to_display_point(&display_map).row().0;
@@ -25907,6 +26036,7 @@ fn process_completion_for_edit(
     intent: CompletionIntent,
     buffer: &Entity<Buffer>,
     cursor_position: &text::Anchor,
+    snippet_variables: Option<&SnippetVariables>,
     cx: &mut Context<Editor>,
 ) -> CompletionEdit {
     let buffer = buffer.read(cx);
@@ -25932,7 +26062,10 @@ fn process_completion_for_edit(
         {
             snippet_source = label;
         }
-        match Snippet::parse(&snippet_source).log_err() {
+        let parsed_snippet = Snippet::parse_with_variables(&snippet_source, |name| {
+            snippet_variables.and_then(|variables| variables.resolve(name))
+        });
+        match parsed_snippet.log_err() {
             Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
             None => (None, completion.new_text.clone()),
         }
@@ -27016,6 +27149,19 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Result<Vec<DocumentHighlight>>>>;
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Vec<DocumentLink>>>;
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLinkTarget>>>>;
+
     fn definitions(
         &self,
         buffer: &Entity<Buffer>,
@@ -27507,6 +27653,33 @@ impl SemanticsProvider for WeakEntity<Project> {
         .ok()
     }
 
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Vec<DocumentLink>>> {
+        self.update(cx, |project, cx| {
+            project
+                .lsp_store()
+                .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx))
+        })
+        .ok()
+    }
+
+    fn resolve_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLinkTarget>>>> {
+        self.update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store.resolve_document_link(buffer, link, cx)
+            })
+        })
+        .ok()
+    }
+
     fn definitions(
         &self,
         buffer: &Entity<Buffer>,
@@ -30606,6 +30606,68 @@ async fn test_insert_snippet(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_snippet_variables_and_transforms(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        one
+        fn «mainˇ»() {}
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.insert_snippet_at_selections(
+            &InsertSnippet {
+                language: None,
+                name: None,
+                snippet: Some(
+                    "${TM_SELECTED_TEXT/(.*)/${1:/pascalcase}/}_$TM_LINE_NUMBER$0".to_string(),
+                ),
+            },
+            window,
+            cx,
+        );
+    });
+    cx.assert_editor_state(indoc! {"
+        one
+        fn Main_2ˇ() {}
+    "});
+
+    cx.set_state(indoc! {"
+        ˇ
+        ˇ
+    "});
+    cx.update_editor(|editor, window, cx| {
+        let snippet = Snippet::parse("let ${1:name} = ${1/(.*)/${1:/upcase}/};$0").unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(&editor.display_snapshot(cx))
+            .iter()
+            .map(|s| s.range())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+    });
+    cx.assert_editor_state(indoc! {"
+        let «nameˇ» = ;
+        let «nameˇ» = ;
+    "});
+
+    // Transformed occurrences are only updated once the tabstop is left.
+    cx.update_editor(|editor, window, cx| editor.insert("count", window, cx));
+    cx.assert_editor_state(indoc! {"
+        let countˇ = ;
+        let countˇ = ;
+    "});
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state(indoc! {"
+        let count = COUNT;ˇ
+        let count = COUNT;ˇ
+    "});
+}
+
 #[gpui::test]
 async fn test_inlay_hints_request_timeout(cx: &mut TestAppContext) {
     use crate::inlays::inlay_hints::InlayHintRefreshReason;
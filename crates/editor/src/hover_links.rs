@@ -1,17 +1,17 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind,
-    HighlightKey, Navigated, PointForPosition, SelectPhase,
+    HighlightKey, Navigated, PointForPosition, SelectPhase, SemanticsProvider,
     editor_settings::GoToDefinitionFallback, scroll::ScrollAmount,
 };
 use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Pixels, Task, Window, px};
 use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
-use project::{InlayId, LocationLink, Project, ResolvedPath};
+use project::{InlayId, LocationLink, Project, ResolvedPath, lsp_store::DocumentLinkTarget};
 use regex::Regex;
 use settings::Settings;
-use std::{ops::Range, sync::LazyLock};
+use std::{ops::Range, rc::Rc, sync::LazyLock};
 use text::OffsetRangeExt;
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
@@ -361,7 +361,16 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, anchor, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(&buffer, anchor, project.clone(), provider.clone(), cx)
+                            .await
+                    {
+                        let range = maybe!({
+                            let range = snapshot.buffer_anchor_range_to_anchor_range(link_range)?;
+                            Some(RangeInEditor::Text(range))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) = find_url(&buffer, anchor, cx.clone()) {
                         this.read_with(cx, |_, _| {
                             let range = maybe!({
                                 let range =
@@ -485,6 +494,50 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the link reported by the language servers through `textDocument/documentLink`
+/// at the given position, resolving file targets into paths that can be opened in the project.
+pub(crate) async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    position: text::Anchor,
+    project: Option<Entity<Project>>,
+    provider: Option<Rc<dyn SemanticsProvider>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let links = cx
+        .update(|_, cx| provider.as_ref()?.document_links(buffer, cx))
+        .ok()??
+        .await;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let link = links.into_iter().find(|link| {
+        link.range.start.cmp(&position, &snapshot).is_le()
+            && link.range.end.cmp(&position, &snapshot).is_ge()
+    })?;
+    // Only the hovered link is resolved, rather than every link of the buffer.
+    let target = cx
+        .update(|_, cx| provider?.resolve_document_link(buffer, &link, cx))
+        .ok()??
+        .await
+        .log_err()??;
+
+    let hover_link = match target {
+        DocumentLinkTarget::Url(url) => HoverLink::Url(url),
+        DocumentLinkTarget::Path(path) => {
+            let project = project?;
+            let resolve_task = project.update(cx, |project, cx| {
+                match project.find_project_path(&path, cx) {
+                    Some(project_path) => Task::ready(Some(ResolvedPath::ProjectPath {
+                        project_path,
+                        is_dir: false,
+                    })),
+                    None => project.resolve_abs_path(&path, cx),
+                }
+            });
+            HoverLink::File(resolve_task.await?)
+        }
+    };
+    Some((link.range, hover_link))
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
@@ -794,6 +847,10 @@ mod tests {
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
     use multi_buffer::MultiBufferOffset;
     use settings::InlayHintSettingsContent;
+    use std::sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    };
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1362,6 +1419,149 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            // See the serde docs for detailsˇ.
+        "});
+
+        let request_count = Arc::new(AtomicUsize::new(0));
+        let mut requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>({
+            let request_count = request_count.clone();
+            move |_, _, _| {
+                request_count.fetch_add(1, atomic::Ordering::Release);
+                async move {
+                    Ok(Some(vec![lsp::DocumentLink {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 11),
+                            lsp::Position::new(0, 21),
+                        ),
+                        target: Some("https://docs.rs/serde".parse().unwrap()),
+                        tooltip: None,
+                        data: None,
+                    }]))
+                }
+            }
+        });
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            // See the serde dˇocs for details.
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::HoveredLinkState,
+            indoc! {"
+            // See the «serde docsˇ» for details.
+        "},
+        );
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+
+        // Hovering again over the unchanged buffer is served from the cache.
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::none());
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::HoveredLinkState,
+            indoc! {"
+            // See the «serde docsˇ» for details.
+        "},
+        );
+        assert_eq!(request_count.load(atomic::Ordering::Acquire), 1);
+    }
+
+    #[gpui::test]
+    async fn test_document_links_resolved_on_hover(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            // See the serde docs for detailsˇ.
+        "});
+
+        let mut requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 6)),
+                        target: None,
+                        tooltip: None,
+                        data: Some(serde_json::json!("see")),
+                    },
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 11),
+                            lsp::Position::new(0, 21),
+                        ),
+                        target: None,
+                        tooltip: None,
+                        data: Some(serde_json::json!("serde")),
+                    },
+                ]))
+            },
+        );
+        let resolved = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        cx.set_request_handler::<lsp::request::DocumentLinkResolve, _, _>({
+            let resolved = resolved.clone();
+            move |_, link, _| {
+                resolved.lock().push(link.data.clone());
+                async move {
+                    Ok(lsp::DocumentLink {
+                        target: Some("https://docs.rs/serde".parse().unwrap()),
+                        ..link
+                    })
+                }
+            }
+        });
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            // See the serde dˇocs for details.
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights(
+            HighlightKey::HoveredLinkState,
+            indoc! {"
+            // See the «serde docsˇ» for details.
+        "},
+        );
+
+        // Hovering the link again uses the cached target.
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::none());
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+        assert_eq!(*resolved.lock(), [Some(serde_json::json!("serde"))]);
+    }
+
     #[gpui::test]
     async fn test_hover_preconditions(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
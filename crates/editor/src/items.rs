@@ -1712,9 +1712,20 @@ impl SearchableItem for Editor {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let text = self.buffer.read(cx);
-        let text = text.snapshot(cx);
-        let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
+        let snapshot = self.buffer.read(cx);
+        let snapshot = snapshot.snapshot(cx);
+        if query.is_structural() {
+            if let Some(replacement) = structural_replacement(&snapshot, identifier, query) {
+                self.transact(window, cx, |this, _, cx| {
+                    this.edit([(identifier.clone(), Arc::from(replacement))], cx);
+                });
+            }
+            return;
+        }
+
+        let text = snapshot
+            .text_for_range(identifier.clone())
+            .collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
         } else {
@@ -1740,9 +1751,16 @@ impl SearchableItem for Editor {
         let text = text.snapshot(cx);
         let mut edits = vec![];
 
-        // A regex might have replacement variables so we cannot apply
+        // Structural matches and regexes might have replacement variables, so we cannot apply
         // the same replacement to all matches
-        if query.is_regex() {
+        if query.is_structural() {
+            edits = matches
+                .filter_map(|m| {
+                    structural_replacement(&text, m, query)
+                        .map(|replacement| (m.clone(), Arc::from(replacement)))
+                })
+                .collect();
+        } else if query.is_regex() {
             edits = matches
                 .filter_map(|m| {
                     let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
@@ -1940,6 +1958,18 @@ pub fn active_match_index(
     }
 }
 
+fn structural_replacement(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<String> {
+    let (buffer, buffer_range, _) = snapshot
+        .range_to_buffer_ranges(range.clone())
+        .into_iter()
+        .next()?;
+    query.structural_replacement_for(&buffer, buffer_range.start.0..buffer_range.end.0)
+}
+
 pub fn entry_label_color(selected: bool) -> Color {
     if selected {
         Color::Default
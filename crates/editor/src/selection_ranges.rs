@@ -0,0 +1,221 @@
+use std::ops::Range;
+
+use collections::HashMap;
+use futures::future::join_all;
+use language::{Selection, SelectionGoal};
+use multi_buffer::{MultiBufferOffset, ToOffset as _};
+use settings::SelectionRanges;
+use ui::{Context, Window};
+use util::ResultExt as _;
+
+use crate::{Editor, larger_syntax_node_range};
+
+impl Editor {
+    /// Expands the selections through the language server's selection ranges, if the
+    /// `selection_ranges` setting of the newest selection's language asks for it.
+    ///
+    /// Returns `false` when the selections should be expanded through tree-sitter instead.
+    pub(super) fn select_larger_lsp_ranges(
+        &mut self,
+        old_selections: Box<[Selection<MultiBufferOffset>]>,
+        visible_row_count: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.lsp_data_enabled() {
+            return false;
+        }
+        let Some(project) = self.project.clone() else {
+            return false;
+        };
+        let Some(newest_selection) = old_selections.iter().max_by_key(|selection| selection.id)
+        else {
+            return false;
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let source = multi_buffer
+            .language_settings_at(newest_selection.head(), cx)
+            .selection_ranges;
+        if !source.lsp_enabled() {
+            return false;
+        }
+
+        let mut positions_by_buffer = HashMap::default();
+        for selection in old_selections.iter() {
+            if let Some((buffer, position)) =
+                multi_buffer.text_anchor_for_position(selection.start, cx)
+            {
+                positions_by_buffer
+                    .entry(buffer.read(cx).remote_id())
+                    .or_insert_with(|| (buffer, Vec::new()))
+                    .1
+                    .push(position);
+            }
+        }
+        if positions_by_buffer.is_empty() {
+            return false;
+        }
+
+        let requests = project.update(cx, |project, cx| {
+            positions_by_buffer
+                .into_values()
+                .map(|(buffer, positions)| project.selection_ranges(&buffer, positions, cx))
+                .collect::<Vec<_>>()
+        });
+        self.selection_ranges_task = cx.spawn_in(window, async move |editor, cx| {
+            let lsp_ranges = join_all(requests)
+                .await
+                .into_iter()
+                .filter_map(|ranges| ranges.log_err())
+                .flatten()
+                .flatten()
+                .collect::<Vec<_>>();
+
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                    let buffer = editor.buffer.read(cx).snapshot(cx);
+
+                    // Drop the response if the selections changed while waiting for the server.
+                    let current_selections = editor
+                        .selections
+                        .all::<MultiBufferOffset>(&editor.display_snapshot(cx));
+                    if current_selections.len() != old_selections.len()
+                        || current_selections
+                            .iter()
+                            .zip(old_selections.iter())
+                            .any(|(current, old)| current.range() != old.range())
+                    {
+                        return;
+                    }
+
+                    let lsp_ranges = lsp_ranges
+                        .into_iter()
+                        .filter_map(|range| buffer.buffer_anchor_range_to_anchor_range(range))
+                        .map(|range| range.start.to_offset(&buffer)..range.end.to_offset(&buffer))
+                        .collect::<Vec<_>>();
+                    let new_selections = old_selections
+                        .iter()
+                        .map(|selection| {
+                            let old_range = selection.range();
+                            let syntax_range =
+                                larger_syntax_node_range(&buffer, &display_map, old_range.clone());
+                            let lsp_range = smallest_enclosing_range(&lsp_ranges, &old_range);
+                            let new_range = match (source, lsp_range) {
+                                (SelectionRanges::Merge, Some(lsp_range))
+                                    if syntax_range != old_range
+                                        && syntax_range.end - syntax_range.start
+                                            < lsp_range.end - lsp_range.start =>
+                                {
+                                    syntax_range
+                                }
+                                (_, Some(lsp_range)) => lsp_range,
+                                (_, None) => syntax_range,
+                            };
+                            Selection {
+                                id: selection.id,
+                                start: new_range.start,
+                                end: new_range.end,
+                                goal: SelectionGoal::None,
+                                reversed: selection.reversed,
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    editor.select_larger_ranges(
+                        old_selections,
+                        new_selections,
+                        visible_row_count,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        });
+        true
+    }
+}
+
+fn smallest_enclosing_range(
+    candidates: &[Range<MultiBufferOffset>],
+    range: &Range<MultiBufferOffset>,
+) -> Option<Range<MultiBufferOffset>> {
+    candidates
+        .iter()
+        .filter(|candidate| {
+            candidate.start <= range.start && candidate.end >= range.end && *candidate != range
+        })
+        .min_by_key(|candidate| candidate.end - candidate.start)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use settings::SelectionRanges;
+
+    use crate::{
+        SelectLargerSyntaxNode, SelectSmallerSyntaxNode,
+        editor_tests::{init_test, update_test_language_settings},
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+
+    #[gpui::test]
+    async fn test_select_larger_syntax_node_uses_lsp_selection_ranges(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+
+        update_test_language_settings(cx, &|settings| {
+            settings.defaults.selection_ranges = Some(SelectionRanges::LanguageServer);
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state("fn main() { let value = foo(ˇbar, baz); }");
+        let mut requests = cx.set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(
+            |_, params, _| async move {
+                assert_eq!(params.positions, vec![lsp::Position::new(0, 28)]);
+                let range = |start, end| {
+                    lsp::Range::new(lsp::Position::new(0, start), lsp::Position::new(0, end))
+                };
+                Ok(Some(vec![lsp::SelectionRange {
+                    range: range(28, 31),
+                    parent: Some(Box::new(lsp::SelectionRange {
+                        range: range(28, 35),
+                        parent: Some(Box::new(lsp::SelectionRange {
+                            range: range(24, 37),
+                            parent: None,
+                        })),
+                    })),
+                }]))
+            },
+        );
+
+        cx.update_editor(|editor, window, cx| {
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        });
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_state("fn main() { let value = foo(«barˇ», baz); }");
+
+        cx.update_editor(|editor, window, cx| {
+            editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+        });
+        requests.next().await;
+        cx.run_until_parked();
+        // The argument list without its parentheses only exists as an LSP selection range.
+        cx.assert_editor_state("fn main() { let value = foo(«bar, bazˇ»); }");
+
+        cx.update_editor(|editor, window, cx| {
+            editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+        });
+        cx.assert_editor_state("fn main() { let value = foo(«barˇ», baz); }");
+    }
+}
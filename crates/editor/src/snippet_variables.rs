@@ -0,0 +1,183 @@
+use std::{path::PathBuf, sync::Arc};
+
+use gpui::App;
+use language::{CharKind, Point};
+use multi_buffer::MultiBufferRow;
+use rand::Rng as _;
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::Editor;
+
+/// The values of the TextMate/LSP snippet variables (`$TM_FILENAME`, `$CLIPBOARD`, ...)
+/// at the newest selection of an editor.
+pub(crate) struct SnippetVariables {
+    selected_text: String,
+    current_line: String,
+    current_word: String,
+    line_index: u32,
+    file: Option<FileVariables>,
+    clipboard: Option<String>,
+    line_comment: Option<Arc<str>>,
+    block_comment: Option<(Arc<str>, Arc<str>)>,
+    now: OffsetDateTime,
+}
+
+struct FileVariables {
+    file_name: String,
+    path: PathBuf,
+    relative_path: String,
+    workspace_name: Option<String>,
+    workspace_folder: Option<PathBuf>,
+}
+
+impl SnippetVariables {
+    pub(crate) fn new(editor: &Editor, cx: &mut App) -> Self {
+        let selection = editor
+            .selections
+            .newest::<Point>(&editor.display_snapshot(cx));
+        let head = selection.head();
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+
+        let selected_text = snapshot.text_for_range(selection.range()).collect();
+        let current_line = snapshot
+            .text_for_range(
+                Point::new(head.row, 0)
+                    ..Point::new(head.row, snapshot.line_len(MultiBufferRow(head.row))),
+            )
+            .collect();
+        let current_word = match snapshot.surrounding_word(head, None) {
+            (range, Some(CharKind::Word)) => snapshot.text_for_range(range).collect(),
+            _ => String::new(),
+        };
+
+        let (line_comment, block_comment) = snapshot
+            .language_scope_at(head)
+            .map(|scope| {
+                (
+                    scope.line_comment_prefixes().first().cloned(),
+                    scope
+                        .block_comment()
+                        .map(|comment| (comment.start.clone(), comment.end.clone())),
+                )
+            })
+            .unwrap_or_default();
+
+        let buffer_position = editor.buffer.read(cx).point_to_buffer_point(head, cx);
+        let line_index = buffer_position
+            .as_ref()
+            .map_or(head.row, |(_, point)| point.row);
+        let file = buffer_position.and_then(|(buffer, _)| {
+            let file = buffer.read(cx).file()?.clone();
+            let full_path = file.full_path(cx);
+            let worktree = editor
+                .project()
+                .and_then(|project| project.read(cx).worktree_for_id(file.worktree_id(cx), cx));
+            Some(FileVariables {
+                file_name: file.file_name(cx).to_string(),
+                path: file
+                    .as_local()
+                    .map_or_else(|| full_path.clone(), |file| file.abs_path(cx)),
+                relative_path: file.path().display(file.path_style(cx)).into_owned(),
+                workspace_name: worktree
+                    .as_ref()
+                    .map(|worktree| worktree.read(cx).root_name_str().to_string()),
+                workspace_folder: worktree
+                    .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+            })
+        });
+
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        Self {
+            selected_text,
+            current_line,
+            current_word,
+            line_index,
+            file,
+            clipboard: cx.read_from_clipboard().and_then(|item| item.text()),
+            line_comment,
+            block_comment,
+            now: OffsetDateTime::now_utc().to_offset(local_offset),
+        }
+    }
+
+    /// Returns the value of a variable, or `None` if the variable is unknown.
+    /// Known variables without a value, like `$TM_FILENAME` in an untitled buffer, are empty.
+    pub(crate) fn resolve(&self, name: &str) -> Option<String> {
+        let file = self.file.as_ref();
+        let now = &self.now;
+        let value = match name {
+            "TM_SELECTED_TEXT" => self.selected_text.clone(),
+            "TM_CURRENT_LINE" => self.current_line.clone(),
+            "TM_CURRENT_WORD" => self.current_word.clone(),
+            "TM_LINE_INDEX" => self.line_index.to_string(),
+            "TM_LINE_NUMBER" => (self.line_index + 1).to_string(),
+            "TM_FILENAME" => file.map(|file| file.file_name.clone()).unwrap_or_default(),
+            "TM_FILENAME_BASE" => file
+                .map(|file| match file.file_name.rsplit_once('.') {
+                    Some((base, _)) if !base.is_empty() => base.to_string(),
+                    _ => file.file_name.clone(),
+                })
+                .unwrap_or_default(),
+            "TM_DIRECTORY" => file
+                .and_then(|file| file.path.parent())
+                .map(|directory| directory.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            "TM_FILEPATH" => file
+                .map(|file| file.path.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            "RELATIVE_FILEPATH" => file
+                .map(|file| file.relative_path.clone())
+                .unwrap_or_default(),
+            "WORKSPACE_NAME" => file
+                .and_then(|file| file.workspace_name.clone())
+                .unwrap_or_default(),
+            "WORKSPACE_FOLDER" => file
+                .and_then(|file| file.workspace_folder.as_ref())
+                .map(|folder| folder.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            "CLIPBOARD" => self.clipboard.clone().unwrap_or_default(),
+            "CURRENT_YEAR" => now.year().to_string(),
+            "CURRENT_YEAR_SHORT" => format!("{:02}", now.year().rem_euclid(100)),
+            "CURRENT_MONTH" => format!("{:02}", u8::from(now.month())),
+            "CURRENT_MONTH_NAME" => now.month().to_string(),
+            "CURRENT_MONTH_NAME_SHORT" => now.month().to_string()[..3].to_string(),
+            "CURRENT_DATE" => format!("{:02}", now.day()),
+            "CURRENT_DAY_NAME" => now.weekday().to_string(),
+            "CURRENT_DAY_NAME_SHORT" => now.weekday().to_string()[..3].to_string(),
+            "CURRENT_HOUR" => format!("{:02}", now.hour()),
+            "CURRENT_MINUTE" => format!("{:02}", now.minute()),
+            "CURRENT_SECOND" => format!("{:02}", now.second()),
+            "CURRENT_SECONDS_UNIX" => now.unix_timestamp().to_string(),
+            "CURRENT_TIMEZONE_OFFSET" => {
+                let offset = now.offset();
+                format!(
+                    "{}{:02}:{:02}",
+                    if offset.is_negative() { '-' } else { '+' },
+                    offset.whole_hours().abs(),
+                    offset.minutes_past_hour().abs()
+                )
+            }
+            "RANDOM" => format!("{:06}", rand::rng().random_range(0..1_000_000)),
+            "RANDOM_HEX" => format!("{:06x}", rand::rng().random_range(0..0x1000000)),
+            "UUID" => uuid::Uuid::new_v4().to_string(),
+            "LINE_COMMENT" => self
+                .line_comment
+                .as_deref()
+                .unwrap_or_default()
+                .trim_end()
+                .to_string(),
+            "BLOCK_COMMENT_START" => self
+                .block_comment
+                .as_ref()
+                .map(|(start, _)| start.trim_end().to_string())
+                .unwrap_or_default(),
+            "BLOCK_COMMENT_END" => self
+                .block_comment
+                .as_ref()
+                .map(|(_, end)| end.trim_start().to_string())
+                .unwrap_or_default(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
@@ -12,6 +12,18 @@
 //!          --instruction "Fix the bug described in..." --timeout 600
 //! ```
 //!
+//! ## Offline runs
+//!
+//! Setting `ZED_LLM_FIXTURES_DIR` registers a `replay` provider that answers requests from the
+//! fixtures in that directory instead of a server. To record fixtures, also set
+//! `ZED_LLM_FIXTURES_RECORD` to the id of the provider to record, and run with its models:
+//!
+//! ```text
+//! ZED_LLM_FIXTURES_DIR=fixtures ZED_LLM_FIXTURES_RECORD=anthropic \
+//!     eval-cli --model replay/claude-sonnet-4-6-latest ...
+//! ZED_LLM_FIXTURES_DIR=fixtures eval-cli --model replay/claude-sonnet-4-6-latest ...
+//! ```
+//!
 //! ## Output
 //!
 //! Writes to `--output-dir` (default `/logs/agent/`):
@@ -4,15 +4,18 @@ use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid, RunHook,
+    bisect::{BisectCommit, BisectLogEntry, BisectState, BisectVerdict},
     blame::Blame,
+    rebase::{RebaseStatus, RebaseTodoAction, RebaseTodoEntry, validate_rebase_todo},
     repository::{
         AskPassDelegate, Branch, CommitDataReader, CommitDetails, CommitOptions, FetchOptions,
-        GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RepoPath, ResetMode, SearchCommitArgs, Worktree,
+        GRAPH_CHUNK_SIZE, GitRepository, GitRepositoryCheckpoint, IndexStage,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote, RepoPath, ResetMode,
+        SearchCommitArgs, Tag, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
-        UnmergedStatus,
+        UnmergedStatus, UnmergedStatusCode,
     },
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
@@ -41,18 +44,50 @@ pub struct FakeGitRepositoryState {
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
+    /// The contents of conflicted paths at each merge stage of the index.
+    pub index_stage_contents: HashMap<(RepoPath, IndexStage), String>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, String>,
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    /// The tags of the repository, most recently created first.
+    pub tags: Vec<Tag>,
     /// List of remotes, keys are names and values are URLs
     pub remotes: HashMap<String, String>,
     pub simulated_index_write_error_message: Option<String>,
     pub simulated_create_worktree_error: Option<String>,
     pub refs: HashMap<String, String>,
     pub graph_commits: Vec<Arc<InitialGraphCommitData>>,
+    /// The shas and subjects of the commits on the current branch, oldest first.
+    pub commit_history: Vec<(String, String)>,
+    /// The full messages of commits in `commit_history` that have more than a subject.
+    pub commit_messages: HashMap<String, String>,
+    /// Commits that conflict at the given paths when they're applied by a rebase.
+    pub rebase_conflicts: HashMap<String, Vec<RepoPath>>,
+    pub rebase: Option<FakeRebase>,
+    pub bisect: Option<FakeBisect>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeRebase {
+    pub status: RebaseStatus,
+    /// The entry that conflicted, which is applied when the rebase is continued.
+    pub conflicted_entry: Option<RebaseTodoEntry>,
+    /// The commit history from before the rebase, which is restored when it's aborted.
+    pub original_history: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeBisect {
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    pub log: Vec<BisectLogEntry>,
+    pub first_bad: Option<String>,
+    /// The HEAD from before the bisect, which is checked out again when it's reset.
+    pub original_head: String,
 }
 
 impl FakeGitRepositoryState {
@@ -61,10 +96,12 @@ impl FakeGitRepositoryState {
             event_emitter,
             head_contents: Default::default(),
             index_contents: Default::default(),
+            index_stage_contents: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            tags: Vec::new(),
             simulated_index_write_error_message: Default::default(),
             simulated_create_worktree_error: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
@@ -72,10 +109,141 @@ impl FakeGitRepositoryState {
             oids: Default::default(),
             remotes: HashMap::default(),
             graph_commits: Vec::new(),
+            commit_history: Vec::new(),
+            commit_messages: HashMap::default(),
+            rebase_conflicts: HashMap::default(),
+            rebase: None,
+            bisect: None,
+        }
+    }
+
+    fn history_position(&self, rev: &str) -> Result<usize> {
+        let sha = self.refs.get(rev).map_or(rev, String::as_str);
+        self.commit_history
+            .iter()
+            .position(|(commit_sha, _)| commit_sha == sha)
+            .with_context(|| format!("unknown revision {rev}"))
+    }
+
+    /// The subject of a commit in the history, or the revision itself for commits outside of it.
+    fn commit_subject(&self, rev: &str) -> String {
+        self.history_position(rev)
+            .map(|position| self.commit_history[position].1.clone())
+            .unwrap_or_else(|_| rev.to_string())
+    }
+
+    fn push_commit(&mut self, sha: String, subject: String) {
+        self.refs.insert("HEAD".into(), sha.clone());
+        self.commit_history.push((sha, subject));
+    }
+
+    /// The commits between the newest good commit and the bad one, oldest first, including the
+    /// bad commit but not the skipped ones.
+    fn bisect_candidates(&self) -> Result<Vec<(String, String)>> {
+        let Some(bisect) = self.bisect.as_ref() else {
+            bail!("Failed to bisect:\nYou need to start by \"git bisect start\"");
+        };
+        let Some(bad) = bisect.bad.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let mut newest_good = None;
+        for good in &bisect.good {
+            let position = self.history_position(good)?;
+            newest_good = newest_good.max(Some(position));
+        }
+        let Some(newest_good) = newest_good else {
+            return Ok(Vec::new());
+        };
+        let bad = self.history_position(bad)?;
+        if newest_good >= bad {
+            bail!("Failed to bisect:\nSome good revs are not ancestors of the bad rev.");
+        }
+        Ok(self.commit_history[newest_good + 1..=bad]
+            .iter()
+            .filter(|(sha, _)| !bisect.skipped.contains(sha))
+            .cloned()
+            .collect())
+    }
+
+    /// Checks out the middle of the remaining commits, or records the first bad commit once only
+    /// the bad one is left.
+    fn advance_bisect(&mut self) -> Result<()> {
+        let candidates = self.bisect_candidates()?;
+        let Some(bisect) = self.bisect.as_mut() else {
+            return Ok(());
+        };
+        let Some(bad) = bisect.bad.clone() else {
+            return Ok(());
+        };
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let untested = &candidates[..candidates.len() - 1];
+        if untested.is_empty() {
+            bisect.first_bad = Some(bad);
+            return Ok(());
+        }
+        let (sha, _) = &untested[untested.len() / 2];
+        self.refs.insert("HEAD".into(), sha.clone());
+        Ok(())
+    }
+
+    fn bisect_commit(&self, rev: &str) -> BisectCommit {
+        let sha = self.refs.get(rev).map_or(rev, String::as_str);
+        BisectCommit::new(sha.to_string(), self.commit_subject(sha))
+    }
+
+    /// Applies todo entries until the list is done or an entry stops the rebase.
+    fn advance_rebase(&mut self) {
+        let Some(rebase) = self.rebase.as_mut() else {
+            return;
+        };
+        while !rebase.status.todo.is_empty() {
+            let entry = rebase.status.todo.remove(0);
+            rebase.status.step += 1;
+            if entry.action == RebaseTodoAction::Drop {
+                continue;
+            }
+            if let Some(paths) = self.rebase_conflicts.remove(entry.sha.as_ref()) {
+                for path in paths {
+                    self.unmerged_paths.insert(
+                        path,
+                        UnmergedStatus {
+                            first_head: UnmergedStatusCode::Updated,
+                            second_head: UnmergedStatusCode::Updated,
+                        },
+                    );
+                }
+                rebase.status.stopped_sha = Some(entry.sha.clone());
+                rebase.conflicted_entry = Some(entry);
+                return;
+            }
+            apply_rebase_entry(&mut self.commit_history, &entry);
+            if entry.action == RebaseTodoAction::Edit {
+                rebase.status.stopped_sha = Some(entry.sha);
+                return;
+            }
+        }
+        self.rebase = None;
+        if let Some((sha, _)) = self.commit_history.last() {
+            self.refs.insert("HEAD".into(), sha.clone());
         }
     }
 }
 
+fn apply_rebase_entry(history: &mut Vec<(String, String)>, entry: &RebaseTodoEntry) {
+    if entry.action.melds_into_previous() {
+        return;
+    }
+    let subject = match &entry.message {
+        Some(message) if entry.action == RebaseTodoAction::Reword => {
+            message.lines().next().unwrap_or_default().to_string()
+        }
+        _ => entry.subject.to_string(),
+    };
+    history.push((entry.sha.to_string(), subject));
+}
+
 impl FakeGitRepository {
     fn with_state_async<F, T>(&self, write: bool, f: F) -> BoxFuture<'static, Result<T>>
     where
@@ -107,6 +275,21 @@ impl GitRepository for FakeGitRepository {
         self.executor.spawn(async move { fut.await.ok() }).boxed()
     }
 
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>> {
+        let fut = self.with_state_async(false, move |state| {
+            state
+                .index_stage_contents
+                .get(&(path, stage))
+                .context("stage not present in index")
+                .cloned()
+        });
+        self.executor.spawn(async move { fut.await.ok() }).boxed()
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let fut = self.with_state_async(false, move |state| {
             state
@@ -214,11 +397,17 @@ impl GitRepository for FakeGitRepository {
 
     fn reset(
         &self,
-        _commit: String,
+        commit: String,
         _mode: ResetMode,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>> {
-        unimplemented!()
+        self.with_state_async(true, move |state| {
+            let position = state.history_position(&commit)?;
+            state.commit_history.truncate(position + 1);
+            let sha = state.commit_history[position].0.clone();
+            state.refs.insert("HEAD".into(), sha);
+            Ok(())
+        })
     }
 
     fn checkout_files(
@@ -230,6 +419,49 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn cherry_pick(
+        &self,
+        commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let subject = state.commit_subject(&commit);
+            state.push_commit(format!("{commit}-cherry-pick"), subject);
+            Ok(())
+        })
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let subject = format!("Revert \"{}\"", state.commit_subject(&commit));
+            state.push_commit(format!("{commit}-revert"), subject);
+            Ok(())
+        })
+    }
+
+    fn checkout_detached(
+        &self,
+        commit: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let sha = match state.history_position(&commit) {
+                Ok(position) => {
+                    state.commit_history.truncate(position + 1);
+                    state.commit_history[position].0.clone()
+                }
+                Err(_) => commit,
+            };
+            state.current_branch_name = None;
+            state.refs.insert("HEAD".into(), sha);
+            Ok(())
+        })
+    }
+
     fn path(&self) -> PathBuf {
         self.repository_dir_path.clone()
     }
@@ -369,6 +601,7 @@ impl GitRepository for FakeGitRepository {
             entries.sort_by(|a, b| a.0.cmp(&b.0));
             anyhow::Ok(GitStatus {
                 entries: entries.into(),
+                rebase: state.rebase.as_ref().map(|rebase| rebase.status.clone()),
             })
         });
         Task::ready(match result {
@@ -697,6 +930,51 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name == name) {
+                bail!("fatal: tag '{name}' already exists");
+            }
+            let rev = commit.unwrap_or_else(|| "HEAD".into());
+            let sha = state.refs.get(&rev).cloned().unwrap_or(rev);
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    message: message.map(Into::into),
+                    timestamp: None,
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let len = state.tags.len();
+            state.tags.retain(|tag| tag.name != name);
+            if state.tags.len() == len {
+                bail!("error: tag '{name}' not found.");
+            }
+            Ok(())
+        })
+    }
+
     fn blame(
         &self,
         path: RepoPath,
@@ -811,6 +1089,190 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        self.with_state_async(false, move |state| {
+            let base = state.history_position(&upstream)?;
+            Ok(state.commit_history[base + 1..]
+                .iter()
+                .map(|(sha, subject)| RebaseTodoEntry {
+                    original_message: Some(
+                        state
+                            .commit_messages
+                            .get(sha)
+                            .unwrap_or(subject)
+                            .clone()
+                            .into(),
+                    ),
+                    ..RebaseTodoEntry::pick(sha.clone(), subject.clone())
+                })
+                .collect())
+        })
+    }
+
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            validate_rebase_todo(&todo)?;
+            anyhow::ensure!(state.rebase.is_none(), "A rebase is already in progress");
+            let base = state.history_position(&upstream)?;
+            let original_history = state.commit_history.clone();
+            state.commit_history.truncate(base + 1);
+            state.rebase = Some(FakeRebase {
+                status: RebaseStatus {
+                    head_name: state
+                        .current_branch_name
+                        .as_ref()
+                        .map(|branch| format!("refs/heads/{branch}").into()),
+                    onto: Some(original_history[base].0.clone().into()),
+                    step: 0,
+                    total: todo.len(),
+                    stopped_sha: None,
+                    todo,
+                },
+                conflicted_entry: None,
+                original_history,
+            });
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_continue(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.as_mut().context("No rebase in progress")?;
+            if let Some(entry) = rebase.conflicted_entry.take() {
+                if !state.unmerged_paths.is_empty() {
+                    rebase.conflicted_entry = Some(entry);
+                    bail!("Failed to rebase:\nerror: you must resolve all merge conflicts");
+                }
+                apply_rebase_entry(&mut state.commit_history, &entry);
+            }
+            rebase.status.stopped_sha = None;
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_skip(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.as_mut().context("No rebase in progress")?;
+            rebase.conflicted_entry = None;
+            rebase.status.stopped_sha = None;
+            state.unmerged_paths.clear();
+            state.advance_rebase();
+            Ok(())
+        })
+    }
+
+    fn rebase_abort(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.take().context("No rebase in progress")?;
+            state.commit_history = rebase.original_history;
+            state.unmerged_paths.clear();
+            Ok(())
+        })
+    }
+
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.bisect.is_some() {
+                bail!("Failed to bisect:\nA bisect is already in progress");
+            }
+            let original_head = state.refs.get("HEAD").cloned().unwrap_or_default();
+            let bad = state.bisect_commit(&bad);
+            let good = good
+                .iter()
+                .map(|good| state.bisect_commit(good))
+                .collect::<Vec<_>>();
+            let mut log = vec![BisectLogEntry {
+                verdict: BisectVerdict::Bad,
+                commit: bad.clone(),
+            }];
+            log.extend(good.iter().map(|commit| BisectLogEntry {
+                verdict: BisectVerdict::Good,
+                commit: commit.clone(),
+            }));
+            state.bisect = Some(FakeBisect {
+                bad: Some(bad.sha.to_string()),
+                good: good.iter().map(|commit| commit.sha.to_string()).collect(),
+                log,
+                original_head,
+                ..Default::default()
+            });
+            let result = state.advance_bisect();
+            if result.is_err() {
+                state.bisect = None;
+            }
+            result
+        })
+    }
+
+    fn bisect_mark(
+        &self,
+        verdict: BisectVerdict,
+        commit: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let commit = state.bisect_commit(commit.as_deref().unwrap_or("HEAD"));
+            let bisect = state
+                .bisect
+                .as_mut()
+                .context("Failed to bisect:\nNot bisecting")?;
+            let sha = commit.sha.to_string();
+            match verdict {
+                BisectVerdict::Good => bisect.good.push(sha),
+                BisectVerdict::Bad => bisect.bad = Some(sha),
+                BisectVerdict::Skip => bisect.skipped.push(sha),
+            }
+            bisect.log.push(BisectLogEntry { verdict, commit });
+            state.advance_bisect()
+        })
+    }
+
+    fn bisect_reset(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if let Some(bisect) = state.bisect.take() {
+                state.refs.insert("HEAD".into(), bisect.original_head);
+            }
+            Ok(())
+        })
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Result<Option<BisectState>>> {
+        self.with_state_async(false, move |state| {
+            let Some(bisect) = state.bisect.as_ref() else {
+                return Ok(None);
+            };
+            let first_bad = bisect
+                .first_bad
+                .as_deref()
+                .map(|sha| state.bisect_commit(sha));
+            let remaining = state
+                .bisect_candidates()?
+                .into_iter()
+                .rev()
+                .map(|(sha, subject)| BisectCommit::new(sha, subject))
+                .collect();
+            let current = first_bad.is_none().then(|| state.bisect_commit("HEAD"));
+            Ok(Some(BisectState {
+                log: bisect.log.clone(),
+                remaining,
+                current,
+                first_bad,
+            }))
+        })
+    }
+
     fn commit(
         &self,
         _message: gpui::SharedString,
@@ -57,7 +57,7 @@ use collections::{BTreeMap, btree_map};
 use fake_git_repo::FakeGitRepositoryState;
 #[cfg(feature = "test-support")]
 use git::{
-    repository::{InitialGraphCommitData, RepoPath, Worktree, repo_path},
+    repository::{IndexStage, InitialGraphCommitData, RepoPath, Worktree, repo_path},
     status::{FileStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
 #[cfg(feature = "test-support")]
@@ -2102,6 +2102,18 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_index_stages_for_repo(&self, dot_git: &Path, stages: &[(&str, IndexStage, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.index_stage_contents.clear();
+            state.index_stage_contents.extend(
+                stages
+                    .iter()
+                    .map(|(path, stage, content)| ((repo_path(path), *stage), content.clone())),
+            );
+        })
+        .unwrap();
+    }
+
     pub fn set_head_for_repo(
         &self,
         dot_git: &Path,
@@ -2161,6 +2173,20 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Sets the commits on the current branch, oldest first, as pairs of sha and subject.
+    pub fn set_commit_history_for_repo(&self, dot_git: &Path, commits: &[(&str, &str)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commit_history = commits
+                .iter()
+                .map(|(sha, subject)| (sha.to_string(), subject.to_string()))
+                .collect();
+            if let Some((sha, _)) = commits.last() {
+                state.refs.insert("HEAD".into(), sha.to_string());
+            }
+        })
+        .unwrap();
+    }
+
     pub fn set_graph_commits(&self, dot_git: &Path, commits: Vec<Arc<InitialGraphCommitData>>) {
         self.with_git_state(dot_git, true, |state| {
             state.graph_commits = commits;
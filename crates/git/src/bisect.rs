@@ -0,0 +1,204 @@
+use anyhow::{Result, bail};
+use gpui::SharedString;
+use std::{fmt, str::FromStr};
+
+/// Whether a commit tested during a bisect has the regression being hunted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BisectVerdict {
+    /// The commit doesn't have the regression.
+    Good,
+    /// The commit has the regression.
+    Bad,
+    /// The commit can't be tested, so another one close to it is tested instead.
+    Skip,
+}
+
+impl BisectVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Bad => "bad",
+            Self::Skip => "skip",
+        }
+    }
+
+    /// Interprets the exit code of a test command: 0 is good, 125 is skip, and 1 to 124 are bad.
+    ///
+    /// Other codes abort the bisect and return `None`. Shells exit with 126 and 127 when the
+    /// command isn't executable or doesn't exist, and with higher codes when it was killed by a
+    /// signal. Counting those as bad would blame every commit for a typo in the command.
+    pub fn from_exit_code(code: i32) -> Option<Self> {
+        match code {
+            0 => Some(Self::Good),
+            125 => Some(Self::Skip),
+            1..=124 => Some(Self::Bad),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BisectVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BisectVerdict {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "good" | "old" => Self::Good,
+            "bad" | "new" => Self::Bad,
+            "skip" => Self::Skip,
+            _ => bail!("unsupported bisect verdict '{s}'"),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BisectCommit {
+    pub sha: SharedString,
+    pub subject: SharedString,
+}
+
+impl BisectCommit {
+    pub fn new(sha: impl Into<SharedString>, subject: impl Into<SharedString>) -> Self {
+        Self {
+            sha: sha.into(),
+            subject: subject.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BisectLogEntry {
+    pub verdict: BisectVerdict,
+    pub commit: BisectCommit,
+}
+
+/// A bisect that is in progress.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BisectState {
+    /// The verdicts given so far, in the order they were given.
+    pub log: Vec<BisectLogEntry>,
+    /// The commits that may have introduced the regression, newest first. This is empty until
+    /// both a good and a bad commit are known.
+    pub remaining: Vec<BisectCommit>,
+    /// The commit that is checked out for testing.
+    pub current: Option<BisectCommit>,
+    /// The commit that introduced the regression, once the bisect has narrowed it down.
+    pub first_bad: Option<BisectCommit>,
+}
+
+impl BisectState {
+    pub fn is_finished(&self) -> bool {
+        self.first_bad.is_some()
+    }
+
+    /// Whether the bisect is still waiting for a good or a bad commit before it can start testing.
+    pub fn needs_bounds(&self) -> bool {
+        let has_verdict = |verdict| self.log.iter().any(|entry| entry.verdict == verdict);
+        !has_verdict(BisectVerdict::Good) || !has_verdict(BisectVerdict::Bad)
+    }
+
+    /// Roughly how many more commits have to be tested to find the first bad one.
+    pub fn steps_left(&self) -> usize {
+        match self.remaining.len() {
+            0 | 1 => 0,
+            remaining => remaining.ilog2() as usize,
+        }
+    }
+}
+
+/// Parses the output of `git bisect log`. Returns the verdicts, along with the first bad commit
+/// if the bisect already found it.
+pub fn parse_bisect_log(log: &str) -> (Vec<BisectLogEntry>, Option<BisectCommit>) {
+    let mut entries = Vec::new();
+    let mut first_bad = None;
+    for line in log.lines() {
+        let Some(comment) = line.strip_prefix("# ") else {
+            continue;
+        };
+        let Some((label, commit)) = comment.split_once(": ") else {
+            continue;
+        };
+        let Some(commit) = parse_bisect_commit(commit) else {
+            continue;
+        };
+        if label == "first bad commit" {
+            first_bad = Some(commit);
+        } else if let Ok(verdict) = label.parse() {
+            entries.push(BisectLogEntry { verdict, commit });
+        }
+    }
+    (entries, first_bad)
+}
+
+/// Parses a commit in the `[<sha>] <subject>` form that git uses in the bisect log.
+fn parse_bisect_commit(text: &str) -> Option<BisectCommit> {
+    let (sha, subject) = text.strip_prefix('[')?.split_once(']')?;
+    Some(BisectCommit::new(
+        sha.to_string(),
+        subject.trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_bisect_log() {
+        let (entries, first_bad) = parse_bisect_log(
+            "git bisect start\n\
+             # status: waiting for both good and bad commits\n\
+             # bad: [c3c3c3] Break the parser\n\
+             git bisect bad c3c3c3\n\
+             # status: waiting for good commit(s), bad commit known\n\
+             # good: [a1a1a1] Add parser\n\
+             git bisect good a1a1a1\n\
+             # skip: [b2b2b2] Rename parser: part 1\n\
+             git bisect skip b2b2b2\n\
+             # first bad commit: [c3c3c3] Break the parser\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                BisectLogEntry {
+                    verdict: BisectVerdict::Bad,
+                    commit: BisectCommit::new("c3c3c3", "Break the parser"),
+                },
+                BisectLogEntry {
+                    verdict: BisectVerdict::Good,
+                    commit: BisectCommit::new("a1a1a1", "Add parser"),
+                },
+                BisectLogEntry {
+                    verdict: BisectVerdict::Skip,
+                    commit: BisectCommit::new("b2b2b2", "Rename parser: part 1"),
+                },
+            ]
+        );
+        assert_eq!(
+            first_bad,
+            Some(BisectCommit::new("c3c3c3", "Break the parser"))
+        );
+    }
+
+    #[test]
+    fn test_verdict_from_exit_code() {
+        assert_eq!(BisectVerdict::from_exit_code(0), Some(BisectVerdict::Good));
+        assert_eq!(BisectVerdict::from_exit_code(1), Some(BisectVerdict::Bad));
+        assert_eq!(
+            BisectVerdict::from_exit_code(125),
+            Some(BisectVerdict::Skip)
+        );
+        assert_eq!(BisectVerdict::from_exit_code(124), Some(BisectVerdict::Bad));
+        // A command that isn't executable or isn't found stops the bisect.
+        assert_eq!(BisectVerdict::from_exit_code(126), None);
+        assert_eq!(BisectVerdict::from_exit_code(127), None);
+        assert_eq!(BisectVerdict::from_exit_code(128), None);
+        assert_eq!(BisectVerdict::from_exit_code(-1), None);
+    }
+}
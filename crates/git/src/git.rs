@@ -1,6 +1,8 @@
+pub mod bisect;
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod rebase;
 mod remote;
 pub mod repository;
 pub mod stash;
@@ -53,6 +55,8 @@ actions!(
         StageFile,
         /// Unstages the current file.
         UnstageFile,
+        /// Opens the conflicted file at the cursor in a three-way merge editor.
+        OpenMergeEditor,
         // repo-wide
         /// Stages all changes in the repository.
         StageAll,
@@ -80,6 +84,22 @@ actions!(
         Pull,
         /// Pulls changes from the remote repository with rebase.
         PullRebase,
+        /// Continues the rebase in progress after resolving conflicts or editing a commit.
+        RebaseContinue,
+        /// Skips the commit the rebase in progress stopped at.
+        RebaseSkip,
+        /// Aborts the rebase in progress and restores the branch.
+        RebaseAbort,
+        /// Opens the bisect assistant to find the commit that introduced a regression.
+        Bisect,
+        /// Marks the commit checked out by the bisect as good.
+        BisectGood,
+        /// Marks the commit checked out by the bisect as bad.
+        BisectBad,
+        /// Skips the commit checked out by the bisect.
+        BisectSkip,
+        /// Ends the bisect in progress and checks out the original branch.
+        BisectReset,
         /// Fetches changes from the remote repository.
         Fetch,
         /// Fetches changes from a specific remote.
@@ -119,6 +139,18 @@ pub struct RenameBranch {
     pub branch: Option<String>,
 }
 
+/// Opens an editor for the todo list of an interactive rebase of the current branch.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = git)]
+#[serde(deny_unknown_fields)]
+pub struct InteractiveRebase {
+    /// The branch or commit to rebase onto.
+    ///
+    /// Default: the upstream of the current branch.
+    #[serde(default)]
+    pub onto: Option<String>,
+}
+
 /// Restores a file to its last committed state, discarding local changes.
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
 #[action(namespace = git, deprecated_aliases = ["editor::RevertFile"])]
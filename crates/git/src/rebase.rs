@@ -0,0 +1,385 @@
+use anyhow::{Context as _, Result, anyhow, bail};
+use gpui::SharedString;
+use std::{fmt, fs, io, path::Path, path::PathBuf, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseTodoAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseTodoAction {
+    pub const ALL: [Self; 6] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Edit,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Edit => "edit",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is combined with the one before it.
+    pub fn melds_into_previous(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+impl fmt::Display for RebaseTodoAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RebaseTodoAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "pick" | "p" => Self::Pick,
+            "reword" | "r" => Self::Reword,
+            "edit" | "e" => Self::Edit,
+            "squash" | "s" => Self::Squash,
+            "fixup" | "f" => Self::Fixup,
+            "drop" | "d" => Self::Drop,
+            _ => bail!("unsupported rebase command '{s}'"),
+        })
+    }
+}
+
+/// How to move on from where a rebase stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseStep {
+    /// Commits the resolved conflicts or the edited commit and applies the rest of the todo list.
+    Continue,
+    /// Leaves out the commit the rebase stopped at.
+    Skip,
+    /// Restores the branch to where it was before the rebase started.
+    Abort,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The commit's full message, including its body and trailers. This is `None` for entries
+    /// read back from a todo file, which only has subjects.
+    pub original_message: Option<SharedString>,
+    /// The new full message of a reworded commit. The commit keeps its message when this is
+    /// `None`.
+    pub message: Option<SharedString>,
+}
+
+impl RebaseTodoEntry {
+    pub fn pick(sha: impl Into<SharedString>, subject: impl Into<SharedString>) -> Self {
+        Self {
+            action: RebaseTodoAction::Pick,
+            sha: sha.into(),
+            subject: subject.into(),
+            original_message: None,
+            message: None,
+        }
+    }
+
+    /// The message a reword starts from: the new message if there is one, otherwise the
+    /// commit's full message.
+    pub fn current_message(&self) -> &SharedString {
+        self.message
+            .as_ref()
+            .or(self.original_message.as_ref())
+            .unwrap_or(&self.subject)
+    }
+}
+
+/// Parses a todo list in the format of `git-rebase-todo`. Comments and commands that don't apply
+/// to a single commit, like `exec` or `break`, are skipped.
+pub fn parse_rebase_todo(text: &str) -> Vec<RebaseTodoEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let mut parts = line.splitn(3, ' ');
+            let action = parts.next()?.parse::<RebaseTodoAction>().ok()?;
+            // `fixup -C <sha>` and `fixup -c <sha>` replace the message; we treat them as fixups.
+            let mut sha = parts.next()?;
+            let mut subject = parts.next().unwrap_or_default();
+            if action == RebaseTodoAction::Fixup && matches!(sha, "-C" | "-c") {
+                (sha, subject) = subject.split_once(' ').unwrap_or((subject, ""));
+            }
+            Some(RebaseTodoEntry {
+                action,
+                sha: sha.to_string().into(),
+                subject: subject.trim_start_matches("# ").to_string().into(),
+                original_message: None,
+                message: None,
+            })
+        })
+        .collect()
+}
+
+/// Checks that a todo list can be handed to git.
+pub fn validate_rebase_todo(entries: &[RebaseTodoEntry]) -> Result<()> {
+    let first_kept = entries
+        .iter()
+        .find(|entry| entry.action != RebaseTodoAction::Drop);
+    if let Some(entry) = first_kept
+        && entry.action.melds_into_previous()
+    {
+        bail!(
+            "cannot {} {} without a previous commit",
+            entry.action,
+            entry.sha
+        );
+    }
+    Ok(())
+}
+
+/// Formats a todo list for `git rebase -i`. A reworded commit with a new message is picked and
+/// then amended with the message file returned by `message_file`, so that git doesn't need to
+/// open an editor.
+pub fn format_rebase_todo(
+    entries: &[RebaseTodoEntry],
+    mut message_file: impl FnMut(usize, &SharedString) -> Result<PathBuf>,
+) -> Result<String> {
+    let mut todo = String::new();
+    for (ix, entry) in entries.iter().enumerate() {
+        match (&entry.action, &entry.message) {
+            (RebaseTodoAction::Reword, Some(message)) => {
+                todo.push_str(&format!("pick {} {}\n", entry.sha, entry.subject));
+                let path = message_file(ix, message)?;
+                let path = path
+                    .to_str()
+                    .with_context(|| format!("invalid message file path {path:?}"))?;
+                todo.push_str(&format!(
+                    "exec git commit --amend --only --allow-empty --quiet -F {}\n",
+                    shell_quote(path)
+                ));
+            }
+            (action, _) => {
+                todo.push_str(&format!("{action} {} {}\n", entry.sha, entry.subject));
+            }
+        }
+    }
+    Ok(todo)
+}
+
+pub(crate) fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// An interactive rebase that is in progress.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RebaseStatus {
+    /// The ref of the branch being rebased, or `None` when rebasing a detached HEAD.
+    pub head_name: Option<SharedString>,
+    pub onto: Option<SharedString>,
+    /// The number of todo entries that were applied, including the current one.
+    pub step: usize,
+    pub total: usize,
+    /// The commit the rebase stopped at, because it conflicted or was marked for editing.
+    pub stopped_sha: Option<SharedString>,
+    /// The todo entries that are left.
+    pub todo: Vec<RebaseTodoEntry>,
+}
+
+impl RebaseStatus {
+    /// Reads the state of the rebase in progress in the given git directory, if any.
+    pub fn load(git_dir: &Path) -> Result<Option<Self>> {
+        let merge_dir = git_dir.join("rebase-merge");
+        if merge_dir.is_dir() {
+            return Ok(Some(Self {
+                head_name: read_head_name(&merge_dir)?,
+                onto: read_state_file(&merge_dir, "onto")?.map(Into::into),
+                step: read_number(&merge_dir, "msgnum")?,
+                total: read_number(&merge_dir, "end")?,
+                stopped_sha: read_state_file(&merge_dir, "stopped-sha")?.map(Into::into),
+                todo: read_state_file(&merge_dir, "git-rebase-todo")?
+                    .map(|todo| parse_rebase_todo(&todo))
+                    .unwrap_or_default(),
+            }));
+        }
+
+        // `git am` uses the same directory, but only a rebase leaves a `rebasing` file in it.
+        let apply_dir = git_dir.join("rebase-apply");
+        if apply_dir.join("rebasing").exists() {
+            return Ok(Some(Self {
+                head_name: read_head_name(&apply_dir)?,
+                onto: read_state_file(&apply_dir, "onto")?.map(Into::into),
+                step: read_number(&apply_dir, "next")?,
+                total: read_number(&apply_dir, "last")?,
+                stopped_sha: None,
+                todo: Vec::new(),
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// The name of the branch being rebased, without the `refs/heads/` prefix.
+    pub fn branch_name(&self) -> Option<&str> {
+        let head_name = self.head_name.as_ref()?;
+        Some(head_name.strip_prefix("refs/heads/").unwrap_or(head_name))
+    }
+}
+
+fn read_state_file(dir: &Path, name: &str) -> Result<Option<String>> {
+    let path = dir.join(name);
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(Some(contents.trim_end().to_string())),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(anyhow!(error).context(format!("reading {path:?}"))),
+    }
+}
+
+fn read_head_name(dir: &Path) -> Result<Option<SharedString>> {
+    Ok(read_state_file(dir, "head-name")?
+        .filter(|head_name| head_name != "detached HEAD")
+        .map(Into::into))
+}
+
+fn read_number(dir: &Path, name: &str) -> Result<usize> {
+    let Some(contents) = read_state_file(dir, name)? else {
+        return Ok(0);
+    };
+    contents
+        .parse()
+        .with_context(|| format!("parsing {name} of rebase in progress"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_rebase_todo() {
+        let todo = parse_rebase_todo(
+            "pick 1a2b3c4 Add parser\n\
+             s 5d6e7f8 Fix parser\n\
+             exec cargo test\n\
+             fixup -C 9a8b7c6 # Rename parser\n\
+             \n\
+             # Rebase 0123456..9a8b7c6 onto 0123456 (3 commands)\n",
+        );
+        assert_eq!(
+            todo,
+            vec![
+                RebaseTodoEntry::pick("1a2b3c4", "Add parser"),
+                RebaseTodoEntry {
+                    action: RebaseTodoAction::Squash,
+                    ..RebaseTodoEntry::pick("5d6e7f8", "Fix parser")
+                },
+                RebaseTodoEntry {
+                    action: RebaseTodoAction::Fixup,
+                    ..RebaseTodoEntry::pick("9a8b7c6", "Rename parser")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_rebase_todo() {
+        let entries = vec![
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Reword,
+                message: Some("Add a parser".into()),
+                ..RebaseTodoEntry::pick("1a2b3c4", "Add parser")
+            },
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Fixup,
+                ..RebaseTodoEntry::pick("5d6e7f8", "Fix parser")
+            },
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Reword,
+                ..RebaseTodoEntry::pick("9a8b7c6", "Rename parser")
+            },
+        ];
+        let todo = format_rebase_todo(&entries, |ix, message| {
+            assert_eq!(message.as_ref(), "Add a parser");
+            Ok(PathBuf::from(format!("/repo/.git/it's message {ix}")))
+        })
+        .unwrap();
+        assert_eq!(
+            todo,
+            "pick 1a2b3c4 Add parser\n\
+             exec git commit --amend --only --allow-empty --quiet -F '/repo/.git/it'\\''s message 0'\n\
+             fixup 5d6e7f8 Fix parser\n\
+             reword 9a8b7c6 Rename parser\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_rebase_todo() {
+        let squash_first = vec![
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Drop,
+                ..RebaseTodoEntry::pick("1a2b3c4", "Add parser")
+            },
+            RebaseTodoEntry {
+                action: RebaseTodoAction::Squash,
+                ..RebaseTodoEntry::pick("5d6e7f8", "Fix parser")
+            },
+        ];
+        assert_eq!(
+            validate_rebase_todo(&squash_first).unwrap_err().to_string(),
+            "cannot squash 5d6e7f8 without a previous commit"
+        );
+
+        let mut squash_after_pick = squash_first;
+        squash_after_pick[0].action = RebaseTodoAction::Pick;
+        assert!(validate_rebase_todo(&squash_after_pick).is_ok());
+    }
+
+    #[test]
+    fn test_load_rebase_status() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(RebaseStatus::load(dir.path()).unwrap(), None);
+
+        let merge_dir = dir.path().join("rebase-merge");
+        fs::create_dir(&merge_dir).unwrap();
+        fs::write(merge_dir.join("head-name"), "refs/heads/feature\n").unwrap();
+        fs::write(merge_dir.join("onto"), "0123456789\n").unwrap();
+        fs::write(merge_dir.join("msgnum"), "2\n").unwrap();
+        fs::write(merge_dir.join("end"), "3\n").unwrap();
+        fs::write(merge_dir.join("stopped-sha"), "5d6e7f8\n").unwrap();
+        fs::write(
+            merge_dir.join("git-rebase-todo"),
+            "drop 9a8b7c6 Rename parser\n",
+        )
+        .unwrap();
+
+        let status = RebaseStatus::load(dir.path()).unwrap().unwrap();
+        assert_eq!(status.branch_name(), Some("feature"));
+        assert_eq!(
+            status,
+            RebaseStatus {
+                head_name: Some("refs/heads/feature".into()),
+                onto: Some("0123456789".into()),
+                step: 2,
+                total: 3,
+                stopped_sha: Some("5d6e7f8".into()),
+                todo: vec![RebaseTodoEntry {
+                    action: RebaseTodoAction::Drop,
+                    ..RebaseTodoEntry::pick("9a8b7c6", "Rename parser")
+                }],
+            }
+        );
+    }
+}
@@ -1,4 +1,8 @@
+use crate::bisect::{BisectCommit, BisectState, BisectVerdict, parse_bisect_log};
 use crate::commit::parse_git_diff_name_status;
+use crate::rebase::{
+    RebaseStatus, RebaseTodoEntry, format_rebase_todo, shell_quote, validate_rebase_todo,
+};
 use crate::stash::GitStash;
 use crate::status::{DiffTreeType, GitStatus, StatusCode, TreeDiff};
 use crate::{Oid, RunHook, SHORT_SHA_LENGTH};
@@ -232,6 +236,23 @@ impl Branch {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to. Annotated tags are peeled to their commit.
+    pub sha: SharedString,
+    /// The subject of the tag message. Only annotated tags have one.
+    pub message: Option<SharedString>,
+    /// When an annotated tag was created, or when the commit of a lightweight tag was made.
+    pub timestamp: Option<i64>,
+}
+
+impl Tag {
+    pub fn is_annotated(&self) -> bool {
+        self.message.is_some()
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Worktree {
     pub path: PathBuf,
@@ -459,6 +480,17 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+/// One of the stages that a conflicted path occupies in the index during a merge.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum IndexStage {
+    /// The version of the file in the common ancestor of both sides.
+    Base = 1,
+    /// The version of the file on the branch that is checked out.
+    Ours = 2,
+    /// The version of the file on the branch that is being merged in.
+    Theirs = 3,
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -466,6 +498,8 @@ pub enum ResetMode {
     /// Reset the branch pointer and index, leave worktree unchanged (this makes it look as though things that were
     /// committed are now unstaged).
     Mixed,
+    /// Reset the branch pointer, index and worktree, discarding every change made since the target commit.
+    Hard,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -663,6 +697,14 @@ pub trait GitRepository: Send + Sync {
     /// Also returns `None` for symlinks.
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
 
+    /// Returns the contents of a conflicted path at the given merge stage of the repository's index, or None if
+    /// that stage has no entry for the path (for example, the base stage of a file that was added on both sides).
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>>;
+
     /// Returns the contents of an entry in the repository's HEAD, or None if HEAD does not exist or has no entry for the given path.
     ///
     /// Also returns `None` for symlinks.
@@ -711,6 +753,25 @@ pub trait GitRepository: Send + Sync {
 
     fn delete_branch(&self, is_remote: bool, name: String) -> BoxFuture<'_, Result<()>>;
 
+    /// Lists the tags of the repository, most recently created first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Creates a tag at `commit`, or at `HEAD` when there's none. The tag is annotated when it
+    /// has a message, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        commit: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>>;
 
     fn create_worktree(
@@ -738,6 +799,27 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Applies the changes introduced by `commit` on top of `HEAD` as a new commit.
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Creates a new commit on top of `HEAD` that undoes the changes introduced by `commit`.
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Checks out `commit` without a branch, leaving `HEAD` detached.
+    fn checkout_detached(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
@@ -817,6 +899,50 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the commits between `upstream` and HEAD as a todo list of picks, oldest first.
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>>;
+
+    /// Starts an interactive rebase of HEAD onto `upstream` that runs the given todo list.
+    ///
+    /// Stopping at a conflict or at an `edit` entry isn't an error. The rebase is then reported
+    /// in [`GitStatus::rebase`] until it's continued, skipped past or aborted.
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_skip(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Starts a bisect between a commit that has a regression and commits that don't, and checks
+    /// out the first commit to test.
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Gives a verdict for a commit of the bisect in progress, or for HEAD when `commit` is `None`,
+    /// and checks out the next commit to test.
+    fn bisect_mark(
+        &self,
+        verdict: BisectVerdict,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Ends the bisect in progress and checks out the branch it was started from.
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns the state of the bisect in progress, or `None` if the repository isn't bisecting.
+    fn bisect_state(&self) -> BoxFuture<'_, Result<Option<BisectState>>>;
+
     fn push(
         &self,
         branch_name: String,
@@ -1254,6 +1380,7 @@ impl GitRepository for RealGitRepository {
             let mode_flag = match mode {
                 ResetMode::Mixed => "--mixed",
                 ResetMode::Soft => "--soft",
+                ResetMode::Hard => "--hard",
             };
 
             let git = git_binary?;
@@ -1301,34 +1428,82 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn cherry_pick(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        async move {
+            let git = git_binary?;
+            let output = git
+                .build_command(&["cherry-pick", &commit])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to cherry-pick:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn revert(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        async move {
+            let git = git_binary?;
+            let output = git
+                .build_command(&["revert", "--no-edit", &commit])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to revert:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn checkout_detached(
+        &self,
+        commit: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        async move {
+            let git = git_binary?;
+            let output = git
+                .build_command(&["switch", "--detach", &commit])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to checkout {commit}:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
     fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
-        // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
-        const GIT_MODE_SYMLINK: u32 = 0o120000;
+        const STAGE_NORMAL: i32 = 0;
 
         let repo = self.repository.clone();
         self.executor
             .spawn(async move {
-                fn logic(repo: &git2::Repository, path: &RepoPath) -> Result<Option<String>> {
-                    let mut index = repo.index()?;
-                    index.read(false)?;
-
-                    const STAGE_NORMAL: i32 = 0;
-                    // git2 unwraps internally on empty paths or `.`
-                    if path.is_empty() {
-                        bail!("empty path has no index text");
-                    }
-                    let Some(entry) = index.get_path(path.as_std_path(), STAGE_NORMAL) else {
-                        return Ok(None);
-                    };
-                    if entry.mode == GIT_MODE_SYMLINK {
-                        return Ok(None);
-                    }
-
-                    let content = repo.find_blob(entry.id)?.content().to_owned();
-                    Ok(String::from_utf8(content).ok())
-                }
-
-                logic(&repo.lock(), &path)
+                load_index_entry_text(&repo.lock(), &path, STAGE_NORMAL)
                     .context("loading index text")
                     .log_err()
                     .flatten()
@@ -1336,6 +1511,22 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                load_index_entry_text(&repo.lock(), &path, stage as i32)
+                    .with_context(|| format!("loading index text for stage {stage:?}"))
+                    .log_err()
+                    .flatten()
+            })
+            .boxed()
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let repo = self.repository.clone();
         self.executor
@@ -1508,12 +1699,15 @@ impl GitRepository for RealGitRepository {
             Err(e) => return Task::ready(Err(e)),
         };
         let args = git_status_args(path_prefixes);
+        let git_dir = self.path();
         log::debug!("Checking for git status in {path_prefixes:?}");
         self.executor.spawn(async move {
             let output = git.build_command(&args).output().await?;
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                stdout.parse()
+                let mut status: GitStatus = stdout.parse()?;
+                status.rebase = RebaseStatus::load(&git_dir).log_err().flatten();
+                Ok(status)
             } else {
                 let stderr = String::from_utf8_lossy(&output.stderr);
                 anyhow::bail!("git status failed: {stderr}");
@@ -1638,6 +1832,89 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(creatordate:unix)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let args = [
+                    "for-each-ref",
+                    "refs/tags",
+                    "--sort=-creatordate",
+                    "--format",
+                    &fields,
+                ];
+                let git = git_binary?;
+                let output = git.build_command(&args).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list tags:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(parse_tag_input(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        commit: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        async move {
+            let mut args = vec!["tag".to_string()];
+            if let Some(message) = message {
+                args.extend(["--annotate".to_string(), "--message".to_string(), message]);
+            }
+            args.push(name);
+            args.extend(commit);
+
+            let git = git_binary?;
+            let output = git.build_command(&args).envs(env.iter()).output().await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to create tag:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        async move {
+            let git = git_binary?;
+            let output = git
+                .build_command(&["tag", "--delete", &name])
+                .envs(env.iter())
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to delete tag:\n{}",
+                String::from_utf8_lossy(&output.stderr),
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>> {
         let git_binary = self.git_binary();
         self.executor
@@ -2135,6 +2412,215 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn rebase_todo(&self, upstream: String) -> BoxFuture<'_, Result<Vec<RebaseTodoEntry>>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let git = git_binary?;
+                // Commits are NUL-separated too, since full messages span several lines.
+                let output = git
+                    .run(&[
+                        "log",
+                        "-z",
+                        "--reverse",
+                        "--no-merges",
+                        "--format=%H%x00%s%x00%B",
+                        &format!("{upstream}..HEAD"),
+                    ])
+                    .await?;
+                let mut fields = output.split('\0');
+                let mut entries = Vec::new();
+                while let (Some(sha), Some(subject), Some(message)) =
+                    (fields.next(), fields.next(), fields.next())
+                {
+                    entries.push(RebaseTodoEntry {
+                        original_message: Some(message.trim_end().to_string().into()),
+                        ..RebaseTodoEntry::pick(sha.trim().to_string(), subject.to_string())
+                    });
+                }
+                Ok(entries)
+            })
+            .boxed()
+    }
+
+    fn rebase_start(
+        &self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let git = git_binary?;
+                validate_rebase_todo(&todo)?;
+                anyhow::ensure!(
+                    RebaseStatus::load(&git_dir)?.is_none(),
+                    "A rebase is already in progress"
+                );
+
+                // Git asks the sequence editor to edit its todo list, which we replace with ours.
+                let scratch_dir = git_dir.join(REBASE_SCRATCH_DIR);
+                std::fs::create_dir_all(&scratch_dir)?;
+                let todo = format_rebase_todo(&todo, |ix, message| {
+                    let path = scratch_dir.join(format!("message-{ix}"));
+                    std::fs::write(&path, message.as_bytes())?;
+                    Ok(path)
+                })?;
+                let todo_path = scratch_dir.join("git-rebase-todo");
+                std::fs::write(&todo_path, todo)?;
+                let todo_path = todo_path.to_str().context("invalid git directory path")?;
+                let sequence_editor = format!("sequence.editor=cp {}", shell_quote(todo_path));
+
+                run_rebase_command(
+                    &git,
+                    &git_dir,
+                    &[
+                        "-c",
+                        &sequence_editor,
+                        "rebase",
+                        "--interactive",
+                        "--no-autosquash",
+                        &upstream,
+                    ],
+                    &env,
+                )
+                .await
+            })
+            .boxed()
+    }
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                run_rebase_command(&git_binary?, &git_dir, &["rebase", "--continue"], &env).await
+            })
+            .boxed()
+    }
+
+    fn rebase_skip(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                run_rebase_command(&git_binary?, &git_dir, &["rebase", "--skip"], &env).await
+            })
+            .boxed()
+    }
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                run_rebase_command(&git_binary?, &git_dir, &["rebase", "--abort"], &env).await
+            })
+            .boxed()
+    }
+
+    fn bisect_start(
+        &self,
+        bad: String,
+        good: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["bisect".to_string(), "start".to_string(), bad];
+                args.extend(good);
+                run_bisect_command(&git_binary?, &args, &env).await
+            })
+            .boxed()
+    }
+
+    fn bisect_mark(
+        &self,
+        verdict: BisectVerdict,
+        commit: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(async move {
+                let mut args = vec!["bisect".to_string(), verdict.to_string()];
+                args.extend(commit);
+                run_bisect_command(&git_binary?, &args, &env).await
+            })
+            .boxed()
+    }
+
+    fn bisect_reset(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let git_binary = self.git_binary();
+        self.executor
+            .spawn(
+                async move { run_bisect_command(&git_binary?, &["bisect", "reset"], &env).await },
+            )
+            .boxed()
+    }
+
+    fn bisect_state(&self) -> BoxFuture<'_, Result<Option<BisectState>>> {
+        let git_binary = self.git_binary();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                if !git_dir.join("BISECT_LOG").exists() {
+                    return Ok(None);
+                }
+                let git = git_binary?;
+                let (log, first_bad) = parse_bisect_log(&git.run(&["bisect", "log"]).await?);
+                let mut state = BisectState {
+                    log,
+                    first_bad,
+                    ..Default::default()
+                };
+
+                let refs = git
+                    .run(&["for-each-ref", "--format=%(refname)", "refs/bisect"])
+                    .await?;
+                let mut bad = None;
+                let mut good = Vec::new();
+                let mut skipped = HashSet::default();
+                for ref_name in refs.lines() {
+                    if ref_name == "refs/bisect/bad" {
+                        bad = Some(ref_name);
+                    } else if ref_name.starts_with("refs/bisect/good-") {
+                        good.push(ref_name);
+                    } else if let Some(sha) = ref_name.strip_prefix("refs/bisect/skip-") {
+                        skipped.insert(sha);
+                    }
+                }
+                if let Some(bad) = bad
+                    && !good.is_empty()
+                {
+                    let mut args = vec!["log", "--format=%H%x00%s", bad, "--not"];
+                    args.extend(good);
+                    state.remaining = git
+                        .run(&args)
+                        .await?
+                        .lines()
+                        .filter_map(|line| {
+                            let (sha, subject) = line.split_once('\0')?;
+                            (!skipped.contains(sha))
+                                .then(|| BisectCommit::new(sha.to_string(), subject.to_string()))
+                        })
+                        .collect();
+                }
+
+                if !state.is_finished() {
+                    let head = git.run(&["log", "-1", "--format=%H%x00%s", "HEAD"]).await?;
+                    state.current = head.split_once('\0').map(|(sha, subject)| {
+                        BisectCommit::new(sha.to_string(), subject.to_string())
+                    });
+                }
+                Ok(Some(state))
+            })
+            .boxed()
+    }
+
     fn commit(
         &self,
         message: SharedString,
@@ -2934,6 +3420,77 @@ fn parse_initial_graph_output<'a>(
         .collect()
 }
 
+/// Holds the todo list and reword messages of the rebase started by [`GitRepository::rebase_start`].
+const REBASE_SCRATCH_DIR: &str = "zed-rebase";
+
+/// Runs a rebase command with an editor that keeps messages as they are. Git exits with an error
+/// when the rebase stops at a conflict or an `edit` entry, which only counts as a failure when the
+/// rebase didn't get any further.
+async fn run_rebase_command(
+    git: &GitBinary,
+    git_dir: &Path,
+    args: &[&str],
+    env: &HashMap<String, String>,
+) -> Result<()> {
+    let before = RebaseStatus::load(git_dir)?;
+    let output = git
+        .build_command(args)
+        .envs(env.iter())
+        .env("GIT_EDITOR", "true")
+        .output()
+        .await?;
+    let after = RebaseStatus::load(git_dir)?;
+    if after.is_none() {
+        std::fs::remove_dir_all(git_dir.join(REBASE_SCRATCH_DIR)).ok();
+    }
+    anyhow::ensure!(
+        output.status.success() || (after.is_some() && after != before),
+        "Failed to rebase:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+async fn run_bisect_command<S: AsRef<OsStr>>(
+    git: &GitBinary,
+    args: &[S],
+    env: &HashMap<String, String>,
+) -> Result<()> {
+    let output = git.build_command(args).envs(env.iter()).output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Failed to bisect:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(())
+}
+
+fn load_index_entry_text(
+    repo: &git2::Repository,
+    path: &RepoPath,
+    stage: i32,
+) -> Result<Option<String>> {
+    // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
+    const GIT_MODE_SYMLINK: u32 = 0o120000;
+
+    let mut index = repo.index()?;
+    index.read(false)?;
+
+    // git2 unwraps internally on empty paths or `.`
+    if path.is_empty() {
+        bail!("empty path has no index text");
+    }
+    let Some(entry) = index.get_path(path.as_std_path(), stage) else {
+        return Ok(None);
+    };
+    if entry.mode == GIT_MODE_SYMLINK {
+        return Ok(None);
+    }
+
+    let content = repo.find_blob(entry.id)?.content().to_owned();
+    Ok(String::from_utf8(content).ok())
+}
+
 fn git_status_args(path_prefixes: &[RepoPath]) -> Vec<OsString> {
     let mut args = vec![
         OsString::from("status"),
@@ -3346,6 +3903,31 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+fn parse_tag_input(input: &str) -> Vec<Tag> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x00');
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let object_type = fields.next()?;
+            let object_sha = fields.next()?;
+            let peeled_sha = fields.next()?;
+            let timestamp = fields.next()?.parse::<i64>().ok();
+            let subject = fields.next()?;
+
+            let is_annotated = object_type == "tag";
+            Some(Tag {
+                name: name.to_string().into(),
+                sha: if is_annotated { peeled_sha } else { object_sha }
+                    .to_string()
+                    .into(),
+                message: is_annotated.then(|| subject.to_string().into()),
+                timestamp,
+            })
+        })
+        .collect()
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     if upstream_track.is_empty() {
         return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
@@ -3389,6 +3971,7 @@ mod tests {
     use std::fs;
 
     use super::*;
+    use crate::rebase::RebaseTodoAction;
     use gpui::TestAppContext;
 
     fn disable_git_global_config() {
@@ -3656,6 +4239,177 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_rebase(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (path, contents, message) in [
+            ("a", "base", "Base"),
+            ("b", "1", "Add b"),
+            ("b", "2", "Fix b"),
+            ("c", "1", "Add c"),
+        ] {
+            smol::fs::write(repo_dir.path().join(path), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(path)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        let base = repo
+            .revparse_batch(vec!["HEAD~3".into()])
+            .await
+            .unwrap()
+            .remove(0)
+            .unwrap();
+        let log = async || {
+            repo.git_binary()
+                .unwrap()
+                .run(&["log", "--format=%s"])
+                .await
+                .unwrap()
+        };
+
+        let mut todo = repo.rebase_todo(base.clone()).await.unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|entry| entry.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add b", "Fix b", "Add c"]
+        );
+        todo[0].action = RebaseTodoAction::Reword;
+        todo[0].message = Some("Add file b".into());
+        todo[1].action = RebaseTodoAction::Fixup;
+        todo[2].action = RebaseTodoAction::Drop;
+        repo.rebase_start(base.clone(), todo, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(log().await, "Add file b\nBase");
+        assert!(!repo_dir.path().join("c").exists());
+        assert!(repo.status(&[]).await.unwrap().rebase.is_none());
+
+        // Stopping at an edit leaves the rebase in progress.
+        smol::fs::write(repo_dir.path().join("d"), "1")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("d")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Add d".into(),
+            None,
+            CommitOptions::default(),
+            AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let mut todo = repo.rebase_todo(base.clone()).await.unwrap();
+        todo[0].action = RebaseTodoAction::Edit;
+        repo.rebase_start(base.clone(), todo, env.clone())
+            .await
+            .unwrap();
+        let rebase = repo.status(&[]).await.unwrap().rebase.unwrap();
+        assert_eq!((rebase.step, rebase.total), (1, 2));
+        assert_eq!(rebase.todo.len(), 1);
+        assert!(rebase.stopped_sha.is_some());
+        assert!(
+            repo.rebase_start(base, Vec::new(), env.clone())
+                .await
+                .is_err()
+        );
+
+        repo.rebase_continue(env.clone()).await.unwrap();
+        assert!(repo.status(&[]).await.unwrap().rebase.is_none());
+        assert_eq!(log().await, "Add d\nAdd file b\nBase");
+    }
+
+    #[gpui::test]
+    async fn test_rebase_reword_keeps_message_body(cx: &mut TestAppContext) {
+        disable_git_global_config();
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (path, message) in [
+            ("a", "Base"),
+            (
+                "b",
+                "Add b\n\nb holds the parser state.\n\nSigned-off-by: Ada <ada@example.com>",
+            ),
+        ] {
+            smol::fs::write(repo_dir.path().join(path), path)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(path)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {}),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let mut todo = repo.rebase_todo("HEAD~1".into()).await.unwrap();
+        assert_eq!(todo.len(), 1);
+        assert_eq!(todo[0].subject.as_ref(), "Add b");
+        let message = todo[0].current_message().replace("Add b", "Add the b file");
+        assert_eq!(
+            message,
+            "Add the b file\n\nb holds the parser state.\n\nSigned-off-by: Ada <ada@example.com>"
+        );
+        todo[0].action = RebaseTodoAction::Reword;
+        todo[0].message = Some(message.clone().into());
+        repo.rebase_start("HEAD~1".into(), todo, env.clone())
+            .await
+            .unwrap();
+
+        let head_message = repo
+            .git_binary()
+            .unwrap()
+            .run(&["log", "-1", "--format=%B"])
+            .await
+            .unwrap();
+        assert_eq!(head_message.trim_end(), message);
+    }
+
     #[gpui::test]
     async fn test_compare_checkpoints(cx: &mut TestAppContext) {
         disable_git_global_config();
@@ -3761,6 +4515,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tags_parsing() {
+        let input = "v1.1\x00tag\x00aaaa\x00bbbb\x001733187470\x00Release 1.1\n\
+                     nightly\x00commit\x00cccc\x00\x001733180000\x00Fix the build\n";
+        assert_eq!(
+            parse_tag_input(input),
+            vec![
+                Tag {
+                    name: "v1.1".into(),
+                    sha: "bbbb".into(),
+                    message: Some("Release 1.1".into()),
+                    timestamp: Some(1733187470),
+                },
+                Tag {
+                    name: "nightly".into(),
+                    sha: "cccc".into(),
+                    message: None,
+                    timestamp: Some(1733180000),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -1,4 +1,4 @@
-use crate::{Oid, repository::RepoPath};
+use crate::{Oid, rebase::RebaseStatus, repository::RepoPath};
 use anyhow::{Result, anyhow};
 use collections::HashMap;
 use gpui::SharedString;
@@ -430,6 +430,8 @@ impl std::ops::Sub for GitSummary {
 #[derive(Clone, Debug)]
 pub struct GitStatus {
     pub entries: Arc<[(RepoPath, FileStatus)]>,
+    /// The interactive rebase that is in progress, if any.
+    pub rebase: Option<RebaseStatus>,
 }
 
 impl FromStr for GitStatus {
@@ -486,6 +488,7 @@ impl FromStr for GitStatus {
         });
         Ok(Self {
             entries: entries.into(),
+            rebase: None,
         })
     }
 }
@@ -494,6 +497,7 @@ impl Default for GitStatus {
     fn default() -> Self {
         Self {
             entries: Arc::new([]),
+            rebase: None,
         }
     }
 }
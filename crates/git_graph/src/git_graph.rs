@@ -4,18 +4,18 @@ use git::{
     BuildCommitPermalinkParams, GitHostingProviderRegistry, GitRemote, Oid, ParsedGitRemote,
     parse_git_remote_url,
     repository::{
-        CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath,
+        CommitDiff, CommitFile, InitialGraphCommitData, LogOrder, LogSource, RepoPath, ResetMode,
         SearchCommitArgs,
     },
     status::{FileStatus, StatusCode, TrackedStatus},
 };
 use git_ui::{commit_tooltip::CommitAvatar, commit_view::CommitView, git_status_icon};
 use gpui::{
-    AnyElement, App, Bounds, ClickEvent, ClipboardItem, Corner, DefiniteLength, DragMoveEvent,
-    ElementId, Empty, Entity, EventEmitter, FocusHandle, Focusable, Hsla, PathBuilder, Pixels,
-    Point, ScrollStrategy, ScrollWheelEvent, SharedString, Subscription, Task, TextStyleRefinement,
-    UniformListScrollHandle, WeakEntity, Window, actions, anchored, deferred, point, prelude::*,
-    px, uniform_list,
+    AnyElement, App, Bounds, ClickEvent, ClipboardItem, Corner, DefiniteLength, DismissEvent,
+    DragMoveEvent, ElementId, Empty, Entity, EventEmitter, FocusHandle, Focusable, Hsla,
+    MouseButton, PathBuilder, Pixels, Point, PromptLevel, ScrollStrategy, ScrollWheelEvent,
+    SharedString, Subscription, Task, TextStyleRefinement, UniformListScrollHandle, WeakEntity,
+    Window, actions, anchored, deferred, point, prelude::*, px, uniform_list,
 };
 use language::line_diff;
 use menu::{Cancel, SelectFirst, SelectLast, SelectNext, SelectPrevious};
@@ -47,8 +47,9 @@ use ui::{
     render_table_header, table_row::TableRow,
 };
 use workspace::{
-    Workspace,
+    ModalView, Workspace,
     item::{Item, ItemEvent, TabTooltipContent},
+    notifications::DetachAndPromptErr,
 };
 
 const COMMIT_CIRCLE_RADIUS: Pixels = px(3.5);
@@ -277,6 +278,20 @@ actions!(
         OpenCommitView,
         /// Focuses the search field.
         FocusSearch,
+        /// Applies the changes of the selected commit on top of the current branch.
+        CherryPick,
+        /// Creates a commit that undoes the changes of the selected commit.
+        RevertCommit,
+        /// Creates a new branch pointing at the selected commit and switches to it.
+        CreateBranchHere,
+        /// Checks out the selected commit without switching to a branch.
+        CheckoutDetached,
+        /// Resets the current branch to the selected commit, keeping its changes staged.
+        ResetSoft,
+        /// Resets the current branch to the selected commit, keeping its changes unstaged.
+        ResetMixed,
+        /// Resets the current branch to the selected commit, discarding all changes made since.
+        ResetHard,
     ]
 );
 
@@ -1157,6 +1172,9 @@ impl GitGraph {
                     self.invalidate_state(cx);
                 }
             }
+            RepositoryEvent::TagsChanged => {
+                self.invalidate_state(cx);
+            }
             RepositoryEvent::GraphEvent(_, _) => {}
             _ => {}
         }
@@ -1179,6 +1197,14 @@ impl GitGraph {
     }
 
     fn render_chip(&self, name: &SharedString, accent_color: gpui::Hsla) -> impl IntoElement {
+        // Tags are decorated as `tag: <name>`. They keep the default chip colors, so they stand
+        // apart from branches, which take the color of their lane.
+        if let Some(tag_name) = name.strip_prefix("tag: ") {
+            return Chip::new(tag_name.to_string())
+                .label_size(LabelSize::Small)
+                .label_color(Color::Muted)
+                .tooltip(Tooltip::text(format!("Tag {tag_name}")));
+        }
         Chip::new(name.clone())
             .label_size(LabelSize::Small)
             .bg_color(accent_color.opacity(0.1))
@@ -1604,6 +1630,156 @@ impl GitGraph {
         );
     }
 
+    fn selected_commit_sha(&self) -> Option<String> {
+        let commit = self.graph_data.commits.get(self.selected_entry_idx?)?;
+        Some(commit.data.sha.to_string())
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if index >= self.graph_data.commits.len() {
+            return;
+        }
+        self.select_entry(index, ScrollStrategy::Nearest, cx);
+
+        let focus_handle = self.focus_handle.clone();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(focus_handle)
+                .action("Open Commit", OpenCommitView.boxed_clone())
+                .separator()
+                .action("Cherry-Pick", CherryPick.boxed_clone())
+                .action("Revert", RevertCommit.boxed_clone())
+                .separator()
+                .action("Create Branch Here…", CreateBranchHere.boxed_clone())
+                .action("Checkout Detached", CheckoutDetached.boxed_clone())
+                .separator()
+                .action("Reset Soft", ResetSoft.boxed_clone())
+                .action("Reset Mixed", ResetMixed.boxed_clone())
+                .action("Reset Hard", ResetHard.boxed_clone())
+        });
+
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this
+                    .context_menu
+                    .as_ref()
+                    .is_some_and(|(context_menu, ..)| {
+                        context_menu.focus_handle(cx).contains_focused(window, cx)
+                    })
+                {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    /// Runs a git operation on the selected commit, prompting with the error if it fails.
+    fn run_on_selected_commit<F, E>(
+        &mut self,
+        error_message: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        operation: impl FnOnce(&mut Repository, String, &mut Context<Repository>) -> F,
+    ) where
+        F: Future<Output = Result<anyhow::Result<()>, E>> + 'static,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let Some(sha) = self.selected_commit_sha() else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        let job = repository.update(cx, |repository, cx| operation(repository, sha, cx));
+        cx.spawn(async move |_, _| job.await?)
+            .detach_and_prompt_err(error_message, window, cx, |_, _, _| None);
+    }
+
+    fn cherry_pick(&mut self, _: &CherryPick, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected_commit("Failed to cherry-pick", window, cx, |repository, sha, _| {
+            repository.cherry_pick(sha)
+        });
+    }
+
+    fn revert_commit(&mut self, _: &RevertCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected_commit("Failed to revert", window, cx, |repository, sha, _| {
+            repository.revert(sha)
+        });
+    }
+
+    fn checkout_detached(
+        &mut self,
+        _: &CheckoutDetached,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_on_selected_commit("Failed to checkout", window, cx, |repository, sha, _| {
+            repository.checkout_detached(sha)
+        });
+    }
+
+    fn reset(&mut self, mode: ResetMode, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_on_selected_commit("Failed to reset", window, cx, |repository, sha, cx| {
+            repository.reset(sha, mode, cx)
+        });
+    }
+
+    fn reset_hard(&mut self, _: &ResetHard, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(sha) = self.selected_commit_sha() else {
+            return;
+        };
+        let short_sha = sha.get(..7).unwrap_or(&sha).to_string();
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Reset to {short_sha}?"),
+            Some("Uncommitted changes and commits after this one will be discarded."),
+            &["Reset Hard", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if prompt.await.ok() == Some(0) {
+                this.update_in(cx, |this, window, cx| {
+                    this.reset(ResetMode::Hard, window, cx);
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn create_branch_here(
+        &mut self,
+        _: &CreateBranchHere,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(sha) = self.selected_commit_sha() else {
+            return;
+        };
+        let Some(repository) = self.get_repository(cx) else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    CreateBranchModal::new(sha, repository, window, cx)
+                });
+            })
+            .ok();
+    }
+
     fn get_remote(
         &self,
         repository: &Repository,
@@ -2668,6 +2844,7 @@ impl Render for GitGraph {
                                                                     focus_handle.is_focused(window);
                                                                 let weak = weak_self.clone();
                                                                 let weak_for_hover = weak.clone();
+                                                                let weak_for_menu = weak.clone();
 
                                                                 let hover_bg = cx
                                                                     .theme()
@@ -2708,6 +2885,22 @@ impl Render for GitGraph {
                                                                             })
                                                                             .ok();
                                                                     })
+                                                                    .on_mouse_down(
+                                                                        MouseButton::Right,
+                                                                        move |event, window, cx| {
+                                                                            weak_for_menu
+                                                                                .update(cx, |this, cx| {
+                                                                                    this.deploy_context_menu(
+                                                                                        event.position,
+                                                                                        index,
+                                                                                        window,
+                                                                                        cx,
+                                                                                    );
+                                                                                })
+                                                                                .ok();
+                                                                            cx.stop_propagation();
+                                                                        },
+                                                                    )
                                                                     .on_click(move |event, window, cx| {
                                                                         let click_count = event.click_count();
                                                                         weak.update(cx, |this, cx| {
@@ -2775,6 +2968,17 @@ impl Render for GitGraph {
                     .editor
                     .update(cx, |editor, cx| editor.focus_handle(cx).focus(window, cx));
             }))
+            .on_action(cx.listener(Self::cherry_pick))
+            .on_action(cx.listener(Self::revert_commit))
+            .on_action(cx.listener(Self::create_branch_here))
+            .on_action(cx.listener(Self::checkout_detached))
+            .on_action(cx.listener(|this, _: &ResetSoft, window, cx| {
+                this.reset(ResetMode::Soft, window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ResetMixed, window, cx| {
+                this.reset(ResetMode::Mixed, window, cx);
+            }))
+            .on_action(cx.listener(Self::reset_hard))
             .on_action(cx.listener(Self::select_first))
             .on_action(cx.listener(Self::select_prev))
             .on_action(cx.listener(Self::select_next))
@@ -2959,6 +3163,85 @@ impl workspace::SerializableItem for GitGraph {
     }
 }
 
+struct CreateBranchModal {
+    sha: String,
+    editor: Entity<Editor>,
+    repository: Entity<Repository>,
+}
+
+impl CreateBranchModal {
+    fn new(
+        sha: String,
+        repository: Entity<Repository>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Branch name", window, cx);
+            editor
+        });
+        Self {
+            sha,
+            editor,
+            repository,
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let branch_name = self.editor.read(cx).text(cx).trim().to_string();
+        if branch_name.is_empty() {
+            cx.emit(DismissEvent);
+            return;
+        }
+
+        let job = self.repository.update(cx, |repository, _| {
+            repository.create_branch(branch_name, Some(self.sha.clone()))
+        });
+        cx.spawn(async move |_, _| job.await?)
+            .detach_and_prompt_err("Failed to create branch", window, cx, |_, _, _| None);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl EventEmitter<DismissEvent> for CreateBranchModal {}
+impl ModalView for CreateBranchModal {}
+impl Focusable for CreateBranchModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for CreateBranchModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let short_sha = self.sha.get(..7).unwrap_or(&self.sha).to_string();
+        v_flex()
+            .key_context("CreateBranchModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                h_flex()
+                    .px_3()
+                    .pt_2()
+                    .pb_1()
+                    .w_full()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!("Create Branch at {short_sha}"))
+                            .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(div().px_3().pb_3().w_full().child(self.editor.clone()))
+    }
+}
+
 mod persistence {
     use std::path::PathBuf;
 
@@ -3863,4 +4146,127 @@ mod tests {
             "graph_data should be repopulated from cache after switching back to the same repo"
         );
     }
+
+    #[gpui::test]
+    async fn test_commit_actions_from_graph(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            Path::new("/project"),
+            json!({
+                ".git": {},
+                "file.txt": "content",
+            }),
+        )
+        .await;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let [newest, middle, oldest] = [(); 3].map(|_| Oid::random(&mut rng));
+        let dot_git = Path::new("/project/.git");
+        fs.set_graph_commits(
+            dot_git,
+            vec![
+                Arc::new(InitialGraphCommitData {
+                    sha: newest,
+                    parents: smallvec![middle],
+                    ref_names: vec!["HEAD -> main".into()],
+                }),
+                Arc::new(InitialGraphCommitData {
+                    sha: middle,
+                    parents: smallvec![oldest],
+                    ref_names: vec![],
+                }),
+                Arc::new(InitialGraphCommitData {
+                    sha: oldest,
+                    parents: smallvec![],
+                    ref_names: vec![],
+                }),
+            ],
+        );
+        fs.set_commit_history_for_repo(
+            dot_git,
+            &[
+                (&oldest.to_string(), "Initial commit"),
+                (&middle.to_string(), "Add parser"),
+                (&newest.to_string(), "Fix parser"),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), [Path::new("/project")], cx).await;
+        cx.run_until_parked();
+        let repository = project.read_with(cx, |project, cx| {
+            project
+                .active_repository(cx)
+                .expect("should have a repository")
+        });
+
+        let (multi_workspace, cx) = cx.add_window_view(|window, cx| {
+            workspace::MultiWorkspace::test_new(project.clone(), window, cx)
+        });
+        let workspace_weak =
+            multi_workspace.read_with(&*cx, |multi, _| multi.workspace().downgrade());
+        let git_graph = cx.new_window_entity(|window, cx| {
+            GitGraph::new(
+                repository.read(cx).id,
+                project.read(cx).git_store().clone(),
+                workspace_weak,
+                window,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+
+        // Actions move `HEAD`, which can invalidate the graph, so draw it to reload the commits
+        // before selecting the next one.
+        let history = |cx: &mut gpui::VisualTestContext| {
+            cx.run_until_parked();
+            cx.draw(
+                point(px(0.), px(0.)),
+                gpui::size(px(1200.), px(800.)),
+                |_, _| git_graph.clone().into_any_element(),
+            );
+            cx.run_until_parked();
+            fs.with_git_state(dot_git, false, |state| {
+                state
+                    .commit_history
+                    .iter()
+                    .map(|(_, subject)| subject.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+        };
+
+        git_graph.update_in(cx, |graph, window, cx| {
+            graph.deploy_context_menu(point(px(10.), px(10.)), 1, window, cx);
+            assert_eq!(graph.selected_entry_idx, Some(1));
+            assert!(graph.context_menu.is_some());
+            graph.revert_commit(&RevertCommit, window, cx);
+        });
+        assert_eq!(
+            history(cx),
+            [
+                "Initial commit",
+                "Add parser",
+                "Fix parser",
+                "Revert \"Add parser\""
+            ]
+        );
+
+        git_graph.update_in(cx, |graph, window, cx| {
+            graph.select_entry(2, ScrollStrategy::Nearest, cx);
+            graph.reset(ResetMode::Mixed, window, cx);
+        });
+        assert_eq!(history(cx), ["Initial commit"]);
+
+        git_graph.update_in(cx, |graph, window, cx| {
+            graph.select_entry(1, ScrollStrategy::Nearest, cx);
+            graph.cherry_pick(&CherryPick, window, cx);
+        });
+        assert_eq!(history(cx), ["Initial commit", "Add parser"]);
+        let head = fs
+            .with_git_state(dot_git, false, |state| state.refs.get("HEAD").cloned())
+            .unwrap();
+        assert_eq!(head, Some(format!("{middle}-cherry-pick")));
+    }
 }
@@ -0,0 +1,843 @@
+use anyhow::{Context as _, Result, bail};
+use editor::Editor;
+use futures::channel::oneshot;
+use git::{
+    Bisect, BisectBad, BisectGood, BisectReset, BisectSkip, SHORT_SHA_LENGTH,
+    bisect::{BisectCommit, BisectState, BisectVerdict},
+};
+use gpui::{
+    App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
+    Render, Subscription, Task, WeakEntity, Window,
+};
+use project::git_store::{Repository, RepositoryEvent};
+use task::{RevealStrategy, TaskContext, TaskTemplate};
+use ui::{ListItem, prelude::*};
+use util::ResultExt as _;
+use workspace::{Item, Workspace, notifications::DetachAndPromptErr};
+
+use crate::commit_view::CommitView;
+
+/// Walks through a `git bisect`: starting it from a bad and a good commit, showing the commits
+/// that are left, and marking the checked out commit by hand or with a test command.
+pub struct BisectView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    state: Option<BisectState>,
+    bad_editor: Entity<Editor>,
+    good_editor: Entity<Editor>,
+    command_editor: Entity<Editor>,
+    error: Option<SharedString>,
+    focus_handle: FocusHandle,
+    pending_operation: Option<Task<()>>,
+    /// Runs the test command against each commit the bisect checks out, until the bisect is
+    /// finished. Dropping it stops the run after the command that's running.
+    auto_run: Option<Task<()>>,
+    _load_state: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BisectView {
+    pub fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &Bisect, window, cx| {
+            let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+                return;
+            };
+            Self::open(repository, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &BisectGood, window, cx| {
+            mark_active_bisect(workspace, BisectVerdict::Good, window, cx);
+        });
+        workspace.register_action(|workspace, _: &BisectBad, window, cx| {
+            mark_active_bisect(workspace, BisectVerdict::Bad, window, cx);
+        });
+        workspace.register_action(|workspace, _: &BisectSkip, window, cx| {
+            mark_active_bisect(workspace, BisectVerdict::Skip, window, cx);
+        });
+        workspace.register_action(|workspace, _: &BisectReset, window, cx| {
+            let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+                return;
+            };
+            let receiver = repository.update(cx, |repository, _| repository.bisect_reset());
+            cx.spawn(async move |_, _| receiver.await?)
+                .detach_and_prompt_err("Failed to reset the bisect", window, cx, |_, _, _| None);
+        });
+    }
+
+    /// Opens the bisect of the given repository, reusing the view that's already open for it.
+    pub fn open(
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|view| view.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            existing.update(cx, |view, cx| view.load_state(cx));
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new(|cx| Self::new(repository, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let single_line_editor =
+            |placeholder: &str, window: &mut Window, cx: &mut Context<Self>| {
+                cx.new(|cx| {
+                    let mut editor = Editor::single_line(window, cx);
+                    editor.set_placeholder_text(placeholder, window, cx);
+                    editor
+                })
+            };
+        let bad_editor = single_line_editor("HEAD", window, cx);
+        let good_editor = single_line_editor("A tag or commit without the regression", window, cx);
+        let command_editor = single_line_editor(
+            "A command that exits with 0 when the commit is good, e.g. cargo test",
+            window,
+            cx,
+        );
+        let subscriptions = vec![cx.subscribe(&repository, Self::handle_repository_event)];
+
+        let mut this = Self {
+            repository,
+            workspace,
+            state: None,
+            bad_editor,
+            good_editor,
+            command_editor,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            pending_operation: None,
+            auto_run: None,
+            _load_state: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.load_state(cx);
+        this
+    }
+
+    fn load_state(&mut self, cx: &mut Context<Self>) {
+        let receiver = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_state());
+        self._load_state = cx.spawn(async move |this, cx| {
+            let result = receiver.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(state) => this.state = state,
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn handle_repository_event(
+        &mut self,
+        _: Entity<Repository>,
+        event: &RepositoryEvent,
+        cx: &mut Context<Self>,
+    ) {
+        // The bisect can also be advanced from a terminal, which only shows up as HEAD moving.
+        if matches!(
+            event,
+            RepositoryEvent::BisectChanged | RepositoryEvent::BranchChanged
+        ) {
+            self.load_state(cx);
+        }
+    }
+
+    fn start(&mut self, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        let bad = self.bad_editor.read(cx).text(cx);
+        let bad = match bad.trim() {
+            "" => "HEAD".to_string(),
+            bad => bad.to_string(),
+        };
+        let good = self
+            .good_editor
+            .read(cx)
+            .text(cx)
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect();
+        let receiver = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_start(bad, good));
+        self.run_operation(receiver, cx);
+    }
+
+    fn mark(&mut self, verdict: BisectVerdict, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() || self.auto_run.is_some() {
+            return;
+        }
+        let receiver = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_mark(verdict, None));
+        self.run_operation(receiver, cx);
+    }
+
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        self.auto_run = None;
+        let receiver = self
+            .repository
+            .update(cx, |repository, _| repository.bisect_reset());
+        self.run_operation(receiver, cx);
+    }
+
+    fn run_operation(&mut self, receiver: oneshot::Receiver<Result<()>>, cx: &mut Context<Self>) {
+        self.error = None;
+        self.pending_operation = Some(cx.spawn(async move |this, cx| {
+            let result = receiver.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| {
+                this.pending_operation = None;
+                if let Err(error) = result {
+                    this.error = Some(error.to_string().into());
+                }
+                this.load_state(cx);
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn run_command(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let command = self.command_editor.read(cx).text(cx);
+        let command = command.trim();
+        if command.is_empty() {
+            self.error = Some("Enter a command to test each commit with".into());
+            cx.notify();
+            return;
+        }
+        let template = TaskTemplate {
+            label: format!("git bisect run {command}"),
+            command: command.to_string(),
+            reveal: RevealStrategy::NoFocus,
+            ..TaskTemplate::default()
+        };
+        self.run_task(template, window, cx);
+    }
+
+    /// Tests each commit the bisect checks out with the given task, the way `git bisect run`
+    /// does, and marks it according to the task's exit code until the first bad commit is found.
+    pub fn run_task(
+        &mut self,
+        template: TaskTemplate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pending_operation.is_some() || self.auto_run.is_some() {
+            return;
+        }
+        let repository = self.repository.clone();
+        let workspace = self.workspace.clone();
+        self.error = None;
+        self.auto_run = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = run_bisect_task(template, repository, workspace, cx).await;
+            this.update(cx, |this, cx| {
+                this.auto_run = None;
+                if let Err(error) = result {
+                    this.error = Some(error.to_string().into());
+                }
+                this.load_state(cx);
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn stop_run(&mut self, cx: &mut Context<Self>) {
+        self.auto_run = None;
+        cx.notify();
+    }
+
+    fn open_commit(&self, sha: &SharedString, window: &mut Window, cx: &mut App) {
+        CommitView::open(
+            sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let summary = self.state.as_ref().map(|state| {
+            if state.is_finished() {
+                "found the first bad commit".to_string()
+            } else if state.needs_bounds() {
+                "waiting for a good and a bad commit".to_string()
+            } else {
+                format!(
+                    "{} commits left, roughly {} steps",
+                    state.remaining.len(),
+                    state.steps_left()
+                )
+            }
+        });
+
+        h_flex()
+            .h(rems_from_px(41.))
+            .pl_3()
+            .pr_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new("Bisect"))
+                    .when_some(summary, |this, summary| {
+                        this.child(Label::new(summary).color(Color::Muted))
+                    }),
+            )
+            .when(self.state.is_some(), |this| {
+                this.child(
+                    Button::new("bisect-reset", "Reset")
+                        .label_size(LabelSize::Small)
+                        .disabled(self.pending_operation.is_some())
+                        .on_click(cx.listener(|this, _, _, cx| this.reset(cx))),
+                )
+            })
+    }
+
+    fn render_field(
+        &self,
+        label: &'static str,
+        editor: &Entity<Editor>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                div()
+                    .p_1()
+                    .border_1()
+                    .rounded_sm()
+                    .border_color(cx.theme().colors().border)
+                    .child(editor.clone()),
+            )
+    }
+
+    fn render_start(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .p_3()
+            .gap_2()
+            .max_w(rems(40.))
+            .child(
+                Label::new("Find the commit that introduced a regression.")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(self.render_field("Bad commit", &self.bad_editor, cx))
+            .child(self.render_field("Good commits", &self.good_editor, cx))
+            .child(
+                h_flex().child(
+                    Button::new("bisect-start", "Start Bisect")
+                        .disabled(self.pending_operation.is_some())
+                        .on_click(cx.listener(|this, _, _, cx| this.start(cx))),
+                ),
+            )
+    }
+
+    fn render_commit(
+        &self,
+        id: impl Into<ElementId>,
+        commit: &BisectCommit,
+        detail: Option<&'static str>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let sha = commit.sha.clone();
+        ListItem::new(id)
+            .toggle_state(
+                self.state
+                    .as_ref()
+                    .and_then(|state| state.current.as_ref())
+                    .is_some_and(|current| current.sha == commit.sha),
+            )
+            .start_slot(
+                Label::new(short_sha(&commit.sha).to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                Label::new(commit.subject.clone())
+                    .size(LabelSize::Small)
+                    .truncate(),
+            )
+            .when_some(detail, |this, detail| {
+                this.end_slot(
+                    Label::new(detail)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open_commit(&sha, window, cx);
+            }))
+    }
+
+    fn render_progress(&self, state: &BisectState, cx: &mut Context<Self>) -> impl IntoElement {
+        let busy = self.pending_operation.is_some() || self.auto_run.is_some();
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .p_3()
+            .gap_2()
+            .map(|this| {
+                if let Some(first_bad) = &state.first_bad {
+                    let sha = first_bad.sha.clone();
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .child(Label::new("First bad commit").color(Color::Muted))
+                            .child(
+                                Label::new(short_sha(&first_bad.sha).to_string()).buffer_font(cx),
+                            )
+                            .child(Label::new(first_bad.subject.clone()).truncate()),
+                    )
+                    .child(h_flex().child(
+                        Button::new("bisect-view-commit", "View Commit").on_click(cx.listener(
+                            move |this, _, window, cx| {
+                                this.open_commit(&sha, window, cx);
+                            },
+                        )),
+                    ))
+                } else {
+                    this.when_some(state.current.as_ref(), |this, current| {
+                        this.child(
+                            h_flex()
+                                .gap_1()
+                                .child(Label::new("Testing").color(Color::Muted))
+                                .child(
+                                    Label::new(short_sha(&current.sha).to_string()).buffer_font(cx),
+                                )
+                                .child(Label::new(current.subject.clone()).truncate()),
+                        )
+                    })
+                    .when(state.needs_bounds(), |this| {
+                        this.child(
+                            Label::new("Mark a good and a bad commit to start narrowing down.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(Button::new("bisect-good", "Good").disabled(busy).on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.mark(BisectVerdict::Good, cx);
+                                }),
+                            ))
+                            .child(Button::new("bisect-bad", "Bad").disabled(busy).on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.mark(BisectVerdict::Bad, cx);
+                                }),
+                            ))
+                            .child(Button::new("bisect-skip", "Skip").disabled(busy).on_click(
+                                cx.listener(|this, _, _, cx| {
+                                    this.mark(BisectVerdict::Skip, cx);
+                                }),
+                            )),
+                    )
+                    .when(!state.needs_bounds(), |this| {
+                        this.child(self.render_auto_run(cx))
+                    })
+                }
+            })
+            .child(
+                v_flex()
+                    .id("bisect-commits")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .when(!state.remaining.is_empty(), |this| {
+                        this.child(
+                            Label::new(format!("{} remaining", state.remaining.len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .children(state.remaining.iter().enumerate().map(|(ix, commit)| {
+                            self.render_commit(("bisect-remaining", ix), commit, None, cx)
+                        }))
+                    })
+                    .when(!state.log.is_empty(), |this| {
+                        this.child(Label::new("Log").size(LabelSize::Small).color(Color::Muted))
+                            .children(state.log.iter().enumerate().map(|(ix, entry)| {
+                                self.render_commit(
+                                    ("bisect-log", ix),
+                                    &entry.commit,
+                                    Some(entry.verdict.as_str()),
+                                    cx,
+                                )
+                            }))
+                    }),
+            )
+    }
+
+    fn render_auto_run(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.auto_run.is_some();
+        h_flex()
+            .gap_2()
+            .items_end()
+            .child(div().flex_1().child(self.render_field(
+                "Test command",
+                &self.command_editor,
+                cx,
+            )))
+            .map(|this| {
+                if running {
+                    this.child(
+                        Button::new("bisect-stop", "Stop")
+                            .on_click(cx.listener(|this, _, _, cx| this.stop_run(cx))),
+                    )
+                } else {
+                    this.child(
+                        Button::new("bisect-run", "Run Automatically")
+                            .disabled(self.pending_operation.is_some())
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.run_command(window, cx);
+                            })),
+                    )
+                }
+            })
+    }
+}
+
+async fn run_bisect_task(
+    template: TaskTemplate,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    cx: &mut AsyncWindowContext,
+) -> Result<()> {
+    let task_context = TaskContext {
+        cwd: Some(repository.read_with(cx, |repository, _| {
+            repository.work_directory_abs_path.to_path_buf()
+        })),
+        ..TaskContext::default()
+    };
+    loop {
+        let state = repository
+            .update(cx, |repository, _| repository.bisect_state())
+            .await??
+            .context("No bisect is in progress")?;
+        if state.is_finished() {
+            return Ok(());
+        }
+        if state.needs_bounds() {
+            bail!("Mark a good and a bad commit before running the test command");
+        }
+
+        let resolved = template
+            .resolve_task("git_bisect_run", &task_context)
+            .context("Failed to resolve the test command")?;
+        let exit_status = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.spawn_in_terminal(resolved.resolved, window, cx)
+            })?
+            .await
+            .context("The test command couldn't be run in a terminal")??;
+        let code = exit_status
+            .code()
+            .context("The test command was terminated by a signal")?;
+        let verdict = BisectVerdict::from_exit_code(code).with_context(|| match code {
+            126 | 127 => format!("The test command couldn't be run (exit code {code})"),
+            _ => format!("The test command exited with code {code}"),
+        })?;
+        repository
+            .update(cx, |repository, _| repository.bisect_mark(verdict, None))
+            .await??;
+    }
+}
+
+fn mark_active_bisect(
+    workspace: &mut Workspace,
+    verdict: BisectVerdict,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let receiver = repository.update(cx, |repository, _| repository.bisect_mark(verdict, None));
+    cx.spawn(async move |_, _| receiver.await?)
+        .detach_and_prompt_err(
+            &format!("Failed to mark the commit as {verdict}"),
+            window,
+            cx,
+            |_, _, _| None,
+        );
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..SHORT_SHA_LENGTH).unwrap_or(sha)
+}
+
+impl EventEmitter<()> for BisectView {}
+
+impl Focusable for BisectView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for BisectView {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Bisect".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+}
+
+impl Render for BisectView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.clone();
+
+        v_flex()
+            .key_context("BisectView")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(cx))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Error),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .map(|this| match &state {
+                Some(state) => this.child(self.render_progress(state, cx)),
+                None => this.child(self.render_start(cx)),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use theme::LoadThemes;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        zlog::init_test();
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn current_sha(view: &BisectView) -> Option<&str> {
+        let current = view.state.as_ref()?.current.as_ref()?;
+        Some(current.sha.as_ref())
+    }
+
+    #[gpui::test]
+    async fn test_bisect_view(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_commit_history_for_repo(
+            dot_git,
+            &[
+                ("c0", "Add parser"),
+                ("c1", "Parse numbers"),
+                ("c2", "Parse strings"),
+                ("c3", "Rename parser module"),
+                ("c4", "Break the parser"),
+                ("c5", "Parse lists"),
+                ("c6", "Parse maps"),
+                ("c7", "Update docs"),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(window_handle.into(), cx);
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        workspace.update_in(cx, |workspace, window, cx| {
+            BisectView::open(repository.clone(), workspace, window, cx);
+        });
+        cx.run_until_parked();
+        let view = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<BisectView>(cx).unwrap()
+        });
+
+        view.update_in(cx, |view, window, cx| {
+            assert_eq!(view.state, None);
+            view.good_editor.update(cx, |editor, cx| {
+                editor.set_text("c0", window, cx);
+            });
+            view.start(cx);
+        });
+        cx.run_until_parked();
+
+        // HEAD is bad by default, so the middle of c1..c6 is tested first.
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.error, None);
+            assert_eq!(current_sha(view), Some("c4"));
+            let state = view.state.as_ref().unwrap();
+            assert_eq!(state.remaining.len(), 7);
+            assert_eq!(state.remaining[0].sha.as_ref(), "c7");
+        });
+
+        view.update(cx, |view, cx| view.mark(BisectVerdict::Bad, cx));
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| assert_eq!(current_sha(view), Some("c2")));
+
+        view.update(cx, |view, cx| view.mark(BisectVerdict::Skip, cx));
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            let state = view.state.as_ref().unwrap();
+            assert!(!state.remaining.iter().any(|commit| commit.sha == "c2"));
+            assert_eq!(current_sha(view), Some("c3"));
+        });
+
+        view.update(cx, |view, cx| view.mark(BisectVerdict::Good, cx));
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            let state = view.state.as_ref().unwrap();
+            assert!(state.is_finished());
+            assert_eq!(
+                state.first_bad,
+                Some(BisectCommit::new("c4", "Break the parser"))
+            );
+            let verdicts = state
+                .log
+                .iter()
+                .map(|entry| format!("{} {}", entry.verdict, entry.commit.sha))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                verdicts,
+                ["bad c7", "good c0", "bad c4", "skip c2", "good c3"]
+            );
+        });
+
+        view.update(cx, |view, cx| view.reset(cx));
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.error, None);
+            assert_eq!(view.state, None);
+        });
+        fs.with_git_state(dot_git, false, |state| {
+            assert_eq!(state.refs.get("HEAD").map(String::as_str), Some("c7"));
+        })
+        .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_bisect_run_without_terminal(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_commit_history_for_repo(
+            dot_git,
+            &[
+                ("c0", "Add parser"),
+                ("c1", "Parse numbers"),
+                ("c2", "Break the parser"),
+            ],
+        );
+
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(window_handle.into(), cx);
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        repository
+            .update(cx, |repository, _| {
+                repository.bisect_start("c2".into(), vec!["c0".into()])
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        workspace.update_in(cx, |workspace, window, cx| {
+            BisectView::open(repository.clone(), workspace, window, cx);
+        });
+        cx.run_until_parked();
+        let view = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<BisectView>(cx).unwrap()
+        });
+
+        // Without a terminal the command can't run, so the bisect stays where it was.
+        view.update_in(cx, |view, window, cx| {
+            assert_eq!(current_sha(view), Some("c1"));
+            view.command_editor.update(cx, |editor, cx| {
+                editor.set_text("cargo test", window, cx);
+            });
+            view.run_command(window, cx);
+            assert!(view.auto_run.is_some());
+        });
+        cx.run_until_parked();
+        view.read_with(cx, |view, _| {
+            assert!(view.auto_run.is_none());
+            assert_eq!(
+                view.error.as_deref(),
+                Some("The test command couldn't be run in a terminal")
+            );
+            assert_eq!(current_sha(view), Some("c1"));
+        });
+    }
+}
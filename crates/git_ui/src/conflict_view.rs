@@ -23,6 +23,8 @@ use zed_actions::agent::{
     ConflictContent, ResolveConflictedFilesWithAgent, ResolveConflictsWithAgent,
 };
 
+use crate::merge_editor;
+
 pub(crate) struct ConflictAddon {
     buffers: HashMap<BufferId, BufferConflicts>,
 }
@@ -360,6 +362,17 @@ fn render_conflict_buttons(
                     }
                 }),
         )
+        .child(
+            Button::new("open-merge-editor", "Open Merge Editor")
+                .label_size(LabelSize::Small)
+                .on_click({
+                    let editor = editor.clone();
+                    let buffer_id = conflict.ours.end.buffer_id;
+                    move |_, window, cx| {
+                        merge_editor::open_for_editor(&editor, buffer_id, window, cx)
+                    }
+                }),
+        )
         .when(is_ai_enabled, |this| {
             this.child(Divider::vertical()).child(
                 Button::new("resolve-with-agent", "Resolve with Agent")
@@ -3144,6 +3144,69 @@ impl GitPanel {
         .detach_and_log_err(cx);
     }
 
+    /// Pushes a tag to the push remote of the current branch, or to a remote picked by the user
+    /// when `HEAD` is detached.
+    pub(crate) fn push_tag(
+        &mut self,
+        tag: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let select_remote = repo.read(cx).branch.is_none();
+        let remote = self.get_remote(select_remote, true, window, cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let remote = match remote.await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => {
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let tag_ref: SharedString = format!("refs/tags/{tag}").into();
+            let push = repo.update(cx, |repo, cx| {
+                repo.push(
+                    tag_ref.clone(),
+                    tag_ref,
+                    remote.name.clone(),
+                    None,
+                    askpass_delegate,
+                    cx,
+                )
+            });
+
+            let remote_output = push.await?;
+
+            let action = RemoteAction::Push(tag, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing tag {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn create_pull_request(&self, window: &mut Window, cx: &mut Context<Self>) {
         let result = (|| -> anyhow::Result<()> {
             let repo = self
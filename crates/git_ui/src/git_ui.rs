@@ -28,6 +28,7 @@ use zed_actions;
 use crate::{git_panel::GitPanel, text_diff_view::TextDiffView};
 
 mod askpass_modal;
+pub mod bisect_view;
 pub mod branch_picker;
 mod commit_modal;
 pub mod commit_tooltip;
@@ -38,12 +39,15 @@ pub mod file_history_view;
 pub mod git_panel;
 mod git_panel_settings;
 pub mod git_picker;
+pub mod merge_editor;
 pub mod multi_diff_view;
 pub mod picker_prompt;
 pub mod project_diff;
+pub mod rebase_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
 pub mod worktree_picker;
 
@@ -135,6 +139,10 @@ pub fn init(cx: &mut App) {
                 });
             });
         }
+        rebase_editor::RebaseEditor::register(workspace);
+        bisect_view::BisectView::register(workspace);
+        merge_editor::MergeEditor::register(workspace);
+        tag_picker::register(workspace);
         workspace.register_action(|workspace, action: &git::StashAll, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
@@ -0,0 +1,871 @@
+use anyhow::{Result, anyhow};
+use buffer_diff::BufferDiff;
+use collections::HashSet;
+use editor::{
+    Editor, EditorEvent, EditorMode, MultiBuffer, RowHighlightOptions, SelectionEffects,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+    scroll::Autoscroll,
+};
+use git::{OpenMergeEditor, repository::RepoPath};
+use gpui::{
+    AnyElement, App, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, Render, Subscription, Task, WeakEntity, Window, relative,
+};
+use language::{Anchor, Buffer, BufferId};
+use project::{
+    ConflictRegion, ConflictSet, ConflictSetUpdate, Project,
+    git_store::{ConflictStages, Repository},
+};
+use std::{
+    any::{Any, TypeId},
+    mem,
+    ops::Range,
+    sync::Arc,
+};
+use ui::prelude::*;
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, SaveOptions},
+    notifications::DetachAndPromptErr,
+};
+
+/// Shows a conflicted file as it was in the merge base and on both sides of the merge, above the
+/// working copy of the file in which the conflicts are resolved.
+pub struct MergeEditor {
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    result_buffer: Entity<Buffer>,
+    result_editor: Entity<Editor>,
+    conflict_set: Entity<ConflictSet>,
+    panes: [MergePane; 3],
+    title: SharedString,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MergeSide {
+    Base,
+    Ours,
+    Theirs,
+}
+
+impl MergeSide {
+    fn range_in(self, conflict: &ConflictRegion) -> Option<Range<Anchor>> {
+        match self {
+            MergeSide::Base => conflict.base.clone(),
+            MergeSide::Ours => Some(conflict.ours.clone()),
+            MergeSide::Theirs => Some(conflict.theirs.clone()),
+        }
+    }
+}
+
+/// A read-only editor for one stage of the conflicted file.
+struct MergePane {
+    side: MergeSide,
+    title: SharedString,
+    editor: Entity<Editor>,
+    /// Where each conflict of the result buffer appears in this stage, if it could be found.
+    conflict_ranges: Vec<Option<Range<editor::Anchor>>>,
+    block_ids: HashSet<CustomBlockId>,
+}
+
+struct MergeConflictHighlight;
+
+impl MergeEditor {
+    pub fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, _: &OpenMergeEditor, window, cx| {
+            let Some(buffer) = workspace
+                .active_item_as::<Editor>(cx)
+                .and_then(|editor| buffer_at_cursor(editor.read(cx), cx))
+            else {
+                return;
+            };
+            Self::open(buffer, workspace, window, cx).detach_and_prompt_err(
+                "Failed to open merge editor",
+                window,
+                cx,
+                |error, _, _| Some(error.to_string()),
+            );
+        });
+    }
+
+    /// Opens the merge editor for a conflicted buffer, reusing the one that's already open for it.
+    pub fn open(
+        buffer: Entity<Buffer>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<Entity<Self>>> {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|editor| editor.read(cx).result_buffer == buffer);
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return Task::ready(Ok(existing));
+        }
+
+        let project = workspace.project().clone();
+        let Some((repository, repo_path)) = project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+        else {
+            return Task::ready(Err(anyhow!("The file is not in a git repository")));
+        };
+        if !repository.read(cx).snapshot().has_conflict(&repo_path) {
+            return Task::ready(Err(anyhow!(
+                "{} has no merge conflicts",
+                repo_path.as_unix_str()
+            )));
+        }
+
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let stages = stages.await??;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_editor = cx.new(|cx| {
+                    Self::new(stages, buffer, repository, repo_path, project, window, cx)
+                });
+                workspace.add_item_to_active_pane(
+                    Box::new(merge_editor.clone()),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+                merge_editor
+            })
+        })
+    }
+
+    fn new(
+        stages: ConflictStages,
+        result_buffer: Entity<Buffer>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let conflict_set = project
+            .read(cx)
+            .git_store()
+            .clone()
+            .update(cx, |git_store, cx| {
+                git_store.open_conflict_set(result_buffer.clone(), cx)
+            });
+        let (ours_branch_name, theirs_branch_name) = conflict_set
+            .read(cx)
+            .snapshot()
+            .conflicts
+            .first()
+            .map(|conflict| {
+                (
+                    conflict.ours_branch_name.clone(),
+                    conflict.theirs_branch_name.clone(),
+                )
+            })
+            .unwrap_or_else(|| ("HEAD".into(), "Incoming".into()));
+
+        let base_text = stages.base.unwrap_or_default();
+        let panes = [
+            Self::new_pane(
+                MergeSide::Base,
+                "Base".into(),
+                base_text.clone(),
+                None,
+                &result_buffer,
+                window,
+                cx,
+            ),
+            Self::new_pane(
+                MergeSide::Ours,
+                format!("Ours ({ours_branch_name})").into(),
+                stages.ours.unwrap_or_default(),
+                Some(&base_text),
+                &result_buffer,
+                window,
+                cx,
+            ),
+            Self::new_pane(
+                MergeSide::Theirs,
+                format!("Theirs ({theirs_branch_name})").into(),
+                stages.theirs.unwrap_or_default(),
+                Some(&base_text),
+                &result_buffer,
+                window,
+                cx,
+            ),
+        ];
+
+        let result_editor = cx
+            .new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project.clone()), window, cx));
+        let subscriptions = vec![
+            cx.subscribe(&conflict_set, |this, _, _: &ConflictSetUpdate, cx| {
+                this.refresh_conflicts(cx);
+            }),
+            cx.subscribe_in(
+                &result_editor,
+                window,
+                |this, _, event: &EditorEvent, window, cx| {
+                    if let EditorEvent::SelectionsChanged { local: true } = event {
+                        this.reveal_conflict_at_cursor(window, cx);
+                    }
+                    cx.emit(event.clone());
+                },
+            ),
+        ];
+
+        let file_name = repo_path
+            .file_name()
+            .unwrap_or(repo_path.as_unix_str())
+            .to_string();
+        let mut this = Self {
+            project,
+            repository,
+            repo_path,
+            result_buffer,
+            result_editor,
+            conflict_set,
+            panes,
+            title: format!("{file_name} (Merge)").into(),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_conflicts(cx);
+        this
+    }
+
+    fn new_pane(
+        side: MergeSide,
+        title: SharedString,
+        text: String,
+        diff_base: Option<&str>,
+        result_buffer: &Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> MergePane {
+        let language = result_buffer.read(cx).language().cloned();
+        let buffer = cx.new(|cx| {
+            let mut buffer = Buffer::local(text, cx);
+            buffer.set_language(language, cx);
+            buffer
+        });
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            if let Some(diff_base) = diff_base {
+                let snapshot = buffer.read(cx).text_snapshot();
+                let diff = cx.new(|cx| BufferDiff::new_with_base_text(diff_base, &snapshot, cx));
+                multibuffer.add_diff(diff, cx);
+            }
+            multibuffer
+        });
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::new(EditorMode::full(), multibuffer, None, window, cx);
+            editor.set_read_only(true);
+            editor.disable_diagnostics(cx);
+            editor
+        });
+        MergePane {
+            side,
+            title,
+            editor,
+            conflict_ranges: Vec::new(),
+            block_ids: HashSet::default(),
+        }
+    }
+
+    /// Highlights each remaining conflict in the stage panes and adds a button for accepting that
+    /// stage's version of it.
+    fn refresh_conflicts(&mut self, cx: &mut Context<Self>) {
+        let conflicts = self.conflict_set.read(cx).snapshot().conflicts;
+        let result_snapshot = self.result_buffer.read(cx).snapshot();
+        let this = cx.weak_entity();
+        for pane in &mut self.panes {
+            let side = pane.side;
+            let side_texts = conflicts
+                .iter()
+                .map(|conflict| {
+                    side.range_in(conflict)
+                        .map(|range| result_snapshot.text_for_range(range).collect::<String>())
+                })
+                .collect::<Vec<_>>();
+            let highlight_color = match side {
+                MergeSide::Base => cx.theme().colors().editor_active_line_background,
+                MergeSide::Ours => cx.theme().colors().version_control_conflict_marker_ours,
+                MergeSide::Theirs => cx.theme().colors().version_control_conflict_marker_theirs,
+            };
+            let accept_label: SharedString = match side {
+                MergeSide::Base => "Accept Base".into(),
+                MergeSide::Ours => "Accept Ours".into(),
+                MergeSide::Theirs => "Accept Theirs".into(),
+            };
+
+            pane.editor.update(cx, |editor, cx| {
+                editor.remove_blocks(mem::take(&mut pane.block_ids), None, cx);
+                editor.clear_row_highlights::<MergeConflictHighlight>();
+                pane.conflict_ranges.clear();
+
+                let multibuffer = editor.buffer().read(cx);
+                let Some(buffer) = multibuffer.as_singleton() else {
+                    return;
+                };
+                let buffer_snapshot = buffer.read(cx).snapshot();
+                let multibuffer_snapshot = multibuffer.snapshot(cx);
+                let located = locate_conflict_sides(&buffer_snapshot.text(), &side_texts);
+
+                let mut blocks = Vec::new();
+                for (conflict, range) in conflicts.iter().zip(located) {
+                    let range = range.and_then(|range| {
+                        multibuffer_snapshot.buffer_anchor_range_to_anchor_range(
+                            buffer_snapshot.anchor_before(range.start)
+                                ..buffer_snapshot.anchor_after(range.end),
+                        )
+                    });
+                    if let Some(range) = &range {
+                        editor.highlight_rows::<MergeConflictHighlight>(
+                            range.clone(),
+                            highlight_color,
+                            RowHighlightOptions {
+                                include_gutter: true,
+                                ..Default::default()
+                            },
+                            cx,
+                        );
+                        blocks.push(BlockProperties {
+                            placement: BlockPlacement::Above(range.start),
+                            height: Some(1),
+                            style: BlockStyle::Sticky,
+                            render: Arc::new({
+                                let this = this.clone();
+                                let conflict = conflict.clone();
+                                let accept_label = accept_label.clone();
+                                move |cx| {
+                                    render_accept_button(
+                                        accept_label.clone(),
+                                        this.clone(),
+                                        conflict.clone(),
+                                        side,
+                                        cx,
+                                    )
+                                }
+                            }),
+                            priority: 0,
+                        });
+                    }
+                    pane.conflict_ranges.push(range);
+                }
+                pane.block_ids = editor.insert_blocks(blocks, None, cx).into_iter().collect();
+            });
+        }
+        cx.notify();
+    }
+
+    fn accept(&mut self, conflict: &ConflictRegion, side: MergeSide, cx: &mut Context<Self>) {
+        if let Some(range) = side.range_in(conflict) {
+            conflict.resolve(self.result_buffer.clone(), &[range], cx);
+        }
+    }
+
+    /// Scrolls every stage pane to the conflict that the cursor is in within the result.
+    fn reveal_conflict_at_cursor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let result_editor = self.result_editor.read(cx);
+        let multibuffer_snapshot = result_editor.buffer().read(cx).snapshot(cx);
+        let Some((buffer_snapshot, cursor)) = multibuffer_snapshot
+            .anchor_range_to_buffer_anchor_range(result_editor.selections.newest_anchor().range())
+        else {
+            return;
+        };
+        let conflicts = self.conflict_set.read(cx).snapshot().conflicts;
+        let Some(ix) = conflicts.iter().position(|conflict| {
+            conflict
+                .range
+                .start
+                .cmp(&cursor.start, buffer_snapshot)
+                .is_le()
+                && conflict
+                    .range
+                    .end
+                    .cmp(&cursor.start, buffer_snapshot)
+                    .is_ge()
+        }) else {
+            return;
+        };
+
+        for pane in &self.panes {
+            let Some(Some(range)) = pane.conflict_ranges.get(ix) else {
+                continue;
+            };
+            let position = range.start;
+            pane.editor.update(cx, |editor, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_anchor_ranges([position..position]),
+                );
+            });
+        }
+    }
+
+    fn mark_resolved(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let buffer = self.result_buffer.clone();
+        let save = self
+            .project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx));
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        cx.spawn(async move |_, cx| {
+            save.await?;
+            repository
+                .update(cx, |repository, cx| {
+                    repository.stage_entries(vec![repo_path], cx)
+                })
+                .await
+        })
+        .detach_and_prompt_err(
+            "Failed to mark file as resolved",
+            window,
+            cx,
+            |e, _, _| Some(e.to_string()),
+        );
+    }
+
+    fn render_header(
+        title: SharedString,
+        end_slot: Option<AnyElement>,
+        cx: &App,
+    ) -> impl IntoElement {
+        h_flex()
+            .h_8()
+            .flex_none()
+            .px_3()
+            .gap_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .children(end_slot)
+    }
+}
+
+fn render_accept_button(
+    label: SharedString,
+    merge_editor: WeakEntity<MergeEditor>,
+    conflict: ConflictRegion,
+    side: MergeSide,
+    cx: &mut BlockContext,
+) -> AnyElement {
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .ml(cx.margins.gutter.width)
+        .bg(cx.theme().colors().editor_background)
+        .child(
+            Button::new("accept", label)
+                .label_size(LabelSize::Small)
+                .on_click(move |_, _, cx| {
+                    merge_editor
+                        .update(cx, |merge_editor, cx| {
+                            merge_editor.accept(&conflict, side, cx)
+                        })
+                        .ok();
+                }),
+        )
+        .into_any()
+}
+
+/// Returns the buffer that the newest cursor of the editor is in.
+fn buffer_at_cursor(editor: &Editor, cx: &App) -> Option<Entity<Buffer>> {
+    let multibuffer = editor.buffer().read(cx);
+    if let Some(buffer) = multibuffer.as_singleton() {
+        return Some(buffer);
+    }
+    let snapshot = multibuffer.snapshot(cx);
+    let (buffer, _) =
+        snapshot.anchor_range_to_buffer_anchor_range(editor.selections.newest_anchor().range())?;
+    multibuffer.buffer(buffer.remote_id())
+}
+
+/// Opens the merge editor for a buffer shown in the given editor.
+pub(crate) fn open_for_editor(
+    editor: &WeakEntity<Editor>,
+    buffer_id: BufferId,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some((workspace, buffer)) = editor
+        .update(cx, |editor, cx| {
+            Some((
+                editor.workspace()?,
+                editor.buffer().read(cx).buffer(buffer_id)?,
+            ))
+        })
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+    workspace.update(cx, |workspace, cx| {
+        MergeEditor::open(buffer, workspace, window, cx).detach_and_prompt_err(
+            "Failed to open merge editor",
+            window,
+            cx,
+            |error, _, _| Some(error.to_string()),
+        );
+    });
+}
+
+/// Finds each conflict side within the text of a merge stage, searching for them in order so that
+/// a side is never found before the one of the preceding conflict. Sides that are empty or can't be
+/// found are `None`.
+fn locate_conflict_sides(
+    stage_text: &str,
+    side_texts: &[Option<String>],
+) -> Vec<Option<Range<usize>>> {
+    let mut offset = 0;
+    side_texts
+        .iter()
+        .map(|side_text| {
+            let side_text = side_text.as_deref().filter(|text| !text.is_empty())?;
+            let start = offset + stage_text[offset..].find(side_text)?;
+            offset = start + side_text.len();
+            Some(start..offset)
+        })
+        .collect()
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.repo_path.as_unix_str().to_string().into())
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitMergeConflict).color(Color::Muted))
+    }
+
+    fn to_item_events(event: &EditorEvent, f: &mut dyn FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.read(cx).for_each_project_item(cx, f)
+    }
+
+    fn navigate(
+        &mut self,
+        data: Arc<dyn Any + Send>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflict_count = self.conflict_set.read(cx).snapshot().conflicts.len();
+        let status = match conflict_count {
+            0 => "All conflicts resolved".to_string(),
+            1 => "1 conflict remaining".to_string(),
+            count => format!("{count} conflicts remaining"),
+        };
+        let border_color = cx.theme().colors().border;
+
+        v_flex()
+            .key_context("MergeEditor")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(relative(0.45))
+                    .border_b_1()
+                    .border_color(border_color)
+                    .children(self.panes.iter().enumerate().map(|(ix, pane)| {
+                        v_flex()
+                            .flex_1()
+                            .h_full()
+                            .min_w_0()
+                            .when(ix > 0, |this| this.border_l_1().border_color(border_color))
+                            .child(Self::render_header(pane.title.clone(), None, cx))
+                            .child(div().flex_1().min_h_0().child(pane.editor.clone()))
+                    })),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(Self::render_header(
+                        "Result".into(),
+                        Some(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Label::new(status)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .child(
+                                    Button::new("mark-resolved", "Mark as Resolved")
+                                        .label_size(LabelSize::Small)
+                                        .disabled(conflict_count > 0)
+                                        .on_click(cx.listener(Self::mark_resolved)),
+                                )
+                                .into_any_element(),
+                        ),
+                        cx,
+                    ))
+                    .child(div().flex_1().min_h_0().child(self.result_editor.clone())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::{
+        repository::{IndexStage, repo_path},
+        status::{UnmergedStatus, UnmergedStatusCode},
+    };
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use theme::LoadThemes;
+    use unindent::Unindent as _;
+    use util::path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        zlog::init_test();
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    #[test]
+    fn test_locate_conflict_sides() {
+        let stage_text = "one\ntwo\nthree\ntwo\nfour\n";
+        assert_eq!(
+            locate_conflict_sides(
+                stage_text,
+                &[
+                    Some("two\n".into()),
+                    None,
+                    Some("two\n".into()),
+                    Some(String::new()),
+                    Some("one\n".into()),
+                ]
+            ),
+            [Some(4..8), None, Some(14..18), None, None]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_merge_editor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        let result_text = "
+            fn main() {
+            <<<<<<< HEAD
+                println!(\"ours\");
+            =======
+                println!(\"theirs\");
+            >>>>>>> feature
+            }
+        "
+        .unindent();
+        fs.insert_tree(
+            path!("/project"),
+            json!({ ".git": {}, "main.rs": result_text }),
+        )
+        .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_index_stages_for_repo(
+            dot_git,
+            &[
+                (
+                    "main.rs",
+                    IndexStage::Base,
+                    "fn main() {\n    println!(\"base\");\n}\n".into(),
+                ),
+                (
+                    "main.rs",
+                    IndexStage::Ours,
+                    "fn main() {\n    println!(\"ours\");\n}\n".into(),
+                ),
+                (
+                    "main.rs",
+                    IndexStage::Theirs,
+                    "fn main() {\n    println!(\"theirs\");\n}\n".into(),
+                ),
+            ],
+        );
+        fs.with_git_state(dot_git, true, |state| {
+            state.unmerged_paths.insert(
+                repo_path("main.rs"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            );
+            state.refs.insert("MERGE_HEAD".into(), "123".into());
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(window_handle.into(), cx);
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let merge_editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeEditor::open(buffer.clone(), workspace, window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            let pane_texts = merge_editor
+                .panes
+                .iter()
+                .map(|pane| pane.editor.read(cx).text(cx))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                pane_texts,
+                [
+                    "fn main() {\n    println!(\"base\");\n}\n",
+                    "fn main() {\n    println!(\"ours\");\n}\n",
+                    "fn main() {\n    println!(\"theirs\");\n}\n",
+                ]
+            );
+            let located = merge_editor
+                .panes
+                .iter()
+                .map(|pane| {
+                    pane.conflict_ranges
+                        .iter()
+                        .map(Option::is_some)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            // The conflict markers have no base section, so only the sides can be located.
+            assert_eq!(located, [vec![false], vec![true], vec![true]]);
+        });
+
+        let conflict = merge_editor.read_with(cx, |merge_editor, cx| {
+            merge_editor.conflict_set.read(cx).snapshot().conflicts[0].clone()
+        });
+        merge_editor.update(cx, |merge_editor, cx| {
+            merge_editor.accept(&conflict, MergeSide::Theirs, cx);
+        });
+        cx.run_until_parked();
+
+        assert_eq!(
+            buffer.read_with(cx, |buffer, _| buffer.text()),
+            "fn main() {\n    println!(\"theirs\");\n}\n"
+        );
+        merge_editor.read_with(cx, |merge_editor, cx| {
+            assert!(
+                merge_editor
+                    .conflict_set
+                    .read(cx)
+                    .snapshot()
+                    .conflicts
+                    .is_empty()
+            );
+            assert!(
+                merge_editor
+                    .panes
+                    .iter()
+                    .all(|pane| pane.conflict_ranges.is_empty())
+            );
+        });
+
+        let merge_editor_again = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeEditor::open(buffer.clone(), workspace, window, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(merge_editor_again, merge_editor);
+    }
+}
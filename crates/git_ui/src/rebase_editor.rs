@@ -0,0 +1,965 @@
+use anyhow::Result;
+use editor::{Editor, EditorEvent};
+use futures::channel::oneshot;
+use git::{
+    InteractiveRebase, RebaseAbort, RebaseContinue, RebaseSkip, SHORT_SHA_LENGTH,
+    rebase::{RebaseStatus, RebaseStep, RebaseTodoAction, RebaseTodoEntry, validate_rebase_todo},
+    repository::RepoPath,
+};
+use gpui::{
+    App, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, Render, Subscription,
+    Task, WeakEntity, Window, actions,
+};
+use project::git_store::{Repository, RepositoryEvent};
+use ui::{ContextMenu, ListItem, PopoverMenu, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Item, Toast, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId},
+};
+
+use crate::project_diff::ProjectDiff;
+
+actions!(
+    rebase_editor,
+    [
+        /// Moves the selected todo entry up, so that it's applied earlier.
+        MoveEntryUp,
+        /// Moves the selected todo entry down, so that it's applied later.
+        MoveEntryDown,
+        /// Starts the rebase with the edited todo list.
+        StartRebase,
+    ]
+);
+
+/// Edits the todo list of an interactive rebase, and shows the progress of the rebase once it
+/// stopped at a conflict or at a commit marked for editing.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    onto: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    selected_entry: Option<usize>,
+    message_editor: Entity<Editor>,
+    error: Option<SharedString>,
+    was_rebasing: bool,
+    focus_handle: FocusHandle,
+    pending_operation: Option<Task<()>>,
+    _load_entries: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone)]
+struct DraggedRebaseEntry {
+    ix: usize,
+    entry: RebaseTodoEntry,
+}
+
+impl RebaseEditor {
+    pub fn register(workspace: &mut Workspace) {
+        workspace.register_action(|workspace, action: &InteractiveRebase, window, cx| {
+            let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+                return;
+            };
+            let onto = action.onto.clone().or_else(|| {
+                let repository = repository.read(cx);
+                if let Some(rebase) = &repository.rebase {
+                    return rebase.onto.as_ref().map(ToString::to_string);
+                }
+                let upstream = repository.branch.as_ref()?.upstream.as_ref()?;
+                Some(upstream.ref_name.to_string())
+            });
+            let Some(onto) = onto else {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<RebaseEditor>(),
+                        "The current branch has no upstream to rebase onto",
+                    ),
+                    cx,
+                );
+                return;
+            };
+            Self::open(onto, repository, workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &RebaseContinue, window, cx| {
+            step_active_rebase(workspace, RebaseStep::Continue, window, cx);
+        });
+        workspace.register_action(|workspace, _: &RebaseSkip, window, cx| {
+            step_active_rebase(workspace, RebaseStep::Skip, window, cx);
+        });
+        workspace.register_action(|workspace, _: &RebaseAbort, window, cx| {
+            step_active_rebase(workspace, RebaseStep::Abort, window, cx);
+        });
+    }
+
+    /// Opens the todo list for rebasing the repository's HEAD onto the given branch or commit,
+    /// reusing the editor that's already open for the repository.
+    pub fn open(
+        onto: String,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace
+            .items_of_type::<Self>(cx)
+            .find(|editor| editor.read(cx).repository == repository);
+        if let Some(existing) = existing {
+            existing.update(cx, |editor, cx| editor.set_onto(onto, cx));
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = workspace.weak_handle();
+        let editor = cx.new(|cx| Self::new(onto, repository, workspace_handle, window, cx));
+        workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+    }
+
+    fn new(
+        onto: String,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(3, 12, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&repository, Self::handle_repository_event),
+            cx.subscribe(&message_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_reword_message(cx);
+                }
+            }),
+        ];
+        let was_rebasing = repository.read(cx).rebase.is_some();
+
+        let mut this = Self {
+            repository,
+            workspace,
+            onto: onto.into(),
+            entries: Vec::new(),
+            selected_entry: None,
+            message_editor,
+            error: None,
+            was_rebasing,
+            focus_handle: cx.focus_handle(),
+            pending_operation: None,
+            _load_entries: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.load_entries(cx);
+        this
+    }
+
+    fn set_onto(&mut self, onto: String, cx: &mut Context<Self>) {
+        if self.onto != onto {
+            self.onto = onto.into();
+            self.load_entries(cx);
+        }
+    }
+
+    fn load_entries(&mut self, cx: &mut Context<Self>) {
+        let receiver = self.repository.update(cx, |repository, _| {
+            repository.rebase_todo(self.onto.to_string())
+        });
+        self._load_entries = cx.spawn(async move |this, cx| {
+            let result = receiver.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(entries) => {
+                        this.entries = entries;
+                        this.selected_entry = None;
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
+
+    fn handle_repository_event(
+        &mut self,
+        _: Entity<Repository>,
+        event: &RepositoryEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(
+            event,
+            RepositoryEvent::StatusesChanged | RepositoryEvent::BranchChanged
+        ) {
+            return;
+        }
+        let rebasing = self.repository.read(cx).rebase.is_some();
+        if self.was_rebasing && !rebasing {
+            self.load_entries(cx);
+        }
+        self.was_rebasing = rebasing;
+        cx.notify();
+    }
+
+    fn select_entry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_entry = Some(ix);
+        if let Some(entry) = self.entries.get(ix)
+            && entry.action == RebaseTodoAction::Reword
+        {
+            let message = entry.current_message().clone();
+            self.message_editor.update(cx, |editor, cx| {
+                editor.set_text(message.as_ref(), window, cx);
+            });
+        }
+        cx.notify();
+    }
+
+    fn update_reword_message(&mut self, cx: &mut Context<Self>) {
+        let message = self.message_editor.read(cx).text(cx);
+        let Some(entry) = self
+            .selected_entry
+            .and_then(|ix| self.entries.get_mut(ix))
+            .filter(|entry| entry.action == RebaseTodoAction::Reword)
+        else {
+            return;
+        };
+        let original_message = entry.original_message.as_ref().unwrap_or(&entry.subject);
+        entry.message = (message.trim() != original_message.trim()).then(|| message.into());
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseTodoAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        if action != RebaseTodoAction::Reword {
+            entry.message = None;
+        }
+        self.error = None;
+        self.select_entry(ix, window, cx);
+    }
+
+    fn move_entry(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if from == to || from >= self.entries.len() || to >= self.entries.len() {
+            return;
+        }
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        self.selected_entry = Some(to);
+        self.error = None;
+        cx.notify();
+    }
+
+    fn move_entry_up(&mut self, _: &MoveEntryUp, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry.filter(|ix| *ix > 0) {
+            self.move_entry(ix, ix - 1, cx);
+        }
+    }
+
+    fn move_entry_down(&mut self, _: &MoveEntryDown, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.move_entry(ix, ix + 1, cx);
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_entry
+            .map_or(0, |ix| (ix + 1).min(self.entries.len() - 1));
+        self.select_entry(ix, window, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let ix = self.selected_entry.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_entry(ix, window, cx);
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, _: &mut Window, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() || self.repository.read(cx).rebase.is_some() {
+            return;
+        }
+        if let Err(error) = validate_rebase_todo(&self.entries) {
+            self.error = Some(error.to_string().into());
+            cx.notify();
+            return;
+        }
+        let todo = self.entries.clone();
+        let receiver = self.repository.update(cx, |repository, _| {
+            repository.rebase_start(self.onto.to_string(), todo)
+        });
+        self.run_operation(receiver, cx);
+    }
+
+    fn step(&mut self, step: RebaseStep, cx: &mut Context<Self>) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        let receiver = self
+            .repository
+            .update(cx, |repository, _| repository.rebase_step(step));
+        self.run_operation(receiver, cx);
+    }
+
+    fn run_operation(&mut self, receiver: oneshot::Receiver<Result<()>>, cx: &mut Context<Self>) {
+        self.error = None;
+        self.pending_operation = Some(cx.spawn(async move |this, cx| {
+            let result = receiver.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| {
+                this.pending_operation = None;
+                match result {
+                    Ok(()) => this.load_entries(cx),
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn conflicted_paths(&self, cx: &App) -> Vec<RepoPath> {
+        self.repository
+            .read(cx)
+            .status()
+            .filter(|entry| entry.status.is_conflicted())
+            .map(|entry| entry.repo_path)
+            .collect()
+    }
+
+    /// Opens a conflicted file in an editor, where `conflict_view` adds the controls for
+    /// resolving each conflict.
+    fn open_conflicted_path(&self, path: &RepoPath, window: &mut Window, cx: &mut App) {
+        let Some(project_path) = self.repository.read(cx).repo_path_to_project_path(path, cx)
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, window, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
+    fn render_header(&self, rebasing: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        let repository = self.repository.read(cx);
+        let branch_name = repository
+            .rebase
+            .as_ref()
+            .and_then(RebaseStatus::branch_name)
+            .map(ToString::to_string)
+            .or_else(|| repository.branch.as_ref().map(|b| b.name().to_string()))
+            .unwrap_or_else(|| "HEAD".to_string());
+
+        h_flex()
+            .h(rems_from_px(41.))
+            .pl_3()
+            .pr_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new("Rebase").color(Color::Muted))
+                    .child(Label::new(branch_name).buffer_font(cx))
+                    .child(Label::new("onto").color(Color::Muted))
+                    .child(Label::new(short_sha(&self.onto).to_string()).buffer_font(cx)),
+            )
+            .when(!rebasing, |this| {
+                this.child(
+                    Button::new("start-rebase", "Start Rebase")
+                        .label_size(LabelSize::Small)
+                        .disabled(self.pending_operation.is_some() || self.entries.is_empty())
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.start_rebase(&StartRebase, window, cx);
+                        })),
+                )
+            })
+    }
+
+    fn render_entry(
+        &self,
+        ix: usize,
+        entry: &RebaseTodoEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let weak = cx.weak_entity();
+        let current_action = entry.action;
+        let subject = entry
+            .message
+            .as_ref()
+            .and_then(|message| message.lines().next())
+            .map(SharedString::new)
+            .unwrap_or_else(|| entry.subject.clone());
+
+        h_flex()
+            .id(("rebase-todo-entry", ix))
+            .w_full()
+            .h_8()
+            .px_2()
+            .gap_2()
+            .cursor_grab()
+            .when(self.selected_entry == Some(ix), |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select_entry(ix, window, cx);
+            }))
+            .on_drag(
+                DraggedRebaseEntry {
+                    ix,
+                    entry: entry.clone(),
+                },
+                |dragged, _, _, cx| cx.new(|_| dragged.clone()),
+            )
+            .drag_over::<DraggedRebaseEntry>(move |this, dragged, _, cx| {
+                let this = this
+                    .bg(cx.theme().colors().drop_target_background)
+                    .border_color(cx.theme().colors().drop_target_border);
+                if ix < dragged.ix {
+                    this.border_t_2()
+                } else {
+                    this.border_b_2()
+                }
+            })
+            .on_drop(
+                cx.listener(move |this, dragged: &DraggedRebaseEntry, _, cx| {
+                    this.move_entry(dragged.ix, ix, cx);
+                }),
+            )
+            .child(
+                PopoverMenu::new(("rebase-action-menu", ix))
+                    .trigger(
+                        Button::new(("rebase-action", ix), current_action.as_str())
+                            .label_size(LabelSize::Small)
+                            .end_icon(Icon::new(IconName::ChevronDown).size(IconSize::XSmall)),
+                    )
+                    .menu(move |window, cx| {
+                        let weak = weak.clone();
+                        Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                            for action in RebaseTodoAction::ALL {
+                                let weak = weak.clone();
+                                menu = menu.toggleable_entry(
+                                    action.as_str(),
+                                    action == current_action,
+                                    IconPosition::Start,
+                                    None,
+                                    move |window, cx| {
+                                        weak.update(cx, |this, cx| {
+                                            this.set_action(ix, action, window, cx)
+                                        })
+                                        .log_err();
+                                    },
+                                );
+                            }
+                            menu
+                        }))
+                    }),
+            )
+            .child(
+                Label::new(short_sha(&entry.sha).to_string())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                Label::new(subject)
+                    .size(LabelSize::Small)
+                    .truncate()
+                    .when(current_action == RebaseTodoAction::Drop, |label| {
+                        label.strikethrough().color(Color::Disabled)
+                    }),
+            )
+    }
+
+    fn render_todo(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let reworded_entry = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .filter(|entry| entry.action == RebaseTodoAction::Reword);
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .child(
+                v_flex()
+                    .id("rebase-todo-entries")
+                    .flex_1()
+                    .py_1()
+                    .overflow_y_scroll()
+                    .when(self.entries.is_empty(), |this| {
+                        this.child(
+                            div().p_3().child(
+                                Label::new(format!("No commits to rebase onto {}", self.onto))
+                                    .color(Color::Muted),
+                            ),
+                        )
+                    })
+                    .children(
+                        self.entries
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, entry)| self.render_entry(ix, entry, cx)),
+                    ),
+            )
+            .when_some(reworded_entry, |this, entry| {
+                this.child(
+                    v_flex()
+                        .p_3()
+                        .gap_1()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Label::new(format!("New message for {}", short_sha(&entry.sha)))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            div()
+                                .p_1()
+                                .border_1()
+                                .rounded_sm()
+                                .border_color(cx.theme().colors().border)
+                                .child(self.message_editor.clone()),
+                        ),
+                )
+            })
+    }
+
+    fn render_progress(&self, rebase: &RebaseStatus, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflicted_paths = self.conflicted_paths(cx);
+        let pending = self.pending_operation.is_some();
+        let hint = if !conflicted_paths.is_empty() {
+            "Resolve the conflicts in these files, then continue."
+        } else if rebase.stopped_sha.is_some() {
+            "Amend the commit or make other changes, then continue."
+        } else {
+            "The rebase was stopped outside of Zed."
+        };
+
+        v_flex()
+            .flex_1()
+            .min_h_0()
+            .p_3()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(format!(
+                        "Step {} of {}",
+                        rebase.step, rebase.total
+                    )))
+                    .when_some(rebase.stopped_sha.as_ref(), |this, sha| {
+                        this.child(Label::new("stopped at").color(Color::Muted))
+                            .child(Label::new(short_sha(sha).to_string()).buffer_font(cx))
+                    }),
+            )
+            .child(Label::new(hint).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                v_flex()
+                    .id("rebase-conflicted-paths")
+                    .overflow_y_scroll()
+                    .children(conflicted_paths.into_iter().enumerate().map(|(ix, path)| {
+                        ListItem::new(("conflicted-path", ix))
+                            .start_slot(
+                                Icon::new(IconName::Warning)
+                                    .size(IconSize::Small)
+                                    .color(Color::Warning),
+                            )
+                            .child(Label::new(path.as_unix_str().to_string()).buffer_font(cx))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.open_conflicted_path(&path, window, cx);
+                            }))
+                    })),
+            )
+            .when(!rebase.todo.is_empty(), |this| {
+                this.child(
+                    Label::new(format!("{} remaining", rebase.todo.len()))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .children(rebase.todo.iter().map(|entry| {
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(entry.action.as_str())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new(short_sha(&entry.sha).to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .buffer_font(cx),
+                        )
+                        .child(
+                            Label::new(entry.subject.clone())
+                                .size(LabelSize::Small)
+                                .truncate(),
+                        )
+                }))
+            })
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("rebase-continue", "Continue")
+                            .disabled(pending)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.step(RebaseStep::Continue, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("rebase-skip", "Skip Commit")
+                            .disabled(pending)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.step(RebaseStep::Skip, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("rebase-abort", "Abort")
+                            .disabled(pending)
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.step(RebaseStep::Abort, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("rebase-open-diff", "Open Project Diff").on_click(cx.listener(
+                            |this, _, window, cx| {
+                                this.workspace
+                                    .update(cx, |workspace, cx| {
+                                        ProjectDiff::deploy_at(workspace, None, window, cx);
+                                    })
+                                    .log_err();
+                            },
+                        )),
+                    ),
+            )
+    }
+}
+
+fn step_active_rebase(
+    workspace: &mut Workspace,
+    step: RebaseStep,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let receiver = repository.update(cx, |repository, _| repository.rebase_step(step));
+    let message = match step {
+        RebaseStep::Continue => "Failed to continue the rebase",
+        RebaseStep::Skip => "Failed to skip the commit",
+        RebaseStep::Abort => "Failed to abort the rebase",
+    };
+    cx.spawn(async move |_, _| receiver.await?)
+        .detach_and_prompt_err(message, window, cx, |_, _, _| None);
+}
+
+fn short_sha(sha: &str) -> &str {
+    let is_sha = sha.len() > SHORT_SHA_LENGTH && sha.chars().all(|c| c.is_ascii_hexdigit());
+    if is_sha {
+        &sha[..SHORT_SHA_LENGTH]
+    } else {
+        sha
+    }
+}
+
+impl Render for DraggedRebaseEntry {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .rounded_sm()
+            .bg(cx.theme().colors().elevated_surface_background)
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(self.entry.action.as_str()).size(LabelSize::Small))
+            .child(Label::new(self.entry.subject.clone()).size(LabelSize::Small))
+    }
+}
+
+impl EventEmitter<()> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseEditor {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Interactive Rebase".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let rebase = self.repository.read(cx).rebase.clone();
+
+        v_flex()
+            .key_context("RebaseEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_entry_up))
+            .on_action(cx.listener(Self::move_entry_down))
+            .on_action(cx.listener(Self::start_rebase))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_header(rebase.is_some(), cx))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Error),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .map(|this| match &rebase {
+                Some(rebase) => this.child(self.render_progress(rebase, cx)),
+                None => this.child(self.render_todo(cx)),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::repo_path;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use theme::LoadThemes;
+    use util::{path, rel_path::rel_path};
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        zlog::init_test();
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme_settings::init(LoadThemes::JustBase, cx);
+            editor::init(cx);
+            crate::init(cx);
+        });
+    }
+
+    fn todo_lines(editor: &RebaseEditor) -> Vec<String> {
+        editor
+            .entries
+            .iter()
+            .map(|entry| format!("{} {} {}", entry.action, entry.sha, entry.subject))
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_rebase_editor(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        fs.set_commit_history_for_repo(
+            dot_git,
+            &[
+                ("base", "Initial commit"),
+                ("aaa", "Add a"),
+                ("bbb", "Fix typo in a"),
+                ("ccc", "Add c"),
+            ],
+        );
+        fs.with_git_state(dot_git, false, |state| {
+            state
+                .rebase_conflicts
+                .insert("ccc".into(), vec![repo_path("a.txt")]);
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(window_handle.into(), cx);
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        workspace.update_in(cx, |workspace, window, cx| {
+            RebaseEditor::open("base".into(), repository.clone(), workspace, window, cx);
+        });
+        cx.run_until_parked();
+        let rebase_editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<RebaseEditor>(cx).unwrap()
+        });
+
+        rebase_editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(
+                todo_lines(editor),
+                ["pick aaa Add a", "pick bbb Fix typo in a", "pick ccc Add c"]
+            );
+
+            editor.set_action(0, RebaseTodoAction::Fixup, window, cx);
+            editor.start_rebase(&StartRebase, window, cx);
+            assert_eq!(
+                editor.error.as_deref(),
+                Some("cannot fixup aaa without a previous commit")
+            );
+
+            editor.move_entry(2, 0, cx);
+            editor.set_action(1, RebaseTodoAction::Reword, window, cx);
+            editor.set_action(2, RebaseTodoAction::Fixup, window, cx);
+            editor.select_entry(1, window, cx);
+            editor.message_editor.update(cx, |message_editor, cx| {
+                message_editor.set_text("Add the a file", window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        rebase_editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(editor.entries[1].message.as_deref(), Some("Add the a file"));
+            editor.start_rebase(&StartRebase, window, cx);
+        });
+        cx.run_until_parked();
+
+        // The first commit conflicts, so the rebase stops and lists the conflicted file.
+        let rebase = repository.read_with(cx, |repository, _| repository.rebase.clone().unwrap());
+        assert_eq!(rebase.stopped_sha.as_deref(), Some("ccc"));
+        assert_eq!((rebase.step, rebase.total), (1, 3));
+        rebase_editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(editor.error, None);
+            let conflicted_paths = editor.conflicted_paths(cx);
+            assert_eq!(conflicted_paths, [repo_path("a.txt")]);
+            editor.open_conflicted_path(&conflicted_paths[0], window, cx);
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            let project_path = workspace.active_item(cx).unwrap().project_path(cx).unwrap();
+            assert_eq!(project_path.path.as_ref(), rel_path("a.txt"));
+        });
+
+        rebase_editor.update(cx, |editor, cx| editor.step(RebaseStep::Continue, cx));
+        cx.run_until_parked();
+        rebase_editor.read_with(cx, |editor, _| {
+            assert!(
+                editor
+                    .error
+                    .as_ref()
+                    .unwrap()
+                    .contains("you must resolve all merge conflicts")
+            );
+        });
+
+        fs.set_unmerged_paths_for_repo(dot_git, &[]);
+        cx.run_until_parked();
+        rebase_editor.update(cx, |editor, cx| editor.step(RebaseStep::Continue, cx));
+        cx.run_until_parked();
+
+        assert_eq!(
+            repository.read_with(cx, |repository, _| repository.rebase.clone()),
+            None
+        );
+        rebase_editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.error, None);
+            assert_eq!(
+                todo_lines(editor),
+                ["pick ccc Add c", "pick aaa Add the a file"]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_reword_keeps_message_body(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(path!("/project"), json!({ ".git": {}, "a.txt": "a\n" }))
+            .await;
+        let dot_git = Path::new(path!("/project/.git"));
+        let body = "\n\nThe a file is read on startup.\n\nSigned-off-by: Ada <ada@example.com>";
+        fs.set_commit_history_for_repo(dot_git, &[("base", "Initial commit"), ("aaa", "Add a")]);
+        fs.with_git_state(dot_git, true, |state| {
+            state
+                .commit_messages
+                .insert("aaa".into(), format!("Add a{body}"));
+        })
+        .unwrap();
+
+        let project = Project::test(fs.clone(), [Path::new(path!("/project"))], cx).await;
+        let window_handle =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project.clone(), window, cx));
+        let workspace = window_handle
+            .read_with(cx, |multi_workspace, _| multi_workspace.workspace().clone())
+            .unwrap();
+        let cx = &mut VisualTestContext::from_window(window_handle.into(), cx);
+        cx.run_until_parked();
+
+        let repository =
+            project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+        workspace.update_in(cx, |workspace, window, cx| {
+            RebaseEditor::open("base".into(), repository.clone(), workspace, window, cx);
+        });
+        cx.run_until_parked();
+        let rebase_editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<RebaseEditor>(cx).unwrap()
+        });
+
+        // Rewording starts from the full message, so editing the subject keeps the body.
+        rebase_editor.update_in(cx, |editor, window, cx| {
+            editor.set_action(0, RebaseTodoAction::Reword, window, cx);
+            let message = editor.message_editor.read(cx).text(cx);
+            assert_eq!(message, format!("Add a{body}"));
+            editor.message_editor.update(cx, |message_editor, cx| {
+                message_editor.set_text(message.replacen("Add a", "Add the a file", 1), window, cx);
+            });
+        });
+        cx.run_until_parked();
+
+        rebase_editor.read_with(cx, |editor, _| {
+            assert_eq!(
+                editor.entries[0].message.as_deref(),
+                Some(format!("Add the a file{body}").as_str())
+            );
+        });
+    }
+}
@@ -0,0 +1,718 @@
+use fuzzy::StringMatchCandidate;
+
+use git::repository::Tag;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
+    Task, WeakEntity, Window, actions, rems,
+};
+use picker::{Picker, PickerDelegate};
+use project::git_store::{Repository, RepositoryEvent};
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::commit_view::CommitView;
+use crate::git_panel::GitPanel;
+
+actions!(
+    tag_picker,
+    [
+        /// Deletes the selected tag.
+        DeleteTag,
+        /// Pushes the selected tag to a remote.
+        PushTag,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::ViewTags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(repository, weak_workspace, rems(34.), window, cx)
+    })
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(repository.clone(), workspace, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx).show_scrollbar(true));
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, _| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+        });
+
+        let mut _subscriptions = vec![cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        })];
+        if let Some(repository) = repository {
+            _subscriptions.push(cx.subscribe_in(
+                &repository,
+                window,
+                |this, _, event, window, cx| {
+                    if matches!(event, RepositoryEvent::TagsChanged) {
+                        this.load_tags(window, cx);
+                    }
+                },
+            ));
+        }
+
+        let mut this = Self {
+            width,
+            picker,
+            picker_focus_handle,
+            _subscriptions,
+        };
+        this.load_tags(window, cx);
+        this
+    }
+
+    fn load_tags(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self.picker.read(cx).delegate.repo.clone() else {
+            return;
+        };
+        let tags = repository.update(cx, |repository, _| repository.tags());
+        cx.spawn_in(window, async move |this, cx| {
+            let tags = tags.await??;
+            this.update_in(cx, |this, window, cx| {
+                this.picker.update(cx, |picker, cx| {
+                    picker.delegate.all_tags = Some(tags);
+                    picker.refresh(window, cx);
+                })
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn handle_delete_tag(&mut self, _: &DeleteTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+    }
+
+    fn handle_push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .push_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+impl Focusable for TagList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagList")
+            .w(self.width)
+            .on_action(cx.listener(Self::handle_delete_tag))
+            .on_action(cx.listener(Self::handle_push_tag))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Tag {
+        tag: Tag,
+        positions: Vec<usize>,
+        formatted_timestamp: Option<String>,
+    },
+    NewTag {
+        name: String,
+    },
+}
+
+impl Entry {
+    fn as_tag(&self) -> Option<&Tag> {
+        match self {
+            Entry::Tag { tag, .. } => Some(tag),
+            Entry::NewTag { .. } => None,
+        }
+    }
+}
+
+pub struct TagListDelegate {
+    matches: Vec<Entry>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    focus_handle: FocusHandle,
+    timezone: UtcOffset,
+}
+
+impl TagListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<TagList>,
+    ) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_tags: None,
+            repo,
+            workspace,
+            selected_index: 0,
+            focus_handle: cx.focus_handle(),
+            timezone: UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        }
+    }
+
+    fn format_timestamp(timestamp: i64, timezone: UtcOffset) -> String {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or(OffsetDateTime::now_utc());
+        time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            timezone,
+            time_format::TimestampFormat::Relative,
+        )
+    }
+
+    fn tag_entry(tag: Tag, positions: Vec<usize>, timezone: UtcOffset) -> Entry {
+        let formatted_timestamp = tag
+            .timestamp
+            .map(|timestamp| Self::format_timestamp(timestamp, timezone));
+        Entry::Tag {
+            tag,
+            positions,
+            formatted_timestamp,
+        }
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        annotated: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let message = annotated.then(|| name.clone());
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name, None, message))
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn delete_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = tag.name.to_string();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.delete_tag(name)).await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        panel.update(cx, |panel, cx| panel.push_tag(tag.name.clone(), window, cx));
+    }
+
+    fn show_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            tag.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn checkout_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tag) = self.matches.get(ix).and_then(Entry::as_tag) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = tag.name.to_string();
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.checkout_detached(name))
+                .await??;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to checkout tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select or create a tag…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+        let timezone = self.timezone;
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let query = query.trim().replace(' ', "-");
+            let mut matches: Vec<Entry> = if query.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| Self::tag_entry(tag, Vec::new(), timezone))
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    let tag = all_tags[candidate.candidate_id].clone();
+                    Self::tag_entry(tag, candidate.positions, timezone)
+                })
+                .collect()
+            };
+
+            if !query.is_empty()
+                && !matches
+                    .iter()
+                    .filter_map(Entry::as_tag)
+                    .any(|tag| tag.name == query)
+            {
+                matches.push(Entry::NewTag { name: query });
+            }
+
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        match entry {
+            Entry::Tag { .. } if secondary => self.checkout_tag_at(self.selected_index, window, cx),
+            Entry::Tag { .. } => self.show_tag_at(self.selected_index, window, cx),
+            Entry::NewTag { name } => self.create_tag(name.clone(), secondary, window, cx),
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+
+        let (icon, title, details) = match entry {
+            Entry::NewTag { name } => (
+                IconName::Plus,
+                Label::new(format!("Create Tag: \"{name}\""))
+                    .single_line()
+                    .truncate()
+                    .into_any_element(),
+                None,
+            ),
+            Entry::Tag {
+                tag,
+                positions,
+                formatted_timestamp,
+            } => {
+                let short_sha = tag.sha.get(..7).unwrap_or(&tag.sha).to_string();
+                let details = [
+                    Some(short_sha),
+                    tag.message.as_ref().map(ToString::to_string),
+                    formatted_timestamp.clone(),
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|detail| !detail.is_empty())
+                .collect::<Vec<_>>()
+                .join(" • ");
+                (
+                    IconName::Hash,
+                    HighlightedLabel::new(tag.name.clone(), positions.clone())
+                        .single_line()
+                        .truncate()
+                        .into_any_element(),
+                    Some(details),
+                )
+            }
+        };
+
+        let end_slot = entry.as_tag().map(|_| {
+            let push_button = {
+                let focus_handle = self.focus_handle.clone();
+                IconButton::new(("push-tag", ix), IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |_, cx| {
+                        Tooltip::for_action_in("Push Tag", &PushTag, &focus_handle, cx)
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.delegate.push_tag_at(ix, window, cx);
+                        cx.emit(DismissEvent);
+                    }))
+            };
+            let delete_button = {
+                let focus_handle = self.focus_handle.clone();
+                IconButton::new(("delete-tag", ix), IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |_, cx| {
+                        Tooltip::for_action_in("Delete Tag", &DeleteTag, &focus_handle, cx)
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.delegate.delete_tag_at(ix, window, cx);
+                    }))
+            };
+            h_flex().gap_0p5().child(push_button).child(delete_button)
+        });
+
+        Some(
+            ListItem::new(format!("tag-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2p5()
+                        .child(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                        .child(div().w_full().overflow_hidden().child(title).children(
+                            details.map(|details| {
+                                Label::new(details)
+                                    .truncate()
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small)
+                            }),
+                        )),
+                )
+                .end_slot(end_slot)
+                .show_end_slot_on_hover(),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No tags found".into())
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let selected_entry = self.matches.get(self.selected_index)?;
+        let focus_handle = self.focus_handle.clone();
+
+        let footer = h_flex()
+            .w_full()
+            .p_1p5()
+            .gap_0p5()
+            .justify_end()
+            .flex_wrap()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant);
+
+        let footer = match selected_entry {
+            Entry::NewTag { .. } => footer
+                .child(
+                    Button::new("create-annotated-tag", "Create Annotated")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::SecondaryConfirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("create-tag", "Create")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                ),
+            Entry::Tag { .. } => footer
+                .child(
+                    Button::new("delete-tag", "Delete")
+                        .key_binding(
+                            KeyBinding::for_action_in(&DeleteTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(DeleteTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("push-tag", "Push")
+                        .key_binding(
+                            KeyBinding::for_action_in(&PushTag, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(PushTag.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("checkout-tag", "Checkout")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::SecondaryConfirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("view-tag", "View")
+                        .key_binding(
+                            KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                        )
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                ),
+        };
+
+        Some(footer.into_any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use workspace::MultiWorkspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+
+            theme_settings::init(theme::LoadThemes::JustBase, cx);
+            editor::init(cx);
+        })
+    }
+
+    #[gpui::test]
+    async fn test_create_and_delete_tags(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            Path::new("/project"),
+            json!({
+                ".git": {},
+                "file.txt": "content",
+            }),
+        )
+        .await;
+        let dot_git = Path::new("/project/.git");
+        fs.set_commit_history_for_repo(dot_git, &[("1111111", "Initial commit")]);
+
+        let project = Project::test(fs.clone(), [Path::new("/project")], cx).await;
+        cx.run_until_parked();
+        let multi_workspace =
+            cx.add_window(|window, cx| MultiWorkspace::test_new(project, window, cx));
+        let cx = &mut VisualTestContext::from_window(*multi_workspace, cx);
+        let workspace = multi_workspace
+            .update(cx, |workspace, _, _| workspace.workspace().clone())
+            .unwrap();
+
+        let open_tag_list = |cx: &mut VisualTestContext| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                open(workspace, &zed_actions::git::ViewTags, window, cx);
+                workspace.active_modal::<TagList>(cx).unwrap()
+            })
+        };
+        let tag_names = || {
+            fs.with_git_state(dot_git, false, |state| {
+                state
+                    .tags
+                    .iter()
+                    .map(|tag| (tag.name.to_string(), tag.is_annotated()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap()
+        };
+
+        let tag_list = open_tag_list(cx);
+        cx.run_until_parked();
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                picker.set_query("v1.0", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                assert!(matches!(
+                    picker.delegate.matches.as_slice(),
+                    [Entry::NewTag { name }] if name == "v1.0"
+                ));
+                picker.delegate.confirm(true, window, cx);
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(tag_names(), [("v1.0".to_string(), true)]);
+
+        let tag_list = open_tag_list(cx);
+        cx.run_until_parked();
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                picker.set_query("nightly", window, cx);
+            })
+        });
+        cx.run_until_parked();
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            tag_list.picker.update(cx, |picker, cx| {
+                picker.delegate.confirm(false, window, cx);
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            tag_names(),
+            [("nightly".to_string(), false), ("v1.0".to_string(), true)]
+        );
+
+        let tag_list = open_tag_list(cx);
+        cx.run_until_parked();
+        tag_list.update_in(cx, |tag_list, window, cx| {
+            let picker = tag_list.picker.read(cx);
+            let names = picker
+                .delegate
+                .matches
+                .iter()
+                .filter_map(Entry::as_tag)
+                .map(|tag| tag.name.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["nightly", "v1.0"]);
+            tag_list.handle_delete_tag(&DeleteTag, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(tag_names(), [("v1.0".to_string(), true)]);
+        tag_list.read_with(cx, |tag_list, cx| {
+            assert_eq!(tag_list.picker.read(cx).delegate.matches.len(), 1);
+        });
+    }
+}
@@ -0,0 +1,734 @@
+mod hierarchy_panel_settings;
+
+use std::ops::Range;
+
+use anyhow::Result;
+use collections::HashMap;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, App, AsyncWindowContext, ClickEvent, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, Pixels, Render, SharedString, Task, UniformListScrollHandle, WeakEntity, Window,
+    actions, uniform_list,
+};
+use language::{Buffer, ToPoint as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{CallHierarchyItem, Fs, Location, Project, TypeHierarchyItem};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{ListItem, Tab, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+pub use hierarchy_panel_settings::HierarchyPanelSettings;
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+actions!(
+    hierarchy_panel,
+    [
+        /// Shows the callers of the symbol under the cursor.
+        ShowIncomingCalls,
+        /// Shows the functions called by the symbol under the cursor.
+        ShowOutgoingCalls,
+        /// Shows the types the type under the cursor inherits from or implements.
+        ShowSupertypes,
+        /// Shows the types inheriting from or implementing the type under the cursor.
+        ShowSubtypes,
+        /// Switches between incoming and outgoing calls, or supertypes and subtypes,
+        /// for the current hierarchy.
+        ToggleDirection,
+        /// Collapses the selected entry in the hierarchy.
+        CollapseSelectedEntry,
+        /// Expands the selected entry in the hierarchy.
+        ExpandSelectedEntry,
+        /// Toggles focus on the hierarchy panel.
+        ToggleFocus,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+            })
+            .register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+                show_hierarchy(workspace, HierarchyMode::IncomingCalls, window, cx);
+            })
+            .register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+                show_hierarchy(workspace, HierarchyMode::OutgoingCalls, window, cx);
+            })
+            .register_action(|workspace, _: &ShowSupertypes, window, cx| {
+                show_hierarchy(workspace, HierarchyMode::Supertypes, window, cx);
+            })
+            .register_action(|workspace, _: &ShowSubtypes, window, cx| {
+                show_hierarchy(workspace, HierarchyMode::Subtypes, window, cx);
+            });
+    })
+    .detach();
+}
+
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    mode: HierarchyMode,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) else {
+        return;
+    };
+    panel.update(cx, |panel, cx| {
+        panel.show_hierarchy(buffer, position, mode, window, cx);
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyMode {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyMode {
+    fn label(&self) -> &'static str {
+        match self {
+            HierarchyMode::IncomingCalls => "Incoming Calls",
+            HierarchyMode::OutgoingCalls => "Outgoing Calls",
+            HierarchyMode::Supertypes => "Supertypes",
+            HierarchyMode::Subtypes => "Subtypes",
+        }
+    }
+
+    fn hierarchy_name(&self) -> &'static str {
+        match self {
+            HierarchyMode::IncomingCalls | HierarchyMode::OutgoingCalls => "call hierarchy",
+            HierarchyMode::Supertypes | HierarchyMode::Subtypes => "type hierarchy",
+        }
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            HierarchyMode::IncomingCalls => HierarchyMode::OutgoingCalls,
+            HierarchyMode::OutgoingCalls => HierarchyMode::IncomingCalls,
+            HierarchyMode::Supertypes => HierarchyMode::Subtypes,
+            HierarchyMode::Subtypes => HierarchyMode::Supertypes,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            HierarchyItem::Call(item) => &item.name,
+            HierarchyItem::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            HierarchyItem::Call(item) => item.detail.as_deref(),
+            HierarchyItem::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn buffer(&self) -> &Entity<Buffer> {
+        match self {
+            HierarchyItem::Call(item) => &item.location.buffer,
+            HierarchyItem::Type(item) => &item.location.buffer,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<language::Anchor> {
+        match self {
+            HierarchyItem::Call(item) => &item.selection_range,
+            HierarchyItem::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+struct HierarchyEntry {
+    item: HierarchyItem,
+    /// The calls connecting this entry to its parent; empty for the roots.
+    call_sites: Vec<Location>,
+    depth: usize,
+    /// `None` until the children have been requested from the language server.
+    children: Option<Vec<usize>>,
+    is_expanded: bool,
+}
+
+pub struct HierarchyPanel {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    mode: HierarchyMode,
+    root_items: Vec<HierarchyItem>,
+    entries: Vec<HierarchyEntry>,
+    visible_entries: Vec<usize>,
+    selected_entry: Option<usize>,
+    fetch_tasks: HashMap<usize, Task<()>>,
+    prepare_task: Option<Task<()>>,
+    status: Option<SharedString>,
+}
+
+impl HierarchyPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, window, cx| Self::new(workspace, window, cx))
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let weak_workspace = workspace.weak_handle();
+        cx.new(|cx| Self {
+            project,
+            workspace: weak_workspace,
+            fs,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            mode: HierarchyMode::IncomingCalls,
+            root_items: Vec::new(),
+            entries: Vec::new(),
+            visible_entries: Vec::new(),
+            selected_entry: None,
+            fetch_tasks: HashMap::default(),
+            prepare_task: None,
+            status: Some(
+                "Show the incoming calls or subtypes of a symbol to explore its hierarchy.".into(),
+            ),
+        })
+    }
+
+    pub fn show_hierarchy(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        mode: HierarchyMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.mode = mode;
+        self.root_items.clear();
+        self.reset_entries();
+        self.status = Some("Loading…".into());
+        cx.notify();
+
+        let items: Task<Result<Vec<HierarchyItem>>> =
+            self.project.update(cx, |project, cx| match mode {
+                HierarchyMode::IncomingCalls | HierarchyMode::OutgoingCalls => {
+                    let items = project.prepare_call_hierarchy(&buffer, position, cx);
+                    cx.background_spawn(async move {
+                        anyhow::Ok(items.await?.into_iter().map(HierarchyItem::Call).collect())
+                    })
+                }
+                HierarchyMode::Supertypes | HierarchyMode::Subtypes => {
+                    let items = project.prepare_type_hierarchy(&buffer, position, cx);
+                    cx.background_spawn(async move {
+                        anyhow::Ok(items.await?.into_iter().map(HierarchyItem::Type).collect())
+                    })
+                }
+            });
+        self.prepare_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let items = items.await;
+            this.update(cx, |this, cx| {
+                this.prepare_task = None;
+                let hierarchy_name = this.mode.hierarchy_name();
+                match items {
+                    Ok(items) if items.is_empty() => {
+                        this.status =
+                            Some(format!("No {hierarchy_name} at the cursor position.").into());
+                    }
+                    Ok(items) => {
+                        this.status = None;
+                        this.root_items = items;
+                        this.rebuild_roots(cx);
+                    }
+                    Err(error) => {
+                        this.status =
+                            Some(format!("Failed to load {hierarchy_name}: {error}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        self.mode = self.mode.opposite();
+        self.rebuild_roots(cx);
+    }
+
+    fn reset_entries(&mut self) {
+        // Dropping the tasks cancels any in-flight requests for the old tree.
+        self.fetch_tasks.clear();
+        self.entries.clear();
+        self.visible_entries.clear();
+        self.selected_entry = None;
+    }
+
+    fn rebuild_roots(&mut self, cx: &mut Context<Self>) {
+        self.reset_entries();
+        for item in &self.root_items {
+            self.entries.push(HierarchyEntry {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                depth: 0,
+                children: None,
+                is_expanded: true,
+            });
+        }
+        for entry_ix in 0..self.entries.len() {
+            self.fetch_children(entry_ix, cx);
+        }
+        self.selected_entry = (!self.entries.is_empty()).then_some(0);
+        self.update_visible_entries(cx);
+    }
+
+    fn fetch_children(&mut self, entry_ix: usize, cx: &mut Context<Self>) {
+        let entry = &self.entries[entry_ix];
+        if entry.children.is_some() || self.fetch_tasks.contains_key(&entry_ix) {
+            return;
+        }
+
+        let item = entry.item.clone();
+        let mode = self.mode;
+        let children: Task<Result<Vec<(HierarchyItem, Vec<Location>)>>> =
+            self.project.update(cx, |project, cx| match (item, mode) {
+                (
+                    HierarchyItem::Call(item),
+                    HierarchyMode::IncomingCalls | HierarchyMode::OutgoingCalls,
+                ) => {
+                    let calls = if mode == HierarchyMode::IncomingCalls {
+                        project.incoming_calls(&item, cx)
+                    } else {
+                        project.outgoing_calls(&item, cx)
+                    };
+                    cx.background_spawn(async move {
+                        anyhow::Ok(
+                            calls
+                                .await?
+                                .into_iter()
+                                .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                                .collect(),
+                        )
+                    })
+                }
+                (
+                    HierarchyItem::Type(item),
+                    HierarchyMode::Supertypes | HierarchyMode::Subtypes,
+                ) => {
+                    let types = if mode == HierarchyMode::Supertypes {
+                        project.supertypes(&item, cx)
+                    } else {
+                        project.subtypes(&item, cx)
+                    };
+                    cx.background_spawn(async move {
+                        anyhow::Ok(
+                            types
+                                .await?
+                                .into_iter()
+                                .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                                .collect(),
+                        )
+                    })
+                }
+                _ => Task::ready(Ok(Vec::new())),
+            });
+        let task = cx.spawn(async move |this, cx| {
+            let children = children.await.log_err().unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.fetch_tasks.remove(&entry_ix);
+                let depth = this.entries[entry_ix].depth + 1;
+                let mut child_indices = Vec::with_capacity(children.len());
+                for (item, call_sites) in children {
+                    child_indices.push(this.entries.len());
+                    this.entries.push(HierarchyEntry {
+                        item,
+                        call_sites,
+                        depth,
+                        children: None,
+                        is_expanded: false,
+                    });
+                }
+                this.entries[entry_ix].children = Some(child_indices);
+                this.update_visible_entries(cx);
+            })
+            .ok();
+        });
+        self.fetch_tasks.insert(entry_ix, task);
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        self.visible_entries.clear();
+        let mut stack = (0..self.entries.len())
+            .filter(|ix| self.entries[*ix].depth == 0)
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(entry_ix) = stack.pop() {
+            self.visible_entries.push(entry_ix);
+            let entry = &self.entries[entry_ix];
+            if entry.is_expanded
+                && let Some(children) = &entry.children
+            {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        cx.notify();
+    }
+
+    fn set_expanded(&mut self, entry_ix: usize, is_expanded: bool, cx: &mut Context<Self>) {
+        self.entries[entry_ix].is_expanded = is_expanded;
+        if is_expanded {
+            self.fetch_children(entry_ix, cx);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn selected_visible_index(&self) -> Option<usize> {
+        let selected = self.selected_entry?;
+        self.visible_entries.iter().position(|ix| *ix == selected)
+    }
+
+    fn select_visible_index(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(entry_ix) = self.visible_entries.get(index) {
+            self.selected_entry = Some(*entry_ix);
+            self.scroll_handle
+                .scroll_to_item(index, gpui::ScrollStrategy::Top);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let index = self.selected_visible_index().map_or(0, |index| {
+            (index + 1).min(self.visible_entries.len().saturating_sub(1))
+        });
+        self.select_visible_index(index, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let index = self
+            .selected_visible_index()
+            .map_or(0, |index| index.saturating_sub(1));
+        self.select_visible_index(index, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_visible_index(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_visible_index(self.visible_entries.len().saturating_sub(1), cx);
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.selected_entry else {
+            return;
+        };
+        if self.entries[entry_ix].is_expanded {
+            if let Some(first_child) = self.entries[entry_ix]
+                .children
+                .as_ref()
+                .and_then(|children| children.first())
+            {
+                self.selected_entry = Some(*first_child);
+                cx.notify();
+            }
+        } else {
+            self.set_expanded(entry_ix, true, cx);
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry_ix) = self.selected_entry else {
+            return;
+        };
+        if self.entries[entry_ix].is_expanded {
+            self.set_expanded(entry_ix, false, cx);
+        } else if let Some(parent_ix) = self.entries.iter().position(|entry| {
+            entry
+                .children
+                .as_ref()
+                .is_some_and(|children| children.contains(&entry_ix))
+        }) {
+            self.selected_entry = Some(parent_ix);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry_ix) = self.selected_entry {
+            self.open_entry(entry_ix, true, window, cx);
+        }
+    }
+
+    fn open_entry(
+        &mut self,
+        entry_ix: usize,
+        focus_editor: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entry = &self.entries[entry_ix];
+        // A caller is most useful when revealed at the call itself rather than at its definition.
+        let (buffer, range) = match (self.mode, entry.call_sites.first()) {
+            (HierarchyMode::IncomingCalls, Some(call_site)) => {
+                (call_site.buffer.clone(), call_site.range.clone())
+            }
+            _ => (
+                entry.item.buffer().clone(),
+                entry.item.selection_range().clone(),
+            ),
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                buffer.clone(),
+                true,
+                focus_editor,
+                true,
+                true,
+                window,
+                cx,
+            );
+            editor.update(cx, |editor, cx| {
+                let position = range.start.to_point(buffer.read(cx));
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_ranges([position..position]),
+                );
+            });
+        });
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        self.visible_entries[range]
+            .iter()
+            .map(|entry_ix| self.render_entry(*entry_ix, cx).into_any_element())
+            .collect()
+    }
+
+    fn render_entry(&self, entry_ix: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry = &self.entries[entry_ix];
+        let is_loading = self.fetch_tasks.contains_key(&entry_ix);
+        let has_children = entry
+            .children
+            .as_ref()
+            .is_none_or(|children| !children.is_empty());
+        let buffer = entry.item.buffer().read(cx);
+        let row = entry.item.selection_range().start.to_point(buffer).row + 1;
+        let location = match buffer.file() {
+            Some(file) => format!("{}:{row}", file.file_name(cx)),
+            None => format!("untitled:{row}"),
+        };
+
+        ListItem::new(("hierarchy-entry", entry_ix))
+            .indent_level(entry.depth)
+            .indent_step_size(px(12.))
+            .toggle_state(self.selected_entry == Some(entry_ix))
+            .when(has_children, |this| {
+                this.toggle(entry.is_expanded)
+                    .on_toggle(cx.listener(move |this, _, _, cx| {
+                        let is_expanded = this.entries[entry_ix].is_expanded;
+                        this.set_expanded(entry_ix, !is_expanded, cx);
+                    }))
+            })
+            .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                this.selected_entry = Some(entry_ix);
+                this.open_entry(entry_ix, event.click_count() > 1, window, cx);
+                cx.notify();
+            }))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(entry.item.name().to_string()))
+                    .when_some(entry.item.detail(), |this, detail| {
+                        this.child(
+                            Label::new(detail.to_string())
+                                .color(Color::Muted)
+                                .size(LabelSize::Small)
+                                .truncate(),
+                        )
+                    })
+                    .child(
+                        Label::new(location)
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    )
+                    .when(is_loading, |this| {
+                        this.child(
+                            Icon::new(IconName::ArrowCircle)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted)
+                                .with_rotate_animation(2),
+                        )
+                    }),
+            )
+    }
+}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("HierarchyPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::toggle_direction))
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(self.mode.label()))
+                    .child(
+                        IconButton::new("toggle-hierarchy-direction", IconName::ArrowRightLeft)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.root_items.is_empty())
+                            .tooltip(Tooltip::for_action_title(
+                                "Toggle Direction",
+                                &ToggleDirection,
+                            ))
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(ToggleDirection.boxed_clone(), cx)
+                            }),
+                    ),
+            )
+            .map(|this| {
+                if let Some(status) = self.status.clone() {
+                    this.child(
+                        v_flex().p_4().child(
+                            Label::new(status)
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            "hierarchy-entries",
+                            self.visible_entries.len(),
+                            cx.processor(|this, range: Range<usize>, window, cx| {
+                                this.render_entries(range, window, cx)
+                            }),
+                        )
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    )
+                }
+            })
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "HierarchyPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        HIERARCHY_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        HierarchyPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.hierarchy_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn default_size(&self, _: &Window, cx: &App) -> Pixels {
+        HierarchyPanelSettings::get_global(cx).default_width
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        8
+    }
+}
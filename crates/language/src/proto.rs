@@ -215,6 +215,7 @@ pub fn serialize_diagnostics<'a>(
             source_kind: match entry.diagnostic.source_kind {
                 DiagnosticSourceKind::Pulled => proto::diagnostic::SourceKind::Pulled,
                 DiagnosticSourceKind::Pushed => proto::diagnostic::SourceKind::Pushed,
+                DiagnosticSourceKind::Task => proto::diagnostic::SourceKind::Task,
                 DiagnosticSourceKind::Other => proto::diagnostic::SourceKind::Other,
             } as i32,
             start: Some(serialize_anchor(&entry.range.start)),
@@ -470,6 +471,7 @@ pub fn deserialize_diagnostics(
                     )? {
                         proto::diagnostic::SourceKind::Pulled => DiagnosticSourceKind::Pulled,
                         proto::diagnostic::SourceKind::Pushed => DiagnosticSourceKind::Pushed,
+                        proto::diagnostic::SourceKind::Task => DiagnosticSourceKind::Task,
                         proto::diagnostic::SourceKind::Other => DiagnosticSourceKind::Other,
                     },
                     data,
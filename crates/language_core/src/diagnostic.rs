@@ -51,6 +51,8 @@ pub struct Diagnostic {
 pub enum DiagnosticSourceKind {
     Pulled,
     Pushed,
+    /// Found in a task's output by its problem matchers.
+    Task,
     Other,
 }
 
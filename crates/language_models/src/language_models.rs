@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use ::settings::{Settings, SettingsStore};
@@ -26,6 +27,7 @@ use crate::provider::open_ai::OpenAiLanguageModelProvider;
 use crate::provider::open_ai_compatible::OpenAiCompatibleLanguageModelProvider;
 use crate::provider::open_router::OpenRouterLanguageModelProvider;
 use crate::provider::opencode::OpenCodeLanguageModelProvider;
+use crate::provider::replay::{self, ReplayLanguageModelProvider};
 use crate::provider::vercel::VercelLanguageModelProvider;
 use crate::provider::vercel_ai_gateway::VercelAiGatewayLanguageModelProvider;
 use crate::provider::x_ai::XAiLanguageModelProvider;
@@ -292,4 +294,32 @@ fn register_language_model_providers(
         cx,
     );
     registry.register_provider(Arc::new(CopilotChatLanguageModelProvider::new(cx)), cx);
+    register_replay_provider(registry, cx);
+}
+
+/// Registers the replay provider when a fixtures directory is configured. It's registered last,
+/// so that any of the providers above can be recorded.
+fn register_replay_provider(
+    registry: &mut LanguageModelRegistry,
+    cx: &mut Context<LanguageModelRegistry>,
+) {
+    let Some(dir) = replay::FIXTURES_DIR_ENV_VAR.value.clone() else {
+        return;
+    };
+    let dir = PathBuf::from(dir);
+    let provider = match replay::RECORD_PROVIDER_ENV_VAR.value.clone() {
+        Some(provider_id) => {
+            let Some(recorded) =
+                registry.provider(&LanguageModelProviderId::from(provider_id.clone()))
+            else {
+                log::error!(
+                    "not recording language model fixtures: no provider with id {provider_id:?}"
+                );
+                return;
+            };
+            ReplayLanguageModelProvider::record(dir, recorded)
+        }
+        None => ReplayLanguageModelProvider::replay(dir),
+    };
+    registry.register_provider(Arc::new(provider), cx);
 }
@@ -0,0 +1,626 @@
+//! A provider that answers completions from recorded fixtures, so prompts and agent profiles can
+//! be evaluated without network access.
+//!
+//! Each fixture is a JSON file in the fixtures directory, named after a hash of the model id and
+//! the request, that holds the request and the events the model responded with. In record mode,
+//! the models of another provider are exposed instead, and every completion they stream is saved
+//! as a fixture along with the model's capabilities in `models.json`.
+
+use anyhow::{Context as _, Result, anyhow};
+use futures::{FutureExt, StreamExt, future::BoxFuture, stream::BoxStream};
+use gpui::{AnyView, App, AsyncApp, Task, Window};
+use language_model::{
+    AuthenticateError, ConfigurationViewTargetAgent, EnvVar, LanguageModel,
+    LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelEffortLevel,
+    LanguageModelId, LanguageModelName, LanguageModelProvider, LanguageModelProviderId,
+    LanguageModelProviderName, LanguageModelProviderState, LanguageModelRequest,
+    LanguageModelToolChoice, LanguageModelToolSchemaFormat, env_var,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
+};
+use ui::prelude::*;
+use util::ResultExt as _;
+
+const PROVIDER_ID: LanguageModelProviderId = LanguageModelProviderId::new("replay");
+const PROVIDER_NAME: LanguageModelProviderName = LanguageModelProviderName::new("Replay");
+
+/// The directory fixtures are replayed from, and recorded to in record mode. The provider is only
+/// registered when this is set.
+pub static FIXTURES_DIR_ENV_VAR: LazyLock<EnvVar> = env_var!("ZED_LLM_FIXTURES_DIR");
+/// The id of the provider whose models are recorded, like `anthropic`.
+pub static RECORD_PROVIDER_ENV_VAR: LazyLock<EnvVar> = env_var!("ZED_LLM_FIXTURES_RECORD");
+
+const MODELS_FILE_NAME: &str = "models.json";
+
+/// The capabilities of a recorded model. They are replayed too, because they change the requests
+/// the agent sends, and a request only matches a fixture when it is sent unchanged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayModelInfo {
+    pub id: String,
+    pub name: String,
+    pub supports_tools: bool,
+    pub supported_tool_choices: Vec<LanguageModelToolChoice>,
+    pub supports_images: bool,
+    pub supports_thinking: bool,
+    #[serde(default)]
+    pub effort_levels: Vec<ReplayEffortLevel>,
+    #[serde(default)]
+    pub json_schema_subset: bool,
+    pub max_token_count: u64,
+    pub max_output_tokens: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEffortLevel {
+    pub name: String,
+    pub value: String,
+    pub is_default: bool,
+}
+
+impl ReplayModelInfo {
+    fn from_model(model: &dyn LanguageModel) -> Self {
+        Self {
+            id: model.id().0.to_string(),
+            name: model.name().0.to_string(),
+            supports_tools: model.supports_tools(),
+            supported_tool_choices: [
+                LanguageModelToolChoice::Auto,
+                LanguageModelToolChoice::Any,
+                LanguageModelToolChoice::None,
+            ]
+            .into_iter()
+            .filter(|choice| model.supports_tool_choice(choice.clone()))
+            .collect(),
+            supports_images: model.supports_images(),
+            supports_thinking: model.supports_thinking(),
+            effort_levels: model
+                .supported_effort_levels()
+                .into_iter()
+                .map(|level| ReplayEffortLevel {
+                    name: level.name.to_string(),
+                    value: level.value.to_string(),
+                    is_default: level.is_default,
+                })
+                .collect(),
+            json_schema_subset: model.tool_input_format()
+                == LanguageModelToolSchemaFormat::JsonSchemaSubset,
+            max_token_count: model.max_token_count(),
+            max_output_tokens: model.max_output_tokens(),
+        }
+    }
+
+    /// Stands in for the models of a fixtures directory that has no `models.json`.
+    fn fallback() -> Self {
+        Self {
+            id: "replay".to_string(),
+            name: "Replay".to_string(),
+            supports_tools: true,
+            supported_tool_choices: vec![
+                LanguageModelToolChoice::Auto,
+                LanguageModelToolChoice::Any,
+                LanguageModelToolChoice::None,
+            ],
+            supports_images: true,
+            supports_thinking: false,
+            effort_levels: Vec::new(),
+            json_schema_subset: false,
+            max_token_count: 200_000,
+            max_output_tokens: None,
+        }
+    }
+}
+
+/// A recorded completion event. Errors are only kept as their message.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayEvent {
+    Event(LanguageModelCompletionEvent),
+    Error(String),
+}
+
+impl ReplayEvent {
+    fn new(event: &Result<LanguageModelCompletionEvent, LanguageModelCompletionError>) -> Self {
+        match event {
+            Ok(event) => Self::Event(event.clone()),
+            Err(error) => Self::Error(error.to_string()),
+        }
+    }
+
+    fn into_result(self) -> Result<LanguageModelCompletionEvent, LanguageModelCompletionError> {
+        match self {
+            Self::Event(event) => Ok(event),
+            Self::Error(message) => Err(LanguageModelCompletionError::Other(anyhow!(message))),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplayFixture {
+    pub model: String,
+    /// The request as it was sent, to make it easier to tell why a request didn't match.
+    pub request: LanguageModelRequest,
+    pub events: Vec<ReplayEvent>,
+}
+
+/// Returns the name fixtures of this request are stored under. Thread and prompt ids are left
+/// out, because they are different in every session.
+pub fn fixture_key(model_id: &str, request: &LanguageModelRequest) -> String {
+    let request = normalize_request(request.clone());
+    let mut hasher = Sha256::new();
+    hasher.update(model_id.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(serde_json::to_vec(&request).unwrap_or_default());
+    hasher
+        .finalize()
+        .iter()
+        .take(16)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn normalize_request(mut request: LanguageModelRequest) -> LanguageModelRequest {
+    request.thread_id = None;
+    request.prompt_id = None;
+    request
+}
+
+struct Fixtures {
+    dir: PathBuf,
+    /// Serializes updates of `models.json`, which are made from several recordings at once.
+    models_lock: Mutex<()>,
+}
+
+impl Fixtures {
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+
+    fn load(&self, model_id: &str, request: &LanguageModelRequest) -> Result<ReplayFixture> {
+        let key = fixture_key(model_id, request);
+        let path = self.path(&key);
+        let json = fs::read_to_string(&path).with_context(|| {
+            format!(
+                "no recorded response to this request for {model_id} (expected {})",
+                path.display()
+            )
+        })?;
+        serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    fn save(&self, info: &ReplayModelInfo, fixture: &ReplayFixture) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let path = self.path(&fixture_key(&fixture.model, &fixture.request));
+        fs::write(&path, serde_json::to_string_pretty(fixture)?)
+            .with_context(|| format!("writing {}", path.display()))?;
+
+        let _lock = self.models_lock.lock();
+        let mut models = read_models(&self.dir)?;
+        if models.iter().any(|model| model == info) {
+            return Ok(());
+        }
+        models.retain(|model| model.id != info.id);
+        models.push(info.clone());
+        let path = self.dir.join(MODELS_FILE_NAME);
+        fs::write(&path, serde_json::to_string_pretty(&models)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn read_models(dir: &Path) -> Result<Vec<ReplayModelInfo>> {
+    let path = dir.join(MODELS_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(json) => {
+            serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(error).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+enum Mode {
+    Replay(Vec<Arc<dyn LanguageModel>>),
+    Record(Arc<dyn LanguageModelProvider>),
+}
+
+pub struct ReplayLanguageModelProvider {
+    fixtures: Arc<Fixtures>,
+    mode: Mode,
+}
+
+impl ReplayLanguageModelProvider {
+    /// Replays the fixtures in `dir`, with the models listed in its `models.json`.
+    pub fn replay(dir: PathBuf) -> Self {
+        let mut models = read_models(&dir).log_err().unwrap_or_default();
+        if models.is_empty() {
+            models.push(ReplayModelInfo::fallback());
+        }
+        let fixtures = Arc::new(Fixtures {
+            dir,
+            models_lock: Mutex::new(()),
+        });
+        let models = models
+            .into_iter()
+            .map(|info| {
+                Arc::new(ReplayLanguageModel {
+                    info,
+                    fixtures: fixtures.clone(),
+                    recorded_model: None,
+                }) as Arc<dyn LanguageModel>
+            })
+            .collect();
+        Self {
+            fixtures,
+            mode: Mode::Replay(models),
+        }
+    }
+
+    /// Exposes the models of `provider`, saving their responses as fixtures in `dir`.
+    pub fn record(dir: PathBuf, provider: Arc<dyn LanguageModelProvider>) -> Self {
+        Self {
+            fixtures: Arc::new(Fixtures {
+                dir,
+                models_lock: Mutex::new(()),
+            }),
+            mode: Mode::Record(provider),
+        }
+    }
+
+    fn recording_model(&self, model: Arc<dyn LanguageModel>) -> Arc<dyn LanguageModel> {
+        Arc::new(ReplayLanguageModel {
+            info: ReplayModelInfo::from_model(model.as_ref()),
+            fixtures: self.fixtures.clone(),
+            recorded_model: Some(model),
+        })
+    }
+}
+
+impl LanguageModelProviderState for ReplayLanguageModelProvider {
+    type ObservableEntity = ();
+
+    fn observable_entity(&self) -> Option<gpui::Entity<Self::ObservableEntity>> {
+        None
+    }
+}
+
+impl LanguageModelProvider for ReplayLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        match &self.mode {
+            Mode::Replay(models) => models.first().cloned(),
+            Mode::Record(provider) => provider
+                .default_model(cx)
+                .map(|model| self.recording_model(model)),
+        }
+    }
+
+    fn default_fast_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        match &self.mode {
+            Mode::Replay(models) => models.first().cloned(),
+            Mode::Record(provider) => provider
+                .default_fast_model(cx)
+                .map(|model| self.recording_model(model)),
+        }
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        match &self.mode {
+            Mode::Replay(models) => models.clone(),
+            Mode::Record(provider) => provider
+                .provided_models(cx)
+                .into_iter()
+                .map(|model| self.recording_model(model))
+                .collect(),
+        }
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        match &self.mode {
+            Mode::Replay(_) => true,
+            Mode::Record(provider) => provider.is_authenticated(cx),
+        }
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        match &self.mode {
+            Mode::Replay(_) => Task::ready(Ok(())),
+            Mode::Record(provider) => provider.authenticate(cx),
+        }
+    }
+
+    fn configuration_view(
+        &self,
+        _target_agent: ConfigurationViewTargetAgent,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> AnyView {
+        let message = match &self.mode {
+            Mode::Replay(_) => format!(
+                "Replaying recorded responses from {}.",
+                self.fixtures.dir.display()
+            ),
+            Mode::Record(provider) => format!(
+                "Recording responses of {} models to {}.",
+                provider.name().0,
+                self.fixtures.dir.display()
+            ),
+        };
+        cx.new(|_| ConfigurationView { message }).into()
+    }
+
+    fn reset_credentials(&self, _: &mut App) -> Task<Result<()>> {
+        Task::ready(Ok(()))
+    }
+}
+
+pub struct ReplayLanguageModel {
+    info: ReplayModelInfo,
+    fixtures: Arc<Fixtures>,
+    /// The model whose responses are recorded, in record mode.
+    recorded_model: Option<Arc<dyn LanguageModel>>,
+}
+
+impl ReplayLanguageModel {
+    fn record_completion(
+        &self,
+        model: &Arc<dyn LanguageModel>,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let stream = model.stream_completion(request.clone(), cx);
+        let executor = cx.background_executor().clone();
+        let fixtures = self.fixtures.clone();
+        let info = self.info.clone();
+        async move {
+            let events = stream.await?;
+            let recorded = Arc::new(Mutex::new(Vec::new()));
+            let events = events.inspect({
+                let recorded = recorded.clone();
+                move |event| recorded.lock().push(ReplayEvent::new(event))
+            });
+            // Only completions that ran to the end are saved.
+            let save = futures::stream::once(async move {
+                let fixture = ReplayFixture {
+                    model: info.id.clone(),
+                    request: normalize_request(request),
+                    events: std::mem::take(&mut *recorded.lock()),
+                };
+                executor
+                    .spawn(async move { fixtures.save(&info, &fixture).log_err() })
+                    .await;
+            })
+            .filter_map(|()| futures::future::ready(None));
+            Ok(events.chain(save).boxed())
+        }
+        .boxed()
+    }
+}
+
+impl LanguageModel for ReplayLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        LanguageModelId::from(self.info.id.clone())
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.info.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        PROVIDER_ID
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        PROVIDER_NAME
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.info.supports_tools
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        self.info.supported_tool_choices.contains(&choice)
+    }
+
+    fn supports_images(&self) -> bool {
+        self.info.supports_images
+    }
+
+    fn supports_thinking(&self) -> bool {
+        self.info.supports_thinking
+    }
+
+    fn supported_effort_levels(&self) -> Vec<LanguageModelEffortLevel> {
+        self.info
+            .effort_levels
+            .iter()
+            .map(|level| LanguageModelEffortLevel {
+                name: level.name.clone().into(),
+                value: level.value.clone().into(),
+                is_default: level.is_default,
+            })
+            .collect()
+    }
+
+    fn tool_input_format(&self) -> LanguageModelToolSchemaFormat {
+        if self.info.json_schema_subset {
+            LanguageModelToolSchemaFormat::JsonSchemaSubset
+        } else {
+            LanguageModelToolSchemaFormat::JsonSchema
+        }
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("replay/{}", self.info.id)
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.info.max_token_count
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.info.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        match &self.recorded_model {
+            Some(model) => model.count_tokens(request, cx),
+            None => futures::future::ready(Ok(0)).boxed(),
+        }
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        if let Some(model) = &self.recorded_model {
+            return self.record_completion(model, request, cx);
+        }
+
+        let fixtures = self.fixtures.clone();
+        let model_id = self.info.id.clone();
+        let fixture = cx.background_spawn(async move { fixtures.load(&model_id, &request) });
+        async move {
+            let fixture = fixture.await.map_err(LanguageModelCompletionError::Other)?;
+            Ok(
+                futures::stream::iter(fixture.events.into_iter().map(ReplayEvent::into_result))
+                    .boxed(),
+            )
+        }
+        .boxed()
+    }
+}
+
+struct ConfigurationView {
+    message: String,
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        Label::new(self.message.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language_model::fake_provider::{FakeLanguageModel, FakeLanguageModelProvider};
+    use language_model::{LanguageModelRequestMessage, MessageContent, Role};
+
+    fn request(text: &str, thread_id: &str) -> LanguageModelRequest {
+        LanguageModelRequest {
+            thread_id: Some(thread_id.to_string()),
+            messages: vec![LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text(text.to_string())],
+                cache: false,
+                reasoning_details: None,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fixture_key_ignores_thread_id() {
+        assert_eq!(
+            fixture_key("model", &request("Hello", "thread-1")),
+            fixture_key("model", &request("Hello", "thread-2"))
+        );
+        assert_ne!(
+            fixture_key("model", &request("Hello", "thread-1")),
+            fixture_key("model", &request("Goodbye", "thread-1"))
+        );
+        assert_ne!(
+            fixture_key("model", &request("Hello", "thread-1")),
+            fixture_key("other-model", &request("Hello", "thread-1"))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_record_and_replay(cx: &mut TestAppContext) {
+        let dir = tempfile::tempdir().unwrap();
+        let fake_model = Arc::new(FakeLanguageModel::default());
+        let fake_provider =
+            Arc::new(FakeLanguageModelProvider::default().with_models(vec![fake_model.clone()]));
+
+        let recorder = ReplayLanguageModelProvider::record(dir.path().to_path_buf(), fake_provider);
+        let model = cx.update(|cx| recorder.default_model(cx)).unwrap();
+        assert_eq!(model.provider_id(), PROVIDER_ID);
+        assert_eq!(model.id(), fake_model.id());
+
+        let events = model.stream_completion(request("Hello", "thread-1"), &cx.to_async());
+        let events = cx
+            .executor()
+            .spawn(async move { events.await.unwrap().collect::<Vec<_>>().await });
+        cx.run_until_parked();
+        fake_model.send_last_completion_stream_text_chunk("Hi");
+        fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::Stop(
+            language_model::StopReason::EndTurn,
+        ));
+        fake_model.end_last_completion_stream();
+        assert_eq!(events.await.len(), 2);
+        cx.run_until_parked();
+
+        let replay = ReplayLanguageModelProvider::replay(dir.path().to_path_buf());
+        let models = cx.update(|cx| replay.provided_models(cx));
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id(), fake_model.id());
+        assert_eq!(models[0].supports_tools(), fake_model.supports_tools());
+
+        let events = models[0]
+            .stream_completion(request("Hello", "thread-2"), &cx.to_async())
+            .await
+            .unwrap()
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            events,
+            vec![
+                LanguageModelCompletionEvent::Text("Hi".to_string()),
+                LanguageModelCompletionEvent::Stop(language_model::StopReason::EndTurn),
+            ]
+        );
+
+        let error = models[0]
+            .stream_completion(request("Goodbye", "thread-2"), &cx.to_async())
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            error
+                .to_string()
+                .contains("no recorded response to this request")
+        );
+    }
+}
@@ -3,7 +3,7 @@ use edit_prediction::EditPredictionStore;
 use editor::{Editor, EditorEvent, MultiBufferOffset, actions::MoveToEnd, scroll::Autoscroll};
 use gpui::{
     App, Context, Corner, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement,
-    Render, Styled, Subscription, Task, WeakEntity, Window, actions, div,
+    PromptLevel, Render, Styled, Subscription, Task, WeakEntity, Window, actions, div,
 };
 use itertools::Itertools as _;
 use language::{LanguageServerId, language_settings::SoftWrap};
@@ -17,8 +17,8 @@ use project::{
     search::SearchQuery,
 };
 use proto::toggle_lsp_logs::LogType;
-use std::{any::TypeId, borrow::Cow, sync::Arc};
-use ui::{Checkbox, ContextMenu, PopoverMenu, ToggleState, prelude::*};
+use std::{any::TypeId, borrow::Cow, path::PathBuf, sync::Arc};
+use ui::{Checkbox, ContextMenu, PopoverMenu, ToggleState, Tooltip, prelude::*};
 use util::ResultExt as _;
 use workspace::{
     SplitDirection, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace, WorkspaceId,
@@ -678,6 +678,66 @@ impl LspLogView {
             Some(())
         });
     }
+
+    fn record_rpc_trace(&mut self, cx: &mut Context<Self>) {
+        let Some(server_id) = self.current_server_id else {
+            return;
+        };
+        self.log_store.update(cx, |log_store, _| {
+            log_store.start_recording_rpc_trace(server_id);
+        });
+        cx.notify();
+    }
+
+    fn export_rpc_trace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(server_id) = self.current_server_id else {
+            return;
+        };
+        cx.notify();
+        let trace = self.log_store.update(cx, |log_store, _| {
+            log_store.finish_recording_rpc_trace(server_id)
+        });
+        let log_store = self.log_store.read(cx);
+        let trace = match trace {
+            Ok(trace) => trace,
+            Err(error) => {
+                let prompt = window.prompt(
+                    PromptLevel::Warning,
+                    "Unable to export the RPC trace",
+                    Some(&error.to_string()),
+                    &["Ok"],
+                    cx,
+                );
+                cx.background_spawn(async move {
+                    prompt.await.ok();
+                })
+                .detach();
+                return;
+            }
+        };
+        let server_name = log_store
+            .language_servers
+            .get(&server_id)
+            .and_then(|state| state.name.clone())
+            .map_or_else(|| "language-server".to_string(), |name| name.0.to_string());
+        let fs = self.project.read(cx).fs().clone();
+        let path = cx.prompt_for_new_path(
+            &PathBuf::default(),
+            Some(&format!("{server_name}-rpc-trace.jsonl")),
+        );
+        cx.background_spawn(async move {
+            let Some(path) = path
+                .await
+                .log_err()
+                .and_then(|path| path.log_err())
+                .flatten()
+            else {
+                return;
+            };
+            fs.atomic_write(path, trace).await.log_err();
+        })
+        .detach();
+    }
 }
 
 fn log_type(log_kind: LogKind) -> Option<LogType> {
@@ -1263,6 +1323,47 @@ impl Render for LspLogToolbarItemView {
                         }),
                     ),
             )
+            .when(
+                log_view.read(cx).active_entry_kind == LogKind::Rpc,
+                |this| {
+                    let recording = current_server_id.is_some_and(|server_id| {
+                        log_view
+                            .read(cx)
+                            .log_store
+                            .read(cx)
+                            .is_recording_rpc_trace(server_id)
+                    });
+                    if recording {
+                        this.child(
+                            Button::new("export_rpc_trace_button", "Export Trace")
+                                .tooltip(Tooltip::text(
+                                    "Stop recording and save the messages as a trace that can be replayed in tests",
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    if let Some(log_view) = this.log_view.as_ref() {
+                                        log_view.update(cx, |log_view, cx| {
+                                            log_view.export_rpc_trace(window, cx)
+                                        });
+                                    }
+                                })),
+                        )
+                    } else {
+                        this.child(
+                            Button::new("record_rpc_trace_button", "Record Trace")
+                                .tooltip(Tooltip::text(
+                                    "Keep every RPC message from now on, until the trace is exported",
+                                ))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    if let Some(log_view) = this.log_view.as_ref() {
+                                        log_view.update(cx, |log_view, cx| {
+                                            log_view.record_rpc_trace(cx)
+                                        });
+                                    }
+                                })),
+                        )
+                    }
+                },
+            )
             .child(
                 Button::new("clear_log_button", "Clear").on_click(cx.listener(
                     |this, _, window, cx| {
@@ -6,7 +6,7 @@ use super::*;
 use futures::StreamExt;
 use gpui::{AppContext as _, TestAppContext, VisualTestContext};
 use language::{FakeLspAdapter, Language, LanguageConfig, LanguageMatcher, tree_sitter_rust};
-use lsp::LanguageServerName;
+use lsp::{LanguageServerName, rpc_trace::RpcDirection};
 use project::{
     FakeFs, Project,
     lsp_store::log_store::{LanguageServerKind, LogKind, LogStore},
@@ -105,6 +105,83 @@ async fn test_lsp_log_view(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_rpc_trace_starts_with_initialization(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(path!("/the-root"), json!({ "test.rs": "" }))
+        .await;
+    let project = Project::test(fs.clone(), [path!("/the-root").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )));
+    let mut fake_rust_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-rust-language-server",
+            ..Default::default()
+        },
+    );
+
+    let log_store = cx.new(|cx| LogStore::new(false, cx));
+    log_store.update(cx, |store, cx| store.add_project(&project, cx));
+    let _rust_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/the-root/test.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let language_server = fake_rust_servers.next().await.unwrap();
+    let server_id = language_server.server.server_id();
+    cx.executor().run_until_parked();
+
+    // Recording only starts after the server was initialized.
+    log_store.update(cx, |store, _| {
+        store.start_recording_rpc_trace(server_id);
+    });
+    assert!(log_store.read_with(cx, |store, _| store.is_recording_rpc_trace(server_id)));
+    language_server.notify::<lsp::notification::LogMessage>(lsp::LogMessageParams {
+        message: "hello from the server".into(),
+        typ: lsp::MessageType::INFO,
+    });
+    cx.executor().run_until_parked();
+
+    let trace = log_store.update(cx, |store, _| {
+        store.finish_recording_rpc_trace(server_id).unwrap()
+    });
+    assert!(!log_store.read_with(cx, |store, _| store.is_recording_rpc_trace(server_id)));
+    assert!(
+        log_store
+            .update(cx, |store, _| store.finish_recording_rpc_trace(server_id))
+            .is_err()
+    );
+    let entries = lsp::rpc_trace::parse_rpc_trace(&trace).unwrap();
+    let messages = entries
+        .iter()
+        .map(|entry| (entry.direction, entry.message["method"].as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages[..3],
+        [
+            (RpcDirection::Send, Some("initialize")),
+            (RpcDirection::Receive, None),
+            (RpcDirection::Send, Some("initialized")),
+        ]
+    );
+    assert!(messages[3..].contains(&(RpcDirection::Receive, Some("window/logMessage"))));
+    assert_eq!(entries[1].message["id"], entries[0].message["id"]);
+}
+
 fn init_test(cx: &mut gpui::TestAppContext) {
     cx.update(|cx| {
         let settings_store = SettingsStore::test(cx);
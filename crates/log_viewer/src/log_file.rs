@@ -0,0 +1,222 @@
+use serde::Deserialize;
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+    str::FromStr,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: log::Level,
+    /// The scope path, or the module path of records logged without a scope. Text lines include
+    /// the line number the record was logged at, like `project::lsp_store:1234`.
+    pub scope: String,
+    pub message: String,
+}
+
+/// A JSON line, as written by `zlog` with `ZED_LOG_FORMAT=json`.
+#[derive(Deserialize)]
+struct JsonRecord {
+    timestamp: String,
+    level: String,
+    scope: String,
+    message: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Parses the lines of a log file in either of the formats `zlog` writes. Lines that don't start a
+/// record, like the rest of a message spanning several lines, are appended to the previous entry.
+pub fn parse_lines<'a>(lines: impl IntoIterator<Item = &'a str>, entries: &mut Vec<LogEntry>) {
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(entry) = parse_line(line) {
+            entries.push(entry);
+        } else if let Some(previous) = entries.last_mut() {
+            previous.message.push('\n');
+            previous.message.push_str(line);
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    if line.starts_with('{') {
+        return parse_json_line(line);
+    }
+
+    // `{timestamp} {LEVEL} [{scope}] {message}`, with the level padded to five characters.
+    let (timestamp, rest) = line.split_once(' ')?;
+    if !timestamp.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let level = log::Level::from_str(rest.get(..5)?.trim_end()).ok()?;
+    let rest = rest.get(5..)?.strip_prefix(" [")?;
+    let (scope, message) = rest.split_once("] ").or_else(|| {
+        let scope = rest.strip_suffix(']')?;
+        Some((scope, ""))
+    })?;
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level,
+        scope: scope.to_string(),
+        message: message.to_string(),
+    })
+}
+
+fn parse_json_line(line: &str) -> Option<LogEntry> {
+    let record = serde_json::from_str::<JsonRecord>(line).ok()?;
+    let mut message = record.message;
+    for (key, value) in record.fields {
+        let value = match value {
+            serde_json::Value::String(value) => value,
+            value => value.to_string(),
+        };
+        message.push_str(&format!(" {key}={value}"));
+    }
+    Some(LogEntry {
+        timestamp: record.timestamp,
+        level: log::Level::from_str(&record.level).ok()?,
+        scope: record.scope,
+        message,
+    })
+}
+
+/// Reads what was appended to a log file since the last read.
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+    /// The start of a line that wasn't completely written yet. It's kept as bytes, since it may
+    /// end in the middle of a character.
+    partial_line: Vec<u8>,
+}
+
+impl LogTail {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial_line: Vec::new(),
+        }
+    }
+
+    /// Returns the complete lines written since the last call. Starts over from the beginning when
+    /// the file got shorter, which happens when `zlog` rotates it.
+    pub fn read_lines(&mut self) -> io::Result<Vec<String>> {
+        let mut file = fs::File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial_line.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = std::mem::take(&mut self.partial_line);
+        let read_len = file.take(len - self.offset).read_to_end(&mut bytes)?;
+        self.offset += read_len as u64;
+
+        let complete_len = bytes
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |ix| ix + 1);
+        self.partial_line = bytes.split_off(complete_len);
+        Ok(String::from_utf8_lossy(&bytes)
+            .lines()
+            .map(ToString::to_string)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write as _;
+
+    fn parse(text: &str) -> Vec<LogEntry> {
+        let mut entries = Vec::new();
+        parse_lines(text.lines(), &mut entries);
+        entries
+    }
+
+    #[test]
+    fn test_parse_text_lines() {
+        let entries = parse(
+            "2026-10-17T09:12:03+02:00 INFO  [zed] Zed started\n\
+             2026-10-17T09:12:04+02:00 ERROR [project::lsp_store:812] failed to start\n\
+             caused by: not found\n\
+             \n\
+             2026-10-17T09:12:05+02:00 DEBUG [gpui.window] ",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                LogEntry {
+                    timestamp: "2026-10-17T09:12:03+02:00".to_string(),
+                    level: log::Level::Info,
+                    scope: "zed".to_string(),
+                    message: "Zed started".to_string(),
+                },
+                LogEntry {
+                    timestamp: "2026-10-17T09:12:04+02:00".to_string(),
+                    level: log::Level::Error,
+                    scope: "project::lsp_store:812".to_string(),
+                    message: "failed to start\ncaused by: not found".to_string(),
+                },
+                LogEntry {
+                    timestamp: "2026-10-17T09:12:05+02:00".to_string(),
+                    level: log::Level::Debug,
+                    scope: "gpui.window".to_string(),
+                    message: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let entries = parse(
+            r#"{"timestamp":"2026-10-17T09:12:03.120+02:00","level":"WARN","scope":"extension_host","module":"extension_host::wasm_host","message":"slow \"init\"","fields":{"extension":"toml","ms":812}}"#,
+        );
+        assert_eq!(
+            entries,
+            vec![LogEntry {
+                timestamp: "2026-10-17T09:12:03.120+02:00".to_string(),
+                level: log::Level::Warn,
+                scope: "extension_host".to_string(),
+                message: "slow \"init\" extension=toml ms=812".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_tail_partial_and_rotated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Zed.log");
+        fs::write(&path, "first\nsec").unwrap();
+
+        let mut tail = LogTail::new(path.clone());
+        assert_eq!(tail.read_lines().unwrap(), vec!["first"]);
+        assert!(tail.read_lines().unwrap().is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"ond\nthird\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["second", "third"]);
+
+        fs::write(&path, "rotated\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["rotated"]);
+
+        // A character split between two reads is decoded once the line is complete.
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        let bytes = "café\n".as_bytes();
+        file.write_all(&bytes[..4]).unwrap();
+        assert!(tail.read_lines().unwrap().is_empty());
+        file.write_all(&bytes[4..]).unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["café"]);
+    }
+}
@@ -0,0 +1,489 @@
+mod log_file;
+
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, Empty, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, ListState,
+    SharedString, Subscription, Task, Window, actions, list, prelude::*,
+};
+use log_file::{LogEntry, LogTail};
+use menu::Confirm;
+use std::{str::FromStr, time::Duration};
+use ui::{ContextMenu, DropdownMenu, Tooltip, WithScrollbar, prelude::*};
+use util::ResultExt as _;
+use workspace::{Item, Workspace};
+
+actions!(
+    dev,
+    [
+        /// Opens a view of Zed's log that can be filtered, and that changes log levels while
+        /// Zed is running.
+        OpenLogViewer,
+    ]
+);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Older entries are dropped once the viewer holds this many.
+const MAX_ENTRIES: usize = 20_000;
+
+const LEVELS: [log::LevelFilter; 5] = [
+    log::LevelFilter::Error,
+    log::LevelFilter::Warn,
+    log::LevelFilter::Info,
+    log::LevelFilter::Debug,
+    log::LevelFilter::Trace,
+];
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &OpenLogViewer, window, cx| {
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items_of_type::<LogViewer>()
+                .next();
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+            } else {
+                let log_viewer = Box::new(cx.new(|cx| LogViewer::new(window, cx)));
+                workspace.add_item_to_active_pane(log_viewer, None, true, window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+pub struct LogViewer {
+    focus_handle: FocusHandle,
+    entries: Vec<LogEntry>,
+    /// Indices of the entries that pass the filters.
+    visible_entries: Vec<usize>,
+    list_state: ListState,
+    scope_filter_input: Entity<Editor>,
+    scope_filter: String,
+    level_filter: log::LevelFilter,
+    /// Sets a log level with `scope=level`.
+    level_input: Entity<Editor>,
+    error: Option<SharedString>,
+    _tail_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LogViewer {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let scope_filter_input = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter by scope…", window, cx);
+            editor
+        });
+        let level_input = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Set a level, e.g. project::lsp_store=debug", window, cx);
+            editor
+        });
+        let subscriptions =
+            vec![
+                cx.subscribe(&scope_filter_input, |this: &mut Self, input, event, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.scope_filter = input.read(cx).text(cx).trim().to_lowercase();
+                        this.refilter(cx);
+                    }
+                }),
+            ];
+
+        let tail_task = cx.spawn(async move |this, cx| {
+            let mut tail = LogTail::new(paths::log_file().clone());
+            loop {
+                let (returned_tail, lines) = cx
+                    .background_spawn(async move {
+                        let lines = tail.read_lines();
+                        (tail, lines)
+                    })
+                    .await;
+                tail = returned_tail;
+                let lines = lines.log_err().unwrap_or_default();
+                if !lines.is_empty()
+                    && this
+                        .update(cx, |this, cx| this.push_lines(&lines, cx))
+                        .is_err()
+                {
+                    break;
+                }
+                cx.background_executor().timer(POLL_INTERVAL).await;
+            }
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            entries: Vec::new(),
+            visible_entries: Vec::new(),
+            list_state: ListState::new(0, ListAlignment::Bottom, px(2048.)),
+            scope_filter_input,
+            scope_filter: String::new(),
+            level_filter: log::LevelFilter::Trace,
+            level_input,
+            error: None,
+            _tail_task: tail_task,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn push_lines(&mut self, lines: &[String], cx: &mut Context<Self>) {
+        let previous_len = self.entries.len();
+        // A message spanning several reads continues the last entry, so filter it again.
+        let first_changed = previous_len.saturating_sub(1);
+        log_file::parse_lines(lines.iter().map(String::as_str), &mut self.entries);
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+            self.refilter(cx);
+            return;
+        }
+
+        let first_visible_changed = self
+            .visible_entries
+            .partition_point(|ix| *ix < first_changed);
+        let new_visible = (first_changed..self.entries.len())
+            .filter(|ix| self.is_visible(&self.entries[*ix]))
+            .collect::<Vec<_>>();
+        let new_count = new_visible.len();
+        let old_range = first_visible_changed..self.visible_entries.len();
+        self.visible_entries.splice(old_range.clone(), new_visible);
+        self.list_state.splice(old_range, new_count);
+        cx.notify();
+    }
+
+    fn is_visible(&self, entry: &LogEntry) -> bool {
+        entry.level <= self.level_filter
+            && (self.scope_filter.is_empty()
+                || entry.scope.to_lowercase().contains(&self.scope_filter))
+    }
+
+    fn refilter(&mut self, cx: &mut Context<Self>) {
+        self.visible_entries = (0..self.entries.len())
+            .filter(|ix| self.is_visible(&self.entries[*ix]))
+            .collect();
+        self.list_state.reset(self.visible_entries.len());
+        cx.notify();
+    }
+
+    fn set_level_filter(&mut self, level: log::LevelFilter, cx: &mut Context<Self>) {
+        self.level_filter = level;
+        self.refilter(cx);
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.level_input.focus_handle(cx).is_focused(window) {
+            return;
+        }
+        let input = self.level_input.read(cx).text(cx);
+        if input.trim().is_empty() {
+            return;
+        }
+        match parse_scope_level(&input) {
+            Some((scope, level)) => {
+                zlog::filter::set_runtime_level(&scope, Some(level));
+                self.error = None;
+                self.level_input
+                    .update(cx, |editor, cx| editor.clear(window, cx));
+            }
+            None => {
+                self.error = Some(
+                    format!("Expected `scope=level` with a level like debug, got `{input}`").into(),
+                );
+            }
+        }
+        cx.notify();
+    }
+
+    fn reset_runtime_level(&mut self, scope: &str, cx: &mut Context<Self>) {
+        zlog::filter::set_runtime_level(scope, None);
+        cx.notify();
+    }
+
+    fn render_entry(
+        &mut self,
+        index: usize,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(entry) = self
+            .visible_entries
+            .get(index)
+            .and_then(|ix| self.entries.get(*ix))
+        else {
+            return Empty.into_any();
+        };
+        let level_color = match entry.level {
+            log::Level::Error => Color::Error,
+            log::Level::Warn => Color::Warning,
+            log::Level::Info => Color::Info,
+            log::Level::Debug | log::Level::Trace => Color::Muted,
+        };
+        let scope = SharedString::from(entry.scope.clone());
+
+        h_flex()
+            .id(index)
+            .w_full()
+            .px_2()
+            .py_0p5()
+            .gap_2()
+            .items_start()
+            .font_buffer(cx)
+            .text_buffer(cx)
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(entry.timestamp.clone())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                div().w(rems(3.)).flex_none().child(
+                    Label::new(entry.level.as_str())
+                        .buffer_font(cx)
+                        .size(LabelSize::Small)
+                        .color(level_color),
+                ),
+            )
+            .child(
+                div()
+                    .id(("scope", index))
+                    .flex_none()
+                    .cursor_pointer()
+                    .tooltip(Tooltip::text("Filter by this scope"))
+                    .on_click(cx.listener({
+                        let scope = scope.clone();
+                        move |this, _, window, cx| {
+                            let scope = scope_without_line(&scope).to_string();
+                            this.scope_filter_input.update(cx, |editor, cx| {
+                                editor.set_text(scope, window, cx);
+                            });
+                        }
+                    }))
+                    .child(
+                        Label::new(scope)
+                            .buffer_font(cx)
+                            .size(LabelSize::Small)
+                            .color(Color::Accent),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .whitespace_normal()
+                    .child(entry.message.clone()),
+            )
+            .into_any()
+    }
+
+    fn render_level_dropdown(&self, window: &mut Window, cx: &mut Context<Self>) -> DropdownMenu {
+        let weak = cx.weak_entity();
+        let current = self.level_filter;
+        DropdownMenu::new(
+            "log-level-filter",
+            level_filter_label(current),
+            ContextMenu::build(window, cx, move |mut menu, _, _| {
+                for level in LEVELS {
+                    let weak = weak.clone();
+                    menu = menu.toggleable_entry(
+                        level_filter_label(level),
+                        level == current,
+                        IconPosition::Start,
+                        None,
+                        move |_, cx| {
+                            weak.update(cx, |this, cx| this.set_level_filter(level, cx))
+                                .log_err();
+                        },
+                    );
+                }
+                menu
+            }),
+        )
+    }
+
+    fn render_runtime_levels(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let runtime_levels = zlog::filter::runtime_levels();
+        h_flex()
+            .flex_wrap()
+            .gap_1()
+            .children(
+                runtime_levels
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, (scope, level))| {
+                        h_flex()
+                            .gap_0p5()
+                            .pl_1p5()
+                            .rounded_sm()
+                            .bg(cx.theme().colors().element_background)
+                            .child(
+                                Label::new(format!(
+                                    "{scope}={}",
+                                    level.as_str().to_ascii_lowercase()
+                                ))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small),
+                            )
+                            .child(
+                                IconButton::new(("reset-level", ix), IconName::Close)
+                                    .icon_size(IconSize::XSmall)
+                                    .tooltip(Tooltip::text("Go back to the configured level"))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.reset_runtime_level(&scope, cx);
+                                    })),
+                            )
+                    }),
+            )
+    }
+}
+
+fn level_filter_label(level: log::LevelFilter) -> &'static str {
+    match level {
+        log::LevelFilter::Off => "Off",
+        log::LevelFilter::Error => "Error",
+        log::LevelFilter::Warn => "Warn",
+        log::LevelFilter::Info => "Info",
+        log::LevelFilter::Debug => "Debug",
+        log::LevelFilter::Trace => "Trace",
+    }
+}
+
+/// Parses `scope=level`, where the scope is a dot separated scope or a module path.
+fn parse_scope_level(input: &str) -> Option<(String, log::LevelFilter)> {
+    let (scope, level) = input.split_once('=')?;
+    let scope = scope.trim();
+    if scope.is_empty() {
+        return None;
+    }
+    let level = log::LevelFilter::from_str(level.trim()).ok()?;
+    Some((scope.to_string(), level))
+}
+
+/// Text log lines append the line number to module paths, which isn't part of the scope.
+fn scope_without_line(scope: &str) -> &str {
+    match scope.rsplit_once(':') {
+        Some((scope, line))
+            if !scope.ends_with(':') && line.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            scope
+        }
+        _ => scope,
+    }
+}
+
+impl EventEmitter<()> for LogViewer {}
+
+impl Item for LogViewer {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Zed Log".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::FileTextOutlined))
+    }
+}
+
+impl Focusable for LogViewer {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LogViewer {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_runtime_levels = !zlog::filter::runtime_levels().is_empty();
+        v_flex()
+            .key_context("LogViewer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().flex_1().child(self.scope_filter_input.clone()))
+                            .child(self.render_level_dropdown(window, cx)),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().flex_1().child(self.level_input.clone())),
+                    )
+                    .when_some(self.error.clone(), |this, error| {
+                        this.child(Label::new(error).color(Color::Error).size(LabelSize::Small))
+                    })
+                    .when(has_runtime_levels, |this| {
+                        this.child(self.render_runtime_levels(cx))
+                    }),
+            )
+            .child(if self.visible_entries.is_empty() {
+                h_flex()
+                    .size_full()
+                    .justify_center()
+                    .items_center()
+                    .child(
+                        Label::new(if self.entries.is_empty() {
+                            "The log is empty"
+                        } else {
+                            "No entries match the filters"
+                        })
+                        .color(Color::Muted),
+                    )
+                    .into_any()
+            } else {
+                div()
+                    .size_full()
+                    .flex_grow()
+                    .child(
+                        list(self.list_state.clone(), cx.processor(Self::render_entry))
+                            .with_sizing_behavior(gpui::ListSizingBehavior::Auto)
+                            .size_full(),
+                    )
+                    .vertical_scrollbar_for(&self.list_state, window, cx)
+                    .into_any()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scope_level() {
+        assert_eq!(
+            parse_scope_level(" project::lsp_store = Debug "),
+            Some(("project::lsp_store".to_string(), log::LevelFilter::Debug))
+        );
+        assert_eq!(
+            parse_scope_level("gpui.window=off"),
+            Some(("gpui.window".to_string(), log::LevelFilter::Off))
+        );
+        assert_eq!(parse_scope_level("=debug"), None);
+        assert_eq!(parse_scope_level("project=loud"), None);
+        assert_eq!(parse_scope_level("project"), None);
+    }
+
+    #[test]
+    fn test_scope_without_line() {
+        assert_eq!(
+            scope_without_line("project::lsp_store:812"),
+            "project::lsp_store"
+        );
+        assert_eq!(
+            scope_without_line("project::lsp_store"),
+            "project::lsp_store"
+        );
+        assert_eq!(scope_without_line("gpui.window"), "gpui.window");
+    }
+}
@@ -1,4 +1,5 @@
 mod input_handler;
+pub mod rpc_trace;
 
 pub use lsp_types::request::*;
 pub use lsp_types::*;
@@ -16,6 +17,7 @@ use gpui::{App, AppContext as _, AsyncApp, BackgroundExecutor, SharedString, Tas
 use notification::DidChangeWorkspaceFolders;
 use parking_lot::{Mutex, RwLock};
 use postage::{barrier, prelude::Stream};
+use rpc_trace::{RpcDirection, RpcTraceEntry};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::{Value, json, value::RawValue};
@@ -110,6 +112,8 @@ pub struct LanguageServer {
     /// buffer. This is represented as the message sent to the LSP in order to avoid cloning it (can
     /// be large in cases like sending schemas to the json server).
     configuration: Arc<DidChangeConfigurationParams>,
+    /// The initialization handshake, which RPC traces of the session have to start with.
+    initialize_trace: Vec<RpcTraceEntry>,
     code_action_kinds: Option<Vec<CodeActionKind>>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
@@ -594,6 +598,7 @@ impl LanguageServer {
             binary,
             capabilities: Default::default(),
             configuration,
+            initialize_trace: Vec::new(),
             code_action_kinds,
             next_id: Default::default(),
             outbound_tx,
@@ -987,6 +992,19 @@ impl LanguageServer {
                     code_lens: Some(CodeLensClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    call_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    type_hierarchy: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: None,
+                    }),
+                    selection_range: Some(SelectionRangeClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(true),
+                        tooltip_support: Some(true),
+                    }),
                     document_symbol: Some(DocumentSymbolClientCapabilities {
                         hierarchical_document_symbol_support: Some(true),
                         dynamic_registration: Some(true),
@@ -1056,6 +1074,12 @@ impl LanguageServer {
         cx: &App,
     ) -> Task<Result<Arc<Self>>> {
         cx.background_spawn(async move {
+            let request_json = json!({
+                "jsonrpc": JSON_RPC_VERSION,
+                "id": 0,
+                "method": <request::Initialize as request::Request>::METHOD,
+                "params": params,
+            });
             let response = self
                 .request::<request::Initialize>(params, timeout)
                 .await
@@ -1067,6 +1091,26 @@ impl LanguageServer {
                         self.server_id()
                     )
                 })?;
+            // The ids of the recorded requests only have to be consistent with each other, since
+            // replays map them to the ids the editor uses then.
+            self.initialize_trace = vec![
+                RpcTraceEntry {
+                    direction: RpcDirection::Send,
+                    message: request_json,
+                },
+                RpcTraceEntry {
+                    direction: RpcDirection::Receive,
+                    message: json!({ "jsonrpc": JSON_RPC_VERSION, "id": 0, "result": response }),
+                },
+                RpcTraceEntry {
+                    direction: RpcDirection::Send,
+                    message: json!({
+                        "jsonrpc": JSON_RPC_VERSION,
+                        "method": <notification::Initialized as notification::Notification>::METHOD,
+                        "params": {},
+                    }),
+                },
+            ];
             if let Some(info) = response.server_info {
                 self.version = info.version.map(SharedString::from);
                 self.process_name = info.name.into();
@@ -1334,6 +1378,11 @@ impl LanguageServer {
         &self.process_name
     }
 
+    /// The messages exchanged while initializing the server, as an RPC trace.
+    pub fn initialize_trace(&self) -> &[RpcTraceEntry] {
+        &self.initialize_trace
+    }
+
     /// Get the reported capabilities of the running language server.
     pub fn capabilities(&self) -> ServerCapabilities {
         self.capabilities.read().clone()
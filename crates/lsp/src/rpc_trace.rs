@@ -0,0 +1,331 @@
+//! JSON-RPC traffic of a language server session, stored as JSON lines.
+//!
+//! Each line holds one message and the direction it went in. A trace exported from the language
+//! server logs can be replayed against a [`crate::FakeLanguageServer`], which answers the editor
+//! with the recorded responses and sends the recorded notifications and requests at the same
+//! points of the session, so misbehaving servers can be reproduced without running them.
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcDirection {
+    /// From the editor to the language server.
+    Send,
+    /// From the language server to the editor.
+    Receive,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RpcTraceEntry {
+    pub direction: RpcDirection,
+    pub message: Value,
+}
+
+impl RpcTraceEntry {
+    pub fn new(direction: RpcDirection, message: &str) -> Result<Self> {
+        Ok(Self {
+            direction,
+            message: serde_json::from_str(message).context("parsing JSON-RPC message")?,
+        })
+    }
+
+    pub fn to_json_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+
+    fn method(&self) -> Option<&str> {
+        self.message.get("method").and_then(Value::as_str)
+    }
+
+    fn id(&self) -> Option<&Value> {
+        self.message.get("id")
+    }
+}
+
+/// Parses a trace written with [`RpcTraceEntry::to_json_line`]. Blank lines are skipped.
+pub fn parse_rpc_trace(jsonl: &str) -> Result<Vec<RpcTraceEntry>> {
+    jsonl
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(ix, line)| {
+            serde_json::from_str(line).with_context(|| format!("parsing trace line {}", ix + 1))
+        })
+        .collect()
+}
+
+/// Removes the responses to requests that aren't in the trace, which a trace recorded from the
+/// middle of a session can have, since a replay couldn't tell what they respond to.
+pub fn remove_unmatched_responses(entries: Vec<RpcTraceEntry>) -> Vec<RpcTraceEntry> {
+    // The ids of the requests waiting for a response, by the direction of the request.
+    let mut pending_requests: Vec<(RpcDirection, Value)> = Vec::new();
+    entries
+        .into_iter()
+        .filter(|entry| {
+            let Some(id) = entry.id() else {
+                return true;
+            };
+            if entry.method().is_some() {
+                pending_requests.push((entry.direction, id.clone()));
+                return true;
+            }
+            let request_direction = match entry.direction {
+                RpcDirection::Send => RpcDirection::Receive,
+                RpcDirection::Receive => RpcDirection::Send,
+            };
+            match pending_requests
+                .iter()
+                .position(|request| *request == (request_direction, id.clone()))
+            {
+                Some(ix) => {
+                    pending_requests.remove(ix);
+                    true
+                }
+                None => false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(any(test, feature = "test-support"))]
+mod replay {
+    use super::*;
+    use crate::{FakeLanguageServer, IoKind, NotificationSerializer, request};
+    use futures::{StreamExt as _, channel::mpsc};
+    use gpui::Task;
+
+    impl FakeLanguageServer {
+        /// Replays a recorded session against the editor.
+        ///
+        /// Recorded messages from the editor are waited for by method, in any order, and the
+        /// messages recorded from the server in between are sent once everything before them was
+        /// received. Responses are sent with the ids the editor used for its requests. The task
+        /// resolves once the whole trace was replayed, and fails if the editor disconnects first.
+        ///
+        /// This replaces the default handlers of the fake server, so the trace should start
+        /// with the editor's `initialize` request.
+        pub fn replay(&self, trace: Vec<RpcTraceEntry>) -> Task<Result<()>> {
+            self.server.remove_request_handler::<request::Initialize>();
+            self.server.remove_request_handler::<request::Shutdown>();
+
+            let (incoming_tx, mut incoming_rx) = mpsc::unbounded();
+            let io_subscription = self.server.on_io(move |kind, message| {
+                if matches!(kind, IoKind::StdOut) {
+                    incoming_tx.unbounded_send(message.to_string()).ok();
+                }
+            });
+            let outgoing_tx = self.server.notification_tx.clone();
+
+            self.server.executor.spawn(async move {
+                let _io_subscription = io_subscription;
+                // Messages from the editor that weren't expected yet.
+                let mut unmatched: Vec<RpcTraceEntry> = Vec::new();
+                // Maps the ids of recorded requests from the editor to the ids it used now.
+                let mut request_ids: Vec<(Value, Value)> = Vec::new();
+
+                for (ix, expected) in trace.into_iter().enumerate() {
+                    match expected.direction {
+                        RpcDirection::Send => {
+                            let matches = |message: &RpcTraceEntry| match expected.method() {
+                                Some(method) => message.method() == Some(method),
+                                None => message.method().is_none() && message.id() == expected.id(),
+                            };
+                            let message = loop {
+                                if let Some(position) =
+                                    unmatched.iter().position(|message| matches(message))
+                                {
+                                    break unmatched.remove(position);
+                                }
+                                let message = incoming_rx.next().await.with_context(|| {
+                                    format!(
+                                        "editor disconnected before sending trace message {}",
+                                        ix + 1
+                                    )
+                                })?;
+                                unmatched.push(RpcTraceEntry::new(RpcDirection::Send, &message)?);
+                            };
+                            if let (Some(_), Some(recorded_id), Some(id)) =
+                                (expected.method(), expected.id(), message.id())
+                            {
+                                request_ids.push((recorded_id.clone(), id.clone()));
+                            }
+                        }
+                        RpcDirection::Receive => {
+                            let mut message = expected.message;
+                            let is_response = message.get("method").is_none();
+                            if is_response && let Some(recorded_id) = message.get("id") {
+                                let position = request_ids
+                                    .iter()
+                                    .position(|(id, _)| id == recorded_id)
+                                    .with_context(|| {
+                                        format!(
+                                            "trace message {} responds to a request that \
+                                            wasn't sent",
+                                            ix + 1
+                                        )
+                                    })?;
+                                message["id"] = request_ids.remove(position).1;
+                            }
+                            let message = serde_json::to_string(&message)?;
+                            outgoing_tx
+                                .send(NotificationSerializer(Box::new(move || message)))
+                                .await
+                                .ok()
+                                .context("editor disconnected")?;
+                        }
+                    }
+                }
+
+                for message in unmatched {
+                    log::info!("message from the editor wasn't in the trace: {message:?}");
+                }
+                Ok(())
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DEFAULT_LSP_REQUEST_TIMEOUT, DidChangeConfigurationParams, DidOpenTextDocumentParams,
+        FakeLanguageServer, HoverProviderCapability, LanguageServerBinary, LanguageServerId,
+        TextDocumentItem, Uri, notification,
+    };
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use std::str::FromStr as _;
+
+    #[test]
+    fn test_rpc_trace_round_trip() {
+        let entries = vec![
+            RpcTraceEntry::new(
+                RpcDirection::Send,
+                r#"{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}"#,
+            )
+            .unwrap(),
+            RpcTraceEntry::new(
+                RpcDirection::Receive,
+                r#"{"jsonrpc":"2.0","id":0,"result":{"capabilities":{}}}"#,
+            )
+            .unwrap(),
+        ];
+        let jsonl = entries
+            .iter()
+            .map(RpcTraceEntry::to_json_line)
+            .collect::<String>();
+        assert_eq!(
+            jsonl.lines().next().unwrap(),
+            r#"{"direction":"send","message":{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}}"#
+        );
+        assert_eq!(parse_rpc_trace(&format!("{jsonl}\n")).unwrap(), entries);
+        assert_eq!(entries[1].message["result"], json!({ "capabilities": {} }));
+    }
+
+    #[test]
+    fn test_parse_rpc_trace_reports_line() {
+        let error =
+            parse_rpc_trace("\n{\"direction\":\"send\",\"message\":{}}\nnot json\n").unwrap_err();
+        assert_eq!(error.to_string(), "parsing trace line 3");
+    }
+
+    #[test]
+    fn test_remove_unmatched_responses() {
+        let trace = parse_rpc_trace(
+            r#"
+{"direction":"send","message":{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}}
+{"direction":"receive","message":{"jsonrpc":"2.0","id":0,"result":{"capabilities":{}}}}
+{"direction":"receive","message":{"jsonrpc":"2.0","id":7,"result":null}}
+{"direction":"send","message":{"jsonrpc":"2.0","id":3,"result":null}}
+{"direction":"receive","message":{"jsonrpc":"2.0","id":3,"method":"workspace/configuration","params":{}}}
+{"direction":"send","message":{"jsonrpc":"2.0","id":3,"result":[]}}
+"#,
+        )
+        .unwrap();
+        let kept = remove_unmatched_responses(trace.clone());
+        assert_eq!(
+            kept,
+            [&trace[0], &trace[1], &trace[4], &trace[5]].map(Clone::clone)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            release_channel::init(semver::Version::new(0, 0, 0), cx);
+        });
+        let (server, fake) = FakeLanguageServer::new(
+            LanguageServerId(0),
+            LanguageServerBinary {
+                path: "path/to/language-server".into(),
+                arguments: vec![],
+                env: None,
+            },
+            "the-lsp".to_string(),
+            Default::default(),
+            &mut cx.to_async(),
+        );
+        // The editor's request ids are different from the recorded ones.
+        let replay = fake.replay(
+            parse_rpc_trace(
+                r#"
+{"direction":"send","message":{"jsonrpc":"2.0","id":41,"method":"initialize","params":{}}}
+{"direction":"receive","message":{"jsonrpc":"2.0","id":41,"result":{"capabilities":{"hoverProvider":true}}}}
+{"direction":"send","message":{"jsonrpc":"2.0","method":"initialized","params":{}}}
+{"direction":"send","message":{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{}}}
+{"direction":"receive","message":{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{"uri":"file:///a/b","diagnostics":[]}}}
+"#,
+            )
+            .unwrap(),
+        );
+
+        let (diagnostics_tx, diagnostics_rx) = smol::channel::unbounded();
+        server
+            .on_notification::<notification::PublishDiagnostics, _>(move |params, _| {
+                diagnostics_tx.try_send(params).unwrap()
+            })
+            .detach();
+
+        let server = cx
+            .update(|cx| {
+                let params = server.default_initialize_params(false, false, cx);
+                let configuration = DidChangeConfigurationParams {
+                    settings: Default::default(),
+                };
+                server.initialize(
+                    params,
+                    configuration.into(),
+                    DEFAULT_LSP_REQUEST_TIMEOUT,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            server.capabilities().hover_provider,
+            Some(HoverProviderCapability::Simple(true))
+        );
+
+        server
+            .notify::<notification::DidOpenTextDocument>(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(
+                    Uri::from_str("file:///a/b").unwrap(),
+                    "rust".to_string(),
+                    0,
+                    "".to_string(),
+                ),
+            })
+            .unwrap();
+        assert_eq!(
+            diagnostics_rx.recv().await.unwrap().uri.as_str(),
+            "file:///a/b"
+        );
+        replay.await.unwrap();
+    }
+}
@@ -15,14 +15,15 @@ use gpui::{
 use rpc::{AnyProtoClient, proto};
 use util::ResultExt;
 use workspace::{
-    Workspace,
+    Toast, Workspace,
+    notifications::NotificationId,
     ui::{
         ActiveTheme, Button, ButtonCommon, ButtonStyle, Checkbox, Clickable, ContextMenu, Divider,
         DropdownMenu, ScrollAxes, ScrollableHandle as _, Scrollbars, ToggleState, Tooltip,
         WithScrollbar, h_flex, v_flex,
     },
 };
-use zed_actions::OpenPerformanceProfiler;
+use zed_actions::{OpenPerformanceProfiler, ToggleTraceRecording};
 
 const NANOS_PER_MS: u128 = 1_000_000;
 const VISIBLE_WINDOW_NANOS: u128 = 10 * 1_000_000_000;
@@ -67,6 +68,76 @@ pub fn init(startup_time: Instant, cx: &mut App) {
         workspace.register_action(move |_workspace, _: &OpenPerformanceProfiler, window, cx| {
             open_performance_profiler(startup_time, workspace_handle.clone(), window, cx);
         });
+        workspace.register_action(|workspace, _: &ToggleTraceRecording, _window, cx| {
+            toggle_trace_recording(workspace, cx);
+        });
+    })
+    .detach();
+
+    if let Some(path) = ztracing::export_path_from_env() {
+        cx.on_app_quit(move |_| {
+            if let Some(trace) = ztracing::stop_recording() {
+                trace.write(&path).log_err();
+            }
+            async {}
+        })
+        .detach();
+    }
+}
+
+fn toggle_trace_recording(workspace: &mut Workspace, cx: &mut Context<Workspace>) {
+    struct TraceRecording;
+
+    let notification_id = NotificationId::unique::<TraceRecording>();
+    if !ztracing::is_available() {
+        workspace.show_toast(
+            Toast::new(
+                notification_id,
+                "Recording traces requires building Zed with ZTRACING=1",
+            ),
+            cx,
+        );
+        return;
+    }
+    let Some(trace) = ztracing::stop_recording() else {
+        ztracing::start_recording();
+        workspace.show_toast(
+            Toast::new(
+                notification_id,
+                "Recording trace, run the action again to save it",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    };
+
+    let directory = workspace
+        .most_recent_active_path(cx)
+        .and_then(|path| path.parent().map(|path| path.to_owned()))
+        .unwrap_or_default();
+    // Naming the file `*.otlp.json` saves it as OTLP instead.
+    let path = cx.prompt_for_new_path(&directory, Some("zed-trace.json"));
+    cx.spawn(async move |workspace, cx| {
+        let Some(path) = path.await.log_err().and_then(|p| p.log_err()).flatten() else {
+            return;
+        };
+        let span_count = trace.spans.len();
+        let result = cx
+            .background_spawn({
+                let path = path.clone();
+                async move { trace.write(&path) }
+            })
+            .await;
+        let message = match result {
+            Ok(()) => format!("Saved {span_count} spans to {}", path.display()),
+            Err(error) => format!("Failed to save trace: {error}"),
+        };
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(Toast::new(notification_id, message), cx)
+            })
+            .ok();
     })
     .detach();
 }
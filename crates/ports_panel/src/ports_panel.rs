@@ -0,0 +1,468 @@
+mod ports_panel_settings;
+
+use anyhow::Result;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, SharedString, Subscription, Task, WeakEntity, Window, actions,
+};
+use menu::Confirm;
+use project::{Fs, Project};
+use remote::{PortForward, RemoteClient, RemoteConnectionOptions, SshPortForwardOption};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{ListItem, Tab, Tooltip, prelude::*};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::{NotificationId, simple_message_notification::MessageNotification},
+};
+
+pub use ports_panel_settings::PortsPanelSettings;
+
+const PORTS_PANEL_KEY: &str = "PortsPanel";
+
+actions!(
+    ports_panel,
+    [
+        /// Focuses the input for forwarding a port in the ports panel.
+        ForwardPort,
+        /// Toggles focus on the ports panel.
+        ToggleFocus,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<PortsPanel>(window, cx);
+            })
+            .register_action(|workspace, _: &ForwardPort, window, cx| {
+                if let Some(panel) = workspace.focus_panel::<PortsPanel>(window, cx) {
+                    panel.update(cx, |panel, cx| {
+                        window.focus(&panel.port_input.focus_handle(cx), cx);
+                    });
+                }
+            });
+    })
+    .detach();
+}
+
+pub struct PortsPanel {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    port_input: Entity<Editor>,
+    /// Ports of servers that terminals reported listening on the remote host, in the order they
+    /// were detected.
+    detected_ports: Vec<u16>,
+    error: Option<SharedString>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PortsPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, window, cx| Self::new(workspace, window, cx))
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let weak_workspace = workspace.weak_handle();
+        cx.new(|cx| {
+            let port_input = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Port or local_port:host:remote_port", window, cx);
+                editor
+            });
+
+            let mut subscriptions =
+                vec![cx.subscribe_in(&project, window, |this, _, event, _, cx| {
+                    if let project::Event::LocalhostPortDetected(port) = event {
+                        this.port_detected(*port, cx);
+                    }
+                })];
+            if let Some(remote_client) = project.read(cx).remote_client() {
+                subscriptions.push(cx.observe(&remote_client, |_, _, cx| cx.notify()));
+            }
+
+            Self {
+                project,
+                workspace: weak_workspace,
+                fs,
+                focus_handle: cx.focus_handle(),
+                port_input,
+                detected_ports: Vec::new(),
+                error: None,
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    /// The remote client of the project, if it is connected over SSH. Ports can't be forwarded
+    /// for other kinds of remote projects.
+    fn ssh_remote_client(&self, cx: &App) -> Option<Entity<RemoteClient>> {
+        let remote_client = self.project.read(cx).remote_client()?;
+        matches!(
+            remote_client.read(cx).connection_options(),
+            RemoteConnectionOptions::Ssh(_)
+        )
+        .then_some(remote_client)
+    }
+
+    fn port_forwards(&self, cx: &App) -> Vec<PortForward> {
+        self.ssh_remote_client(cx)
+            .map(|remote_client| remote_client.read(cx).port_forwards())
+            .unwrap_or_default()
+    }
+
+    fn is_forwarded(&self, port: u16, cx: &App) -> bool {
+        self.port_forwards(cx)
+            .iter()
+            .any(|forward| forward.option.remote_port == port)
+    }
+
+    fn port_detected(&mut self, port: u16, cx: &mut Context<Self>) {
+        if self.ssh_remote_client(cx).is_none()
+            || self.detected_ports.contains(&port)
+            || self.is_forwarded(port, cx)
+        {
+            return;
+        }
+        self.detected_ports.push(port);
+        cx.notify();
+
+        struct PortDetectedNotification;
+
+        let panel = cx.entity().downgrade();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_notification(
+                    NotificationId::composite::<PortDetectedNotification>(SharedString::from(
+                        port.to_string(),
+                    )),
+                    cx,
+                    |cx| {
+                        cx.new(|cx| {
+                            MessageNotification::new(
+                                format!(
+                                    "A server is listening on port {port} of the remote host. \
+                                    Do you want to forward it to localhost:{port}?"
+                                ),
+                                cx,
+                            )
+                            .primary_message("Forward Port")
+                            .primary_icon(IconName::Check)
+                            .primary_icon_color(Color::Success)
+                            .primary_on_click(move |_, cx| {
+                                panel
+                                    .update(cx, |panel, cx| {
+                                        panel.forward_port(same_port_forward(port), cx)
+                                    })
+                                    .ok();
+                            })
+                            .secondary_message("Show Ports")
+                            .secondary_icon(IconName::Server)
+                            .secondary_on_click(|window, cx| {
+                                window.dispatch_action(ToggleFocus.boxed_clone(), cx)
+                            })
+                        })
+                    },
+                );
+            })
+            .ok();
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let spec = self.port_input.read(cx).text(cx);
+        if spec.trim().is_empty() {
+            return;
+        }
+        match remote::parse_port_forward(&spec) {
+            Ok(option) => {
+                self.port_input
+                    .update(cx, |editor, cx| editor.clear(window, cx));
+                self.forward_port(option, cx);
+            }
+            Err(error) => {
+                self.error = Some(format!("{error:#}").into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn forward_port(&mut self, option: SshPortForwardOption, cx: &mut Context<Self>) {
+        let Some(remote_client) = self.ssh_remote_client(cx) else {
+            return;
+        };
+        self.error = None;
+        let task = remote_client.update(cx, |client, cx| client.add_port_forward(option, cx));
+        self.update_after(task, cx);
+    }
+
+    fn stop_forwarding_port(&mut self, option: &SshPortForwardOption, cx: &mut Context<Self>) {
+        let Some(remote_client) = self.ssh_remote_client(cx) else {
+            return;
+        };
+        self.error = None;
+        let task = remote_client.update(cx, |client, cx| client.remove_port_forward(option, cx));
+        self.update_after(task, cx);
+    }
+
+    fn update_after(&mut self, task: Task<Result<()>>, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                if let Err(error) = result {
+                    this.error = Some(format!("{error:#}").into());
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn render_forward(
+        &self,
+        ix: usize,
+        forward: PortForward,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let PortForward {
+            option,
+            from_settings,
+        } = forward;
+        let local_port = option.local_port;
+        let remote_host = option.remote_host.as_deref().unwrap_or("localhost");
+        let local_address = match option.local_host.as_deref() {
+            Some(host) => format!("{host}:{local_port}"),
+            None => format!("localhost:{local_port}"),
+        };
+
+        ListItem::new(("port-forward", ix))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(local_address))
+                    .child(
+                        Icon::new(IconName::ArrowRight)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(format!("{remote_host}:{}", option.remote_port))
+                            .color(Color::Muted),
+                    )
+                    .when(from_settings, |this| {
+                        this.child(
+                            Label::new("From settings")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        )
+                    }),
+            )
+            .end_slot(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new(("open-port-forward", ix), IconName::ArrowUpRight)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Open in Browser"))
+                            .on_click(move |_, _, cx| {
+                                cx.open_url(&format!("http://localhost:{local_port}"))
+                            }),
+                    )
+                    .when(!from_settings, |this| {
+                        this.child(
+                            IconButton::new(("remove-port-forward", ix), IconName::Close)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Stop Forwarding Port"))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.stop_forwarding_port(&option, cx);
+                                })),
+                        )
+                    }),
+            )
+    }
+
+    fn render_detected_port(&self, port: u16, cx: &mut Context<Self>) -> impl IntoElement {
+        ListItem::new(("detected-port", port as usize))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(format!("localhost:{port}")))
+                    .child(
+                        Label::new("Detected in terminal output")
+                            .color(Color::Muted)
+                            .size(LabelSize::Small),
+                    ),
+            )
+            .end_slot(
+                Button::new(("forward-detected-port", port as usize), "Forward")
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.forward_port(same_port_forward(port), cx);
+                    })),
+            )
+    }
+}
+
+fn same_port_forward(port: u16) -> SshPortForwardOption {
+    SshPortForwardOption {
+        local_host: None,
+        local_port: port,
+        remote_host: None,
+        remote_port: port,
+    }
+}
+
+impl Render for PortsPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_ssh = self.ssh_remote_client(cx).is_some();
+        let forwards = self.port_forwards(cx);
+        let detected_ports = self
+            .detected_ports
+            .iter()
+            .copied()
+            .filter(|port| !self.is_forwarded(*port, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("PortsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Forwarded Ports")),
+            )
+            .map(|this| {
+                if !is_ssh {
+                    return this.child(
+                        v_flex().p_4().child(
+                            Label::new("Ports can be forwarded in projects opened over SSH.")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    );
+                }
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.port_input.clone()),
+                )
+                .when_some(self.error.clone(), |this, error| {
+                    this.child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(error).color(Color::Error).size(LabelSize::Small)),
+                    )
+                })
+                .child(
+                    v_flex()
+                        .id("port-forwards")
+                        .overflow_y_scroll()
+                        .size_full()
+                        .p_1()
+                        .when(forwards.is_empty() && detected_ports.is_empty(), |this| {
+                            this.child(
+                                v_flex().p_3().child(
+                                    Label::new(
+                                        "No ports are forwarded. Enter a port above to forward it.",
+                                    )
+                                    .color(Color::Muted)
+                                    .size(LabelSize::Small),
+                                ),
+                            )
+                        })
+                        .children(
+                            forwards
+                                .into_iter()
+                                .enumerate()
+                                .map(|(ix, forward)| self.render_forward(ix, forward, cx)),
+                        )
+                        .children(
+                            detected_ports
+                                .into_iter()
+                                .map(|port| self.render_detected_port(port, cx)),
+                        ),
+                )
+            })
+    }
+}
+
+impl Focusable for PortsPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for PortsPanel {}
+
+impl Panel for PortsPanel {
+    fn persistent_name() -> &'static str {
+        "PortsPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        PORTS_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        PortsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.ports_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn default_size(&self, _: &Window, cx: &App) -> Pixels {
+        PortsPanelSettings::get_global(cx).default_width
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        // The panel is only useful in projects opened over SSH.
+        (PortsPanelSettings::get_global(cx).button && self.ssh_remote_client(cx).is_some())
+            .then_some(IconName::Server)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Ports Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        9
+    }
+}
@@ -23,7 +23,7 @@ use rpc::{
 };
 
 use settings::Settings;
-use std::{io, sync::Arc, time::Instant};
+use std::{collections::BTreeMap, io, sync::Arc, time::Instant};
 use text::{BufferId, ReplicaId};
 use util::{ResultExt as _, TryFutureExt, debug_panic, maybe, rel_path::RelPath};
 use worktree::{File, PathChange, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings};
@@ -71,6 +71,30 @@ struct RemoteBufferStore {
     remote_buffer_listeners:
         HashMap<BufferId, Vec<oneshot::Sender<anyhow::Result<Entity<Buffer>>>>>,
     worktree_store: Entity<WorktreeStore>,
+    /// Whether the connection to the remote server is lost, in which case saves are queued
+    /// until it is restored.
+    offline: bool,
+    unsynced_saves: BTreeMap<BufferId, UnsyncedSave>,
+}
+
+/// A save of a remote buffer that was made while disconnected from the remote server.
+struct UnsyncedSave {
+    buffer: Entity<Buffer>,
+    /// The modification time of the file when the buffer was saved. The save is only replayed if
+    /// the file still has it.
+    mtime: Option<proto::Timestamp>,
+    state: UnsyncedBufferState,
+    replaying: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsyncedBufferState {
+    /// The save will be replayed once the connection to the remote server is restored.
+    Pending,
+    /// The file changed on the remote server before the save could be replayed.
+    Conflict,
+    /// Replaying the save failed.
+    Failed,
 }
 
 struct LocalBufferStore {
@@ -92,6 +116,7 @@ pub enum BufferStoreEvent {
         buffer: Entity<Buffer>,
         old_file: Option<Arc<dyn language::File>>,
     },
+    UnsyncedBuffersChanged,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -134,6 +159,7 @@ impl RemoteBufferStore {
         &self,
         buffer_handle: Entity<Buffer>,
         new_path: Option<proto::ProjectPath>,
+        expected_mtime: Option<proto::Timestamp>,
         cx: &Context<BufferStore>,
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
@@ -148,6 +174,7 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    expected_mtime,
                 })
                 .await?;
             let version = deserialize_version(&response.version);
@@ -161,6 +188,89 @@ impl RemoteBufferStore {
         })
     }
 
+    fn queue_save(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<BufferStore>,
+    ) -> Task<Result<()>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        let mtime = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.disk_state().mtime())
+            .map(proto::Timestamp::from);
+        self.unsynced_saves.insert(
+            buffer_id,
+            UnsyncedSave {
+                buffer,
+                mtime,
+                state: UnsyncedBufferState::Pending,
+                replaying: false,
+            },
+        );
+        cx.emit(BufferStoreEvent::UnsyncedBuffersChanged);
+        Task::ready(Ok(()))
+    }
+
+    fn replay_saves(&mut self, cx: &mut Context<BufferStore>) -> Task<()> {
+        let mut saves = Vec::new();
+        for (buffer_id, unsynced) in &mut self.unsynced_saves {
+            if unsynced.state == UnsyncedBufferState::Pending && !unsynced.replaying {
+                unsynced.replaying = true;
+                saves.push((*buffer_id, unsynced.buffer.clone(), unsynced.mtime.clone()));
+            }
+        }
+        let saves = saves
+            .into_iter()
+            .map(|(buffer_id, buffer, mtime)| {
+                (buffer_id, self.save_remote_buffer(buffer, None, mtime, cx))
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            for (buffer_id, save) in saves {
+                let result = save.await;
+                this.update(cx, |this, cx| {
+                    if let Some(remote) = this.as_remote_mut() {
+                        remote.finish_replayed_save(buffer_id, result, cx);
+                    }
+                })
+                .ok();
+            }
+        })
+    }
+
+    fn finish_replayed_save(
+        &mut self,
+        buffer_id: BufferId,
+        result: Result<()>,
+        cx: &mut Context<BufferStore>,
+    ) {
+        let Some(unsynced) = self.unsynced_saves.get_mut(&buffer_id) else {
+            return;
+        };
+        // The buffer was saved again while the save was being replayed.
+        if !unsynced.replaying {
+            return;
+        }
+        match result {
+            Ok(()) => {
+                self.unsynced_saves.remove(&buffer_id);
+            }
+            Err(error) if error.error_code() == ErrorCode::SaveConflict => {
+                log::info!("not replaying save of buffer {buffer_id}, its file changed on disk");
+                unsynced.state = UnsyncedBufferState::Conflict;
+                unsynced.replaying = false;
+            }
+            Err(error) => {
+                log::error!("failed to replay save of buffer {buffer_id}: {error:#}");
+                unsynced.state = UnsyncedBufferState::Failed;
+                unsynced.replaying = false;
+            }
+        }
+        cx.emit(BufferStoreEvent::UnsyncedBuffersChanged);
+    }
+
     pub fn handle_create_buffer_for_peer(
         &mut self,
         envelope: TypedEnvelope<proto::CreateBufferForPeer>,
@@ -811,6 +921,8 @@ impl BufferStore {
                 project_id: remote_id,
                 upstream_client,
                 worktree_store: worktree_store.clone(),
+                offline: false,
+                unsynced_saves: BTreeMap::new(),
             }),
             downstream_client: None,
             opened_buffers: Default::default(),
@@ -918,7 +1030,15 @@ impl BufferStore {
     ) -> Task<Result<()>> {
         match &mut self.state {
             BufferStoreState::Local(this) => this.save_buffer(buffer, cx),
-            BufferStoreState::Remote(this) => this.save_remote_buffer(buffer, None, cx),
+            BufferStoreState::Remote(this) if this.offline => this.queue_save(buffer, cx),
+            BufferStoreState::Remote(this) => {
+                let buffer_id = buffer.read(cx).remote_id();
+                let save = this.save_remote_buffer(buffer, None, None, cx);
+                cx.spawn(async move |this, cx| {
+                    save.await?;
+                    this.update(cx, |this, cx| this.forget_unsynced_save(buffer_id, cx))
+                })
+            }
         }
     }
 
@@ -931,8 +1051,11 @@ impl BufferStore {
         let old_file = buffer.read(cx).file().cloned();
         let task = match &self.state {
             BufferStoreState::Local(this) => this.save_buffer_as(buffer.clone(), path, cx),
+            BufferStoreState::Remote(this) if this.offline => Task::ready(Err(anyhow!(
+                "can't save to a new path while disconnected from the remote server"
+            ))),
             BufferStoreState::Remote(this) => {
-                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), cx)
+                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), None, cx)
             }
         };
         cx.spawn(async move |this, cx| {
@@ -1095,6 +1218,45 @@ impl BufferStore {
         }
     }
 
+    /// Queues saves of remote buffers until [`Self::remote_connection_restored`] is called.
+    pub fn remote_connection_lost(&mut self) {
+        if let Some(remote) = self.as_remote_mut() {
+            remote.offline = true;
+        }
+    }
+
+    /// Replays the saves that were queued while the connection to the remote server was lost.
+    /// Saves of files that changed on disk in the meantime aren't replayed, and are reported as
+    /// [`UnsyncedBufferState::Conflict`] instead.
+    pub fn remote_connection_restored(&mut self, cx: &mut Context<Self>) -> Task<()> {
+        let Some(remote) = self.as_remote_mut() else {
+            return Task::ready(());
+        };
+        remote.offline = false;
+        remote.replay_saves(cx)
+    }
+
+    /// Returns the buffers whose saves haven't reached the remote server, ordered by id.
+    pub fn unsynced_buffers(&self) -> Vec<(Entity<Buffer>, UnsyncedBufferState)> {
+        self.as_remote()
+            .map(|remote| {
+                remote
+                    .unsynced_saves
+                    .values()
+                    .map(|unsynced| (unsynced.buffer.clone(), unsynced.state))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn forget_unsynced_save(&mut self, buffer_id: BufferId, cx: &mut Context<Self>) {
+        if let Some(remote) = self.as_remote_mut()
+            && remote.unsynced_saves.remove(&buffer_id).is_some()
+        {
+            cx.emit(BufferStoreEvent::UnsyncedBuffersChanged);
+        }
+    }
+
     pub fn shared(&mut self, remote_id: u64, downstream_client: AnyProtoClient, _cx: &mut App) {
         self.downstream_client = Some((downstream_client, remote_id));
     }
@@ -1389,6 +1551,16 @@ impl BufferStore {
             .await?;
         let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id());
 
+        if let Some(expected_mtime) = envelope.payload.expected_mtime {
+            let mtime = buffer.read_with(&cx, |buffer, _| {
+                buffer
+                    .file()
+                    .and_then(|file| file.disk_state().mtime())
+                    .map(proto::Timestamp::from)
+            });
+            anyhow::ensure!(mtime == Some(expected_mtime), ErrorCode::SaveConflict);
+        }
+
         if let Some(new_path) = envelope.payload.new_path
             && let Some(new_path) = ProjectPath::from_proto(new_path)
         {
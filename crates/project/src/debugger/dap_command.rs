@@ -481,6 +481,75 @@ impl DapCommand for ContinueCommand {
     }
 }
 
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub(crate) struct ReverseContinueCommand {
+    pub thread_id: i64,
+    pub single_thread: Option<bool>,
+}
+
+impl LocalDapCommand for ReverseContinueCommand {
+    type Response = <dap::requests::ReverseContinue as dap::requests::Request>::Response;
+    type DapRequest = dap::requests::ReverseContinue;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_step_back.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::ReverseContinueArguments {
+            thread_id: self.thread_id,
+            single_thread: self.single_thread,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        _message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
+impl DapCommand for ReverseContinueCommand {
+    type ProtoRequest = proto::DapReverseContinueRequest;
+    type ProtoResponse = proto::Ack;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            thread_id: request.thread_id,
+            single_thread: request.single_thread,
+        }
+    }
+
+    fn to_proto(
+        &self,
+        debug_client_id: SessionId,
+        upstream_project_id: u64,
+    ) -> proto::DapReverseContinueRequest {
+        proto::DapReverseContinueRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            thread_id: self.thread_id,
+            single_thread: self.single_thread,
+        }
+    }
+
+    fn response_to_proto(
+        _debug_client_id: SessionId,
+        _message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::Ack {}
+    }
+
+    fn response_from_proto(&self, _message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub(crate) struct PauseCommand {
     pub thread_id: i64,
@@ -973,6 +1042,234 @@ impl DapCommand for SetVariableValueCommand {
     }
 }
 
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub(crate) struct SetExpressionCommand {
+    pub expression: String,
+    pub value: String,
+    pub frame_id: Option<u64>,
+}
+
+impl LocalDapCommand for SetExpressionCommand {
+    type Response = dap::SetExpressionResponse;
+    type DapRequest = dap::requests::SetExpression;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities.supports_set_expression.unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetExpressionArguments {
+            expression: self.expression.clone(),
+            value: self.value.clone(),
+            frame_id: self.frame_id,
+            format: None,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message)
+    }
+}
+
+impl DapCommand for SetExpressionCommand {
+    type ProtoRequest = proto::DapSetExpressionRequest;
+    type ProtoResponse = proto::DapSetExpressionResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapSetExpressionRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            expression: self.expression.clone(),
+            value: self.value.clone(),
+            frame_id: self.frame_id,
+        }
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            expression: request.expression.clone(),
+            value: request.value.clone(),
+            frame_id: request.frame_id,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapSetExpressionResponse {
+            client_id: debug_client_id.to_proto(),
+            value: message.value,
+            expression_type: message.type_,
+            variables_reference: message.variables_reference,
+            named_variables: message.named_variables,
+            indexed_variables: message.indexed_variables,
+            memory_reference: message.memory_reference,
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(dap::SetExpressionResponse {
+            value: message.value,
+            type_: message.expression_type,
+            presentation_hint: None,
+            variables_reference: message.variables_reference,
+            named_variables: message.named_variables,
+            indexed_variables: message.indexed_variables,
+            memory_reference: message.memory_reference,
+            value_location_reference: None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct GotoTargetsCommand {
+    pub source: dap::Source,
+    pub line: u64,
+    pub column: Option<u64>,
+}
+
+impl LocalDapCommand for GotoTargetsCommand {
+    type Response = Vec<dap::GotoTarget>;
+    type DapRequest = dap::requests::GotoTargets;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_goto_targets_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::GotoTargetsArguments {
+            source: self.source.clone(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.targets)
+    }
+}
+
+impl DapCommand for GotoTargetsCommand {
+    type ProtoRequest = proto::DapGotoTargetsRequest;
+    type ProtoResponse = proto::DapGotoTargetsResponse;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            source: dap::Source::from_proto(request.source.clone().unwrap_or_default()),
+            line: request.line,
+            column: request.column,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapGotoTargetsRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            source: Some(self.source.clone().to_proto()),
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn response_to_proto(
+        debug_client_id: SessionId,
+        message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::DapGotoTargetsResponse {
+            client_id: debug_client_id.to_proto(),
+            targets: message.to_proto(),
+        }
+    }
+
+    fn response_from_proto(&self, message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(Vec::from_proto(message.targets))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub(crate) struct GotoCommand {
+    pub thread_id: i64,
+    pub target_id: u64,
+}
+
+impl LocalDapCommand for GotoCommand {
+    type Response = <dap::requests::Goto as dap::requests::Request>::Response;
+    type DapRequest = dap::requests::Goto;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_goto_targets_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::GotoArguments {
+            thread_id: self.thread_id,
+            target_id: self.target_id,
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        _message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
+impl DapCommand for GotoCommand {
+    type ProtoRequest = proto::DapGotoRequest;
+    type ProtoResponse = proto::Ack;
+
+    fn client_id_from_proto(request: &Self::ProtoRequest) -> SessionId {
+        SessionId::from_proto(request.client_id)
+    }
+
+    fn from_proto(request: &Self::ProtoRequest) -> Self {
+        Self {
+            thread_id: request.thread_id,
+            target_id: request.target_id,
+        }
+    }
+
+    fn to_proto(&self, debug_client_id: SessionId, upstream_project_id: u64) -> Self::ProtoRequest {
+        proto::DapGotoRequest {
+            project_id: upstream_project_id,
+            client_id: debug_client_id.to_proto(),
+            thread_id: self.thread_id,
+            target_id: self.target_id,
+        }
+    }
+
+    fn response_to_proto(
+        _debug_client_id: SessionId,
+        _message: Self::Response,
+    ) -> Self::ProtoResponse {
+        proto::Ack {}
+    }
+
+    fn response_from_proto(&self, _message: Self::ProtoResponse) -> Result<Self::Response> {
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub(crate) struct RestartStackFrameCommand {
     pub stack_frame_id: u64,
@@ -1792,6 +2089,35 @@ impl LocalDapCommand for SetDataBreakpointsCommand {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct SetInstructionBreakpointsCommand {
+    pub breakpoints: Vec<dap::InstructionBreakpoint>,
+}
+
+impl LocalDapCommand for SetInstructionBreakpointsCommand {
+    type Response = Vec<dap::Breakpoint>;
+    type DapRequest = dap::requests::SetInstructionBreakpoints;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_instruction_breakpoints
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::SetInstructionBreakpointsArguments {
+            breakpoints: self.breakpoints.clone(),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.breakpoints)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub(super) enum SetExceptionBreakpoints {
     Plain {
@@ -1973,3 +2299,37 @@ impl LocalDapCommand for dap::WriteMemoryArguments {
         Ok(message)
     }
 }
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct DisassembleCommand {
+    pub(crate) memory_reference: String,
+    pub(crate) instruction_count: u64,
+}
+
+impl LocalDapCommand for DisassembleCommand {
+    type Response = Vec<dap::DisassembledInstruction>;
+    type DapRequest = dap::requests::Disassemble;
+
+    fn is_supported(capabilities: &Capabilities) -> bool {
+        capabilities
+            .supports_disassemble_request
+            .unwrap_or_default()
+    }
+
+    fn to_dap(&self) -> <Self::DapRequest as dap::requests::Request>::Arguments {
+        dap::DisassembleArguments {
+            memory_reference: self.memory_reference.clone(),
+            offset: None,
+            instruction_offset: None,
+            instruction_count: self.instruction_count,
+            resolve_symbols: Some(true),
+        }
+    }
+
+    fn response_from_dap(
+        &self,
+        message: <Self::DapRequest as dap::requests::Request>::Response,
+    ) -> Result<Self::Response> {
+        Ok(message.instructions)
+    }
+}
@@ -2,12 +2,14 @@ use super::breakpoint_store::{
     BreakpointStore, BreakpointStoreEvent, BreakpointUpdatedReason, SourceBreakpoint,
 };
 use super::dap_command::{
-    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand, DisconnectCommand,
-    EvaluateCommand, Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand,
-    ModulesCommand, NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand,
-    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetVariableValueCommand,
-    StackTraceCommand, StepBackCommand, StepCommand, StepInCommand, StepOutCommand,
-    TerminateCommand, TerminateThreadsCommand, ThreadsCommand, VariablesCommand,
+    self, Attach, ConfigurationDone, ContinueCommand, DataBreakpointInfoCommand,
+    DisassembleCommand, DisconnectCommand, EvaluateCommand, GotoCommand, GotoTargetsCommand,
+    Initialize, Launch, LoadedSourcesCommand, LocalDapCommand, LocationsCommand, ModulesCommand,
+    NextCommand, PauseCommand, RestartCommand, RestartStackFrameCommand, ReverseContinueCommand,
+    ScopesCommand, SetDataBreakpointsCommand, SetExceptionBreakpoints, SetExpressionCommand,
+    SetInstructionBreakpointsCommand, SetVariableValueCommand, StackTraceCommand, StepBackCommand,
+    StepCommand, StepInCommand, StepOutCommand, TerminateCommand, TerminateThreadsCommand,
+    ThreadsCommand, VariablesCommand,
 };
 use super::dap_store::DapStore;
 use crate::debugger::breakpoint_store::BreakpointSessionState;
@@ -152,6 +154,13 @@ pub struct DataBreakpointState {
     pub context: Arc<DataBreakpointContext>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionBreakpointState {
+    pub dap: dap::InstructionBreakpoint,
+    pub verified: bool,
+    pub message: Option<String>,
+}
+
 pub enum SessionState {
     /// Represents a session that is building/initializing
     /// even if a session doesn't have a pre build task this state
@@ -709,6 +718,7 @@ pub struct Session {
     ignore_breakpoints: bool,
     exception_breakpoints: BTreeMap<String, (ExceptionBreakpointsFilter, IsEnabled)>,
     data_breakpoints: BTreeMap<String, DataBreakpointState>,
+    instruction_breakpoints: BTreeMap<String, InstructionBreakpointState>,
     background_tasks: Vec<Task<()>>,
     restart_task: Option<Task<()>>,
     task_context: SharedTaskContext,
@@ -883,6 +893,7 @@ impl Session {
                 ignore_breakpoints: false,
                 breakpoint_store,
                 data_breakpoints: Default::default(),
+                instruction_breakpoints: Default::default(),
                 exception_breakpoints: Default::default(),
                 label,
                 adapter,
@@ -1898,6 +1909,23 @@ impl Session {
         })
     }
 
+    /// Disassembles `instruction_count` instructions, starting at the given memory reference.
+    pub fn disassemble(
+        &mut self,
+        memory_reference: String,
+        instruction_count: u64,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<dap::DisassembledInstruction>>> {
+        self.request(
+            DisassembleCommand {
+                memory_reference,
+                instruction_count,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        )
+    }
+
     pub fn write_memory(&mut self, address: u64, data: &[u8], cx: &mut Context<Self>) {
         let data = base64::engine::general_purpose::STANDARD.encode(data);
         self.request(
@@ -2086,6 +2114,66 @@ impl Session {
         self.send_data_breakpoints(cx);
     }
 
+    pub fn instruction_breakpoints(&self) -> impl Iterator<Item = &InstructionBreakpointState> {
+        self.instruction_breakpoints.values()
+    }
+
+    /// Sets a breakpoint on the instruction at the given reference, or removes the one already there.
+    pub fn toggle_instruction_breakpoint(
+        &mut self,
+        instruction_reference: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .instruction_breakpoints
+            .remove(&instruction_reference)
+            .is_none()
+        {
+            self.instruction_breakpoints.insert(
+                instruction_reference.clone(),
+                InstructionBreakpointState {
+                    dap: dap::InstructionBreakpoint {
+                        instruction_reference,
+                        offset: None,
+                        condition: None,
+                        hit_condition: None,
+                        mode: None,
+                    },
+                    verified: false,
+                    message: None,
+                },
+            );
+        }
+        self.send_instruction_breakpoints(cx);
+    }
+
+    fn send_instruction_breakpoints(&mut self, cx: &mut Context<Self>) {
+        let Some(mode) = self.as_running() else {
+            return;
+        };
+        let breakpoints = self
+            .instruction_breakpoints
+            .values()
+            .map(|state| state.dap.clone())
+            .collect();
+        let request = mode.request(SetInstructionBreakpointsCommand { breakpoints });
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            let breakpoints = request.await?;
+            this.update(cx, |this, cx| {
+                // Adapters answer with one breakpoint per requested instruction, in the same order.
+                for (state, breakpoint) in
+                    this.instruction_breakpoints.values_mut().zip(breakpoints)
+                {
+                    state.verified = breakpoint.verified;
+                    state.message = breakpoint.message;
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn breakpoints_enabled(&self) -> bool {
         self.ignore_breakpoints
     }
@@ -2301,6 +2389,71 @@ impl Session {
         .detach();
     }
 
+    pub fn reverse_continue(&mut self, thread_id: ThreadId, cx: &mut Context<Self>) {
+        self.select_historic_snapshot(None, cx);
+
+        let supports_single_thread_execution_requests =
+            self.capabilities.supports_single_thread_execution_requests;
+        self.active_snapshot
+            .thread_states
+            .continue_thread(thread_id);
+        self.request(
+            ReverseContinueCommand {
+                thread_id: thread_id.0,
+                single_thread: supports_single_thread_execution_requests,
+            },
+            Self::on_step_response::<ReverseContinueCommand>(thread_id),
+            cx,
+        )
+        .detach();
+    }
+
+    /// Moves the next statement of a stopped thread to the given row, without running the code in between.
+    pub fn go_to_position(
+        &mut self,
+        path: Arc<Path>,
+        row: u32,
+        thread_id: ThreadId,
+        cx: &mut Context<Self>,
+    ) {
+        if !matches!(
+            self.active_snapshot.thread_states.thread_state(thread_id),
+            Some(ThreadStatus::Stopped)
+        ) {
+            return;
+        }
+
+        let targets = self.request(
+            GotoTargetsCommand {
+                source: client_source(&path),
+                line: row as u64 + 1,
+                column: None,
+            },
+            |_, response, _| response.log_err(),
+            cx,
+        );
+
+        cx.spawn(async move |this, cx| {
+            let Some(target) = targets.await.and_then(|targets| targets.into_iter().next()) else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.active_snapshot.thread_states.process_step(thread_id);
+                this.request(
+                    GotoCommand {
+                        thread_id: thread_id.0,
+                        target_id: target.id,
+                    },
+                    Self::on_step_response::<GotoCommand>(thread_id),
+                    cx,
+                )
+                .detach();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn adapter_client(&self) -> Option<Arc<DebugAdapterClient>> {
         match self.state {
             SessionState::Running(ref local) => Some(local.client.clone()),
@@ -2564,6 +2717,10 @@ impl Session {
             .unwrap_or_default()
     }
 
+    pub fn stack_frame(&self, stack_frame_id: StackFrameId) -> Option<&StackFrame> {
+        self.session_state().stack_frames.get(&stack_frame_id)
+    }
+
     pub fn variables_by_stack_frame_id(
         &self,
         stack_frame_id: StackFrameId,
@@ -2743,6 +2900,39 @@ impl Session {
         }
     }
 
+    pub fn set_expression(
+        &mut self,
+        stack_frame_id: u64,
+        expression: String,
+        value: String,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .capabilities
+            .supports_set_expression
+            .unwrap_or_default()
+        {
+            self.request(
+                SetExpressionCommand {
+                    expression,
+                    value,
+                    frame_id: Some(stack_frame_id),
+                },
+                move |this, response, cx| {
+                    let response = response.log_err()?;
+                    this.invalidate_command_type::<VariablesCommand>();
+                    this.invalidate_command_type::<ReadMemory>();
+                    this.memory.clear(cx.background_executor());
+                    this.refresh_watchers(stack_frame_id, cx);
+                    cx.emit(SessionEvent::Variables);
+                    Some(response)
+                },
+                cx,
+            )
+            .detach();
+        }
+    }
+
     pub fn evaluate(
         &mut self,
         expression: String,
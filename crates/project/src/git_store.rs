@@ -29,13 +29,16 @@ use futures::{
 };
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid, RunHook,
+    bisect::{BisectCommit, BisectLogEntry, BisectState, BisectVerdict},
     blame::Blame,
     parse_git_remote_url,
+    rebase::{RebaseStatus, RebaseStep, RebaseTodoAction, RebaseTodoEntry},
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, GraphCommitData, InitialGraphCommitData, LogOrder,
-        LogSource, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode, SearchCommitArgs,
-        UpstreamTrackingStatus, Worktree as GitWorktree,
+        GitRepository, GitRepositoryCheckpoint, GraphCommitData, IndexStage,
+        InitialGraphCommitData, LogOrder, LogSource, PushOptions, Remote, RemoteCommandOutput,
+        RepoPath, ResetMode, SearchCommitArgs, Tag, UpstreamTrackingStatus,
+        Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -56,7 +59,7 @@ use pending_op::{PendingOp, PendingOpId, PendingOps, PendingOpsSummary};
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{self, git_rebase_step, git_rebase_todo_entry, git_reset, split_repository_update},
 };
 use serde::Deserialize;
 use settings::WorktreeId;
@@ -269,6 +272,14 @@ pub struct MergeDetails {
     pub message: Option<SharedString>,
 }
 
+/// The contents of a conflicted path at each merge stage of the index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Clone)]
 pub enum CommitDataState {
     Loading,
@@ -294,6 +305,7 @@ pub struct RepositorySnapshot {
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
     pub linked_worktrees: Arc<[GitWorktree]>,
+    pub rebase: Option<RebaseStatus>,
 }
 
 type JobId = u64;
@@ -430,8 +442,14 @@ pub enum RepositoryEvent {
     StatusesChanged,
     BranchChanged,
     StashEntriesChanged,
+    /// A tag was created or deleted through this repository.
+    TagsChanged,
+    /// A bisect was started, advanced or reset through this repository.
+    BisectChanged,
     GitWorktreeListChanged,
-    PendingOpsChanged { pending_ops: SumTree<PendingOps> },
+    PendingOpsChanged {
+        pending_ops: SumTree<PendingOps>,
+    },
     GraphEvent((LogSource, LogOrder), GitGraphEvent),
 }
 
@@ -546,6 +564,9 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_create_remote);
         client.add_entity_request_handler(Self::handle_remove_remote);
         client.add_entity_request_handler(Self::handle_delete_branch);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
@@ -559,12 +580,23 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_run_hook);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_rebase_todo);
+        client.add_entity_request_handler(Self::handle_rebase_start);
+        client.add_entity_request_handler(Self::handle_rebase_step);
+        client.add_entity_request_handler(Self::handle_bisect_start);
+        client.add_entity_request_handler(Self::handle_bisect_mark);
+        client.add_entity_request_handler(Self::handle_bisect_reset);
+        client.add_entity_request_handler(Self::handle_get_bisect_state);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_checkout_detached);
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_create_checkpoint);
         client.add_entity_request_handler(Self::handle_restore_checkpoint);
         client.add_entity_request_handler(Self::handle_compare_checkpoints);
         client.add_entity_request_handler(Self::handle_diff_checkpoints);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
+        client.add_entity_request_handler(Self::handle_load_conflict_stages);
         client.add_entity_request_handler(Self::handle_file_history);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
@@ -1708,6 +1740,7 @@ impl GitStore {
                     .detach();
                 }
             }
+            BufferStoreEvent::UnsyncedBuffersChanged => {}
         }
     }
 
@@ -2498,10 +2531,11 @@ impl GitStore {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
         let branch_name = envelope.payload.branch_name;
+        let base_branch = envelope.payload.base_branch;
 
         repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.create_branch(branch_name, None)
+                repository_handle.create_branch(branch_name, base_branch)
             })
             .await??;
 
@@ -2583,6 +2617,63 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())
+            .await??;
+
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let proto::GitCreateTag {
+            name,
+            commit,
+            message,
+            ..
+        } = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(name, commit, message)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
     async fn handle_remove_remote(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitRemoveRemote>,
@@ -2736,6 +2827,27 @@ impl GitStore {
         })
     }
 
+    async fn handle_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitConflictStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_stages(path)
+            })
+            .await??;
+        Ok(proto::GitConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
     async fn handle_file_history(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitFileHistory>,
@@ -2781,6 +2893,7 @@ impl GitStore {
         let mode = match envelope.payload.mode() {
             git_reset::ResetMode::Soft => ResetMode::Soft,
             git_reset::ResetMode::Mixed => ResetMode::Mixed,
+            git_reset::ResetMode::Hard => ResetMode::Hard,
         };
 
         repository_handle
@@ -2791,6 +2904,182 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_todo(envelope.payload.upstream)
+            })
+            .await??;
+        Ok(proto::GitRebaseTodoResponse {
+            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+        })
+    }
+
+    async fn handle_rebase_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let todo = envelope
+            .payload
+            .todo
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_start(envelope.payload.upstream, todo)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase_step(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseStep>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let step = match envelope.payload.step() {
+            git_rebase_step::Step::Continue => RebaseStep::Continue,
+            git_rebase_step::Step::Skip => RebaseStep::Skip,
+            git_rebase_step::Step::Abort => RebaseStep::Abort,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_step(step)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_start(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectStart>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let proto::GitBisectStart { bad, good, .. } = envelope.payload;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_start(bad, good)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_mark(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectMark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let verdict = proto_to_bisect_verdict(envelope.payload.verdict());
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_mark(verdict, envelope.payload.commit)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_bisect_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBisectReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_reset()
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_bisect_state(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetBisectState>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBisectStateResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let state = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.bisect_state()
+            })
+            .await??;
+        Ok(bisect_state_to_proto(state.as_ref()))
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(envelope.payload.commit)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(envelope.payload.commit)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_checkout_detached(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckoutDetached>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.checkout_detached(envelope.payload.commit)
+            })
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -3693,6 +3982,7 @@ impl RepositorySnapshot {
             remote_upstream_url: None,
             stash_entries: Default::default(),
             linked_worktrees: Arc::from([]),
+            rebase: None,
             path_style,
         }
     }
@@ -3736,6 +4026,7 @@ impl RepositorySnapshot {
                 .iter()
                 .map(worktree_to_proto)
                 .collect(),
+            rebase: self.rebase.as_ref().map(rebase_status_to_proto),
         }
     }
 
@@ -3817,6 +4108,7 @@ impl RepositorySnapshot {
                 .iter()
                 .map(worktree_to_proto)
                 .collect(),
+            rebase: self.rebase.as_ref().map(rebase_status_to_proto),
         }
     }
 
@@ -4053,6 +4345,8 @@ impl Repository {
                     this.initial_graph_data.clear();
                 }
             }
+            // Tags are part of the ref names shown in the graph.
+            RepositoryEvent::TagsChanged => this.initial_graph_data.clear(),
             _ => {}
         })
         .detach();
@@ -4555,6 +4849,7 @@ impl Repository {
                             mode: match reset_mode {
                                 ResetMode::Soft => git_reset::ResetMode::Soft.into(),
                                 ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                                ResetMode::Hard => git_reset::ResetMode::Hard.into(),
                             },
                         })
                         .await?;
@@ -4565,92 +4860,424 @@ impl Repository {
         })
     }
 
-    pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+    /// Lists the commits an interactive rebase onto `upstream` would replay, as a todo list that
+    /// picks each of them.
+    pub fn rebase_todo(
+        &mut self,
+        upstream: String,
+    ) -> oneshot::Receiver<Result<Vec<RebaseTodoEntry>>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
             match git_repo {
                 RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.show(commit).await
+                    backend.rebase_todo(upstream).await
                 }
                 RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
-                    let resp = client
-                        .request(proto::GitShow {
+                    let response = client
+                        .request(proto::GitRebaseTodo {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
+                            upstream,
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
-                    })
+                    Ok(response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect())
                 }
             }
         })
     }
 
-    pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
+    pub fn rebase_start(
+        &mut self,
+        upstream: String,
+        todo: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |git_repo, cx| async move {
+        self.send_job(Some("git rebase".into()), move |git_repo, _cx| async move {
             match git_repo {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.load_commit(commit, cx).await
-                }
-                RepositoryState::Remote(RemoteRepositoryState {
-                    client, project_id, ..
-                }) => {
-                    let response = client
-                        .request(proto::LoadCommitDiff {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.rebase_start(upstream, todo, environment).await,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitRebaseStart {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
+                            upstream,
+                            todo: todo.iter().map(rebase_todo_entry_to_proto).collect(),
                         })
                         .await?;
-                    Ok(CommitDiff {
-                        files: response
-                            .files
-                            .into_iter()
-                            .map(|file| {
-                                Ok(CommitFile {
-                                    path: RepoPath::from_proto(&file.path)?,
-                                    old_text: file.old_text,
-                                    new_text: file.new_text,
-                                    is_binary: file.is_binary,
-                                })
-                            })
-                            .collect::<Result<Vec<_>>>()?,
-                    })
+
+                    Ok(())
                 }
             }
         })
     }
 
-    pub fn file_history(
-        &mut self,
-        path: RepoPath,
-    ) -> oneshot::Receiver<Result<git::repository::FileHistory>> {
-        self.file_history_paginated(path, 0, None)
-    }
-
-    pub fn file_history_paginated(
-        &mut self,
-        path: RepoPath,
-        skip: usize,
-        limit: Option<usize>,
-    ) -> oneshot::Receiver<Result<git::repository::FileHistory>> {
+    pub fn rebase_step(&mut self, step: RebaseStep) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |git_repo, _cx| async move {
+        let status = match step {
+            RebaseStep::Continue => "git rebase --continue",
+            RebaseStep::Skip => "git rebase --skip",
+            RebaseStep::Abort => "git rebase --abort",
+        };
+        self.send_job(Some(status.into()), move |git_repo, _cx| async move {
             match git_repo {
-                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
-                    backend.file_history_paginated(path, skip, limit).await
-                }
-                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
-                    let response = client
-                        .request(proto::GitFileHistory {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => match step {
+                    RebaseStep::Continue => backend.rebase_continue(environment).await,
+                    RebaseStep::Skip => backend.rebase_skip(environment).await,
+                    RebaseStep::Abort => backend.rebase_abort(environment).await,
+                },
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitRebaseStep {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            step: match step {
+                                RebaseStep::Continue => git_rebase_step::Step::Continue.into(),
+                                RebaseStep::Skip => git_rebase_step::Step::Skip.into(),
+                                RebaseStep::Abort => git_rebase_step::Step::Abort.into(),
+                            },
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Starts a bisect with `bad` as the commit known to have the regression and `good` as the
+    /// commits known not to have it.
+    pub fn bisect_start(
+        &mut self,
+        bad: String,
+        good: Vec<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        let status = format!("git bisect start {bad} {}", good.join(" "));
+        self.send_job(Some(status.into()), move |repo, mut cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.bisect_start(bad, good, environment).await?,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitBisectStart {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            bad,
+                            good,
+                        })
+                        .await?;
+                }
+            }
+            this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BisectChanged))
+                .ok();
+            Ok(())
+        })
+    }
+
+    /// Marks `commit`, or the checked out commit, and checks out the next one to test.
+    pub fn bisect_mark(
+        &mut self,
+        verdict: BisectVerdict,
+        commit: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        let status = match &commit {
+            Some(commit) => format!("git bisect {verdict} {commit}"),
+            None => format!("git bisect {verdict}"),
+        };
+        self.send_job(Some(status.into()), move |repo, mut cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => backend.bisect_mark(verdict, commit, environment).await?,
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitBisectMark {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            verdict: bisect_verdict_to_proto(verdict).into(),
+                            commit,
+                        })
+                        .await?;
+                }
+            }
+            this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BisectChanged))
+                .ok();
+            Ok(())
+        })
+    }
+
+    pub fn bisect_reset(&mut self) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        self.send_job(
+            Some("git bisect reset".into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.bisect_reset(environment).await?,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitBisectReset {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await?;
+                    }
+                }
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::BisectChanged))
+                    .ok();
+                Ok(())
+            },
+        )
+    }
+
+    /// Reads the bisect in progress, or `None` when the repository isn't bisecting.
+    pub fn bisect_state(&mut self) -> oneshot::Receiver<Result<Option<BisectState>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.bisect_state().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetBisectState {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+                    Ok(proto_to_bisect_state(response))
+                }
+            }
+        })
+    }
+
+    pub fn cherry_pick(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git cherry-pick {commit}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.cherry_pick(commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git revert {commit}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.revert(commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitRevert {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn checkout_detached(&mut self, commit: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git switch --detach {commit}").into()),
+            move |git_repo, _cx| async move {
+                match git_repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.checkout_detached(commit, environment).await,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitCheckoutDetached {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.show(commit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let resp = client
+                        .request(proto::GitShow {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(CommitDetails {
+                        sha: resp.sha.into(),
+                        message: resp.message.into(),
+                        commit_timestamp: resp.commit_timestamp,
+                        author_email: resp.author_email.into(),
+                        author_name: resp.author_name.into(),
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.load_commit(commit, cx).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState {
+                    client, project_id, ..
+                }) => {
+                    let response = client
+                        .request(proto::LoadCommitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+                    Ok(CommitDiff {
+                        files: response
+                            .files
+                            .into_iter()
+                            .map(|file| {
+                                Ok(CommitFile {
+                                    path: RepoPath::from_proto(&file.path)?,
+                                    old_text: file.old_text,
+                                    new_text: file.new_text,
+                                    is_binary: file.is_binary,
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()?,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn load_conflict_stages(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    let (base, ours, theirs) = futures::join!(
+                        backend.load_index_stage_text(path.clone(), IndexStage::Base),
+                        backend.load_index_stage_text(path.clone(), IndexStage::Ours),
+                        backend.load_index_stage_text(path, IndexStage::Theirs),
+                    );
+                    Ok(ConflictStages { base, ours, theirs })
+                }
+                RepositoryState::Remote(RemoteRepositoryState {
+                    client, project_id, ..
+                }) => {
+                    let response = client
+                        .request(proto::GitLoadConflictStages {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(ConflictStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn file_history(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<git::repository::FileHistory>> {
+        self.file_history_paginated(path, 0, None)
+    }
+
+    pub fn file_history_paginated(
+        &mut self,
+        path: RepoPath,
+        skip: usize,
+        limit: Option<usize>,
+    ) -> oneshot::Receiver<Result<git::repository::FileHistory>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.file_history_paginated(path, skip, limit).await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { client, project_id }) => {
+                    let response = client
+                        .request(proto::GitFileHistory {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
                             path: path.to_proto(),
@@ -6194,6 +6821,7 @@ impl Repository {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
                             branch_name,
+                            base_branch,
                         })
                         .await?;
 
@@ -6265,6 +6893,99 @@ impl Repository {
         )
     }
 
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState { backend, .. }) => {
+                    backend.tags().await
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    /// Creates a tag at `commit`, or at `HEAD`. Passing a message creates an annotated tag.
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        commit: Option<String>,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        let status = if message.is_some() {
+            format!("git tag -a {name}")
+        } else {
+            format!("git tag {name}")
+        };
+        self.send_job(Some(status.into()), move |repo, mut cx| async move {
+            match repo {
+                RepositoryState::Local(LocalRepositoryState {
+                    backend,
+                    environment,
+                    ..
+                }) => {
+                    backend
+                        .create_tag(name, commit, message, environment)
+                        .await?
+                }
+                RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                    client
+                        .request(proto::GitCreateTag {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            name,
+                            commit,
+                            message,
+                        })
+                        .await?;
+                }
+            }
+            this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::TagsChanged))
+                .ok();
+            Ok(())
+        })
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let this = self.this.clone();
+        self.send_job(
+            Some(format!("git tag -d {name}").into()),
+            move |repo, mut cx| async move {
+                match repo {
+                    RepositoryState::Local(LocalRepositoryState {
+                        backend,
+                        environment,
+                        ..
+                    }) => backend.delete_tag(name, environment).await?,
+                    RepositoryState::Remote(RemoteRepositoryState { project_id, client }) => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+                    }
+                }
+                this.update(&mut cx, |_, cx| cx.emit(RepositoryEvent::TagsChanged))
+                    .ok();
+                Ok(())
+            },
+        )
+    }
+
     pub fn rename_branch(
         &mut self,
         branch: String,
@@ -6417,6 +7138,9 @@ impl Repository {
             cx.emit(RepositoryEvent::GitWorktreeListChanged);
         }
         self.snapshot.linked_worktrees = new_linked_worktrees;
+        let new_rebase = update.rebase.as_ref().map(proto_to_rebase_status);
+        let rebase_changed = self.snapshot.rebase != new_rebase;
+        self.snapshot.rebase = new_rebase;
         self.snapshot.remote_upstream_url = update.remote_upstream_url;
         self.snapshot.remote_origin_url = update.remote_origin_url;
 
@@ -6437,7 +7161,7 @@ impl Repository {
                     }),
             )
             .collect::<Vec<_>>();
-        if conflicts_changed || !edits.is_empty() {
+        if conflicts_changed || rebase_changed || !edits.is_empty() {
             cx.emit(RepositoryEvent::StatusesChanged);
         }
         self.snapshot.statuses_by_path.edit(edits, ());
@@ -7204,6 +7928,157 @@ fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     }
 }
 
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::GitRebaseTodoEntry {
+    let action = match entry.action {
+        RebaseTodoAction::Pick => git_rebase_todo_entry::Action::Pick,
+        RebaseTodoAction::Reword => git_rebase_todo_entry::Action::Reword,
+        RebaseTodoAction::Edit => git_rebase_todo_entry::Action::Edit,
+        RebaseTodoAction::Squash => git_rebase_todo_entry::Action::Squash,
+        RebaseTodoAction::Fixup => git_rebase_todo_entry::Action::Fixup,
+        RebaseTodoAction::Drop => git_rebase_todo_entry::Action::Drop,
+    };
+    proto::GitRebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        original_message: entry
+            .original_message
+            .as_ref()
+            .map(|message| message.to_string()),
+        message: entry.message.as_ref().map(|message| message.to_string()),
+    }
+}
+
+fn proto_to_rebase_todo_entry(proto: &proto::GitRebaseTodoEntry) -> RebaseTodoEntry {
+    let action = match proto.action() {
+        git_rebase_todo_entry::Action::Pick => RebaseTodoAction::Pick,
+        git_rebase_todo_entry::Action::Reword => RebaseTodoAction::Reword,
+        git_rebase_todo_entry::Action::Edit => RebaseTodoAction::Edit,
+        git_rebase_todo_entry::Action::Squash => RebaseTodoAction::Squash,
+        git_rebase_todo_entry::Action::Fixup => RebaseTodoAction::Fixup,
+        git_rebase_todo_entry::Action::Drop => RebaseTodoAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        original_message: proto.original_message.clone().map(SharedString::from),
+        message: proto.message.clone().map(SharedString::from),
+    }
+}
+
+fn rebase_status_to_proto(status: &RebaseStatus) -> proto::GitRebaseStatus {
+    proto::GitRebaseStatus {
+        head_name: status.head_name.as_ref().map(|name| name.to_string()),
+        onto: status.onto.as_ref().map(|onto| onto.to_string()),
+        step: status.step as u64,
+        total: status.total as u64,
+        stopped_sha: status.stopped_sha.as_ref().map(|sha| sha.to_string()),
+        todo: status.todo.iter().map(rebase_todo_entry_to_proto).collect(),
+    }
+}
+
+fn proto_to_rebase_status(proto: &proto::GitRebaseStatus) -> RebaseStatus {
+    RebaseStatus {
+        head_name: proto.head_name.clone().map(SharedString::from),
+        onto: proto.onto.clone().map(SharedString::from),
+        step: proto.step as usize,
+        total: proto.total as usize,
+        stopped_sha: proto.stopped_sha.clone().map(SharedString::from),
+        todo: proto.todo.iter().map(proto_to_rebase_todo_entry).collect(),
+    }
+}
+
+fn bisect_verdict_to_proto(verdict: BisectVerdict) -> proto::GitBisectVerdict {
+    match verdict {
+        BisectVerdict::Good => proto::GitBisectVerdict::Good,
+        BisectVerdict::Bad => proto::GitBisectVerdict::Bad,
+        BisectVerdict::Skip => proto::GitBisectVerdict::Skip,
+    }
+}
+
+fn proto_to_bisect_verdict(verdict: proto::GitBisectVerdict) -> BisectVerdict {
+    match verdict {
+        proto::GitBisectVerdict::Good => BisectVerdict::Good,
+        proto::GitBisectVerdict::Bad => BisectVerdict::Bad,
+        proto::GitBisectVerdict::Skip => BisectVerdict::Skip,
+    }
+}
+
+fn bisect_commit_to_proto(commit: &BisectCommit) -> proto::GitBisectCommit {
+    proto::GitBisectCommit {
+        sha: commit.sha.to_string(),
+        subject: commit.subject.to_string(),
+    }
+}
+
+fn proto_to_bisect_commit(proto: proto::GitBisectCommit) -> BisectCommit {
+    BisectCommit::new(proto.sha, proto.subject)
+}
+
+fn bisect_state_to_proto(state: Option<&BisectState>) -> proto::GitBisectStateResponse {
+    let Some(state) = state else {
+        return proto::GitBisectStateResponse::default();
+    };
+    proto::GitBisectStateResponse {
+        in_progress: true,
+        log: state
+            .log
+            .iter()
+            .map(|entry| proto::GitBisectLogEntry {
+                verdict: bisect_verdict_to_proto(entry.verdict).into(),
+                commit: Some(bisect_commit_to_proto(&entry.commit)),
+            })
+            .collect(),
+        remaining: state.remaining.iter().map(bisect_commit_to_proto).collect(),
+        current: state.current.as_ref().map(bisect_commit_to_proto),
+        first_bad: state.first_bad.as_ref().map(bisect_commit_to_proto),
+    }
+}
+
+fn proto_to_bisect_state(proto: proto::GitBisectStateResponse) -> Option<BisectState> {
+    if !proto.in_progress {
+        return None;
+    }
+    Some(BisectState {
+        log: proto
+            .log
+            .into_iter()
+            .filter_map(|entry| {
+                Some(BisectLogEntry {
+                    verdict: proto_to_bisect_verdict(entry.verdict()),
+                    commit: proto_to_bisect_commit(entry.commit?),
+                })
+            })
+            .collect(),
+        remaining: proto
+            .remaining
+            .into_iter()
+            .map(proto_to_bisect_commit)
+            .collect(),
+        current: proto.current.map(proto_to_bisect_commit),
+        first_bad: proto.first_bad.map(proto_to_bisect_commit),
+    })
+}
+
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+        timestamp: tag.timestamp,
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().map(SharedString::from),
+        timestamp: proto.timestamp,
+    }
+}
+
 fn worktree_to_proto(worktree: &git::repository::Worktree) -> proto::Worktree {
     proto::Worktree {
         path: worktree.path.to_string_lossy().to_string(),
@@ -7425,7 +8300,10 @@ async fn compute_snapshot(
     log::debug!("new merge details: {merge_details:?}");
 
     Ok(this.update(cx, |this, cx| {
-        if conflicts_changed || statuses_by_path != this.snapshot.statuses_by_path {
+        if conflicts_changed
+            || statuses_by_path != this.snapshot.statuses_by_path
+            || statuses.rebase != this.snapshot.rebase
+        {
             cx.emit(RepositoryEvent::StatusesChanged);
         }
         if stash_entries != this.snapshot.stash_entries {
@@ -7435,6 +8313,7 @@ async fn compute_snapshot(
         this.snapshot.scan_id += 1;
         this.snapshot.merge = merge_details;
         this.snapshot.statuses_by_path = statuses_by_path;
+        this.snapshot.rebase = statuses.rebase;
         this.snapshot.stash_entries = stash_entries;
 
         this.snapshot.clone()
@@ -1,12 +1,12 @@
 pub mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
-    lsp_store::{LocalLspStore, LspFoldingRange, LspStore},
+    CallHierarchyCall, CallHierarchyItem, CodeAction, CompletionSource, CoreCompletion,
+    CoreCompletionResponse, DocumentColor, DocumentHighlight, DocumentSymbol, Hover, HoverBlock,
+    HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip,
+    InlayHintTooltip, Location, LocationLink, LspAction, LspPullDiagnostics, MarkupContent,
+    PrepareRenameResponse, ProjectTransaction, PulledDiagnostics, ResolveState, TypeHierarchyItem,
+    lsp_store::{DocumentLink, DocumentLinkTarget, LocalLspStore, LspFoldingRange, LspStore},
 };
 use anyhow::{Context as _, Result};
 use async_trait::async_trait;
@@ -218,6 +218,41 @@ pub(crate) struct GetDocumentHighlights {
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentSymbols;
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetIncomingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSupertypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSubtypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSelectionRanges {
+    pub positions: Vec<PointUtf16>,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -301,6 +336,9 @@ pub(crate) struct GetDocumentColor;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetFoldingRanges;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -1686,9 +1724,1063 @@ impl LspCommand for GetDocumentSymbols {
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::DocumentSymbolParams> {
-        Ok(lsp::DocumentSymbolParams {
+    ) -> Result<lsp::DocumentSymbolParams> {
+        Ok(lsp::DocumentSymbolParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_symbols: Option<lsp::DocumentSymbolResponse>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<DocumentSymbol>> {
+        let Some(lsp_symbols) = lsp_symbols else {
+            return Ok(Vec::new());
+        };
+
+        let symbols = match lsp_symbols {
+            lsp::DocumentSymbolResponse::Flat(symbol_information) => symbol_information
+                .into_iter()
+                .map(|lsp_symbol| DocumentSymbol {
+                    name: lsp_symbol.name,
+                    kind: lsp_symbol.kind,
+                    range: range_from_lsp(lsp_symbol.location.range),
+                    selection_range: range_from_lsp(lsp_symbol.location.range),
+                    children: Vec::new(),
+                })
+                .collect(),
+            lsp::DocumentSymbolResponse::Nested(nested_responses) => {
+                fn convert_symbol(lsp_symbol: lsp::DocumentSymbol) -> DocumentSymbol {
+                    DocumentSymbol {
+                        name: lsp_symbol.name,
+                        kind: lsp_symbol.kind,
+                        range: range_from_lsp(lsp_symbol.range),
+                        selection_range: range_from_lsp(lsp_symbol.selection_range),
+                        children: lsp_symbol
+                            .children
+                            .map(|children| {
+                                children.into_iter().map(convert_symbol).collect::<Vec<_>>()
+                            })
+                            .unwrap_or_default(),
+                    }
+                }
+                nested_responses.into_iter().map(convert_symbol).collect()
+            }
+        };
+        Ok(symbols)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentSymbols {
+        proto::GetDocumentSymbols {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetDocumentSymbols,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Vec<DocumentSymbol>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentSymbolsResponse {
+        let symbols = response
+            .into_iter()
+            .map(|symbol| {
+                fn convert_symbol_to_proto(symbol: DocumentSymbol) -> proto::DocumentSymbol {
+                    proto::DocumentSymbol {
+                        name: symbol.name.clone(),
+                        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(symbol.kind) },
+                        start: Some(proto::PointUtf16 {
+                            row: symbol.range.start.0.row,
+                            column: symbol.range.start.0.column,
+                        }),
+                        end: Some(proto::PointUtf16 {
+                            row: symbol.range.end.0.row,
+                            column: symbol.range.end.0.column,
+                        }),
+                        selection_start: Some(proto::PointUtf16 {
+                            row: symbol.selection_range.start.0.row,
+                            column: symbol.selection_range.start.0.column,
+                        }),
+                        selection_end: Some(proto::PointUtf16 {
+                            row: symbol.selection_range.end.0.row,
+                            column: symbol.selection_range.end.0.column,
+                        }),
+                        children: symbol
+                            .children
+                            .into_iter()
+                            .map(convert_symbol_to_proto)
+                            .collect(),
+                    }
+                }
+                convert_symbol_to_proto(symbol)
+            })
+            .collect::<Vec<_>>();
+
+        proto::GetDocumentSymbolsResponse { symbols }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentSymbolsResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<DocumentSymbol>> {
+        let mut symbols = Vec::with_capacity(message.symbols.len());
+        for serialized_symbol in message.symbols {
+            fn deserialize_symbol_with_children(
+                serialized_symbol: proto::DocumentSymbol,
+            ) -> Result<DocumentSymbol> {
+                let kind =
+                    unsafe { mem::transmute::<i32, lsp::SymbolKind>(serialized_symbol.kind) };
+
+                let start = serialized_symbol.start.context("invalid start")?;
+                let end = serialized_symbol.end.context("invalid end")?;
+
+                let selection_start = serialized_symbol
+                    .selection_start
+                    .context("invalid selection start")?;
+                let selection_end = serialized_symbol
+                    .selection_end
+                    .context("invalid selection end")?;
+
+                Ok(DocumentSymbol {
+                    name: serialized_symbol.name,
+                    kind,
+                    range: Unclipped(PointUtf16::new(start.row, start.column))
+                        ..Unclipped(PointUtf16::new(end.row, end.column)),
+                    selection_range: Unclipped(PointUtf16::new(
+                        selection_start.row,
+                        selection_start.column,
+                    ))
+                        ..Unclipped(PointUtf16::new(selection_end.row, selection_end.column)),
+                    children: serialized_symbol
+                        .children
+                        .into_iter()
+                        .filter_map(|symbol| deserialize_symbol_with_children(symbol).ok())
+                        .collect::<Vec<_>>(),
+                })
+            }
+
+            symbols.push(deserialize_symbol_with_children(serialized_symbol)?);
+        }
+
+        Ok(symbols)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetDocumentSymbols) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+fn supports_call_hierarchy(capabilities: &ServerCapabilities) -> bool {
+    match capabilities.call_hierarchy_provider {
+        Some(lsp::CallHierarchyServerCapability::Simple(supported)) => supported,
+        Some(lsp::CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+async fn call_hierarchy_item_from_lsp(
+    lsp_item: lsp::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(lsp_item.uri.clone(), server_id, cx)
+        })
+        .await?;
+    Ok(buffer.read_with(cx, |snapshot, _| {
+        let range = anchor_range_from_lsp(snapshot, lsp_item.range);
+        let selection_range = anchor_range_from_lsp(snapshot, lsp_item.selection_range);
+        CallHierarchyItem {
+            name: lsp_item.name.clone(),
+            kind: lsp_item.kind,
+            detail: lsp_item.detail.clone(),
+            location: Location {
+                buffer: buffer.clone(),
+                range,
+            },
+            selection_range,
+            server_id,
+            lsp_item,
+        }
+    }))
+}
+
+fn location_to_proto(
+    location: &Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing location start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing location end")?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn call_hierarchy_item_to_proto(
+    item: CallHierarchyItem,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::CallHierarchyItem {
+    proto::CallHierarchyItem {
+        location: Some(location_to_proto(&item.location, lsp_store, peer_id, cx)),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        server_id: item.server_id.to_proto(),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap_or_default(),
+        name: item.name,
+        detail: item.detail,
+    }
+}
+
+async fn call_hierarchy_item_from_proto(
+    item: proto::CallHierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<CallHierarchyItem> {
+    let location = location_from_proto(
+        item.location
+            .context("missing call hierarchy item location")?,
+        lsp_store,
+        cx,
+    )
+    .await?;
+    let selection_start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .context("missing selection start")?;
+    let selection_end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .context("missing selection end")?;
+    Ok(CallHierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location,
+        selection_range: selection_start..selection_end,
+        server_id: LanguageServerId::from_proto(item.server_id),
+        lsp_item: serde_json::from_slice(&item.lsp_item)
+            .context("deserializing call hierarchy item")?,
+    })
+}
+
+async fn call_hierarchy_calls_from_proto(
+    calls: Vec<proto::CallHierarchyCall>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut result = Vec::with_capacity(calls.len());
+    for call in calls {
+        let item = call_hierarchy_item_from_proto(
+            call.item.context("missing call hierarchy item")?,
+            &lsp_store,
+            &mut cx,
+        )
+        .await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            call_sites.push(location_from_proto(call_site, &lsp_store, &mut cx).await?);
+        }
+        result.push(CallHierarchyCall { item, call_sites });
+    }
+    Ok(result)
+}
+
+fn call_hierarchy_calls_to_proto(
+    calls: Vec<CallHierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::CallHierarchyCall> {
+    calls
+        .into_iter()
+        .map(|call| proto::CallHierarchyCall {
+            call_sites: call
+                .call_sites
+                .iter()
+                .map(|call_site| location_to_proto(call_site, lsp_store, peer_id, cx))
+                .collect(),
+            item: Some(call_hierarchy_item_to_proto(
+                call.item, lsp_store, peer_id, cx,
+            )),
+        })
+        .collect()
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut result = Vec::new();
+        for lsp_item in items.unwrap_or_default() {
+            result.push(
+                call_hierarchy_item_from_lsp(lsp_item, &lsp_store, server_id, &mut cx).await?,
+            );
+        }
+        Ok(result)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer)),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .into_iter()
+                .map(|item| call_hierarchy_item_to_proto(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let mut items = Vec::with_capacity(message.items.len());
+        for item in message.items {
+            items.push(call_hierarchy_item_from_proto(item, &lsp_store, &mut cx).await?);
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut result = Vec::new();
+        for call in calls.unwrap_or_default() {
+            let item =
+                call_hierarchy_item_from_lsp(call.from, &lsp_store, server_id, &mut cx).await?;
+            // Incoming call ranges are relative to the caller.
+            let call_sites = item.location.buffer.read_with(&cx, |buffer, _| {
+                call.from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: item.location.buffer.clone(),
+                        range: anchor_range_from_lsp(buffer, range),
+                    })
+                    .collect()
+            });
+            result.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(result)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing call hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_call_hierarchy(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut result = Vec::new();
+        for call in calls.unwrap_or_default() {
+            let item =
+                call_hierarchy_item_from_lsp(call.to, &lsp_store, server_id, &mut cx).await?;
+            // Outgoing call ranges are relative to the item the calls were requested for,
+            // which is the buffer this request was issued against.
+            let call_sites = buffer.read_with(&cx, |snapshot, _| {
+                call.from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: buffer.clone(),
+                        range: anchor_range_from_lsp(snapshot, range),
+                    })
+                    .collect()
+            });
+            result.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(result)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing call hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+async fn type_hierarchy_items_from_lsp(
+    lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut result = Vec::new();
+    for lsp_item in lsp_items.unwrap_or_default() {
+        let buffer = lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.open_local_buffer_via_lsp(lsp_item.uri.clone(), server_id, cx)
+            })
+            .await?;
+        result.push(buffer.read_with(cx, |snapshot, _| {
+            let range = anchor_range_from_lsp(snapshot, lsp_item.range);
+            let selection_range = anchor_range_from_lsp(snapshot, lsp_item.selection_range);
+            TypeHierarchyItem {
+                name: lsp_item.name.clone(),
+                kind: lsp_item.kind,
+                detail: lsp_item.detail.clone(),
+                location: Location {
+                    buffer: buffer.clone(),
+                    range,
+                },
+                selection_range,
+                server_id,
+                lsp_item,
+            }
+        }));
+    }
+    Ok(result)
+}
+
+fn type_hierarchy_items_to_proto(
+    items: Vec<TypeHierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::TypeHierarchyItem> {
+    items
+        .into_iter()
+        .map(|item| proto::TypeHierarchyItem {
+            location: Some(location_to_proto(&item.location, lsp_store, peer_id, cx)),
+            selection_start: Some(serialize_anchor(&item.selection_range.start)),
+            selection_end: Some(serialize_anchor(&item.selection_range.end)),
+            kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+            server_id: item.server_id.to_proto(),
+            lsp_item: serde_json::to_vec(&item.lsp_item).unwrap_or_default(),
+            name: item.name,
+            detail: item.detail,
+        })
+        .collect()
+}
+
+async fn type_hierarchy_items_from_proto(
+    items: Vec<proto::TypeHierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<TypeHierarchyItem>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        let location = location_from_proto(
+            item.location
+                .context("missing type hierarchy item location")?,
+            &lsp_store,
+            &mut cx,
+        )
+        .await?;
+        let selection_start = item
+            .selection_start
+            .and_then(deserialize_anchor)
+            .context("missing selection start")?;
+        let selection_end = item
+            .selection_end
+            .and_then(deserialize_anchor)
+            .context("missing selection end")?;
+        result.push(TypeHierarchyItem {
+            name: item.name,
+            kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+            detail: item.detail,
+            location,
+            selection_range: selection_start..selection_end,
+            server_id: LanguageServerId::from_proto(item.server_id),
+            lsp_item: serde_json::from_slice(&item.lsp_item)
+                .context("deserializing type hierarchy item")?,
+        });
+    }
+    Ok(result)
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        // `typeHierarchyProvider` is not modeled by `lsp::ServerCapabilities`, so every server of
+        // the buffer is asked and the ones that don't support it reply with an error.
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer)),
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing type hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_lsp(items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap_or_default(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)
+                .context("deserializing type hierarchy item")?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: type_hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSelectionRanges {
+    type Response = Vec<Vec<Range<Anchor>>>;
+    type LspRequest = lsp::request::SelectionRangeRequest;
+    type ProtoRequest = proto::GetSelectionRanges;
+
+    fn display_name(&self) -> &str {
+        "Get selection ranges"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.selection_range_provider {
+            Some(lsp::SelectionRangeProviderCapability::Simple(supported)) => supported,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SelectionRangeParams> {
+        Ok(lsp::SelectionRangeParams {
             text_document: make_text_document_identifier(path)?,
+            positions: self
+                .positions
+                .iter()
+                .map(|position| point_to_lsp(*position))
+                .collect(),
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -1696,164 +2788,116 @@ impl LspCommand for GetDocumentSymbols {
 
     async fn response_from_lsp(
         self,
-        lsp_symbols: Option<lsp::DocumentSymbolResponse>,
+        selection_ranges: Option<Vec<lsp::SelectionRange>>,
         _: Entity<LspStore>,
-        _: Entity<Buffer>,
+        buffer: Entity<Buffer>,
         _: LanguageServerId,
-        _: AsyncApp,
-    ) -> Result<Vec<DocumentSymbol>> {
-        let Some(lsp_symbols) = lsp_symbols else {
-            return Ok(Vec::new());
-        };
-
-        let symbols = match lsp_symbols {
-            lsp::DocumentSymbolResponse::Flat(symbol_information) => symbol_information
+        cx: AsyncApp,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        Ok(buffer.read_with(&cx, |buffer, _| {
+            selection_ranges
+                .unwrap_or_default()
                 .into_iter()
-                .map(|lsp_symbol| DocumentSymbol {
-                    name: lsp_symbol.name,
-                    kind: lsp_symbol.kind,
-                    range: range_from_lsp(lsp_symbol.location.range),
-                    selection_range: range_from_lsp(lsp_symbol.location.range),
-                    children: Vec::new(),
-                })
-                .collect(),
-            lsp::DocumentSymbolResponse::Nested(nested_responses) => {
-                fn convert_symbol(lsp_symbol: lsp::DocumentSymbol) -> DocumentSymbol {
-                    DocumentSymbol {
-                        name: lsp_symbol.name,
-                        kind: lsp_symbol.kind,
-                        range: range_from_lsp(lsp_symbol.range),
-                        selection_range: range_from_lsp(lsp_symbol.selection_range),
-                        children: lsp_symbol
-                            .children
-                            .map(|children| {
-                                children.into_iter().map(convert_symbol).collect::<Vec<_>>()
-                            })
-                            .unwrap_or_default(),
+                .map(|selection_range| {
+                    // Flatten the linked list of parents, from the innermost range outwards.
+                    let mut ranges = Vec::new();
+                    let mut next = Some(Box::new(selection_range));
+                    while let Some(selection_range) = next {
+                        ranges.push(anchor_range_from_lsp(buffer, selection_range.range));
+                        next = selection_range.parent;
                     }
-                }
-                nested_responses.into_iter().map(convert_symbol).collect()
-            }
-        };
-        Ok(symbols)
+                    ranges
+                })
+                .collect()
+        }))
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentSymbols {
-        proto::GetDocumentSymbols {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSelectionRanges {
+        proto::GetSelectionRanges {
             project_id,
             buffer_id: buffer.remote_id().into(),
+            positions: self
+                .positions
+                .iter()
+                .map(|position| serialize_anchor(&buffer.anchor_before(*position)))
+                .collect(),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        message: proto::GetDocumentSymbols,
+        message: proto::GetSelectionRanges,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
+        let positions = message
+            .positions
+            .into_iter()
+            .map(|position| deserialize_anchor(position).context("invalid position"))
+            .collect::<Result<Vec<_>>>()?;
         buffer
             .update(&mut cx, |buffer, _| {
                 buffer.wait_for_version(deserialize_version(&message.version))
             })
             .await?;
-        Ok(Self)
+        Ok(Self {
+            positions: buffer.read_with(&cx, |buffer, _| {
+                positions
+                    .iter()
+                    .map(|position| position.to_point_utf16(buffer))
+                    .collect()
+            }),
+        })
     }
 
     fn response_to_proto(
-        response: Vec<DocumentSymbol>,
+        response: Vec<Vec<Range<Anchor>>>,
         _: &mut LspStore,
         _: PeerId,
         _: &clock::Global,
         _: &mut App,
-    ) -> proto::GetDocumentSymbolsResponse {
-        let symbols = response
-            .into_iter()
-            .map(|symbol| {
-                fn convert_symbol_to_proto(symbol: DocumentSymbol) -> proto::DocumentSymbol {
-                    proto::DocumentSymbol {
-                        name: symbol.name.clone(),
-                        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(symbol.kind) },
-                        start: Some(proto::PointUtf16 {
-                            row: symbol.range.start.0.row,
-                            column: symbol.range.start.0.column,
-                        }),
-                        end: Some(proto::PointUtf16 {
-                            row: symbol.range.end.0.row,
-                            column: symbol.range.end.0.column,
-                        }),
-                        selection_start: Some(proto::PointUtf16 {
-                            row: symbol.selection_range.start.0.row,
-                            column: symbol.selection_range.start.0.column,
-                        }),
-                        selection_end: Some(proto::PointUtf16 {
-                            row: symbol.selection_range.end.0.row,
-                            column: symbol.selection_range.end.0.column,
-                        }),
-                        children: symbol
-                            .children
-                            .into_iter()
-                            .map(convert_symbol_to_proto)
-                            .collect(),
-                    }
-                }
-                convert_symbol_to_proto(symbol)
-            })
-            .collect::<Vec<_>>();
-
-        proto::GetDocumentSymbolsResponse { symbols }
+    ) -> proto::GetSelectionRangesResponse {
+        proto::GetSelectionRangesResponse {
+            chains: response
+                .into_iter()
+                .map(|ranges| proto::SelectionRangeChain {
+                    ranges: ranges.into_iter().map(serialize_anchor_range).collect(),
+                })
+                .collect(),
+        }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetDocumentSymbolsResponse,
+        message: proto::GetSelectionRangesResponse,
         _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: AsyncApp,
-    ) -> Result<Vec<DocumentSymbol>> {
-        let mut symbols = Vec::with_capacity(message.symbols.len());
-        for serialized_symbol in message.symbols {
-            fn deserialize_symbol_with_children(
-                serialized_symbol: proto::DocumentSymbol,
-            ) -> Result<DocumentSymbol> {
-                let kind =
-                    unsafe { mem::transmute::<i32, lsp::SymbolKind>(serialized_symbol.kind) };
-
-                let start = serialized_symbol.start.context("invalid start")?;
-                let end = serialized_symbol.end.context("invalid end")?;
-
-                let selection_start = serialized_symbol
-                    .selection_start
-                    .context("invalid selection start")?;
-                let selection_end = serialized_symbol
-                    .selection_end
-                    .context("invalid selection end")?;
-
-                Ok(DocumentSymbol {
-                    name: serialized_symbol.name,
-                    kind,
-                    range: Unclipped(PointUtf16::new(start.row, start.column))
-                        ..Unclipped(PointUtf16::new(end.row, end.column)),
-                    selection_range: Unclipped(PointUtf16::new(
-                        selection_start.row,
-                        selection_start.column,
-                    ))
-                        ..Unclipped(PointUtf16::new(selection_end.row, selection_end.column)),
-                    children: serialized_symbol
-                        .children
-                        .into_iter()
-                        .filter_map(|symbol| deserialize_symbol_with_children(symbol).ok())
-                        .collect::<Vec<_>>(),
-                })
-            }
-
-            symbols.push(deserialize_symbol_with_children(serialized_symbol)?);
-        }
-
-        Ok(symbols)
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<Vec<Range<Anchor>>>> {
+        let chains = message
+            .chains
+            .into_iter()
+            .map(|chain| {
+                chain
+                    .ranges
+                    .into_iter()
+                    .map(deserialize_anchor_range)
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let anchors = chains
+            .iter()
+            .flatten()
+            .flat_map(|range| [range.start, range.end])
+            .collect::<Vec<_>>();
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors(anchors))
+            .await?;
+        Ok(chains)
     }
 
-    fn buffer_id_from_proto(message: &proto::GetDocumentSymbols) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::GetSelectionRanges) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
@@ -4889,6 +5933,135 @@ impl LspCommand for GetFoldingRanges {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
+
+    fn display_name(&self) -> &str {
+        "Document links"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .document_link_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::DocumentLink>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot());
+        let can_resolve = cx.update(|cx| {
+            lsp_store
+                .read(cx)
+                .language_server_for_id(server_id)
+                .and_then(|server| {
+                    server
+                        .capabilities()
+                        .document_link_provider
+                        .and_then(|options| options.resolve_provider)
+                })
+                .unwrap_or(false)
+        });
+        Ok(message
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|link| {
+                // Servers may leave out targets that are expensive to compute and only fill them
+                // in on `documentLink/resolve`, which is sent when the link is used.
+                if link.target.is_none() && !can_resolve {
+                    return None;
+                }
+                let range = range_from_lsp(link.range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                Some(DocumentLink {
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    target: link.target.as_ref().map(DocumentLinkTarget::from_lsp),
+                    tooltip: link.tooltip.clone().map(SharedString::from),
+                    server_id,
+                    unresolved_link: link.target.is_none().then_some(link),
+                })
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetDocumentLinks {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.into_iter().map(|link| link.to_proto()).collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetDocumentLinksResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })
+            .await?;
+        message
+            .links
+            .into_iter()
+            .map(DocumentLink::from_proto)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
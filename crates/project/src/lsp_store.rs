@@ -12,6 +12,7 @@
 pub mod clangd_ext;
 mod code_lens;
 mod document_colors;
+mod document_links;
 mod document_symbols;
 mod folding_ranges;
 mod inlay_hints;
@@ -20,17 +21,19 @@ pub mod log_store;
 pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 mod semantic_tokens;
+mod task_diagnostics;
 pub mod vue_language_server_ext;
 
 use self::code_lens::CodeLensData;
 use self::document_colors::DocumentColorData;
+use self::document_links::DocumentLinksData;
 use self::document_symbols::DocumentSymbolsData;
 use self::inlay_hints::BufferInlayHints;
 use crate::{
     CodeAction, Completion, CompletionDisplayOptions, CompletionResponse, CompletionSource,
     CoreCompletion, Hover, InlayHint, InlayId, LocationLink, LspAction, LspPullDiagnostics,
     ManifestProvidersStore, Project, ProjectItem, ProjectPath, ProjectTransaction,
-    PulledDiagnostics, ResolveState, Symbol,
+    PulledDiagnostics, ResolveState, Symbol, TypeHierarchyItem,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -143,6 +146,7 @@ use util::{
 };
 
 pub use document_colors::DocumentColors;
+pub use document_links::{DocumentLink, DocumentLinkTarget};
 pub use folding_ranges::LspFoldingRange;
 pub use fs::*;
 pub use language::Location;
@@ -317,6 +321,7 @@ pub struct LocalLspStore {
         >,
     >,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    task_diagnostics: task_diagnostics::TaskDiagnostics,
     _subscription: gpui::Subscription,
     lsp_tree: LanguageServerTree,
     registered_buffers: HashMap<BufferId, usize>,
@@ -841,7 +846,9 @@ impl LocalLspStore {
                                     DiagnosticSourceKind::Other | DiagnosticSourceKind::Pushed => {
                                         adapter.retain_old_diagnostic(diagnostic)
                                     }
-                                    DiagnosticSourceKind::Pulled => true,
+                                    DiagnosticSourceKind::Pulled | DiagnosticSourceKind::Task => {
+                                        true
+                                    }
                                 },
                                 cx,
                             )
@@ -3976,6 +3983,7 @@ pub struct BufferLspData {
     semantic_tokens: Option<SemanticTokensData>,
     folding_ranges: Option<FoldingRangeData>,
     document_symbols: Option<DocumentSymbolsData>,
+    document_links: Option<DocumentLinksData>,
     inlay_hints: BufferInlayHints,
     lsp_requests: HashMap<LspKey, HashMap<LspRequestId, Task<()>>>,
     chunk_lsp_requests: HashMap<LspKey, HashMap<RowChunk, LspRequestId>>,
@@ -3996,6 +4004,7 @@ impl BufferLspData {
             semantic_tokens: None,
             folding_ranges: None,
             document_symbols: None,
+            document_links: None,
             inlay_hints: BufferInlayHints::new(buffer, cx),
             lsp_requests: HashMap::default(),
             chunk_lsp_requests: HashMap::default(),
@@ -4024,6 +4033,10 @@ impl BufferLspData {
         if let Some(document_symbols) = &mut self.document_symbols {
             document_symbols.remove_server_data(for_server);
         }
+
+        if let Some(document_links) = &mut self.document_links {
+            document_links.links.remove(&for_server);
+        }
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -4131,12 +4144,14 @@ impl LspStore {
         client.add_entity_message_handler(Self::handle_update_language_server);
         client.add_entity_message_handler(Self::handle_language_server_log);
         client.add_entity_message_handler(Self::handle_update_diagnostic_summary);
+        client.add_entity_request_handler(Self::handle_update_task_diagnostics);
         client.add_entity_request_handler(Self::handle_format_buffers);
         client.add_entity_request_handler(Self::handle_apply_code_action_kind);
         client.add_entity_request_handler(Self::handle_resolve_completion_documentation);
         client.add_entity_request_handler(Self::handle_apply_code_action);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
@@ -4153,6 +4168,13 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
         client.add_entity_request_handler(Self::handle_lsp_ext_run_flycheck);
@@ -4262,6 +4284,7 @@ impl LspStore {
                 yarn,
                 next_diagnostic_group_id: Default::default(),
                 diagnostics: Default::default(),
+                task_diagnostics: Default::default(),
                 _subscription: cx.on_app_quit(|this, _| {
                     this.as_local_mut()
                         .unwrap()
@@ -6087,6 +6110,73 @@ impl LspStore {
         }
     }
 
+    /// Asks every language server of the buffer for the type hierarchy items at the position,
+    /// since servers don't advertise whether they support it, and returns the first non-empty
+    /// reply. The items remember the server that returned them, which expands them later.
+    pub fn prepare_type_hierarchy(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: PointUtf16,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = PrepareTypeHierarchy { position };
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(Vec::new()));
+            }
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            let request_task = upstream_client.request_lsp(
+                project_id,
+                None,
+                request_timeout,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let Some(lsp_store) = weak_lsp_store.upgrade() else {
+                    return Ok(Vec::new());
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(Vec::new());
+                };
+                for response in responses.payload {
+                    let items = PrepareTypeHierarchy { position }
+                        .response_from_proto(
+                            response.response,
+                            lsp_store.clone(),
+                            buffer.clone(),
+                            cx.clone(),
+                        )
+                        .await
+                        .log_err()
+                        .unwrap_or_default();
+                    if !items.is_empty() {
+                        return Ok(items);
+                    }
+                }
+                Ok(Vec::new())
+            })
+        } else {
+            let items_task = self.request_multiple_lsp_locally(
+                buffer,
+                Some(position),
+                PrepareTypeHierarchy { position },
+                cx,
+            );
+            cx.background_spawn(async move {
+                Ok(items_task
+                    .await
+                    .into_iter()
+                    .map(|(_, items)| items)
+                    .find(|items| !items.is_empty())
+                    .unwrap_or_default())
+            })
+        }
+    }
+
     pub fn references(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -7491,9 +7581,9 @@ impl LspStore {
                                                     unchanged_buffers.contains(&document_uri)
                                                 })
                                     }
-                                    DiagnosticSourceKind::Other | DiagnosticSourceKind::Pushed => {
-                                        true
-                                    }
+                                    DiagnosticSourceKind::Other
+                                    | DiagnosticSourceKind::Pushed
+                                    | DiagnosticSourceKind::Task => true,
                                 },
                                 cx,
                             )
@@ -9037,6 +9127,18 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::GetDocumentLinks(get_document_links) => {
+                Self::query_lsp_locally::<GetDocumentLinks>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_document_links,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
             Request::GetHover(get_hover) => {
                 let position = get_hover.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetHover>(
@@ -9151,6 +9253,22 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::PrepareTypeHierarchy(prepare_type_hierarchy) => {
+                let position = prepare_type_hierarchy
+                    .position
+                    .clone()
+                    .and_then(deserialize_anchor);
+                Self::query_lsp_locally::<PrepareTypeHierarchy>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    prepare_type_hierarchy,
+                    position,
+                    &mut cx,
+                )
+                .await?;
+            }
             Request::InlayHints(inlay_hints) => {
                 let query_start = inlay_hints
                     .start
@@ -12419,7 +12537,9 @@ impl LspStore {
                                         unchanged_buffers.contains(&document_uri)
                                     })
                         }
-                        DiagnosticSourceKind::Other | DiagnosticSourceKind::Pushed => true,
+                        DiagnosticSourceKind::Other
+                        | DiagnosticSourceKind::Pushed
+                        | DiagnosticSourceKind::Task => true,
                     },
                     cx,
                 )
@@ -12764,6 +12884,42 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::CallHierarchyServerCapability::Simple(value),
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::SelectionRangeProviderCapability::Simple(value),
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(_) => lsp::DocumentLinkOptions {
+                            resolve_provider: None,
+                            work_done_progress_options: Default::default(),
+                        },
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -12967,6 +13123,24 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareCallHierarchy" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.call_hierarchy_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
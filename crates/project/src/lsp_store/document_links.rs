@@ -0,0 +1,395 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use clock::Global;
+use collections::HashMap;
+use futures::FutureExt as _;
+use futures::future::{Shared, join_all};
+use gpui::{AppContext as _, AsyncApp, Context, Entity, SharedString, Task};
+use language::{
+    Buffer,
+    proto::{deserialize_anchor_range, serialize_anchor_range},
+};
+use lsp::LanguageServerId;
+use rpc::{TypedEnvelope, proto};
+use settings::Settings as _;
+use text::{Anchor, BufferId};
+
+use crate::lsp_command::{GetDocumentLinks, LspCommand as _};
+use crate::lsp_store::LspStore;
+use crate::project_settings::ProjectSettings;
+
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<Anchor>,
+    /// `None` until [`LspStore::resolve_document_link`] asks the server for it.
+    pub target: Option<DocumentLinkTarget>,
+    pub tooltip: Option<SharedString>,
+    pub server_id: LanguageServerId,
+    /// The link as the server sent it, kept to resolve its target.
+    pub(crate) unresolved_link: Option<lsp::DocumentLink>,
+}
+
+impl DocumentLink {
+    pub(crate) fn to_proto(self) -> proto::DocumentLink {
+        proto::DocumentLink {
+            range: Some(serialize_anchor_range(self.range)),
+            target: self.target.map(DocumentLinkTarget::to_proto),
+            tooltip: self.tooltip.map(|tooltip| tooltip.to_string()),
+            server_id: self.server_id.to_proto(),
+            unresolved_link: self
+                .unresolved_link
+                .and_then(|link| serde_json::to_vec(&link).ok()),
+        }
+    }
+
+    pub(crate) fn from_proto(link: proto::DocumentLink) -> anyhow::Result<Self> {
+        Ok(Self {
+            range: deserialize_anchor_range(link.range.context("missing document link range")?)?,
+            target: link.target.map(DocumentLinkTarget::from_proto),
+            tooltip: link.tooltip.map(SharedString::from),
+            server_id: LanguageServerId::from_proto(link.server_id),
+            unresolved_link: link
+                .unresolved_link
+                .map(|link| serde_json::from_slice(&link))
+                .transpose()
+                .context("deserializing document link")?,
+        })
+    }
+}
+
+/// Where a [`DocumentLink`] points to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DocumentLinkTarget {
+    /// An absolute path on the host the language server runs on.
+    Path(String),
+    /// Any non-`file://` URI, to be opened externally.
+    Url(String),
+}
+
+impl DocumentLinkTarget {
+    pub(crate) fn from_lsp(target: &lsp::Uri) -> Self {
+        match target.to_file_path() {
+            Ok(path) => Self::Path(path.to_string_lossy().into_owned()),
+            Err(_) => Self::Url(target.as_str().to_owned()),
+        }
+    }
+
+    fn to_proto(self) -> proto::document_link::Target {
+        match self {
+            Self::Url(url) => proto::document_link::Target::Url(url),
+            Self::Path(path) => proto::document_link::Target::Path(path),
+        }
+    }
+
+    fn from_proto(target: proto::document_link::Target) -> Self {
+        match target {
+            proto::document_link::Target::Url(url) => Self::Url(url),
+            proto::document_link::Target::Path(path) => Self::Path(path),
+        }
+    }
+}
+
+pub(super) type DocumentLinksTask =
+    Shared<Task<std::result::Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>;
+
+#[derive(Debug, Default)]
+pub(super) struct DocumentLinksData {
+    pub(super) links: HashMap<LanguageServerId, Vec<DocumentLink>>,
+    links_update: Option<(Global, DocumentLinksTask)>,
+}
+
+impl LspStore {
+    /// Returns a task that resolves to the document links of the given buffer.
+    ///
+    /// Links are cached per buffer version, so hovering over the same buffer repeatedly
+    /// only queries the language servers again after the buffer has been edited.
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<DocumentLink>> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        let current_language_servers = self.as_local().map(|local| {
+            local
+                .buffers_opened_in_servers
+                .get(&buffer_id)
+                .cloned()
+                .unwrap_or_default()
+        });
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id) {
+            if let Some(cached) = &lsp_data.document_links {
+                if !version_queried_for.changed_since(&lsp_data.buffer_version) {
+                    let has_different_servers =
+                        current_language_servers.is_some_and(|current_language_servers| {
+                            current_language_servers != cached.links.keys().copied().collect()
+                        });
+                    if !has_different_servers {
+                        return Task::ready(cached.links.values().flatten().cloned().collect());
+                    }
+                }
+            }
+        }
+
+        let links_lsp_data = self
+            .latest_lsp_data(buffer, cx)
+            .document_links
+            .get_or_insert_default();
+        if let Some((updating_for, running_update)) = &links_lsp_data.links_update {
+            if !version_queried_for.changed_since(updating_for) {
+                let running = running_update.clone();
+                return cx.background_spawn(async move { running.await.unwrap_or_default() });
+            }
+        }
+
+        let buffer = buffer.clone();
+        let query_version = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                let fetched = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_document_links_for_buffer(&buffer, cx)
+                    })
+                    .map_err(Arc::new)?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+
+                let fetched = match fetched {
+                    Ok(fetched) => fetched,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id) {
+                                    if let Some(document_links) = &mut lsp_data.document_links {
+                                        document_links.links_update = None;
+                                    }
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        let document_links = lsp_data.document_links.get_or_insert_default();
+
+                        if let Some(fetched_links) = fetched {
+                            if lsp_data.buffer_version == query_version {
+                                document_links.links.extend(fetched_links);
+                            } else if !lsp_data.buffer_version.changed_since(&query_version) {
+                                lsp_data.buffer_version = query_version;
+                                document_links.links = fetched_links;
+                            }
+                        }
+                        document_links.links_update = None;
+                        document_links.links.values().flatten().cloned().collect()
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+
+        links_lsp_data.links_update = Some((version_queried_for, new_task.clone()));
+
+        cx.background_spawn(async move { new_task.await.unwrap_or_default() })
+    }
+
+    fn fetch_document_links_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Vec<DocumentLink>>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetDocumentLinks;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                request_timeout,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let Some(lsp_store) = weak_lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let document_links = join_all(responses.payload.into_iter().map(|response| {
+                    let lsp_store = lsp_store.clone();
+                    let buffer = buffer.clone();
+                    let cx = cx.clone();
+                    async move {
+                        (
+                            LanguageServerId::from_proto(response.server_id),
+                            GetDocumentLinks
+                                .response_from_proto(response.response, lsp_store, buffer, cx)
+                                .await,
+                        )
+                    }
+                }))
+                .await;
+
+                let mut has_errors = false;
+                let result = document_links
+                    .into_iter()
+                    .filter_map(|(server_id, links)| match links {
+                        Ok(links) => Some((server_id, links)),
+                        Err(e) => {
+                            has_errors = true;
+                            log::error!("Failed to fetch document links: {e:#}");
+                            None
+                        }
+                    })
+                    .collect::<HashMap<_, _>>();
+                anyhow::ensure!(
+                    !has_errors || !result.is_empty(),
+                    "Failed to fetch document links"
+                );
+                Ok(Some(result))
+            })
+        } else {
+            let links_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentLinks, cx);
+            cx.background_spawn(async move { Ok(Some(links_task.await.into_iter().collect())) })
+        }
+    }
+
+    /// Returns the target of a document link, asking the server that reported it with
+    /// `documentLink/resolve` if needed. The resolved target is cached with the buffer's links,
+    /// so every link is resolved at most once per buffer version.
+    pub fn resolve_document_link(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        link: &DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<DocumentLinkTarget>>> {
+        if link.target.is_some() {
+            return Task::ready(Ok(link.target.clone()));
+        }
+        let Some(lsp_link) = link.unresolved_link.clone() else {
+            return Task::ready(Ok(None));
+        };
+        let server_id = link.server_id;
+        let resolve_task = self.resolve_lsp_document_link(buffer, server_id, lsp_link, cx);
+        let buffer_id = buffer.read(cx).remote_id();
+        let range = link.range.clone();
+        cx.spawn(async move |lsp_store, cx| {
+            let target = resolve_task.await?;
+            lsp_store
+                .update(cx, |lsp_store, _| {
+                    lsp_store.cache_resolved_document_link(
+                        buffer_id,
+                        server_id,
+                        &range,
+                        target.clone(),
+                    )
+                })
+                .ok();
+            Ok(target)
+        })
+    }
+
+    fn resolve_lsp_document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        lsp_link: lsp::DocumentLink,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<DocumentLinkTarget>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().to_proto(),
+                language_server_id: server_id.to_proto(),
+                lsp_link: serde_json::to_vec(&lsp_link).unwrap_or_default(),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("resolving document link")?;
+                Ok(response.target.map(DocumentLinkTarget::from_proto))
+            })
+        } else {
+            let Some(language_server) = self.language_server_for_id(server_id) else {
+                return Task::ready(Ok(None));
+            };
+            let request_timeout = ProjectSettings::get_global(cx)
+                .global_lsp_settings
+                .get_request_timeout();
+            cx.background_spawn(async move {
+                let resolved_link = language_server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link, request_timeout)
+                    .await
+                    .into_response()
+                    .context("resolving document link")?;
+                Ok(resolved_link
+                    .target
+                    .as_ref()
+                    .map(DocumentLinkTarget::from_lsp))
+            })
+        }
+    }
+
+    fn cache_resolved_document_link(
+        &mut self,
+        buffer_id: BufferId,
+        server_id: LanguageServerId,
+        range: &Range<Anchor>,
+        target: Option<DocumentLinkTarget>,
+    ) {
+        let Some(links) = self
+            .lsp_data
+            .get_mut(&buffer_id)
+            .and_then(|lsp_data| lsp_data.document_links.as_mut())
+            .and_then(|document_links| document_links.links.get_mut(&server_id))
+        else {
+            return;
+        };
+        for link in links {
+            if link.unresolved_link.is_some() && &link.range == range {
+                link.target = target.clone();
+                link.unresolved_link = None;
+            }
+        }
+    }
+
+    pub(super) async fn handle_resolve_document_link(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<proto::ResolveDocumentLinkResponse> {
+        let lsp_link = serde_json::from_slice(&envelope.payload.lsp_link)
+            .context("deserializing document link")?;
+        let resolve_task = lsp_store.update(&mut cx, |lsp_store, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            let buffer = lsp_store.buffer_store.read(cx).get_existing(buffer_id)?;
+            anyhow::Ok(lsp_store.resolve_lsp_document_link(
+                &buffer,
+                LanguageServerId::from_proto(envelope.payload.language_server_id),
+                lsp_link,
+                cx,
+            ))
+        })?;
+        let target = resolve_task.await?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            target: target.map(DocumentLinkTarget::to_proto),
+        })
+    }
+}
@@ -1,14 +1,17 @@
 use std::{collections::VecDeque, sync::Arc};
 
+use anyhow::Context as _;
 use collections::HashMap;
 use futures::{StreamExt, channel::mpsc};
 use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Subscription, WeakEntity};
 use lsp::{
     IoKind, LanguageServer, LanguageServerId, LanguageServerName, LanguageServerSelector,
     MessageType, TraceValue,
+    rpc_trace::{RpcDirection, RpcTraceEntry, remove_unmatched_responses},
 };
 use rpc::proto;
 use settings::WorktreeId;
+use util::ResultExt as _;
 
 use crate::{LanguageServerLogType, LspStore, Project, ProjectItem as _};
 
@@ -108,9 +111,10 @@ impl Message for TraceMessage {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RpcMessage {
     message: String,
+    kind: MessageKind,
 }
 
 impl AsRef<str> for RpcMessage {
@@ -130,6 +134,9 @@ pub struct LanguageServerState {
     log_messages: VecDeque<LogMessage>,
     trace_messages: VecDeque<TraceMessage>,
     pub rpc_state: Option<LanguageServerRpcState>,
+    /// The server's initialization, which exported RPC traces start with. Only known for the
+    /// servers started by this instance.
+    initialize_trace: Vec<RpcTraceEntry>,
     pub trace_level: TraceValue,
     pub log_level: MessageType,
     io_logs_subscription: Option<lsp::Subscription>,
@@ -185,6 +192,9 @@ impl LanguageServerKind {
 #[derive(Debug)]
 pub struct LanguageServerRpcState {
     pub rpc_messages: VecDeque<RpcMessage>,
+    /// The messages recorded for a trace, while recording one. Unlike `rpc_messages`, these
+    /// aren't capped, so they're only kept between starting and exporting the recording.
+    recorded_messages: Option<Vec<RpcMessage>>,
     last_message_kind: Option<MessageKind>,
 }
 
@@ -194,6 +204,15 @@ enum MessageKind {
     Receive,
 }
 
+impl MessageKind {
+    fn separator(&self) -> &'static str {
+        match self {
+            MessageKind::Send => SEND_LINE,
+            MessageKind::Receive => RECEIVE_LINE,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum LogKind {
     Rpc,
@@ -378,6 +397,7 @@ impl LogStore {
                 worktree_id: None,
                 kind,
                 rpc_state: None,
+                initialize_trace: Vec::new(),
                 log_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
                 trace_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
                 trace_level: TraceValue::Off,
@@ -395,6 +415,7 @@ impl LogStore {
         }
 
         if let Some(server) = server.filter(|_| server_state.io_logs_subscription.is_none()) {
+            server_state.initialize_trace = server.initialize_trace().to_vec();
             let io_tx = self.io_tx.clone();
             let server_id = server.server_id();
             server_state.io_logs_subscription = Some(server.on_io(move |io_kind, message| {
@@ -533,13 +554,11 @@ impl LogStore {
             while rpc_log_lines.len() + 1 >= MAX_STORED_LOG_ENTRIES {
                 rpc_log_lines.pop_front();
             }
-            let line_before_message = match kind {
-                MessageKind::Send => SEND_LINE,
-                MessageKind::Receive => RECEIVE_LINE,
-            };
+            let line_before_message = kind.separator();
             if store_logs {
                 rpc_log_lines.push_back(RpcMessage {
                     message: line_before_message.to_string(),
+                    kind,
                 });
             }
             // Do not send a synthetic message over the wire, it will be derived from the actual RPC message
@@ -555,9 +574,14 @@ impl LogStore {
         }
 
         if store_logs {
-            rpc_log_lines.push_back(RpcMessage {
+            let message = RpcMessage {
                 message: message.trim().to_owned(),
-            });
+                kind,
+            };
+            if let Some(recorded_messages) = &mut state.recorded_messages {
+                recorded_messages.push(message.clone());
+            }
+            rpc_log_lines.push_back(message);
         }
 
         self.emit_event(
@@ -601,6 +625,56 @@ impl LogStore {
             })
     }
 
+    /// Starts recording the RPC messages of a server, enabling RPC logging for it if needed.
+    pub fn start_recording_rpc_trace(&mut self, server_id: LanguageServerId) {
+        if let Some(rpc_state) = self.enable_rpc_trace_for_language_server(server_id) {
+            rpc_state.recorded_messages = Some(Vec::new());
+        }
+    }
+
+    pub fn is_recording_rpc_trace(&self, server_id: LanguageServerId) -> bool {
+        self.language_servers
+            .get(&server_id)
+            .and_then(|state| state.rpc_state.as_ref())
+            .is_some_and(|rpc_state| rpc_state.recorded_messages.is_some())
+    }
+
+    /// Stops recording the RPC messages of a server and returns them as a JSONL trace that can
+    /// be replayed with [`lsp::FakeLanguageServer::replay`].
+    ///
+    /// The trace starts with the server's initialization, which replays need. Servers that were
+    /// started elsewhere, e.g. on a remote host, can't be exported.
+    pub fn finish_recording_rpc_trace(
+        &mut self,
+        server_id: LanguageServerId,
+    ) -> anyhow::Result<String> {
+        let state = self
+            .language_servers
+            .get_mut(&server_id)
+            .context("unknown language server")?;
+        let recorded_messages = state
+            .rpc_state
+            .as_mut()
+            .and_then(|rpc_state| rpc_state.recorded_messages.take())
+            .context("no RPC trace is being recorded for this language server")?;
+        anyhow::ensure!(
+            !state.initialize_trace.is_empty(),
+            "the initialization of this language server wasn't recorded, so its trace couldn't be replayed"
+        );
+        let mut entries = state.initialize_trace.clone();
+        entries.extend(recorded_messages.iter().filter_map(|message| {
+            let direction = match message.kind {
+                MessageKind::Send => RpcDirection::Send,
+                MessageKind::Receive => RpcDirection::Receive,
+            };
+            RpcTraceEntry::new(direction, &message.message).log_err()
+        }));
+        Ok(remove_unmatched_responses(entries)
+            .iter()
+            .map(RpcTraceEntry::to_json_line)
+            .collect())
+    }
+
     pub fn enable_rpc_trace_for_language_server(
         &mut self,
         server_id: LanguageServerId,
@@ -611,6 +685,7 @@ impl LogStore {
             .rpc_state
             .get_or_insert_with(|| LanguageServerRpcState {
                 rpc_messages: VecDeque::with_capacity(MAX_STORED_LOG_ENTRIES),
+                recorded_messages: None,
                 last_message_kind: None,
             });
         Some(rpc_state)
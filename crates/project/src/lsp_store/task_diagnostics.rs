@@ -0,0 +1,225 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use client::{TypedEnvelope, proto};
+use collections::{HashMap, HashSet};
+use gpui::{AsyncApp, Context, Entity, Task};
+use language::{Diagnostic, DiagnosticEntry, DiagnosticSourceKind, PointUtf16, Unclipped};
+use lsp::{DiagnosticSeverity, LanguageServerId, NumberOrString};
+use task::{Problem, ProblemSeverity, TaskId};
+use util::{ResultExt as _, paths::normalize_lexically, post_inc};
+
+use crate::lsp_store::{DocumentDiagnostics, DocumentDiagnosticsUpdate, LspStore};
+
+#[derive(Default)]
+pub(super) struct TaskDiagnostics {
+    /// Diagnostics are stored per language server, so the problems of all tasks are stored under
+    /// an id that the language registry hands out the first time a task reports any.
+    server_id: Option<LanguageServerId>,
+    by_task: HashMap<TaskId, HashMap<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>>,
+}
+
+impl LspStore {
+    /// Replaces the diagnostics from the previous run of the given task with the problems found in its latest output.
+    ///
+    /// Relative paths of the problems are resolved against `cwd`, and problems outside of the project's worktrees are ignored.
+    /// Remote projects send the problems to the host, where the task ran.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_id: TaskId,
+        cwd: &Path,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = client.request(proto::UpdateTaskDiagnostics {
+                project_id,
+                task_id: task_id.0,
+                cwd: cwd.to_string_lossy().into_owned(),
+                problems: problems.into_iter().map(problem_to_proto).collect(),
+            });
+            return cx.background_spawn(async move {
+                request.await?;
+                Ok(())
+            });
+        }
+        Task::ready(self.update_local_task_diagnostics(task_id, cwd, problems, cx))
+    }
+
+    pub(super) async fn handle_update_task_diagnostics(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateTaskDiagnostics>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let payload = envelope.payload;
+        let problems = payload
+            .problems
+            .into_iter()
+            .map(problem_from_proto)
+            .collect::<Result<Vec<_>>>()?;
+        lsp_store.update(&mut cx, |lsp_store, cx| {
+            lsp_store.update_local_task_diagnostics(
+                TaskId(payload.task_id),
+                Path::new(&payload.cwd),
+                problems,
+                cx,
+            )
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    fn update_local_task_diagnostics(
+        &mut self,
+        task_id: TaskId,
+        cwd: &Path,
+        problems: Vec<Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let local = self
+            .as_local_mut()
+            .context("cannot update task diagnostics of a remote project")?;
+        let server_id = *local
+            .task_diagnostics
+            .server_id
+            .get_or_insert_with(|| local.languages.next_language_server_id());
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let Some(abs_path) = normalize_lexically(&cwd.join(&problem.path)).log_err() else {
+                continue;
+            };
+            let group_id = post_inc(&mut local.next_diagnostic_group_id);
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(diagnostic_entry(problem, group_id));
+        }
+
+        let mut updated_paths = diagnostics_by_path.keys().cloned().collect::<HashSet<_>>();
+        if let Some(previous_diagnostics) = local
+            .task_diagnostics
+            .by_task
+            .insert(task_id.clone(), diagnostics_by_path)
+        {
+            updated_paths.extend(previous_diagnostics.into_keys());
+        }
+        if local
+            .task_diagnostics
+            .by_task
+            .get(&task_id)
+            .is_some_and(|diagnostics| diagnostics.is_empty())
+        {
+            local.task_diagnostics.by_task.remove(&task_id);
+        }
+
+        // Several tasks may report problems in the same file, all of them are stored under the same id.
+        let updates = updated_paths
+            .into_iter()
+            .filter(|abs_path| {
+                self.worktree_store
+                    .read(cx)
+                    .find_worktree(abs_path, cx)
+                    .is_some()
+            })
+            .map(|abs_path| {
+                let diagnostics = self
+                    .as_local()
+                    .into_iter()
+                    .flat_map(|local| local.task_diagnostics.by_task.values())
+                    .filter_map(|diagnostics_by_path| diagnostics_by_path.get(&abs_path))
+                    .flatten()
+                    .cloned()
+                    .collect();
+                DocumentDiagnosticsUpdate {
+                    diagnostics: DocumentDiagnostics {
+                        diagnostics,
+                        document_abs_path: abs_path,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                }
+            })
+            .collect();
+        self.merge_diagnostic_entries(updates, |_, _, _| false, cx)
+    }
+}
+
+fn diagnostic_entry(problem: Problem, group_id: usize) -> DiagnosticEntry<Unclipped<PointUtf16>> {
+    let row = problem.line.saturating_sub(1);
+    let start = PointUtf16::new(row, problem.column.unwrap_or(1).saturating_sub(1));
+    let end = match problem.end {
+        Some((end_line, Some(end_column))) => {
+            PointUtf16::new(end_line.saturating_sub(1), end_column.saturating_sub(1))
+        }
+        Some((end_line, None)) => PointUtf16::new(end_line.saturating_sub(1), u32::MAX),
+        // Without a column, the whole line is highlighted.
+        None if problem.column.is_none() => PointUtf16::new(row, u32::MAX),
+        None => start,
+    };
+    DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end),
+        diagnostic: Diagnostic {
+            source: Some(problem.source),
+            source_kind: DiagnosticSourceKind::Task,
+            code: problem.code.map(NumberOrString::String),
+            severity: match problem.severity {
+                ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                ProblemSeverity::Information => DiagnosticSeverity::INFORMATION,
+                ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            },
+            message: problem.message,
+            group_id,
+            is_primary: true,
+            // Tasks see the files as they are on disk, so the ranges are adjusted for any unsaved edits.
+            is_disk_based: true,
+            ..Diagnostic::default()
+        },
+    }
+}
+
+fn problem_to_proto(problem: Problem) -> proto::TaskProblem {
+    proto::TaskProblem {
+        source: problem.source,
+        path: problem.path.to_string_lossy().into_owned(),
+        line: problem.line,
+        column: problem.column,
+        end_line: problem.end.map(|(line, _)| line),
+        end_column: problem.end.and_then(|(_, column)| column),
+        severity: match problem.severity {
+            ProblemSeverity::Error => proto::task_problem::Severity::Error,
+            ProblemSeverity::Warning => proto::task_problem::Severity::Warning,
+            ProblemSeverity::Information => proto::task_problem::Severity::Information,
+            ProblemSeverity::Hint => proto::task_problem::Severity::Hint,
+        } as i32,
+        code: problem.code,
+        message: problem.message,
+    }
+}
+
+fn problem_from_proto(problem: proto::TaskProblem) -> Result<Problem> {
+    let severity = match proto::task_problem::Severity::from_i32(problem.severity)
+        .context("invalid task problem severity")?
+    {
+        proto::task_problem::Severity::Error => ProblemSeverity::Error,
+        proto::task_problem::Severity::Warning => ProblemSeverity::Warning,
+        proto::task_problem::Severity::Information => ProblemSeverity::Information,
+        proto::task_problem::Severity::Hint => ProblemSeverity::Hint,
+    };
+    Ok(Problem {
+        source: problem.source,
+        path: PathBuf::from(problem.path),
+        line: problem.line,
+        column: problem.column,
+        end: problem
+            .end_line
+            .map(|end_line| (end_line, problem.end_column)),
+        severity,
+        code: problem.code,
+        message: problem.message,
+    })
+}
@@ -154,10 +154,10 @@ pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use prettier::RANGE_FORMAT_SUFFIX as TEST_PRETTIER_RANGE_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskNode, TaskSourceKind,
 };
 
-pub use buffer_store::ProjectTransaction;
+pub use buffer_store::{ProjectTransaction, UnsyncedBufferState};
 pub use lsp_store::{
     DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
     LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
@@ -374,6 +374,10 @@ pub enum Event {
     DisconnectedFromRemote {
         server_not_running: bool,
     },
+    UnsyncedBuffersChanged,
+    /// A terminal of a remote project printed a `localhost:PORT` address, suggesting that a
+    /// server was started on the remote host.
+    LocalhostPortDetected(u16),
     Closed,
     DeletedEntry(WorktreeId, ProjectEntryId),
     CollaboratorUpdated {
@@ -848,6 +852,43 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the whole symbol, e.g. a function including its body.
+    pub location: Location,
+    /// The range to reveal when navigating to the symbol, e.g. the function name.
+    pub selection_range: Range<language::Anchor>,
+    pub server_id: LanguageServerId,
+    /// The item as reported by the language server, sent back verbatim when querying its calls.
+    pub lsp_item: lsp::CallHierarchyItem,
+}
+
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The ranges of the call expressions.
+    pub call_sites: Vec<Location>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The range enclosing the whole type definition.
+    pub location: Location,
+    /// The range to reveal when navigating to the type, e.g. its name.
+    pub selection_range: Range<language::Anchor>,
+    pub server_id: LanguageServerId,
+    /// The item as reported by the language server, sent back verbatim when querying
+    /// its supertypes or subtypes.
+    pub lsp_item: lsp::TypeHierarchyItem,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -3196,6 +3237,12 @@ impl Project {
         })
     }
 
+    /// Returns the buffers that were saved while disconnected from the remote server, and whose
+    /// saves haven't been written to disk there yet.
+    pub fn unsynced_buffers(&self, cx: &App) -> Vec<(Entity<Buffer>, UnsyncedBufferState)> {
+        self.buffer_store.read(cx).unsynced_buffers()
+    }
+
     pub fn get_open_buffer(&self, path: &ProjectPath, cx: &App) -> Option<Entity<Buffer>> {
         self.buffer_store.read(cx).get_by_path(path)
     }
@@ -3387,6 +3434,7 @@ impl Project {
                         .log_err();
                 }
             }
+            BufferStoreEvent::UnsyncedBuffersChanged => cx.emit(Event::UnsyncedBuffersChanged),
             _ => {}
         }
     }
@@ -3591,6 +3639,18 @@ impl Project {
                 });
                 cx.emit(Event::DisconnectedFromRemote { server_not_running });
             }
+            remote::RemoteClientEvent::ConnectionLost => {
+                self.buffer_store
+                    .update(cx, |buffer_store, _| buffer_store.remote_connection_lost());
+            }
+            remote::RemoteClientEvent::Reconnected => {
+                self.buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        buffer_store.remote_connection_restored(cx)
+                    })
+                    .detach();
+            }
+            remote::RemoteClientEvent::PortForwardsChanged => {}
         }
     }
 
@@ -4176,6 +4236,124 @@ impl Project {
         )
     }
 
+    /// Resolves the call hierarchy items at the given position, to be expanded with
+    /// [`Project::incoming_calls`] and [`Project::outgoing_calls`].
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Resolves the type hierarchy items at the given position, to be expanded with
+    /// [`Project::supertypes`] and [`Project::subtypes`].
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        let guard = self.retain_remotely_created_models(cx);
+        let task = self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.prepare_type_hierarchy(buffer, position, cx)
+        });
+        cx.background_spawn(async move {
+            let result = task.await;
+            drop(guard);
+            result
+        })
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes {
+                item: item.lsp_item.clone(),
+            },
+            cx,
+        )
+    }
+
+    /// Returns, for each of the given positions, the ranges a selection expands through
+    /// according to the language server, ordered from the innermost to the outermost.
+    pub fn selection_ranges<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: impl IntoIterator<Item = T>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<language::Anchor>>>>> {
+        let snapshot = buffer.read(cx);
+        let positions = positions
+            .into_iter()
+            .map(|position| position.to_point_utf16(snapshot))
+            .collect();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
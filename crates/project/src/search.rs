@@ -18,6 +18,10 @@ use util::{
     rel_path::RelPath,
 };
 
+mod structural;
+
+pub use structural::StructuralPattern;
+
 #[derive(Debug)]
 pub enum SearchResult {
     Buffer {
@@ -77,6 +81,12 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+    Structural {
+        pattern: StructuralPattern,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -206,6 +216,33 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, matching syntax nodes in the buffers of every language the pattern is valid in.
+    ///
+    /// See [`StructuralPattern`] for the supported patterns.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = Arc::<str>::from(query.to_string());
+        let inner = SearchInputs {
+            query: query.clone(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+        };
+        Ok(Self::Structural {
+            pattern: StructuralPattern::new(query),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     /// Extracts case sensitivity settings from pattern items in the provided
     /// query and returns the same query, with the pattern items removed.
     ///
@@ -270,7 +307,16 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include, path_style)?,
+                PathMatcher::new(files_to_exclude, path_style)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -305,6 +351,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -318,6 +368,7 @@ impl SearchQuery {
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -387,19 +438,31 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => {
+                let required_words = pattern.required_words();
+                if required_words.is_empty() {
+                    return Ok(true);
+                }
+                let mut text = String::new();
+                reader.read_to_string(&mut text)?;
+                Ok(required_words.iter().all(|word| text.contains(word)))
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Structural queries need the syntax tree around their matches, see [`Self::structural_replacement_for`].
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
+            SearchQuery::Structural { .. } => None,
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
                 regex, replacement, ..
@@ -424,6 +487,22 @@ impl SearchQuery {
         }
     }
 
+    /// Expands the replacement template of a structural query with the captures of its match at `range` in the buffer.
+    pub fn structural_replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                pattern,
+                replacement: Some(replacement),
+                ..
+            } => pattern.replacement(replacement, buffer, range),
+            _ => None,
+        }
+    }
+
     pub async fn search(
         &self,
         buffer: &BufferSnapshot,
@@ -519,6 +598,15 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { pattern, .. } => {
+                matches.extend(
+                    pattern
+                        .matches(buffer, range_offset..range_offset + rope.len())
+                        .into_iter()
+                        .map(|range| range.start - range_offset..range.end - range_offset),
+                );
+            }
         }
 
         matches
@@ -536,6 +624,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -543,6 +632,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -554,6 +644,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -561,6 +654,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -604,20 +701,22 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
+    /// Returns `None` for text and structural searches, as only regex searches support this
     /// option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
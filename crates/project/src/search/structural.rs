@@ -0,0 +1,398 @@
+use std::{
+    cmp::Reverse,
+    fmt::{self, Write as _},
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+
+use collections::HashMap;
+use language::{BufferSnapshot, Grammar, GrammarId, with_parser, with_query_cursor};
+use parking_lot::Mutex;
+use regex::{Captures, Regex};
+use tree_sitter::{Node, Query, QueryMatch, StreamingIterator as _};
+use util::post_inc;
+
+/// Metavariables are replaced with identifiers starting with this prefix before the pattern gets parsed.
+const METAVARIABLE_PREFIX: &str = "zed_metavariable_";
+/// Name of the capture holding the matched node in tree-sitter queries.
+const MATCH_CAPTURE: &str = "match";
+
+static METAVARIABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$([A-Z_][A-Z0-9_]*)").unwrap());
+static REPLACEMENT_VARIABLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\$|\$\{([^}]+)\}|\$([A-Za-z_][A-Za-z0-9_]*)").unwrap());
+static WORD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9_]+").unwrap());
+
+/// A pattern matched against the syntax trees of buffers.
+///
+/// The pattern is either a tree-sitter query, where the `@match` capture (or the span of all captures) is the match,
+/// or a code snippet where `$NAME` metavariables stand for any syntax node, e.g. `$X.unwrap()`.
+/// A metavariable used several times only matches nodes with the same text, and metavariables starting with `_` are not captured.
+///
+/// Patterns are compiled lazily for every grammar of the searched buffers, buffers in languages where the pattern is not valid have no matches.
+#[derive(Clone)]
+pub struct StructuralPattern {
+    source: Arc<str>,
+    compiled: Arc<Mutex<HashMap<GrammarId, Option<Arc<CompiledPattern>>>>>,
+}
+
+struct CompiledPattern {
+    query: Query,
+    match_capture_ix: Option<u32>,
+}
+
+impl fmt::Debug for StructuralPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StructuralPattern")
+            .field(&self.source)
+            .finish()
+    }
+}
+
+impl StructuralPattern {
+    pub fn new(source: Arc<str>) -> Self {
+        Self {
+            source,
+            compiled: Arc::default(),
+        }
+    }
+
+    pub fn is_tree_sitter_query(&self) -> bool {
+        self.source.trim_start().starts_with(['(', '['])
+    }
+
+    /// Words that every match of a code pattern contains, used to skip files without parsing them.
+    pub(crate) fn required_words(&self) -> Vec<&str> {
+        if self.is_tree_sitter_query() {
+            return Vec::new();
+        }
+        METAVARIABLE_REGEX
+            .split(&self.source)
+            .flat_map(|text| WORD_REGEX.find_iter(text).map(|word| word.as_str()))
+            .collect()
+    }
+
+    /// Returns the outermost matches within the given range of the buffer, sorted by position.
+    pub(crate) fn matches(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers_for_range(range.clone(), false) {
+            let Some(pattern) = layer
+                .language
+                .grammar()
+                .and_then(|grammar| self.compiled(grammar))
+            else {
+                continue;
+            };
+            with_query_cursor(|cursor| {
+                cursor.set_byte_range(range.clone());
+                let mut query_matches =
+                    cursor.matches(&pattern.query, layer.node(), |node: Node<'_>| {
+                        buffer
+                            .as_rope()
+                            .chunks_in_range(node.byte_range())
+                            .map(str::as_bytes)
+                    });
+                while let Some(query_match) = query_matches.next() {
+                    if let Some(match_range) = pattern.match_range(query_match)
+                        && !match_range.is_empty()
+                        && range.start <= match_range.start
+                        && match_range.end <= range.end
+                    {
+                        matches.push(match_range);
+                    }
+                }
+            });
+        }
+
+        // Nested matches would make replacements overlap, so only the outermost ones are kept.
+        matches.sort_by_key(|range| (range.start, Reverse(range.end)));
+        let mut last_end = 0;
+        matches.retain(|range| {
+            if range.start < last_end {
+                false
+            } else {
+                last_end = range.end;
+                true
+            }
+        });
+        matches
+    }
+
+    /// Expands the replacement template for the match at the given range of the buffer.
+    ///
+    /// `$NAME` and `${NAME}` are replaced with the text of the corresponding metavariable or capture, and `$$` with a single `$`.
+    pub(crate) fn replacement(
+        &self,
+        template: &str,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        buffer
+            .syntax_layers_for_range(range.clone(), false)
+            .find_map(|layer| {
+                let pattern = self.compiled(layer.language.grammar()?)?;
+                with_query_cursor(|cursor| {
+                    cursor.set_byte_range(range.clone());
+                    let mut query_matches =
+                        cursor.matches(&pattern.query, layer.node(), |node: Node<'_>| {
+                            buffer
+                                .as_rope()
+                                .chunks_in_range(node.byte_range())
+                                .map(str::as_bytes)
+                        });
+                    while let Some(query_match) = query_matches.next() {
+                        if pattern.match_range(query_match).as_ref() != Some(&range) {
+                            continue;
+                        }
+                        let capture_names = pattern.query.capture_names();
+                        let captures = query_match
+                            .captures
+                            .iter()
+                            .map(|capture| {
+                                (
+                                    capture_names[capture.index as usize],
+                                    buffer
+                                        .text_for_range(capture.node.byte_range())
+                                        .collect::<String>(),
+                                )
+                            })
+                            .collect::<HashMap<_, _>>();
+                        return Some(expand_replacement(template, &captures));
+                    }
+                    None
+                })
+            })
+    }
+
+    fn compiled(&self, grammar: &Grammar) -> Option<Arc<CompiledPattern>> {
+        self.compiled
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| self.compile(grammar).map(Arc::new))
+            .clone()
+    }
+
+    fn compile(&self, grammar: &Grammar) -> Option<CompiledPattern> {
+        let source = self.source.trim();
+        let query = if self.is_tree_sitter_query()
+            && let Ok(query) = Query::new(&grammar.ts_language, source)
+        {
+            query
+        } else {
+            let query_source = query_for_code_pattern(grammar, source)?;
+            match Query::new(&grammar.ts_language, &query_source) {
+                Ok(query) => query,
+                Err(error) => {
+                    log::debug!(
+                        "failed to compile structural search query {query_source}: {error}"
+                    );
+                    return None;
+                }
+            }
+        };
+        let match_capture_ix = query.capture_index_for_name(MATCH_CAPTURE);
+        Some(CompiledPattern {
+            query,
+            match_capture_ix,
+        })
+    }
+}
+
+impl CompiledPattern {
+    fn match_range(&self, query_match: &QueryMatch<'_, '_>) -> Option<Range<usize>> {
+        if let Some(match_capture_ix) = self.match_capture_ix {
+            return query_match
+                .nodes_for_capture_index(match_capture_ix)
+                .next()
+                .map(|node| node.byte_range());
+        }
+        query_match
+            .captures
+            .iter()
+            .map(|capture| capture.node.byte_range())
+            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+    }
+}
+
+/// Parses a code pattern with the given grammar and translates its syntax tree into a tree-sitter query.
+fn query_for_code_pattern(grammar: &Grammar, pattern: &str) -> Option<String> {
+    let text = METAVARIABLE_REGEX.replace_all(pattern, format!("{METAVARIABLE_PREFIX}${{1}}"));
+    // Some grammars only accept expressions as statements.
+    [text.to_string(), format!("{text};")]
+        .into_iter()
+        .find_map(|text| {
+            let tree = with_parser(|parser| {
+                parser.set_language(&grammar.ts_language).ok()?;
+                parser.parse(&text, None)
+            })?;
+            let root = tree.root_node();
+            if root.has_error() {
+                return None;
+            }
+            let node = pattern_root(root);
+            // A lone metavariable would match every node.
+            if metavariable_name(node, &text).is_some() {
+                return None;
+            }
+
+            let mut builder = QueryBuilder {
+                text: &text,
+                query: String::from("("),
+                predicates: Vec::new(),
+                metavariable_occurrences: HashMap::default(),
+                literal_count: 0,
+            };
+            builder.push_node(node);
+            write!(builder.query, " @{MATCH_CAPTURE}").ok()?;
+            for predicate in &builder.predicates {
+                builder.query.push(' ');
+                builder.query.push_str(predicate);
+            }
+            builder.query.push(')');
+            Some(builder.query)
+        })
+}
+
+/// Skips the nodes wrapping the pattern, such as the source file and the statement around an expression.
+fn pattern_root(mut node: Node<'_>) -> Node<'_> {
+    loop {
+        let mut named_children = Vec::new();
+        let mut has_other_children = false;
+        for child in node.children(&mut node.walk()) {
+            if child.is_extra() {
+                continue;
+            } else if child.is_named() {
+                named_children.push(child);
+            } else if child.kind() != ";" {
+                has_other_children = true;
+            }
+        }
+        match named_children.as_slice() {
+            [child] if !has_other_children => node = *child,
+            _ => return node,
+        }
+    }
+}
+
+fn metavariable_name<'a>(node: Node<'_>, text: &'a str) -> Option<&'a str> {
+    let name = text[node.byte_range()].strip_prefix(METAVARIABLE_PREFIX)?;
+    (!name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+    .then_some(name)
+}
+
+struct QueryBuilder<'a> {
+    text: &'a str,
+    query: String,
+    predicates: Vec<String>,
+    metavariable_occurrences: HashMap<&'a str, usize>,
+    literal_count: usize,
+}
+
+impl<'a> QueryBuilder<'a> {
+    fn push_node(&mut self, node: Node<'_>) {
+        if let Some(name) = metavariable_name(node, self.text) {
+            self.query.push_str("(_)");
+            if !name.starts_with('_') {
+                let occurrence = post_inc(self.metavariable_occurrences.entry(name).or_insert(0));
+                if occurrence == 0 {
+                    write!(self.query, " @{name}").ok();
+                } else {
+                    write!(self.query, " @{name}__{occurrence}").ok();
+                    self.predicates
+                        .push(format!("(#eq? @{name} @{name}__{occurrence})"));
+                }
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        let children = node
+            .children(&mut cursor)
+            .enumerate()
+            .filter(|(_, child)| !child.is_extra())
+            .collect::<Vec<_>>();
+        if !children.iter().any(|(_, child)| child.is_named()) {
+            let capture = format!("_literal_{}", post_inc(&mut self.literal_count));
+            write!(self.query, "({}) @{capture}", node.kind()).ok();
+            self.predicates.push(format!(
+                "(#eq? @{capture} {})",
+                quote(&self.text[node.byte_range()])
+            ));
+            return;
+        }
+
+        // Named children are anchored to each other, so that matched nodes have no additional children.
+        // Anonymous children are only kept when they distinguish nodes of the same kind, such as operators and keywords.
+        write!(self.query, "({}", node.kind()).ok();
+        let mut previous_is_named = true;
+        for (ix, child) in children {
+            let field_name = node.field_name_for_child(ix as u32);
+            if child.is_named() {
+                if previous_is_named {
+                    self.query.push_str(" .");
+                }
+                self.push_field_name(field_name);
+                self.push_node(child);
+                previous_is_named = true;
+            } else if field_name.is_some()
+                || child
+                    .kind()
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_')
+            {
+                self.push_field_name(field_name);
+                self.query.push_str(&quote(child.kind()));
+                previous_is_named = false;
+            }
+        }
+        if previous_is_named {
+            self.query.push_str(" .");
+        }
+        self.query.push(')');
+    }
+
+    fn push_field_name(&mut self, field_name: Option<&str>) {
+        self.query.push(' ');
+        if let Some(field_name) = field_name {
+            write!(self.query, "{field_name}: ").ok();
+        }
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn expand_replacement(template: &str, captures: &HashMap<&str, String>) -> String {
+    REPLACEMENT_VARIABLE_REGEX
+        .replace_all(template, |variable: &Captures| {
+            match variable.get(1).or_else(|| variable.get(2)) {
+                Some(name) => captures
+                    .get(name.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| variable[0].to_string()),
+                None => "$".to_string(),
+            }
+        })
+        .into_owned()
+}
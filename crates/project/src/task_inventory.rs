@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -37,6 +37,14 @@ pub struct DebugScenarioContext {
     pub active_buffer: Option<WeakEntity<Buffer>>,
 }
 
+/// A resolved task along with the tasks that have to finish before it runs, see [`TaskTemplate::depends_on`].
+#[derive(Clone, Debug)]
+pub struct TaskNode {
+    pub source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    pub dependencies: Vec<TaskNode>,
+}
+
 /// Inventory tracks available tasks for a given project.
 pub struct Inventory {
     last_scheduled_tasks: VecDeque<(TaskSourceKind, ResolvedTask)>,
@@ -657,6 +665,21 @@ impl Inventory {
             .collect()
     }
 
+    /// Resolves the tasks that the given task depends on, recursively, with the same context the task got resolved with.
+    /// Dependencies are looked up by label among the worktree tasks first, and the global tasks second.
+    pub fn resolve_dependencies(
+        &self,
+        task: &ResolvedTask,
+        worktree: Option<WorktreeId>,
+    ) -> Result<Vec<TaskNode>> {
+        let templates = worktree
+            .into_iter()
+            .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
+            .chain(self.global_templates_from_settings())
+            .collect::<Vec<_>>();
+        resolve_dependencies(task, &templates, &mut Vec::new())
+    }
+
     fn global_templates_from_settings(
         &self,
     ) -> impl '_ + Iterator<Item = (TaskSourceKind, TaskTemplate)> {
@@ -885,6 +908,43 @@ impl Inventory {
     }
 }
 
+fn resolve_dependencies(
+    task: &ResolvedTask,
+    templates: &[(TaskSourceKind, TaskTemplate)],
+    dependents: &mut Vec<String>,
+) -> Result<Vec<TaskNode>> {
+    let label = &task.original_task().label;
+    dependents.push(label.clone());
+    let dependencies = task
+        .original_task()
+        .depends_on
+        .iter()
+        .map(|dependency| {
+            if dependents.contains(dependency) {
+                bail!(
+                    "Task `{label}` depends on `{dependency}`, which forms a cycle: {} -> {dependency}",
+                    dependents.join(" -> ")
+                );
+            }
+            let (source_kind, template) = templates
+                .iter()
+                .find(|(_, template)| &template.label == dependency)
+                .with_context(|| format!("Task `{label}` depends on unknown task `{dependency}`"))?;
+            let resolved = template
+                .resolve_task(&source_kind.to_id_base(), task.context())
+                .with_context(|| format!("Failed to resolve task `{dependency}`"))?;
+            let dependencies = resolve_dependencies(&resolved, templates, dependents)?;
+            Ok(TaskNode {
+                source_kind: source_kind.clone(),
+                task: resolved,
+                dependencies,
+            })
+        })
+        .collect();
+    dependents.pop();
+    dependencies
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -279,6 +279,7 @@ impl Project {
                     }
                 })
                 .detach();
+                this.forward_detected_ports(&terminal_handle, cx);
 
                 terminal_handle
             })
@@ -444,6 +445,7 @@ impl Project {
                     }
                 })
                 .detach();
+                this.forward_detected_ports(&terminal_handle, cx);
 
                 terminal_handle
             })
@@ -491,6 +493,7 @@ impl Project {
                     }
                 })
                 .detach();
+                project.forward_detected_ports(&terminal_handle, cx);
 
                 terminal_handle
             })
@@ -579,6 +582,18 @@ impl Project {
         &self.terminals.local_handles
     }
 
+    fn forward_detected_ports(&self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        if !self.is_via_remote_server() {
+            return;
+        }
+        cx.subscribe(terminal, |_, _, event, cx| {
+            if let terminal::Event::LocalhostPortDetected(port) = event {
+                cx.emit(crate::Event::LocalhostPortDetected(*port));
+            }
+        })
+        .detach();
+    }
+
     fn resolve_directory_environment(
         &self,
         shell: &str,
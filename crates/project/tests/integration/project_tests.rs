@@ -3688,6 +3688,139 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_task_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({ "src": { "a.rs": "let a = 1;\nlet b = 2;\n", "b.rs": "let c = 3;\n" } }),
+    )
+    .await;
+
+    let project = Project::test(fs, [Path::new(path!("/dir"))], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/src/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let problem = |path: &str, line, severity, message: &str| task::Problem {
+        source: "rustc".to_string(),
+        path: PathBuf::from(path),
+        line,
+        column: Some(5),
+        end: None,
+        severity,
+        code: None,
+        message: message.to_string(),
+    };
+    let build = task::TaskId("build".to_string());
+    let lint = task::TaskId("lint".to_string());
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(
+                build.clone(),
+                Path::new(path!("/dir")),
+                vec![
+                    problem("src/a.rs", 1, task::ProblemSeverity::Error, "build error"),
+                    problem(
+                        "src/b.rs",
+                        1,
+                        task::ProblemSeverity::Warning,
+                        "build warning",
+                    ),
+                    problem(
+                        path!("/outside/c.rs"),
+                        1,
+                        task::ProblemSeverity::Error,
+                        "outside of the project",
+                    ),
+                ],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(
+                lint.clone(),
+                Path::new(path!("/dir/src")),
+                vec![problem(
+                    "a.rs",
+                    2,
+                    task::ProblemSeverity::Warning,
+                    "lint warning",
+                )],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    lsp_store.update(cx, |lsp_store, cx| {
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 2,
+            }
+        );
+    });
+    cx.run_until_parked();
+
+    let buffer_diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message.clone()))
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(
+        buffer_diagnostics(cx),
+        [
+            (
+                Point::new(0, 4)..Point::new(0, 5),
+                "build error".to_string()
+            ),
+            (
+                Point::new(1, 4)..Point::new(1, 5),
+                "lint warning".to_string()
+            ),
+        ]
+    );
+
+    // Rerunning a task replaces its diagnostics, and keeps the ones of other tasks.
+    lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.update_task_diagnostics(build, Path::new(path!("/dir")), Vec::new(), cx)
+        })
+        .await
+        .unwrap();
+    lsp_store.update(cx, |lsp_store, cx| {
+        assert_eq!(
+            lsp_store.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 0,
+                warning_count: 1,
+            }
+        );
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        buffer_diagnostics(cx),
+        [(
+            Point::new(1, 4)..Point::new(1, 5),
+            "lint warning".to_string()
+        )]
+    );
+}
+
 #[gpui::test]
 async fn test_diagnostic_summaries_cleared_on_worktree_entry_removal(
     cx: &mut gpui::TestAppContext,
@@ -3948,6 +4081,101 @@ async fn test_diagnostic_summaries_cleared_on_buffer_reload(cx: &mut gpui::TestA
     );
 }
 
+#[gpui::test]
+async fn test_replay_language_server_trace(cx: &mut gpui::TestAppContext) {
+    use lsp::rpc_trace::{RpcDirection, RpcTraceEntry};
+
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "one two three" }))
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let uri = Uri::from_file_path(path!("/dir/a.rs")).unwrap();
+    let trace = vec![
+        RpcTraceEntry {
+            direction: RpcDirection::Send,
+            message: json!({ "jsonrpc": "2.0", "id": 7, "method": "initialize", "params": {} }),
+        },
+        RpcTraceEntry {
+            direction: RpcDirection::Receive,
+            message: json!({ "jsonrpc": "2.0", "id": 7, "result": { "capabilities": {} } }),
+        },
+        RpcTraceEntry {
+            direction: RpcDirection::Send,
+            message: json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        },
+        RpcTraceEntry {
+            direction: RpcDirection::Send,
+            message: json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {} }),
+        },
+        RpcTraceEntry {
+            direction: RpcDirection::Receive,
+            message: json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": {
+                    "uri": uri.as_str(),
+                    "diagnostics": [{
+                        "range": {
+                            "start": { "line": 0, "character": 0 },
+                            "end": { "line": 0, "character": 3 },
+                        },
+                        "severity": 1,
+                        "message": "recorded error",
+                    }],
+                },
+            }),
+        },
+    ];
+    // Round trip through the file format, as a trace attached to a bug report would be.
+    let trace = lsp::rpc_trace::parse_rpc_trace(
+        &trace
+            .iter()
+            .map(RpcTraceEntry::to_json_line)
+            .collect::<String>(),
+    )
+    .unwrap();
+
+    let (replay_tx, mut replay_rx) = futures::channel::mpsc::unbounded();
+    let _fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            initializer: Some(Box::new(move |fake_server| {
+                replay_tx
+                    .unbounded_send(fake_server.replay(trace.clone()))
+                    .ok();
+            })),
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (_buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    replay_rx.next().await.unwrap().await.unwrap();
+    cx.executor().run_until_parked();
+
+    project.update(cx, |project, cx| {
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 0,
+            }
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4496,6 +4724,321 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() { crate::a(); }",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    let item_a = lsp::CallHierarchyItem {
+        name: "a".to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some("fn a()".to_string()),
+        uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+        range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 9)),
+        selection_range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+        data: Some(json!({ "id": "a" })),
+    };
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item_a = item_a.clone();
+        move |params, _| {
+            let item_a = item_a.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![item_a]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.data, Some(json!({ "id": "a" })));
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp::CallHierarchyItem {
+                    name: "b".to_string(),
+                    kind: lsp::SymbolKind::FUNCTION,
+                    tags: None,
+                    detail: None,
+                    uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 22)),
+                    selection_range: lsp::Range::new(
+                        lsp::Position::new(0, 3),
+                        lsp::Position::new(0, 4),
+                    ),
+                    data: None,
+                },
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 17),
+                )],
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = &items[0];
+    assert_eq!(item.name, "a");
+    assert_eq!(item.detail.as_deref(), Some("fn a()"));
+    cx.update(|cx| {
+        let buffer = item.location.buffer.read(cx);
+        assert_eq!(item.selection_range.to_offset(buffer), 3..4);
+    });
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(item, cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        assert_eq!(call.item.name, "b");
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/b.rs")),
+        );
+        assert_eq!(call.call_sites.len(), 1);
+        assert_eq!(
+            caller_buffer
+                .text_for_range(call.call_sites[0].range.clone())
+                .collect::<String>(),
+            "crate::a"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "trait Shape {}",
+            "b.rs": "struct Circle; impl Shape for Circle {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |params, _| async move {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 8)
+            );
+            Ok(Some(vec![lsp::TypeHierarchyItem {
+                name: "Shape".to_string(),
+                kind: lsp::SymbolKind::INTERFACE,
+                tags: None,
+                detail: None,
+                uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(0, 6),
+                    lsp::Position::new(0, 11),
+                ),
+                data: Some(json!({ "id": "Shape" })),
+            }]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySubtypes, _, _>(
+        |params, _| async move {
+            assert_eq!(params.item.data, Some(json!({ "id": "Shape" })));
+            Ok(Some(vec![lsp::TypeHierarchyItem {
+                name: "Circle".to_string(),
+                kind: lsp::SymbolKind::STRUCT,
+                tags: None,
+                detail: Some("impl Shape for Circle".to_string()),
+                uri: lsp::Uri::from_file_path(path!("/dir/b.rs")).unwrap(),
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(0, 7),
+                    lsp::Position::new(0, 13),
+                ),
+                data: None,
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 8, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = &items[0];
+    assert_eq!(item.name, "Shape");
+    assert_eq!(item.kind, lsp::SymbolKind::INTERFACE);
+
+    let subtypes = project
+        .update(cx, |project, cx| project.subtypes(item, cx))
+        .await
+        .unwrap();
+    assert_eq!(subtypes.len(), 1);
+    cx.update(|cx| {
+        let subtype = &subtypes[0];
+        assert_eq!(subtype.name, "Circle");
+        assert_eq!(subtype.detail.as_deref(), Some("impl Shape for Circle"));
+        let subtype_buffer = subtype.location.buffer.read(cx);
+        assert_eq!(
+            subtype_buffer
+                .text_for_range(subtype.selection_range.clone())
+                .collect::<String>(),
+            "Circle"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy_with_multiple_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "trait Shape {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    // The linter comes first, and doesn't support type hierarchies.
+    let mut fake_linters = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-linter",
+            ..Default::default()
+        },
+    );
+    let mut fake_rust_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-rust-language-server",
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_linter = fake_linters.next().await.unwrap();
+    let fake_rust_server = fake_rust_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+
+    fake_linter.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |_, _| async move { Err(anyhow::anyhow!("Unhandled method")) },
+    );
+    fake_rust_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>(
+        |_, _| async move {
+            Ok(Some(vec![lsp::TypeHierarchyItem {
+                name: "Shape".to_string(),
+                kind: lsp::SymbolKind::INTERFACE,
+                tags: None,
+                detail: None,
+                uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+                range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 14)),
+                selection_range: lsp::Range::new(
+                    lsp::Position::new(0, 6),
+                    lsp::Position::new(0, 11),
+                ),
+                data: None,
+            }]))
+        },
+    );
+    fake_rust_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>(
+        |_, _| async move { Ok(Some(Vec::new())) },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 8, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "Shape");
+    assert_eq!(items[0].server_id, fake_rust_server.server.server_id());
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&items[0], cx))
+        .await
+        .unwrap();
+    assert!(supertypes.is_empty());
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -154,3 +154,86 @@ async fn test_multiline_regex(cx: &mut gpui::TestAppContext) {
     let results = search_query.search(&snapshot, None).await;
     assert_eq!(results, vec![0..6, 12..18]);
 }
+
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    use language::{Buffer, rust_lang};
+
+    let text = "fn main() {\n    let a = foo.unwrap();\n    let b = foo.bar().unwrap();\n    let c = foo.unwrap_or(1);\n    let d = a + a;\n    let e = a + b;\n}\n";
+    let snapshot = cx
+        .update(|app| Buffer::build_snapshot(Rope::from(text), Some(rust_lang()), None, None, app))
+        .await;
+    let range_of = |needle: &str| {
+        let start = text.find(needle).unwrap();
+        start..start + needle.len()
+    };
+
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("$X.expect(\"$X\")".to_string());
+    let results = query.search(&snapshot, None).await;
+    assert_eq!(
+        results,
+        vec![range_of("foo.unwrap()"), range_of("foo.bar().unwrap()")]
+    );
+    assert_eq!(
+        results
+            .into_iter()
+            .map(|range| query.structural_replacement_for(&snapshot, range))
+            .collect::<Vec<_>>(),
+        vec![
+            Some("foo.expect(\"foo\")".to_string()),
+            Some("foo.bar().expect(\"foo.bar()\")".to_string()),
+        ]
+    );
+
+    // Repeated metavariables only match nodes with the same text.
+    let query = SearchQuery::structural(
+        "$A + $A",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(query.search(&snapshot, None).await, vec![range_of("a + a")]);
+
+    // Matches are reported relative to the searched range.
+    let subrange = range_of("let b = foo.bar().unwrap();");
+    let query = SearchQuery::structural(
+        "$X.unwrap()",
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(query.search(&snapshot, Some(subrange)).await, vec![8..26]);
+
+    // Tree-sitter queries are supported as well, with their captures available in replacements.
+    let query = SearchQuery::structural(
+        r#"(call_expression function: (field_expression value: (_) @receiver field: (field_identifier) @method (#eq? @method "unwrap_or")) arguments: (arguments (_) @default)) @match"#,
+        false,
+        Default::default(),
+        Default::default(),
+        false,
+        None,
+    )
+    .unwrap()
+    .with_replacement("${receiver}.unwrap_or_else(|| $default)".to_string());
+    let results = query.search(&snapshot, None).await;
+    assert_eq!(results, vec![range_of("foo.unwrap_or(1)")]);
+    assert_eq!(
+        query.structural_replacement_for(&snapshot, results[0].clone()),
+        Some("foo.unwrap_or_else(|| 1)".to_string())
+    );
+}
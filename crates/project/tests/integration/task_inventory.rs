@@ -608,6 +608,119 @@ async fn test_zed_tasks_take_precedence_over_vscode(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_resolving_task_dependencies(cx: &mut TestAppContext) {
+    init_test(cx);
+    let inventory = cx.update(|cx| Inventory::new(cx));
+    let worktree_id = WorktreeId::from_usize(0);
+
+    inventory.update(cx, |inventory, _| {
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Global(tasks_file()),
+                Some(
+                    &json!([
+                        { "label": "lint", "command": "echo", "args": ["lint"] },
+                        { "label": "cycle_a", "command": "echo", "depends_on": ["cycle_b"] },
+                        { "label": "cycle_b", "command": "echo", "depends_on": ["cycle_a"] },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+        inventory
+            .update_file_based_tasks(
+                TaskSettingsLocation::Worktree(SettingsLocation {
+                    worktree_id,
+                    path: rel_path(".zed"),
+                }),
+                Some(
+                    &json!([
+                        { "label": "build", "command": "echo", "args": ["build"] },
+                        { "label": "test", "command": "echo", "depends_on": ["build", "lint"] },
+                        {
+                            "label": "ci",
+                            "depends_on": ["test", "lint"],
+                            "depends_order": "parallel",
+                        },
+                        { "label": "broken", "command": "echo", "depends_on": ["missing"] },
+                    ])
+                    .to_string(),
+                ),
+            )
+            .unwrap();
+    });
+
+    async fn resolve(
+        inventory: &Entity<Inventory>,
+        worktree_id: WorktreeId,
+        label: &str,
+        cx: &mut TestAppContext,
+    ) -> anyhow::Result<Vec<TaskNode>> {
+        let template = inventory
+            .update(cx, |inventory, cx| {
+                inventory.task_template_by_label(None, Some(worktree_id), label, cx)
+            })
+            .await
+            .unwrap_or_else(|| panic!("Failed to find task with name {label}"));
+        let task = template
+            .resolve_task("test", &task::TaskContext::default())
+            .unwrap();
+        inventory.read_with(cx, |inventory, _| {
+            inventory.resolve_dependencies(&task, Some(worktree_id))
+        })
+    }
+    fn labels(nodes: &[TaskNode]) -> Vec<(String, Vec<String>)> {
+        nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.task.original_task().label.clone(),
+                    node.dependencies
+                        .iter()
+                        .map(|dependency| dependency.task.original_task().label.clone())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    let dependencies = resolve(&inventory, worktree_id, "ci", cx).await.unwrap();
+    assert_eq!(
+        labels(&dependencies),
+        vec![
+            (
+                "test".to_string(),
+                vec!["build".to_string(), "lint".to_string()]
+            ),
+            ("lint".to_string(), Vec::new()),
+        ]
+    );
+    assert!(matches!(
+        dependencies[0].source_kind,
+        TaskSourceKind::Worktree { .. }
+    ));
+    assert!(matches!(
+        dependencies[1].source_kind,
+        TaskSourceKind::AbsPath { .. }
+    ));
+
+    let error = resolve(&inventory, worktree_id, "broken", cx)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert_eq!(error, "Task `broken` depends on unknown task `missing`");
+
+    let error = resolve(&inventory, worktree_id, "cycle_a", cx)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        error,
+        "Task `cycle_b` depends on `cycle_a`, which forms a cycle: cycle_a -> cycle_b -> cycle_a"
+    );
+}
+
 fn init_test(_cx: &mut TestAppContext) {
     zlog::init_test();
     TaskStore::init(None);
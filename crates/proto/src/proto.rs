@@ -120,6 +120,16 @@ messages!(
     (GetTypeDefinitionResponse, Background),
     (GetImplementation, Background),
     (GetImplementationResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
     (OpenUnstagedDiff, Foreground),
     (OpenUnstagedDiffResponse, Foreground),
     (OpenUncommittedDiff, Foreground),
@@ -160,6 +170,8 @@ messages!(
     (ListToolchainsResponse, Foreground),
     (LoadCommitDiff, Foreground),
     (LoadCommitDiffResponse, Foreground),
+    (GitLoadConflictStages, Background),
+    (GitConflictStagesResponse, Background),
     (LspExtExpandMacro, Background),
     (LspExtExpandMacroResponse, Background),
     (LspExtOpenDocs, Background),
@@ -193,6 +205,10 @@ messages!(
     (PerformRename, Background),
     (PerformRenameResponse, Background),
     (Ping, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
     (PrepareRename, Background),
     (PrepareRenameResponse, Background),
     (ProjectEntryResponse, Foreground),
@@ -220,11 +236,15 @@ messages!(
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
     (ResolveInlayHintResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (GetDocumentColor, Background),
     (GetDocumentColorResponse, Background),
     (GetColorPresentation, Background),
     (GetColorPresentationResponse, Background),
     (GetFoldingRanges, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
     (GetFoldingRangesResponse, Background),
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
@@ -280,6 +300,7 @@ messages!(
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
+    (UpdateTaskDiagnostics, Foreground),
     (UpdateUserChannels, Foreground),
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
@@ -288,6 +309,22 @@ messages!(
     (RemoveRepository, Foreground),
     (UsersResponse, Foreground),
     (GitReset, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitRebaseStart, Background),
+    (GitRebaseStep, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitCheckoutDetached, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitBisectStart, Background),
+    (GitBisectMark, Background),
+    (GitBisectReset, Background),
+    (GitGetBisectState, Background),
+    (GitBisectStateResponse, Background),
     (GitDeleteBranch, Background),
     (GitCheckoutFiles, Background),
     (GitShow, Background),
@@ -400,6 +437,11 @@ request_messages!(
     (GetDefinition, GetDefinitionResponse),
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
@@ -426,6 +468,7 @@ request_messages!(
     (LeaveChannelBuffer, Ack),
     (LeaveRoom, Ack),
     (LoadCommitDiff, LoadCommitDiffResponse),
+    (GitLoadConflictStages, GitConflictStagesResponse),
     (MarkNotificationRead, Ack),
     (MoveChannel, Ack),
     (OnTypeFormatting, OnTypeFormattingResponse),
@@ -437,6 +480,8 @@ request_messages!(
     (OpenNewBuffer, OpenBufferResponse),
     (PerformRename, PerformRenameResponse),
     (Ping, Ack),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
     (RefreshSemanticTokens, Ack),
@@ -457,8 +502,10 @@ request_messages!(
         ResolveCompletionDocumentationResponse
     ),
     (ResolveInlayHint, ResolveInlayHintResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
@@ -494,6 +541,7 @@ request_messages!(
     (LspQuery, Ack),
     (LspQueryResponse, Ack),
     (RestartLanguageServers, Ack),
+    (UpdateTaskDiagnostics, Ack),
     (StopLanguageServers, Ack),
     (LspExtSwitchSourceHeader, LspExtSwitchSourceHeaderResponse),
     (LspExtGoToParentModule, LspExtGoToParentModuleResponse),
@@ -526,6 +574,19 @@ request_messages!(
     (GitCompareCheckpoints, GitCompareCheckpointsResponse),
     (GitDiffCheckpoints, GitDiffCheckpointsResponse),
     (GitReset, Ack),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitRebaseStart, Ack),
+    (GitRebaseStep, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitCheckoutDetached, Ack),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitBisectStart, Ack),
+    (GitBisectMark, Ack),
+    (GitBisectReset, Ack),
+    (GitGetBisectState, GitBisectStateResponse),
     (GitDeleteBranch, Ack),
     (GitCheckoutFiles, Ack),
     (SetIndexText, Ack),
@@ -573,6 +634,8 @@ lsp_messages!(
     (GetReferences, GetReferencesResponse, true),
     (GetDocumentColor, GetDocumentColorResponse, true),
     (GetFoldingRanges, GetFoldingRangesResponse, true),
+    (GetDocumentLinks, GetDocumentLinksResponse, true),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse, true),
     (GetDocumentSymbols, GetDocumentSymbolsResponse, true),
     (GetHover, GetHoverResponse, true),
     (GetCodeActions, GetCodeActionsResponse, true),
@@ -608,6 +671,7 @@ entity_messages!(
     CreateProjectEntry,
     GetDocumentColor,
     GetFoldingRanges,
+    GetDocumentLinks,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -620,6 +684,11 @@ entity_messages!(
     GetDefinition,
     GetDeclaration,
     GetImplementation,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    GetSupertypes,
+    GetSubtypes,
+    GetSelectionRanges,
     GetDocumentHighlights,
     GetDocumentSymbols,
     GetHover,
@@ -637,6 +706,7 @@ entity_messages!(
     KillKernel,
     LinkedEditingRange,
     LoadCommitDiff,
+    GitLoadConflictStages,
     LspQuery,
     LspQueryResponse,
     RestartLanguageServers,
@@ -649,6 +719,8 @@ entity_messages!(
     OpenBufferForSymbol,
     OpenCommitMessageBuffer,
     PerformRename,
+    PrepareCallHierarchy,
+    PrepareTypeHierarchy,
     PrepareRename,
     RefreshInlayHints,
     RefreshSemanticTokens,
@@ -658,6 +730,7 @@ entity_messages!(
     RenameProjectEntry,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    ResolveDocumentLink,
     SaveBuffer,
     Stage,
     StartLanguageServer,
@@ -676,6 +749,7 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateTaskDiagnostics,
     UpdateWorktree,
     UpdateRepository,
     RemoveRepository,
@@ -712,6 +786,19 @@ entity_messages!(
     GitCompareCheckpoints,
     GitDiffCheckpoints,
     GitReset,
+    GitRebaseTodo,
+    GitRebaseStart,
+    GitRebaseStep,
+    GitCherryPick,
+    GitRevert,
+    GitCheckoutDetached,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitBisectStart,
+    GitBisectMark,
+    GitBisectReset,
+    GitGetBisectState,
     GitDeleteBranch,
     GitCheckoutFiles,
     SetIndexText,
@@ -945,6 +1032,8 @@ impl LspQuery {
             Some(lsp_query::Request::GetDocumentColor(_)) => ("GetDocumentColor", false),
             Some(lsp_query::Request::GetFoldingRanges(_)) => ("GetFoldingRanges", false),
             Some(lsp_query::Request::GetDocumentSymbols(_)) => ("GetDocumentSymbols", false),
+            Some(lsp_query::Request::GetDocumentLinks(_)) => ("GetDocumentLinks", false),
+            Some(lsp_query::Request::PrepareTypeHierarchy(_)) => ("PrepareTypeHierarchy", false),
             Some(lsp_query::Request::InlayHints(_)) => ("InlayHints", false),
             Some(lsp_query::Request::SemanticTokens(_)) => ("SemanticTokens", false),
             None => ("<unknown>", true),
@@ -7,12 +7,13 @@ mod transport;
 #[cfg(target_os = "windows")]
 pub use remote_client::OpenWslPath;
 pub use remote_client::{
-    CommandTemplate, ConnectionIdentifier, ConnectionState, Interactive, RemoteArch, RemoteClient,
-    RemoteClientDelegate, RemoteClientEvent, RemoteConnection, RemoteConnectionOptions, RemoteOs,
-    RemotePlatform, connect,
+    CommandTemplate, ConnectionIdentifier, ConnectionState, Interactive, PortForward, RemoteArch,
+    RemoteClient, RemoteClientDelegate, RemoteClientEvent, RemoteConnection,
+    RemoteConnectionOptions, RemoteOs, RemotePlatform, connect,
 };
+pub use transport::command::CommandConnectionOptions;
 pub use transport::docker::DockerConnectionOptions;
-pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
+pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption, parse_port_forward};
 pub use transport::wsl::WslConnectionOptions;
 #[cfg(target_os = "windows")]
 pub use transport::wsl::wsl_path_to_windows_path;
@@ -1,10 +1,11 @@
 #[cfg(any(test, feature = "test-support"))]
 use crate::transport::mock::ConnectGuard;
 use crate::{
-    SshConnectionOptions,
+    SshConnectionOptions, SshPortForwardOption,
     protocol::MessageId,
     proxy::ProxyLaunchError,
     transport::{
+        command::{CommandConnectionOptions, CommandRemoteConnection},
         docker::{DockerConnectionOptions, DockerExecConnection},
         ssh::SshRemoteConnection,
         wsl::{WslConnectionOptions, WslRemoteConnection},
@@ -237,6 +238,10 @@ impl State {
         matches!(self, Self::ServerNotRunning)
     }
 
+    fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
     fn is_reconnecting(&self) -> bool {
         matches!(self, Self::Reconnecting { .. })
     }
@@ -320,11 +325,29 @@ pub struct RemoteClient {
     connection_options: RemoteConnectionOptions,
     path_style: PathStyle,
     state: Option<State>,
+    /// Ports forwarded while connected, in addition to the ones in the connection options.
+    port_forwards: Vec<SshPortForwardOption>,
+}
+
+/// A port forwarded from the local machine to the remote host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortForward {
+    pub option: SshPortForwardOption,
+    /// Whether the forward comes from the connection options, in which case it can't be removed
+    /// while connected.
+    pub from_settings: bool,
 }
 
 #[derive(Debug)]
 pub enum RemoteClientEvent {
-    Disconnected { server_not_running: bool },
+    Disconnected {
+        server_not_running: bool,
+    },
+    /// The connection was lost, and the client started reconnecting.
+    ConnectionLost,
+    /// The client reconnected after the connection was lost.
+    Reconnected,
+    PortForwardsChanged,
 }
 
 impl EventEmitter<RemoteClientEvent> for RemoteClient {}
@@ -409,6 +432,7 @@ impl RemoteClient {
                     connection_options: remote_connection.connection_options(),
                     path_style,
                     state: Some(State::Connecting),
+                    port_forwards: Vec::new(),
                 });
 
                 let io_task = remote_connection.start_proxy(
@@ -599,6 +623,9 @@ impl RemoteClient {
         }
 
         self.set_state(State::Reconnecting, cx);
+        if attempts == 1 {
+            cx.emit(RemoteClientEvent::ConnectionLost);
+        }
 
         log::info!(
             "Trying to reconnect to remote server... Attempt {}",
@@ -710,6 +737,10 @@ impl RemoteClient {
 
                 if this.state_is(State::is_reconnect_failed) {
                     this.reconnect(cx)
+                } else if this.state_is(State::is_connected) {
+                    this.restore_port_forwards(cx);
+                    cx.emit(RemoteClientEvent::Reconnected);
+                    Ok(())
                 } else if this.state_is(State::is_reconnect_exhausted) {
                     Ok(())
                 } else {
@@ -951,6 +982,118 @@ impl RemoteClient {
         connection.build_forward_ports_command(forwards)
     }
 
+    pub fn port_forwards(&self) -> Vec<PortForward> {
+        let from_settings = match &self.connection_options {
+            RemoteConnectionOptions::Ssh(options) => {
+                options.port_forwards.clone().unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        from_settings
+            .into_iter()
+            .map(|option| PortForward {
+                option,
+                from_settings: true,
+            })
+            .chain(self.port_forwards.iter().map(|option| PortForward {
+                option: option.clone(),
+                from_settings: false,
+            }))
+            .collect()
+    }
+
+    /// Starts forwarding a port over the existing connection.
+    pub fn add_port_forward(
+        &mut self,
+        option: SshPortForwardOption,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if self
+            .port_forwards()
+            .iter()
+            .any(|forward| forward.option.local_port == option.local_port)
+        {
+            return Task::ready(Err(anyhow!(
+                "local port {} is already forwarded",
+                option.local_port
+            )));
+        }
+        let command = self.run_port_forward_control_command(&option, false, cx);
+        cx.spawn(async move |this, cx| {
+            command.await?;
+            this.update(cx, |this, cx| {
+                this.port_forwards.push(option);
+                cx.emit(RemoteClientEvent::PortForwardsChanged);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Stops forwarding a port that was forwarded with [`Self::add_port_forward`].
+    pub fn remove_port_forward(
+        &mut self,
+        option: &SshPortForwardOption,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !self.port_forwards.contains(option) {
+            return Task::ready(Err(anyhow!(
+                "local port {} wasn't forwarded while connected",
+                option.local_port
+            )));
+        }
+        let command = self.run_port_forward_control_command(option, true, cx);
+        let option = option.clone();
+        cx.spawn(async move |this, cx| {
+            command.await?;
+            this.update(cx, |this, cx| {
+                this.port_forwards.retain(|forward| *forward != option);
+                cx.emit(RemoteClientEvent::PortForwardsChanged);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Forwards the ports added while connected again, in case the connection they went through
+    /// was replaced while reconnecting.
+    fn restore_port_forwards(&self, cx: &mut Context<Self>) {
+        for option in &self.port_forwards {
+            let command = self.run_port_forward_control_command(option, false, cx);
+            cx.background_spawn(async move {
+                if let Err(error) = command.await {
+                    log::warn!("failed to restore port forward: {error:#}");
+                }
+            })
+            .detach();
+        }
+    }
+
+    fn run_port_forward_control_command(
+        &self,
+        option: &SshPortForwardOption,
+        cancel: bool,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let command = self
+            .remote_connection()
+            .context("no remote connection")
+            .and_then(|connection| connection.build_port_forward_control_command(option, cancel));
+        cx.background_spawn(async move {
+            let command = command?;
+            let output = util::command::new_command(&command.program)
+                .args(&command.args)
+                .envs(&command.env)
+                .output()
+                .await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "failed to {} port forward: {}",
+                if cancel { "cancel" } else { "start" },
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Ok(())
+        })
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1231,6 +1374,11 @@ impl ConnectionPool {
                                 .await
                                 .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
                         }
+                        RemoteConnectionOptions::Command(opts) => {
+                            CommandRemoteConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
                         #[cfg(any(test, feature = "test-support"))]
                         RemoteConnectionOptions::Mock(opts) => match cx.update(|cx| {
                             cx.default_global::<crate::transport::mock::MockConnectionRegistry>()
@@ -1278,6 +1426,7 @@ pub enum RemoteConnectionOptions {
     Ssh(SshConnectionOptions),
     Wsl(WslConnectionOptions),
     Docker(DockerConnectionOptions),
+    Command(CommandConnectionOptions),
     #[cfg(any(test, feature = "test-support"))]
     Mock(crate::transport::mock::MockConnectionOptions),
 }
@@ -1297,6 +1446,7 @@ impl RemoteConnectionOptions {
                     opts.name.clone()
                 }
             }
+            RemoteConnectionOptions::Command(opts) => opts.nickname.clone(),
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(opts) => format!("mock-{}", opts.id),
         }
@@ -1327,6 +1477,18 @@ mod tests {
 
         assert_eq!(options.display_name(), "1.2.3.4");
     }
+
+    #[test]
+    fn test_command_display_name_is_nickname() {
+        let options = RemoteConnectionOptions::Command(CommandConnectionOptions {
+            nickname: "Dev Pod".to_string(),
+            program: "kubectl".to_string(),
+            args: vec!["exec".into(), "-i".into(), "dev-0".into(), "--".into()],
+            ..Default::default()
+        });
+
+        assert_eq!(options.display_name(), "Dev Pod");
+    }
 }
 
 impl From<SshConnectionOptions> for RemoteConnectionOptions {
@@ -1341,6 +1503,12 @@ impl From<WslConnectionOptions> for RemoteConnectionOptions {
     }
 }
 
+impl From<CommandConnectionOptions> for RemoteConnectionOptions {
+    fn from(opts: CommandConnectionOptions) -> Self {
+        RemoteConnectionOptions::Command(opts)
+    }
+}
+
 #[cfg(any(test, feature = "test-support"))]
 impl From<crate::transport::mock::MockConnectionOptions> for RemoteConnectionOptions {
     fn from(opts: crate::transport::mock::MockConnectionOptions) -> Self {
@@ -1393,6 +1561,14 @@ pub trait RemoteConnection: Send + Sync {
         &self,
         forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate>;
+    /// Builds a command that starts or cancels a port forward through the existing connection.
+    fn build_port_forward_control_command(
+        &self,
+        _forward: &SshPortForwardOption,
+        _cancel: bool,
+    ) -> Result<CommandTemplate> {
+        anyhow::bail!("ports can only be forwarded while connected over SSH")
+    }
     fn connection_options(&self) -> RemoteConnectionOptions;
     fn path_style(&self) -> PathStyle;
     fn shell(&self) -> String;
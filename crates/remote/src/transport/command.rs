@@ -0,0 +1,723 @@
+use crate::{
+    RemoteArch, RemoteClientDelegate, RemoteOs, RemotePlatform,
+    remote_client::{CommandTemplate, Interactive, RemoteConnection, RemoteConnectionOptions},
+    transport::{parse_platform, parse_shell},
+};
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use semver::Version;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use util::{
+    command::Stdio,
+    paths::{PathStyle, RemotePathBuf},
+    rel_path::RelPath,
+    shell::ShellKind,
+};
+
+/// Options for connecting through a user-specified command, such as `kubectl exec -i my-pod --`
+/// or `podman exec -i my-container`, that runs the program given after its arguments in the
+/// remote environment with stdin and stdout attached.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub struct CommandConnectionOptions {
+    pub nickname: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Arguments used instead of `args` for terminals, so the command can allocate a TTY.
+    pub terminal_args: Option<Vec<String>>,
+    pub remote_env: BTreeMap<String, String>,
+    pub upload_binary_over_command: bool,
+}
+
+impl CommandConnectionOptions {
+    /// The command used to connect, as it would be typed in a shell.
+    pub fn command_line(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| {
+                ShellKind::Posix
+                    .try_quote(arg)
+                    .unwrap_or(Cow::Borrowed(arg))
+                    .into_owned()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn args_for(&self, interactive: Interactive) -> &[String] {
+        match (interactive, &self.terminal_args) {
+            (Interactive::Yes, Some(terminal_args)) => terminal_args,
+            _ => &self.args,
+        }
+    }
+}
+
+impl From<settings::CommandConnection> for CommandConnectionOptions {
+    fn from(val: settings::CommandConnection) -> Self {
+        CommandConnectionOptions {
+            nickname: val.nickname,
+            program: val.program,
+            args: val.args,
+            terminal_args: val.terminal_args,
+            remote_env: val.remote_env,
+            upload_binary_over_command: val.upload_binary_over_command.unwrap_or_default(),
+        }
+    }
+}
+
+pub(crate) struct CommandRemoteConnection {
+    connection_options: CommandConnectionOptions,
+    remote_binary_path: Option<Arc<RelPath>>,
+    platform: RemotePlatform,
+    shell: String,
+}
+
+impl CommandRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to {} through `{}`",
+            connection_options.nickname,
+            connection_options.command_line()
+        );
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        });
+
+        let mut this = Self {
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform {
+                os: RemoteOs::Linux,
+                arch: RemoteArch::X86_64,
+            },
+            shell: String::new(),
+        };
+
+        delegate.set_status(Some("Detecting remote environment"), cx);
+        this.platform = this
+            .detect_platform()
+            .await
+            .context("failed detecting platform")?;
+        log::info!("Remote platform discovered: {:?}", this.platform);
+
+        this.shell = this.detect_shell().await;
+        log::info!("Remote shell discovered: {}", this.shell);
+
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await
+                .context("failed ensuring server binary")?,
+        );
+
+        Ok(this)
+    }
+
+    async fn detect_platform(&self) -> Result<RemotePlatform> {
+        let output = self.run("uname -sm").await?;
+        parse_platform(&output)
+    }
+
+    async fn detect_shell(&self) -> String {
+        const DEFAULT_SHELL: &str = "sh";
+        match self.run("echo $SHELL").await {
+            Ok(output) => parse_shell(&output, DEFAULT_SHELL),
+            Err(e) => {
+                log::error!("Failed to detect remote shell: {e}");
+                DEFAULT_SHELL.to_owned()
+            }
+        }
+    }
+
+    /// Runs a shell script in the remote environment and returns its output.
+    async fn run(&self, script: &str) -> Result<String> {
+        let mut command = remote_command(&self.connection_options, script, Interactive::No);
+        let output = command
+            .output()
+            .await
+            .with_context(|| format!("failed to run command {command:?}"))?;
+        anyhow::ensure!(
+            output.status.success(),
+            "command {command:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: Version,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<Arc<RelPath>> {
+        let version_str = match release_channel {
+            ReleaseChannel::Nightly => {
+                let commit = commit.map(|s| s.full()).unwrap_or_default();
+                format!("{}-{}", version, commit)
+            }
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let dst_path =
+            paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
+
+        let binary_exists_on_server = self
+            .run(&format!(
+                "cd && {} version",
+                quote(&dst_path.display(PathStyle::Posix))?
+            ))
+            .await
+            .is_ok();
+
+        #[cfg(any(debug_assertions, feature = "build-remote-server-binary"))]
+        if let Some(remote_server_path) = super::build_remote_server_from_source(
+            &self.platform,
+            delegate.as_ref(),
+            binary_exists_on_server,
+            cx,
+        )
+        .await?
+        {
+            let tmp_path = paths::remote_server_dir_relative().join(
+                RelPath::unix(&format!(
+                    "download-{}-{}",
+                    std::process::id(),
+                    remote_server_path.file_name().unwrap().to_string_lossy()
+                ))
+                .unwrap(),
+            );
+            self.upload_file(&remote_server_path, &tmp_path, delegate, cx)
+                .await?;
+            self.extract_server_binary(&dst_path, &tmp_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if binary_exists_on_server {
+            return Ok(dst_path);
+        }
+
+        let wanted_version = cx.update(|cx| match release_channel {
+            ReleaseChannel::Nightly => Ok(None),
+            ReleaseChannel::Dev => {
+                anyhow::bail!(
+                    "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                    dst_path
+                )
+            }
+            _ => Ok(Some(AppVersion::global(cx))),
+        })?;
+
+        let tmp_path_gz = paths::remote_server_dir_relative().join(
+            RelPath::unix(&format!(
+                "{}-download-{}.gz",
+                binary_name,
+                std::process::id()
+            ))
+            .unwrap(),
+        );
+        if !self.connection_options.upload_binary_over_command
+            && let Some(url) = delegate
+                .get_download_url(self.platform, release_channel, wanted_version.clone(), cx)
+                .await?
+        {
+            match self
+                .download_binary_on_server(&url, &tmp_path_gz, delegate, cx)
+                .await
+            {
+                Ok(()) => {
+                    self.extract_server_binary(&dst_path, &tmp_path_gz, delegate, cx)
+                        .await
+                        .context("extracting server binary")?;
+                    return Ok(dst_path);
+                }
+                Err(e) => {
+                    log::error!(
+                        "Failed to download binary on server, attempting to download locally and then upload it the server: {e:#}",
+                    )
+                }
+            }
+        }
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await
+            .context("downloading server binary locally")?;
+        self.upload_file(&src_path, &tmp_path_gz, delegate, cx)
+            .await
+            .context("uploading server binary")?;
+        self.extract_server_binary(&dst_path, &tmp_path_gz, delegate, cx)
+            .await
+            .context("extracting server binary")?;
+        Ok(dst_path)
+    }
+
+    async fn download_binary_on_server(
+        &self,
+        url: &str,
+        tmp_path_gz: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Downloading remote development server on host"), cx);
+
+        let url = quote(url)?;
+        let tmp_path = quote(&tmp_path_gz.display(PathStyle::Posix))?.into_owned();
+        let parent = match tmp_path_gz.parent() {
+            Some(parent) => quote(&parent.display(PathStyle::Posix))?.into_owned(),
+            None => ".".to_owned(),
+        };
+        let script = format!(
+            "cd && mkdir -p {parent} && \
+            if command -v curl >/dev/null 2>&1; then curl -f -L {url} -o {tmp_path}; \
+            elif command -v wget >/dev/null 2>&1; then wget {url} -O {tmp_path}; \
+            else echo 'Neither curl nor wget is available' >&2; exit 1; fi"
+        );
+        self.run(&script).await?;
+        Ok(())
+    }
+
+    /// Uploads a file by writing it to the stdin of `cat` in the remote environment, since the
+    /// command is the only way to reach it.
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dst_path: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let size = smol::fs::metadata(src_path)
+            .await
+            .with_context(|| format!("source path does not exist: {}", src_path.display()))?
+            .len();
+
+        let t0 = Instant::now();
+        delegate.set_status(Some("Uploading remote development server"), cx);
+        log::info!(
+            "uploading remote development server to {:?} ({}kb)",
+            dst_path,
+            size / 1024
+        );
+
+        let dst = quote(&dst_path.display(PathStyle::Posix))?.into_owned();
+        let script = match dst_path.parent() {
+            Some(parent) => format!(
+                "cd && mkdir -p {} && cat > {dst}",
+                quote(&parent.display(PathStyle::Posix))?
+            ),
+            None => format!("cd && cat > {dst}"),
+        };
+        let mut child = remote_command(&self.connection_options, &script, Interactive::No)
+            .spawn()
+            .context("failed to spawn upload command")?;
+        let mut stdin = child.stdin.take().context("upload command has no stdin")?;
+        let mut file = smol::fs::File::open(src_path).await?;
+        futures::io::copy(&mut file, &mut stdin).await?;
+        stdin.close().await?;
+        drop(stdin);
+
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to upload {} to {:?}: {}",
+            src_path.display(),
+            dst_path,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        log::info!("uploaded remote development server in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    async fn extract_server_binary(
+        &self,
+        dst_path: &RelPath,
+        tmp_path: &RelPath,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Extracting remote development server"), cx);
+
+        let tmp_path = tmp_path.display(PathStyle::Posix);
+        let dst_path = quote(&dst_path.display(PathStyle::Posix))?.into_owned();
+        let script = if let Some(uncompressed) = tmp_path.strip_suffix(".gz") {
+            let compressed = quote(&tmp_path)?;
+            let uncompressed = quote(uncompressed)?;
+            format!(
+                "cd && gunzip -f {compressed} && chmod 755 {uncompressed} && mv -f {uncompressed} {dst_path}"
+            )
+        } else {
+            let tmp_path = quote(&tmp_path)?;
+            format!("cd && chmod 755 {tmp_path} && mv -f {tmp_path} {dst_path}")
+        };
+        self.run(&script).await?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut env = self.connection_options.remote_env.clone();
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                env.insert(env_var.to_string(), value);
+            }
+        }
+
+        let mut proxy_args = vec![
+            "proxy".to_string(),
+            "--identifier".to_string(),
+            unique_identifier,
+        ];
+        if reconnect {
+            proxy_args.push("--reconnect".to_string());
+        }
+
+        let script = match exec_script(
+            &env,
+            &remote_binary_path.display(PathStyle::Posix),
+            &proxy_args,
+        ) {
+            Ok(exec) => format!("cd && {exec}"),
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let proxy_process = match remote_command(&self.connection_options, &script, Interactive::No)
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let connection_options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            let dest_path = dest_path.to_string();
+            let dest = quote(&dest_path)?;
+            let script = format!("mkdir -p {dest} && tar -xf - -C {dest}");
+
+            let mut tar = util::command::new_command("tar")
+                .arg("-C")
+                .arg(&src_path)
+                .args(["-cf", "-", "."])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .context("failed to spawn tar")?;
+            let mut remote = remote_command(&connection_options, &script, Interactive::No)
+                .spawn()
+                .context("failed to spawn upload command")?;
+
+            let mut archive = tar.stdout.take().context("tar has no stdout")?;
+            let mut stdin = remote.stdin.take().context("upload command has no stdin")?;
+            futures::io::copy(&mut archive, &mut stdin).await?;
+            stdin.close().await?;
+            drop(stdin);
+
+            let tar_output = tar.output().await?;
+            let output = remote.output().await?;
+            for output in [tar_output, output] {
+                anyhow::ensure!(
+                    output.status.success(),
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        _port_forward: Option<(u16, String, u16)>,
+        interactive: Interactive,
+    ) -> Result<CommandTemplate> {
+        let working_dir =
+            working_dir.map(|dir| RemotePathBuf::new(dir, PathStyle::Posix).to_string());
+
+        let mut env_vars = self.connection_options.remote_env.clone();
+        env_vars.extend(env.iter().map(|(key, value)| (key.clone(), value.clone())));
+
+        let exec = match program {
+            Some(program) => exec_script(&env_vars, &program, args)?,
+            None => exec_script(&env_vars, &self.shell, &["-l".to_string()])?,
+        };
+        let script = format!("{} && {exec}", cd_script(working_dir.as_deref())?);
+
+        let mut command_args = self.connection_options.args_for(interactive).to_vec();
+        command_args.extend(["sh".to_string(), "-c".to_string(), script]);
+
+        Ok(CommandTemplate {
+            program: self.connection_options.program.clone(),
+            args: command_args,
+            // The environment is set by the script, in the remote environment.
+            env: HashMap::default(),
+        })
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _forwards: Vec<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        Err(anyhow!("Not currently supported for command connections"))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+}
+
+/// Creates a command that runs `script` with `sh -c` in the remote environment.
+fn remote_command(
+    options: &CommandConnectionOptions,
+    script: &str,
+    interactive: Interactive,
+) -> util::command::Command {
+    let mut command = util::command::new_command(&options.program);
+    command
+        .args(options.args_for(interactive))
+        .args(["sh", "-c", script])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    log::debug!("remote command {:?}", command);
+    command
+}
+
+fn quote(arg: &str) -> Result<Cow<'_, str>> {
+    ShellKind::Posix.try_quote(arg).context("shell quoting")
+}
+
+/// Changes to `working_dir`, or to the home directory if there is none. Leading tildes are
+/// expanded, since they wouldn't be once quoted.
+fn cd_script(working_dir: Option<&str>) -> Result<String> {
+    let Some(working_dir) = working_dir else {
+        return Ok("cd".to_string());
+    };
+    match working_dir.strip_prefix('~') {
+        Some("") => Ok("cd".to_string()),
+        Some(relative) if relative.starts_with('/') => {
+            Ok(format!("cd \"$HOME\"{}", quote(relative)?))
+        }
+        _ => Ok(format!("cd {}", quote(working_dir)?)),
+    }
+}
+
+/// Replaces the shell with `program`, run with `args` and `env`.
+fn exec_script(env: &BTreeMap<String, String>, program: &str, args: &[String]) -> Result<String> {
+    let mut script = String::from("exec");
+    if !env.is_empty() {
+        script.push_str(" env");
+        for (key, value) in env {
+            script.push(' ');
+            script.push_str(&quote(&format!("{key}={value}"))?);
+        }
+    }
+    for arg in std::iter::once(program).chain(args.iter().map(String::as_str)) {
+        script.push(' ');
+        script.push_str(&quote(arg)?);
+    }
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection() -> CommandRemoteConnection {
+        CommandRemoteConnection {
+            connection_options: CommandConnectionOptions {
+                nickname: "dev pod".to_string(),
+                program: "kubectl".to_string(),
+                args: vec![
+                    "exec".to_string(),
+                    "-i".to_string(),
+                    "dev-0".to_string(),
+                    "--".to_string(),
+                ],
+                terminal_args: Some(vec![
+                    "exec".to_string(),
+                    "-it".to_string(),
+                    "dev-0".to_string(),
+                    "--".to_string(),
+                ]),
+                ..Default::default()
+            },
+            remote_binary_path: None,
+            platform: RemotePlatform {
+                os: RemoteOs::Linux,
+                arch: RemoteArch::X86_64,
+            },
+            shell: "/bin/bash".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut options = connection().connection_options;
+        assert_eq!(options.command_line(), "kubectl exec -i dev-0 --");
+
+        options.args.push("my pod".to_string());
+        assert_eq!(options.command_line(), "kubectl exec -i dev-0 -- 'my pod'");
+    }
+
+    #[test]
+    fn test_cd_script() {
+        assert_eq!(cd_script(None).unwrap(), "cd");
+        assert_eq!(cd_script(Some("~")).unwrap(), "cd");
+        assert_eq!(cd_script(Some("~/src")).unwrap(), "cd \"$HOME\"/src");
+        assert_eq!(cd_script(Some("/my dir")).unwrap(), "cd '/my dir'");
+        assert_eq!(cd_script(Some("~user")).unwrap(), "cd '~user'");
+    }
+
+    #[test]
+    fn test_build_command() {
+        let connection = connection();
+
+        let command = connection
+            .build_command(
+                None,
+                &[],
+                &HashMap::default(),
+                Some("~/project".to_string()),
+                None,
+                Interactive::Yes,
+            )
+            .unwrap();
+        assert_eq!(command.program, "kubectl");
+        assert_eq!(
+            command.args,
+            [
+                "exec",
+                "-it",
+                "dev-0",
+                "--",
+                "sh",
+                "-c",
+                "cd \"$HOME\"/project && exec /bin/bash -l"
+            ]
+        );
+
+        let command = connection
+            .build_command(
+                Some("cargo".to_string()),
+                &["test".to_string(), "my test".to_string()],
+                &HashMap::default(),
+                None,
+                None,
+                Interactive::No,
+            )
+            .unwrap();
+        assert_eq!(
+            command.args,
+            [
+                "exec",
+                "-i",
+                "dev-0",
+                "--",
+                "sh",
+                "-c",
+                "cd && exec cargo test 'my test'"
+            ]
+        );
+    }
+}
@@ -367,6 +367,38 @@ impl RemoteConnection for SshRemoteConnection {
         })
     }
 
+    #[cfg(not(windows))]
+    fn build_port_forward_control_command(
+        &self,
+        forward: &SshPortForwardOption,
+        cancel: bool,
+    ) -> Result<CommandTemplate> {
+        let Self { socket, .. } = self;
+        let mut args = socket.connection_options.additional_args_for_scp();
+        args.extend([
+            "-o".to_string(),
+            format!("ControlPath={}", socket.socket_path.display()),
+            "-O".to_string(),
+            if cancel { "cancel" } else { "forward" }.to_string(),
+            format!("-L{}", port_forward_spec(forward)),
+            socket.connection_options.ssh_destination(),
+        ]);
+        Ok(CommandTemplate {
+            program: "ssh".into(),
+            args,
+            env: Default::default(),
+        })
+    }
+
+    #[cfg(windows)]
+    fn build_port_forward_control_command(
+        &self,
+        _forward: &SshPortForwardOption,
+        _cancel: bool,
+    ) -> Result<CommandTemplate> {
+        anyhow::bail!("forwarding ports while connected is not supported on Windows")
+    }
+
     fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -1423,6 +1455,34 @@ fn parse_port_forward_spec(spec: &str) -> Result<SshPortForwardOption> {
     }
 }
 
+/// Parses a port forward entered by the user. A lone port number forwards that port on the local
+/// machine to the same port on the remote host, anything else uses the syntax of `ssh -L`.
+pub fn parse_port_forward(spec: &str) -> Result<SshPortForwardOption> {
+    let spec = spec.trim();
+    if let Ok(port) = spec.parse::<u16>() {
+        return Ok(SshPortForwardOption {
+            local_host: None,
+            local_port: port,
+            remote_host: None,
+            remote_port: port,
+        });
+    }
+    parse_port_forward_spec(spec)
+}
+
+/// Formats a port forward as the argument of `ssh -L`.
+fn port_forward_spec(forward: &SshPortForwardOption) -> String {
+    let local_host = forward.local_host.as_deref().unwrap_or("localhost");
+    let remote_host = forward.remote_host.as_deref().unwrap_or("localhost");
+    format!(
+        "{}:{}:{}:{}",
+        bracket_ipv6(local_host),
+        forward.local_port,
+        bracket_ipv6(remote_host),
+        forward.remote_port
+    )
+}
+
 impl SshConnectionOptions {
     pub fn parse_command_line(input: &str) -> Result<Self> {
         let input = input.trim_start_matches("ssh ");
@@ -1573,24 +1633,11 @@ impl SshConnectionOptions {
         }
 
         if let Some(forwards) = &self.port_forwards {
-            args.extend(forwards.iter().map(|pf| {
-                let local_host = match &pf.local_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
-                let remote_host = match &pf.remote_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
-
-                format!(
-                    "-L{}:{}:{}:{}",
-                    bracket_ipv6(local_host),
-                    pf.local_port,
-                    bracket_ipv6(remote_host),
-                    pf.remote_port
-                )
-            }));
+            args.extend(
+                forwards
+                    .iter()
+                    .map(|pf| format!("-L{}", port_forward_spec(pf))),
+            );
         }
 
         args
@@ -2063,6 +2110,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_port_forward() -> Result<()> {
+        let pf = parse_port_forward(" 3000 ")?;
+        assert_eq!(pf.local_host, None);
+        assert_eq!(pf.local_port, 3000);
+        assert_eq!(pf.remote_host, None);
+        assert_eq!(pf.remote_port, 3000);
+
+        let pf = parse_port_forward("8080:localhost:80")?;
+        assert_eq!(pf.local_port, 8080);
+        assert_eq!(pf.remote_host, Some("localhost".to_string()));
+        assert_eq!(pf.remote_port, 80);
+
+        assert!(parse_port_forward("70000").is_err());
+        assert!(parse_port_forward("localhost").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_port_forward_ipv6_formatting() {
         let options = SshConnectionOptions {
@@ -24,7 +24,7 @@ use lsp::{
 };
 use node_runtime::NodeRuntime;
 use project::{
-    ProgressToken, Project,
+    ProgressToken, Project, UnsyncedBufferState,
     agent_server_store::AgentServerCommand,
     search::{SearchQuery, SearchResult},
 };
@@ -940,6 +940,65 @@ async fn test_remote_cancel_language_server_work(
     }
 }
 
+#[gpui::test]
+async fn test_remote_task_diagnostics(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, headless) = init_test(&fs, cx, server_cx).await;
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    // The problems are matched on the client, against the output of a task that ran on the host.
+    project
+        .update(cx, |project, cx| {
+            project.lsp_store().update(cx, |lsp_store, cx| {
+                lsp_store.update_task_diagnostics(
+                    task::TaskId("build".to_string()),
+                    Path::new(path!("/code/project1")),
+                    vec![task::Problem {
+                        source: "rustc".to_string(),
+                        path: PathBuf::from("src/lib.rs"),
+                        line: 1,
+                        column: Some(4),
+                        end: None,
+                        severity: task::ProblemSeverity::Error,
+                        code: None,
+                        message: "unused function".to_string(),
+                    }],
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    server_cx.run_until_parked();
+
+    headless.read_with(server_cx, |headless, cx| {
+        let summary = headless.lsp_store.read(cx).diagnostic_summary(false, cx);
+        assert_eq!(summary.error_count, 1);
+    });
+    project.read_with(cx, |project, cx| {
+        assert_eq!(project.diagnostic_summary(false, cx).error_count, 1);
+    });
+}
+
 #[gpui::test]
 async fn test_remote_reload(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -1346,6 +1405,143 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_saves_queued_while_offline(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                    "main.rs": "fn main() {}"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+
+    let lib = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    let main = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/main.rs")), cx)
+        })
+        .await
+        .unwrap();
+
+    project.update(cx, |project, cx| {
+        project
+            .buffer_store()
+            .update(cx, |buffer_store, _| buffer_store.remote_connection_lost())
+    });
+
+    lib.update(cx, |buffer, cx| {
+        let ix = buffer.text().find('1').unwrap();
+        buffer.edit([(ix..ix + 1, "100")], None, cx);
+    });
+    main.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// main\n")], None, cx);
+    });
+    for buffer in [&lib, &main] {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+    }
+    cx.run_until_parked();
+
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn one() -> usize { 1 }"
+    );
+    project.read_with(cx, |project, cx| {
+        let states = project
+            .unsynced_buffers(cx)
+            .into_iter()
+            .map(|(_, state)| state)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            [UnsyncedBufferState::Pending, UnsyncedBufferState::Pending]
+        );
+    });
+
+    // The file changes on the server while the client is offline.
+    fs.save(
+        path!("/code/project1/src/main.rs").as_ref(),
+        &"fn main() { changed() }".into(),
+        LineEnding::Unix,
+    )
+    .await
+    .unwrap();
+    cx.run_until_parked();
+
+    project
+        .update(cx, |project, cx| {
+            project.buffer_store().update(cx, |buffer_store, cx| {
+                buffer_store.remote_connection_restored(cx)
+            })
+        })
+        .await;
+    cx.run_until_parked();
+
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn one() -> usize { 100 }"
+    );
+    assert_eq!(
+        fs.load(path!("/code/project1/src/main.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn main() { changed() }"
+    );
+    project.read_with(cx, |project, cx| {
+        let unsynced_buffers = project.unsynced_buffers(cx);
+        assert_eq!(unsynced_buffers.len(), 1);
+        assert_eq!(unsynced_buffers[0].0, main);
+        assert_eq!(unsynced_buffers[0].1, UnsyncedBufferState::Conflict);
+    });
+    lib.read_with(cx, |buffer, _| assert!(!buffer.is_dirty()));
+    main.read_with(cx, |buffer, _| {
+        assert!(buffer.is_dirty());
+        assert!(buffer.has_conflict());
+    });
+
+    // Saving the buffer again overwrites the file, and clears the conflict.
+    project
+        .update(cx, |project, cx| project.save_buffer(main.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(path!("/code/project1/src/main.rs").as_ref())
+            .await
+            .unwrap(),
+        "// main\nfn main() {}"
+    );
+    project.read_with(cx, |project, cx| {
+        assert!(project.unsynced_buffers(cx).is_empty())
+    });
+}
+
 #[gpui::test]
 async fn test_remote_root_rename(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
@@ -1,7 +1,8 @@
 use crate::{
     BufferSearchBar, FocusSearch, HighlightKey, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
     ReplaceNext, SearchOption, SearchOptions, SearchSource, SelectNextMatch, SelectPreviousMatch,
-    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleWholeWord,
+    ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex, ToggleReplace, ToggleStructural,
+    ToggleWholeWord,
     buffer_search::Deploy,
     search_bar::{
         ActionButtonState, HistoryNavigationDirection, alignment_element, input_base_styles,
@@ -130,6 +131,12 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -771,6 +778,12 @@ impl ProjectSearchView {
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
         self.search_options.toggle(option);
+        // Structural patterns are not regexes, so the two modes are exclusive.
+        if option.contains(SearchOptions::REGEX) {
+            self.search_options.remove(SearchOptions::STRUCTURAL);
+        } else if option.contains(SearchOptions::STRUCTURAL) {
+            self.search_options.remove(SearchOptions::REGEX);
+        }
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.entity.read(cx).project.downgrade(),
@@ -1377,8 +1390,17 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
-            match SearchQuery::regex(
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            )
+        } else if self.search_options.contains(SearchOptions::REGEX) {
+            SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
                 self.search_options.contains(SearchOptions::CASE_SENSITIVE),
@@ -1389,28 +1411,9 @@ impl ProjectSearchView {
                 excluded_files,
                 match_full_paths,
                 open_buffers,
-            ) {
-                Ok(query) => {
-                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
-                    if should_unmark_error.is_some() {
-                        cx.notify();
-                    }
-
-                    Some(query)
-                }
-                Err(e) => {
-                    let should_mark_error = self
-                        .panels_with_errors
-                        .insert(InputPanel::Query, e.to_string());
-                    if should_mark_error.is_none() {
-                        cx.notify();
-                    }
-
-                    None
-                }
-            }
+            )
         } else {
-            match SearchQuery::text(
+            SearchQuery::text(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
                 self.search_options.contains(SearchOptions::CASE_SENSITIVE),
@@ -1419,25 +1422,26 @@ impl ProjectSearchView {
                 excluded_files,
                 match_full_paths,
                 open_buffers,
-            ) {
-                Ok(query) => {
-                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
-                    if should_unmark_error.is_some() {
-                        cx.notify();
-                    }
-
-                    Some(query)
+            )
+        };
+        let query = match query {
+            Ok(query) => {
+                let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                if should_unmark_error.is_some() {
+                    cx.notify();
                 }
-                Err(e) => {
-                    let should_mark_error = self
-                        .panels_with_errors
-                        .insert(InputPanel::Query, e.to_string());
-                    if should_mark_error.is_none() {
-                        cx.notify();
-                    }
 
-                    None
+                Some(query)
+            }
+            Err(e) => {
+                let should_mark_error = self
+                    .panels_with_errors
+                    .insert(InputPanel::Query, e.to_string());
+                if should_mark_error.is_none() {
+                    cx.notify();
                 }
+
+                None
             }
         };
         if !self.panels_with_errors.is_empty() {
@@ -2188,6 +2192,11 @@ impl Render for ProjectSearchBar {
                         search.search_options,
                         SearchSource::Project(cx),
                         focus_handle.clone(),
+                    ))
+                    .child(SearchOption::Structural.as_button(
+                        search.search_options,
+                        SearchSource::Project(cx),
+                        focus_handle.clone(),
                     )),
             );
 
@@ -191,6 +191,7 @@ impl VsCodeSettings {
                 ..GlobalLspSettingsContent::default()
             }),
             helix_mode: None,
+            hierarchy_panel: None,
             image_viewer: None,
             journal: None,
             language_models: None,
@@ -200,6 +201,7 @@ impl VsCodeSettings {
             node: self.node_binary_settings(),
             notification_panel: None,
             outline_panel: self.outline_panel_settings_content(),
+            ports_panel: None,
             preview_tabs: self.preview_tabs_settings_content(),
             project: self.project_settings_content(),
             project_panel: self.project_panel_settings_content(),
@@ -565,6 +567,7 @@ impl VsCodeSettings {
                 }),
             document_folding_ranges: None,
             document_symbols: None,
+            selection_ranges: None,
             linked_edits: self.read_bool("editor.linkedEditing"),
             preferred_line_length: self.read_u32("editor.wordWrapColumn"),
             prettier: None,
@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize, de::Error as _};
 use settings_macros::{MergeFrom, with_fallible_options};
 use std::sync::Arc;
 
-use crate::{DocumentFoldingRanges, DocumentSymbols, ExtendingVec, SemanticTokens, merge_from};
+use crate::{
+    DocumentFoldingRanges, DocumentSymbols, ExtendingVec, SelectionRanges, SemanticTokens,
+    merge_from,
+};
 
 /// The state of the modifier keys at some point in time
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
@@ -501,6 +504,15 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: "off"
     pub document_symbols: Option<DocumentSymbols>,
+    /// Controls which ranges `editor::SelectLargerSyntaxNode` expands the selection through.
+    ///
+    /// Options:
+    /// - "tree_sitter": Use tree-sitter syntax nodes (default).
+    /// - "language_server": Use the language server's `textDocument/selectionRange` response, falling back to tree-sitter.
+    /// - "merge": Use whichever of the two yields the smallest enclosing range.
+    ///
+    /// Default: "tree_sitter"
+    pub selection_ranges: Option<SelectionRanges>,
     /// Controls where the `editor::Rewrap` action is allowed for this language.
     ///
     /// Note: This setting has no effect in Vim mode, as rewrap is already
@@ -142,6 +142,9 @@ pub struct SettingsContent {
     /// Common language server settings.
     pub global_lsp_settings: Option<GlobalLspSettingsContent>,
 
+    /// Configuration for the hierarchy panel visual settings.
+    pub hierarchy_panel: Option<PanelSettingsContent>,
+
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
@@ -177,6 +180,9 @@ pub struct SettingsContent {
     /// Configuration for the Notification Panel
     pub notification_panel: Option<NotificationPanelSettingsContent>,
 
+    /// Configuration for the ports panel visual settings.
+    pub ports_panel: Option<PanelSettingsContent>,
+
     pub proxy: Option<String>,
 
     /// The URL of the Zed server to connect to.
@@ -1040,6 +1046,7 @@ pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub wsl_connections: Option<Vec<WslConnection>>,
     pub dev_container_connections: Option<Vec<DevContainerConnection>>,
+    pub command_connections: Option<Vec<CommandConnection>>,
     pub read_ssh_config: Option<bool>,
     pub use_podman: Option<bool>,
 }
@@ -1081,6 +1088,33 @@ pub struct SshConnection {
     pub connection_timeout: Option<u16>,
 }
 
+/// A remote environment reached through a command, such as `kubectl exec`, that runs the program
+/// given after its arguments with stdin and stdout attached.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct CommandConnection {
+    /// Name to use for this connection in UI.
+    pub nickname: String,
+    /// The program to run, e.g. `kubectl`.
+    pub program: String,
+    /// Arguments passed to the program before the command to run remotely,
+    /// e.g. `["exec", "-i", "my-pod", "--"]`.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Arguments used instead of `args` when opening terminals, so that a TTY
+    /// can be allocated, e.g. `["exec", "-it", "my-pod", "--"]`.
+    pub terminal_args: Option<Vec<String>>,
+    /// Environment variables to set for the remote server and terminals.
+    #[serde(default)]
+    pub remote_env: BTreeMap<String, String>,
+    // By default Zed will download the binary in the remote environment directly.
+    // If this is set to true, Zed will download the binary to your local machine,
+    // and then upload it through the command.
+    pub upload_binary_over_command: Option<bool>,
+    #[serde(default)]
+    pub projects: BTreeSet<RemoteProject>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Debug)]
 pub struct WslConnection {
     pub distro_name: String,
@@ -932,6 +932,39 @@ impl DocumentSymbols {
     }
 }
 
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionRanges {
+    /// Expand and shrink selections through tree-sitter syntax nodes (default).
+    #[default]
+    TreeSitter,
+    /// Use the language server's `textDocument/selectionRange` response, falling back to
+    /// tree-sitter when the server returns no larger range.
+    LanguageServer,
+    /// Expand to the smallest enclosing range found by either tree-sitter or the language server.
+    Merge,
+}
+
+impl SelectionRanges {
+    /// Returns true if selection ranges should be requested from language servers.
+    pub fn lsp_enabled(&self) -> bool {
+        self != &Self::TreeSitter
+    }
+}
+
 #[with_fallible_options]
 #[derive(Copy, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug)]
 pub struct FocusFollowsMouse {
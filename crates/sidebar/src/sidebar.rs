@@ -1297,6 +1297,7 @@ impl Sidebar {
         let remote_icon_per_type = match host? {
             RemoteConnectionOptions::Wsl(_) => IconName::Linux,
             RemoteConnectionOptions::Docker(_) => IconName::Box,
+            RemoteConnectionOptions::Command(_) => IconName::Terminal,
             _ => IconName::Server,
         };
 
@@ -1,4 +1,5 @@
 use anyhow::{Context as _, Result};
+use regex::{Captures, Regex, RegexBuilder};
 use smallvec::SmallVec;
 use std::{collections::BTreeMap, ops::Range};
 
@@ -12,18 +13,178 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Occurrences of this tabstop written as `${1/regex/format/flags}`, which are
+    /// filled with the transformed text of the tabstop once it is left.
+    pub transforms: Vec<TabStopTransform>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabStopTransform {
+    pub range: Range<isize>,
+    pub transform: Transform,
+}
+
+/// A `/regex/format/flags` rewrite, as applied to tabstops and variables.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    global: bool,
+    format: Vec<FormatItem>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    Case(usize, Case),
+    Conditional {
+        group: usize,
+        if_matched: String,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Case {
+    Upcase,
+    Downcase,
+    Capitalize,
+    Camelcase,
+    Pascalcase,
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.global == other.global
+            && self.format == other.format
+    }
+}
+
+impl Transform {
+    /// Rewrites the first match of the regex in `text`, or all of them with the `g` flag.
+    /// Text outside of the matches is kept as is.
+    pub fn apply(&self, text: &str) -> String {
+        let limit = if self.global { 0 } else { 1 };
+        self.regex
+            .replacen(text, limit, |captures: &Captures| {
+                let mut output = String::new();
+                for item in &self.format {
+                    let group = |index: usize| {
+                        captures
+                            .get(index)
+                            .map(|group| group.as_str())
+                            .unwrap_or_default()
+                    };
+                    match item {
+                        FormatItem::Text(text) => output.push_str(text),
+                        FormatItem::Group(index) => output.push_str(group(*index)),
+                        FormatItem::Case(index, case) => {
+                            output.push_str(&case.apply(group(*index)))
+                        }
+                        FormatItem::Conditional {
+                            group: index,
+                            if_matched,
+                            otherwise,
+                        } => {
+                            if group(*index).is_empty() {
+                                output.push_str(otherwise);
+                            } else {
+                                output.push_str(if_matched);
+                            }
+                        }
+                    }
+                }
+                output
+            })
+            .into_owned()
+    }
+}
+
+impl Case {
+    fn apply(self, text: &str) -> String {
+        let words = || {
+            text.split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+        };
+        match self {
+            Case::Upcase => text.to_uppercase(),
+            Case::Downcase => text.to_lowercase(),
+            Case::Capitalize => capitalize(text),
+            Case::Pascalcase => words()
+                .map(|word| capitalize(&word.to_lowercase()))
+                .collect(),
+            Case::Camelcase => words()
+                .enumerate()
+                .map(|(ix, word)| {
+                    if ix == 0 {
+                        word.to_lowercase()
+                    } else {
+                        capitalize(&word.to_lowercase())
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Tracks how `$NAME` variables are resolved while parsing a snippet.
+struct Variables<'a> {
+    resolve: &'a dyn Fn(&str) -> Option<String>,
+    /// Variables that could not be resolved. Like in VS Code, their names are inserted
+    /// as placeholders, so that they are easy to spot and replace.
+    unresolved: Vec<(String, Range<isize>)>,
 }
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
+        Self::parse_with_variables(source, |_| None)
+    }
+
+    /// Parses a snippet, replacing variables such as `$TM_FILENAME` with the values
+    /// returned by `resolve_variable`. Variables it returns `None` for are treated as
+    /// unknown.
+    pub fn parse_with_variables(
+        source: &str,
+        resolve_variable: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
         let mut text = String::with_capacity(source.len());
         let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        let mut variables = Variables {
+            resolve: &resolve_variable,
+            unresolved: Vec::new(),
+        };
+        parse_snippet(source, false, &mut text, &mut tabstops, &mut variables)
             .context("failed to parse snippet")?;
 
+        let mut next_index = tabstops.keys().last().map_or(1, |index| index + 1);
+        let mut unresolved_indices = BTreeMap::new();
+        for (name, range) in variables.unresolved {
+            let index = *unresolved_indices.entry(name).or_insert_with(|| {
+                next_index += 1;
+                next_index - 1
+            });
+            tabstops
+                .entry(index)
+                .or_insert_with(TabStop::default)
+                .ranges
+                .push(range);
+        }
+
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
-        let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
+        let mut tabstops = tabstops
+            .into_values()
+            .filter(|tabstop| !tabstop.ranges.is_empty())
+            .collect::<Vec<_>>();
 
         if let Some(final_tabstop) = final_tabstop {
             tabstops.push(final_tabstop);
@@ -31,6 +192,7 @@ impl Snippet {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
                 choices: None,
+                transforms: Vec::new(),
             };
 
             if !tabstops.last().is_some_and(|t| *t == end_tabstop) {
@@ -47,12 +209,13 @@ fn parse_snippet<'a>(
     nested: bool,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    variables: &mut Variables,
 ) -> Result<&'a str> {
     loop {
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                source = parse_tabstop(&source[1..], text, tabstops, variables)?;
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -93,12 +256,19 @@ fn parse_tabstop<'a>(
     mut source: &'a str,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    variables: &mut Variables,
 ) -> Result<&'a str> {
+    let braced = source.starts_with('{');
+    if starts_with_variable_name(&source[braced as usize..]) {
+        return parse_variable(source, text, tabstops, variables);
+    }
+
     let tabstop_start = text.len();
     let tabstop_index;
     let mut choices = None;
+    let mut transform = None;
 
-    if source.starts_with('{') {
+    if braced {
         let (index, rest) = parse_int(&source[1..])?;
         tabstop_index = index;
         source = rest;
@@ -108,7 +278,11 @@ fn parse_tabstop<'a>(
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, text, tabstops, variables)?;
+        } else if source.starts_with('/') {
+            let parsed;
+            (parsed, source) = parse_transform(&source[1..])?;
+            transform = Some(parsed);
         }
 
         if source.starts_with('}') {
@@ -122,17 +296,267 @@ fn parse_tabstop<'a>(
         source = rest;
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
-        .ranges
-        .push(tabstop_start as isize..text.len() as isize);
+    let tabstop = tabstops.entry(tabstop_index).or_default();
+    let range = tabstop_start as isize..text.len() as isize;
+    if let Some(transform) = transform {
+        tabstop
+            .transforms
+            .push(TabStopTransform { range, transform });
+    } else {
+        if tabstop.choices.is_none() {
+            tabstop.choices = choices;
+        }
+        tabstop.ranges.push(range);
+    }
     Ok(source)
 }
 
+fn starts_with_variable_name(source: &str) -> bool {
+    source
+        .chars()
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+}
+
+fn parse_variable<'a>(
+    mut source: &'a str,
+    text: &mut String,
+    tabstops: &mut BTreeMap<usize, TabStop>,
+    variables: &mut Variables,
+) -> Result<&'a str> {
+    let braced = source.starts_with('{');
+    if braced {
+        source = &source[1..];
+    }
+    let name_len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    let (name, rest) = source.split_at(name_len);
+    source = rest;
+
+    let value = (variables.resolve)(name);
+    let start = text.len();
+    let mut transform = None;
+
+    if braced {
+        if source.starts_with(':') {
+            if value.is_some() {
+                // The default is still parsed, to find where it ends, but its text is discarded.
+                source = parse_snippet(
+                    &source[1..],
+                    true,
+                    &mut String::new(),
+                    &mut BTreeMap::new(),
+                    &mut Variables {
+                        resolve: variables.resolve,
+                        unresolved: Vec::new(),
+                    },
+                )?;
+            } else {
+                source = parse_snippet(&source[1..], true, text, tabstops, variables)?;
+            }
+        } else if source.starts_with('/') {
+            let parsed;
+            (parsed, source) = parse_transform(&source[1..])?;
+            transform = Some(parsed);
+        }
+
+        source = source
+            .strip_prefix('}')
+            .context("expected a closing brace")?;
+    }
+
+    match value {
+        Some(value) => match transform {
+            Some(transform) => text.push_str(&transform.apply(&value)),
+            None => text.push_str(&value),
+        },
+        // An unresolved variable's default was inserted while parsing it.
+        None if text.len() > start => {}
+        None => {
+            text.push_str(name);
+            variables
+                .unresolved
+                .push((name.to_string(), start as isize..text.len() as isize));
+        }
+    }
+    Ok(source)
+}
+
+/// Parses the `regex/format/flags}` part of a transform, leaving the closing brace.
+fn parse_transform(source: &str) -> Result<(Transform, &str)> {
+    let (pattern, source) = split_transform_part(source, true)?;
+    let (format, source) = split_transform_part(source, false)?;
+    let flags_len = source.find('}').context("expected a closing brace")?;
+    let (flags, source) = source.split_at(flags_len);
+
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .build()
+        .context("invalid transform regex")?;
+    Ok((
+        Transform {
+            regex,
+            global: flags.contains('g'),
+            format: parse_format(&format)?,
+        },
+        source,
+    ))
+}
+
+/// Splits off the text up to the next unescaped `/`, skipping over `${...}` groups in
+/// formats. Escaped slashes are unescaped, while other escapes are kept for the regex,
+/// or for the format parser to handle.
+fn split_transform_part(source: &str, is_regex: bool) -> Result<(String, &str)> {
+    let mut part = String::new();
+    let mut group_depth = 0;
+    let mut chars = source.char_indices().peekable();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '/' if group_depth == 0 => return Ok((part, &source[ix + 1..])),
+            '$' if !is_regex && chars.peek().is_some_and(|(_, next)| *next == '{') => {
+                group_depth += 1;
+                part.push(c);
+            }
+            '}' if group_depth > 0 => {
+                group_depth -= 1;
+                part.push(c);
+            }
+            '\\' => match chars.next() {
+                Some((_, '/')) => part.push('/'),
+                Some((_, escaped)) => {
+                    part.push('\\');
+                    part.push(escaped);
+                }
+                None => part.push('\\'),
+            },
+            c => part.push(c),
+        }
+    }
+    anyhow::bail!(
+        "expected a closing '/' after the transform {}",
+        if is_regex { "regex" } else { "format" }
+    )
+}
+
+fn parse_format(mut source: &str) -> Result<Vec<FormatItem>> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    loop {
+        match source.chars().next() {
+            None => break,
+            Some('\\') => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(c) => text.push(c),
+                    None => {
+                        text.push('\\');
+                        break;
+                    }
+                }
+                source = &source[source.chars().next().map_or(0, char::len_utf8)..];
+            }
+            Some('$') if source[1..].starts_with(|c: char| c.is_ascii_digit()) => {
+                let (group, rest) = parse_int(&source[1..])?;
+                items.push(FormatItem::Text(std::mem::take(&mut text)));
+                items.push(FormatItem::Group(group));
+                source = rest;
+            }
+            Some('$') if source[1..].starts_with('{') => {
+                let (group, rest) = parse_int(&source[2..])?;
+                let (item, rest) = parse_format_group(group, rest)?;
+                items.push(FormatItem::Text(std::mem::take(&mut text)));
+                items.push(item);
+                source = rest;
+            }
+            Some(c) => {
+                text.push(c);
+                source = &source[c.len_utf8()..];
+            }
+        }
+    }
+    items.push(FormatItem::Text(text));
+    items.retain(|item| !matches!(item, FormatItem::Text(text) if text.is_empty()));
+    Ok(items)
+}
+
+/// Parses what follows the group number in `${1...}`, including the closing brace.
+fn parse_format_group(group: usize, source: &str) -> Result<(FormatItem, &str)> {
+    if let Some(rest) = source.strip_prefix('}') {
+        return Ok((FormatItem::Group(group), rest));
+    }
+    let source = source
+        .strip_prefix(':')
+        .context("expected ':' or '}' in a format group")?;
+
+    let (case, rest) = match source.strip_prefix('/') {
+        Some(rest) => {
+            let name_len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let case = match &rest[..name_len] {
+                "upcase" => Case::Upcase,
+                "downcase" => Case::Downcase,
+                "capitalize" => Case::Capitalize,
+                "camelcase" => Case::Camelcase,
+                "pascalcase" => Case::Pascalcase,
+                other => anyhow::bail!("unknown format modifier '/{other}'"),
+            };
+            (Some(case), &rest[name_len..])
+        }
+        None => (None, source),
+    };
+    if let Some(case) = case {
+        let rest = rest.strip_prefix('}').context("expected a closing brace")?;
+        return Ok((FormatItem::Case(group, case), rest));
+    }
+
+    let (if_matched, otherwise, rest) = if let Some(rest) = source.strip_prefix('+') {
+        let (if_matched, rest) = split_format_text(rest, &['}'])?;
+        (if_matched, String::new(), rest)
+    } else if let Some(rest) = source.strip_prefix('?') {
+        let (if_matched, rest) = split_format_text(rest, &[':'])?;
+        let (otherwise, rest) = split_format_text(&rest[1..], &['}'])?;
+        (if_matched, otherwise, rest)
+    } else {
+        let rest = source.strip_prefix('-').unwrap_or(source);
+        let (otherwise, rest) = split_format_text(rest, &['}'])?;
+        (String::new(), otherwise, rest)
+    };
+    let rest = rest.strip_prefix('}').context("expected a closing brace")?;
+    Ok((
+        FormatItem::Conditional {
+            group,
+            if_matched,
+            otherwise,
+        },
+        rest,
+    ))
+}
+
+/// Reads conditional format text up to (but not including) one of the `terminators`.
+fn split_format_text<'a>(source: &'a str, terminators: &[char]) -> Result<(String, &'a str)> {
+    let mut text = String::new();
+    let mut chars = source.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if terminators.contains(&c) {
+            return Ok((text, &source[ix..]));
+        }
+        if c == '\\' {
+            if let Some((_, escaped)) = chars.next() {
+                text.push(escaped);
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    anyhow::bail!("unterminated format group")
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -324,6 +748,104 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let resolve = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet = Snippet::parse_with_variables("// $TM_FILENAME: $1", resolve).unwrap();
+        assert_eq!(snippet.text, "// main.rs: ");
+        assert_eq!(tabstops(&snippet), &[vec![12..12]]);
+
+        // Defaults are only used when the variable has no value.
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME:untitled} ${NOPE:$1 default}", resolve)
+                .unwrap();
+        assert_eq!(snippet.text, "main.rs  default");
+        assert_eq!(tabstops(&snippet), &[vec![8..8], vec![16..16]]);
+
+        let snippet = Snippet::parse_with_variables("[${TM_SELECTED_TEXT}]", resolve).unwrap();
+        assert_eq!(snippet.text, "[]");
+
+        // Unknown variables become placeholders holding their name.
+        let snippet =
+            Snippet::parse_with_variables("$1 $UNKNOWN ${UNKNOWN} $OTHER$0", resolve).unwrap();
+        assert_eq!(snippet.text, " UNKNOWN UNKNOWN OTHER");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![0..0], vec![1..8, 9..16], vec![17..22], vec![22..22]]
+        );
+
+        // Without a resolver, every variable is unknown.
+        let snippet = Snippet::parse("$TM_FILENAME").unwrap();
+        assert_eq!(snippet.text, "TM_FILENAME");
+        assert_eq!(tabstops(&snippet), &[vec![0..11], vec![11..11]]);
+    }
+
+    #[test]
+    fn test_snippet_with_variable_transforms() {
+        let resolve = |name: &str| match name {
+            "TM_FILENAME" => Some("my-module.test.ts".to_string()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables(r"${TM_FILENAME/(.*?)\..+$/$1/}", resolve).unwrap();
+        assert_eq!(snippet.text, "my-module");
+
+        let snippet = Snippet::parse_with_variables(
+            r"${TM_FILENAME/(\w+)-(\w+).*/${1:/pascalcase}${2:/upcase}/}",
+            resolve,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "MyMODULE");
+
+        let snippet = Snippet::parse_with_variables(r"${TM_FILENAME/[-.]/_/g}", resolve).unwrap();
+        assert_eq!(snippet.text, "my_module_test_ts");
+
+        let snippet = Snippet::parse_with_variables(r"${TM_FILENAME/[-.]/_/}", resolve).unwrap();
+        assert_eq!(snippet.text, "my_module.test.ts");
+    }
+
+    #[test]
+    fn test_snippet_with_tabstop_transforms() {
+        let snippet = Snippet::parse(r"${1:name} ${1/(.)(.*)/${1:/upcase}$2/}$0").unwrap();
+        assert_eq!(snippet.text, "name ");
+        assert_eq!(tabstops(&snippet), &[vec![0..4], vec![5..5]]);
+
+        let transforms = &snippet.tabstops[0].transforms;
+        assert_eq!(transforms.len(), 1);
+        assert_eq!(transforms[0].range, 5..5);
+        assert_eq!(transforms[0].transform.apply("count"), "Count");
+    }
+
+    #[test]
+    fn test_transform_formats() {
+        let transform = |source: &str| parse_transform(source).unwrap().0;
+
+        assert_eq!(
+            transform(r"^(\w+)-(\w+)$/${1:/camelcase}|${2:/capitalize}/}").apply("Foo_bar-baz"),
+            "fooBar|Baz"
+        );
+        assert_eq!(
+            transform(r"(a)?b/${1:+had a}${1:-no a}/}").apply("ab"),
+            "had a"
+        );
+        assert_eq!(
+            transform(r"(a)?b/${1:+had a}${1:-no a}/}").apply("b"),
+            "no a"
+        );
+        assert_eq!(transform(r"(a)?b/${1:?yes\: a:no}/}").apply("b"), "no");
+        assert_eq!(transform(r"(a)?b/${1:?yes\: a:no}/}").apply("ab"), "yes: a");
+        assert_eq!(transform(r"x/\/\$1\n/}").apply("x"), "/$1\n");
+        assert_eq!(transform(r"A/b/gi}").apply("aAa"), "bbb");
+        assert!(parse_transform(r"(/x/}").is_err());
+        assert!(parse_transform(r"x/${1:/shout}/}").is_err());
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
@@ -0,0 +1,602 @@
+//! Problem matchers extract errors and warnings from a task's output, so that they can be shown as diagnostics.
+
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+/// A way to find problems in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the matchers Zed knows about.
+    BuiltIn(BuiltInProblemMatcher),
+    /// A matcher defined by the user.
+    Custom(CustomProblemMatcher),
+}
+
+/// Problem matchers for the tools commonly run as tasks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltInProblemMatcher {
+    /// Errors and warnings of `rustc`, also reported by `cargo build`, `cargo check` and `cargo clippy`.
+    Rustc,
+    /// Errors, warnings and notes of `gcc` and `clang`.
+    #[serde(alias = "clang")]
+    Gcc,
+    /// Errors of the TypeScript compiler.
+    Tsc,
+    /// Problems reported by ESLint with its default, `stylish`, formatter.
+    Eslint,
+}
+
+/// A user-defined problem matcher.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CustomProblemMatcher {
+    /// A built-in matcher to use the patterns and the source of, unless they are set explicitly.
+    #[serde(default)]
+    pub base: Option<BuiltInProblemMatcher>,
+    /// The name to show as the source of the diagnostics produced by this matcher.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Severity of the problems that do not have their severity captured.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns to match against consecutive lines of the output.
+    /// A problem is reported when the last pattern matches, with all values captured by the previous patterns.
+    #[serde(default)]
+    pub pattern: Vec<ProblemPattern>,
+    /// The directory that relative paths in the output are relative to, defaults to the task's working directory.
+    /// May use task variables.
+    #[serde(default)]
+    pub file_location: Option<String>,
+}
+
+/// A regular expression matched against a line of the task output,
+/// with the indices of the capture groups that hold the problem's properties.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match a line against.
+    pub regexp: String,
+    /// The capture group with the path of the file, either absolute or relative to the task's working directory.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The capture group with the 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The capture group with the 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The capture group with the 1-based line the problem ends at.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    /// The capture group with the 1-based column the problem ends at.
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The capture group with the severity of the problem, such as `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The capture group with the code of the problem.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The capture group with the message of the problem.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether this pattern, if it is the last one, may match multiple lines in a row, reporting a problem for each of them.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// How severe a problem is.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(severity: &str) -> Option<Self> {
+        let severity = severity.trim().to_ascii_lowercase();
+        if severity.starts_with("err") || severity.starts_with("fatal") {
+            Some(Self::Error)
+        } else if severity.starts_with("warn") {
+            Some(Self::Warning)
+        } else if severity.starts_with("info") {
+            Some(Self::Information)
+        } else if severity.starts_with("note") || severity.starts_with("hint") {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A problem found in a task's output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The name of the tool that reported the problem.
+    pub source: String,
+    /// The path of the file with the problem, as it was printed.
+    pub path: PathBuf,
+    /// The 1-based line of the problem.
+    pub line: u32,
+    /// The 1-based column of the problem, if it was reported.
+    pub column: Option<u32>,
+    /// The 1-based line and column the problem ends at, if they were reported.
+    pub end: Option<(u32, Option<u32>)>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl ProblemMatcher {
+    /// Finds all problems in the given task output.
+    pub fn match_output(&self, output: &str) -> Vec<Problem> {
+        match self {
+            Self::BuiltIn(built_in) => match_patterns(
+                &built_in.patterns(),
+                built_in.source(),
+                ProblemSeverity::Error,
+                output,
+            ),
+            Self::Custom(custom) => custom.match_output(output),
+        }
+    }
+}
+
+impl CustomProblemMatcher {
+    /// Finds all problems in the given task output.
+    pub fn match_output(&self, output: &str) -> Vec<Problem> {
+        let base_patterns;
+        let patterns = if self.pattern.is_empty() {
+            base_patterns = self
+                .base
+                .map(BuiltInProblemMatcher::patterns)
+                .unwrap_or_default();
+            &base_patterns
+        } else {
+            &self.pattern
+        };
+        let source = self
+            .source
+            .as_deref()
+            .or(self.base.map(BuiltInProblemMatcher::source))
+            .unwrap_or("task");
+
+        let mut problems = match_patterns(patterns, source, self.severity, output);
+        if let Some(file_location) = &self.file_location {
+            for problem in &mut problems {
+                if problem.path.is_relative() {
+                    problem.path = Path::new(file_location).join(&problem.path);
+                }
+            }
+        }
+        problems
+    }
+}
+
+impl BuiltInProblemMatcher {
+    fn source(self) -> &'static str {
+        match self {
+            Self::Rustc => "rustc",
+            Self::Gcc => "gcc",
+            Self::Tsc => "tsc",
+            Self::Eslint => "eslint",
+        }
+    }
+
+    fn patterns(self) -> Vec<ProblemPattern> {
+        match self {
+            Self::Rustc => vec![
+                ProblemPattern {
+                    regexp: r"^(error|warning)(?:\[(\w+)\])?: (.+)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*--> (.+?):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+            Self::Gcc => vec![ProblemPattern {
+                regexp: r"^(.+?):(\d+):(\d+):\s+(?:fatal\s+)?(error|warning|note):\s+(.+)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+            Self::Tsc => vec![ProblemPattern {
+                regexp: r"^(\S.*?)[(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.+)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+            // Stylish output lists the path of a file first, then all of its problems below.
+            Self::Eslint => vec![
+                ProblemPattern {
+                    regexp: r"^(\S.*)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(\S+))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        }
+    }
+}
+
+/// Values captured for a problem so far, by the patterns matched before.
+#[derive(Clone, Default)]
+struct PartialProblem {
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<ProblemSeverity>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl PartialProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let text = |group: Option<usize>| {
+            group
+                .and_then(|group| captures.get(group))
+                .map(|capture| capture.as_str().trim())
+                .filter(|text| !text.is_empty())
+        };
+        let number = |group: Option<usize>| text(group).and_then(|text| text.parse().ok());
+
+        if let Some(path) = text(pattern.file) {
+            self.path = Some(path.to_string());
+        }
+        if let Some(line) = number(pattern.line) {
+            self.line = Some(line);
+        }
+        if let Some(column) = number(pattern.column) {
+            self.column = Some(column);
+        }
+        if let Some(end_line) = number(pattern.end_line) {
+            self.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(pattern.end_column) {
+            self.end_column = Some(end_column);
+        }
+        if let Some(severity) = text(pattern.severity).and_then(ProblemSeverity::parse) {
+            self.severity = Some(severity);
+        }
+        if let Some(code) = text(pattern.code) {
+            self.code = Some(code.to_string());
+        }
+        if let Some(message) = text(pattern.message) {
+            self.message = Some(message.to_string());
+        }
+    }
+
+    fn into_problem(self, source: &str, default_severity: ProblemSeverity) -> Option<Problem> {
+        Some(Problem {
+            source: source.to_string(),
+            path: PathBuf::from(self.path?),
+            line: self.line?,
+            column: self.column,
+            end: self.end_line.map(|end_line| (end_line, self.end_column)),
+            severity: self.severity.unwrap_or(default_severity),
+            code: self.code,
+            message: self.message?,
+        })
+    }
+}
+
+fn match_patterns(
+    patterns: &[ProblemPattern],
+    source: &str,
+    default_severity: ProblemSeverity,
+    output: &str,
+) -> Vec<Problem> {
+    let Some(patterns) = patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(&pattern.regexp)
+                .map(|regex| (pattern, regex))
+                .log_err()
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Vec::new();
+    };
+    let Some(((last_pattern, last_regex), leading_patterns)) = patterns.split_last() else {
+        return Vec::new();
+    };
+
+    let lines = output.lines().collect::<Vec<_>>();
+    let mut problems = Vec::new();
+    let mut start = 0;
+    'lines: while start < lines.len() {
+        let mut partial_problem = PartialProblem::default();
+        for (ix, (pattern, regex)) in leading_patterns.iter().enumerate() {
+            match lines.get(start + ix).and_then(|line| regex.captures(line)) {
+                Some(captures) => partial_problem.capture(pattern, &captures),
+                None => {
+                    start += 1;
+                    continue 'lines;
+                }
+            }
+        }
+
+        let mut line_ix = start + leading_patterns.len();
+        while let Some(captures) = lines
+            .get(line_ix)
+            .and_then(|line| last_regex.captures(line))
+        {
+            let mut problem = partial_problem.clone();
+            problem.capture(last_pattern, &captures);
+            problems.extend(problem.into_problem(source, default_severity));
+            line_ix += 1;
+            if !last_pattern.repeat {
+                break;
+            }
+        }
+        start = if line_ix > start + leading_patterns.len() {
+            line_ix
+        } else {
+            start + 1
+        };
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use util::path;
+
+    use super::*;
+
+    fn problem(
+        source: &str,
+        path: &str,
+        (line, column): (u32, u32),
+        severity: ProblemSeverity,
+        code: Option<&str>,
+        message: &str,
+    ) -> Problem {
+        Problem {
+            source: source.to_string(),
+            path: PathBuf::from(path),
+            line,
+            column: Some(column),
+            end: None,
+            severity,
+            code: code.map(ToString::to_string),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rustc_problem_matcher() {
+        let output = r#"   Compiling demo v0.1.0 (/projects/demo)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 5;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+
+error[E0308]: mismatched types
+  --> src/lib.rs:10:18
+   |
+10 |     let y: u32 = "five";
+   |            ---   ^^^^^^ expected `u32`, found `&str`
+
+error: could not compile `demo` (bin "demo") due to 1 previous error; 1 warning emitted
+"#;
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc).match_output(output),
+            vec![
+                problem(
+                    "rustc",
+                    "src/main.rs",
+                    (2, 9),
+                    ProblemSeverity::Warning,
+                    None,
+                    "unused variable: `x`"
+                ),
+                problem(
+                    "rustc",
+                    "src/lib.rs",
+                    (10, 18),
+                    ProblemSeverity::Error,
+                    Some("E0308"),
+                    "mismatched types"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gcc_and_tsc_problem_matchers() {
+        let output = "main.c: In function 'main':
+main.c:3:5: warning: implicit declaration of function 'foo' [-Wimplicit-function-declaration]
+/abs/main.c:4:1: fatal error: stdio.h: No such file or directory
+";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc).match_output(output),
+            vec![
+                problem(
+                    "gcc",
+                    "main.c",
+                    (3, 5),
+                    ProblemSeverity::Warning,
+                    None,
+                    "implicit declaration of function 'foo' [-Wimplicit-function-declaration]"
+                ),
+                problem(
+                    "gcc",
+                    "/abs/main.c",
+                    (4, 1),
+                    ProblemSeverity::Error,
+                    None,
+                    "stdio.h: No such file or directory"
+                ),
+            ]
+        );
+
+        let output =
+            "src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+src/app.ts:12:1 - error TS2304: Cannot find name 'foo'.
+
+Found 2 errors.
+";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc).match_output(output),
+            vec![
+                problem(
+                    "tsc",
+                    "src/index.ts",
+                    (3, 7),
+                    ProblemSeverity::Error,
+                    Some("TS2322"),
+                    "Type 'string' is not assignable to type 'number'."
+                ),
+                problem(
+                    "tsc",
+                    "src/app.ts",
+                    (12, 1),
+                    ProblemSeverity::Error,
+                    Some("TS2304"),
+                    "Cannot find name 'foo'."
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eslint_problem_matcher() {
+        let output = "
+/projects/demo/src/a.js
+   1:10  error    'unused' is defined but never used  no-unused-vars
+  12:3   warning  Unexpected console statement        no-console
+
+/projects/demo/src/b.js
+  4:1  error  Parsing error: Unexpected token
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        assert_eq!(
+            ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Eslint).match_output(output),
+            vec![
+                problem(
+                    "eslint",
+                    "/projects/demo/src/a.js",
+                    (1, 10),
+                    ProblemSeverity::Error,
+                    Some("no-unused-vars"),
+                    "'unused' is defined but never used"
+                ),
+                problem(
+                    "eslint",
+                    "/projects/demo/src/a.js",
+                    (12, 3),
+                    ProblemSeverity::Warning,
+                    Some("no-console"),
+                    "Unexpected console statement"
+                ),
+                problem(
+                    "eslint",
+                    "/projects/demo/src/b.js",
+                    (4, 1),
+                    ProblemSeverity::Error,
+                    None,
+                    "Parsing error: Unexpected token"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let matcher: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "source": "mylint",
+            "severity": "warning",
+            "pattern": [{
+                "regexp": "^(.+):(\\d+): (.+)$",
+                "file": 1,
+                "line": 2,
+                "message": 3,
+            }],
+            "file_location": path!("/projects/demo"),
+        }))
+        .unwrap();
+        assert_eq!(
+            matcher.match_output("checking...\nsrc/lib.rs:7: trailing whitespace\n"),
+            vec![Problem {
+                source: "mylint".to_string(),
+                path: PathBuf::from(path!("/projects/demo/src/lib.rs")),
+                line: 7,
+                column: None,
+                end: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "trailing whitespace".to_string(),
+            }]
+        );
+
+        let built_in: Vec<ProblemMatcher> =
+            serde_json::from_value(serde_json::json!(["rustc", "clang"])).unwrap();
+        assert_eq!(
+            built_in,
+            vec![
+                ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc),
+                ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Gcc),
+            ]
+        );
+
+        let based_on_tsc: ProblemMatcher = serde_json::from_value(serde_json::json!({
+            "base": "tsc",
+            "file_location": path!("/projects/demo/editors/code"),
+        }))
+        .unwrap();
+        assert_eq!(
+            based_on_tsc.match_output("src/main.ts(1,2): error TS1005: ';' expected.\n"),
+            vec![Problem {
+                source: "tsc".to_string(),
+                path: PathBuf::from(path!("/projects/demo/editors/code/src/main.ts")),
+                line: 1,
+                column: Some(2),
+                end: None,
+                severity: ProblemSeverity::Error,
+                code: Some("TS1005".to_string()),
+                message: "';' expected.".to_string(),
+            }]
+        );
+    }
+}
@@ -2,6 +2,7 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
 mod task_template;
@@ -22,9 +23,13 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    BuiltInProblemMatcher, CustomProblemMatcher, Problem, ProblemMatcher, ProblemPattern,
+    ProblemSeverity,
+};
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, SaveStrategy, TaskHook, TaskTemplate,
-    TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, SaveStrategy, TaskHook,
+    TaskTemplate, TaskTemplates, substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use util::shell::{Shell, ShellKind};
 pub use util::shell_builder::ShellBuilder;
@@ -77,6 +82,8 @@ pub struct SpawnInTerminal {
     pub show_rerun: bool,
     /// Which edited buffers to save before running the task.
     pub save: SaveStrategy,
+    /// How to find problems in the task's output, to show them as diagnostics.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -120,6 +127,8 @@ pub struct ResolvedTask {
     pub id: TaskId,
     /// A template the task got resolved from.
     original_task: TaskTemplate,
+    /// A context the task got resolved with, also used to resolve the tasks it depends on.
+    context: TaskContext,
     /// Full, unshortened label of the task after all resolutions are made.
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
@@ -135,6 +144,11 @@ impl ResolvedTask {
         &self.original_task
     }
 
+    /// A context the task template got resolved with.
+    pub fn context(&self) -> &TaskContext {
+        &self.context
+    }
+
     /// Variables that were substituted during the task template resolution.
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
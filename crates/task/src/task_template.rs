@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, CustomProblemMatcher, ProblemMatcher, ResolvedTask, RevealTarget, Shell,
+    SpawnInTerminal, TaskContext, TaskId, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be omitted for tasks that only run the tasks they depend on.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -78,6 +81,21 @@ pub struct TaskTemplate {
     /// Hooks that this task runs when emitted.
     #[serde(default)]
     pub hooks: HashSet<TaskHook>,
+    /// Labels of the worktree or global tasks that have to finish successfully before this task starts.
+    /// If any of them fails, this task is not run.
+    /// A task with dependencies may omit its `command`, to only run the tasks it depends on.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// In which order to run the tasks from `depends_on`:
+    /// * `sequence` — one after another, in the order they are listed (default)
+    /// * `parallel` — all at the same time
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// How to find problems in the task's output, to show them as diagnostics once the task finishes.
+    /// Either the name of a built-in matcher (`rustc`, `gcc`, `clang`, `tsc` or `eslint`),
+    /// or an object with a regular expression `pattern` to match the output lines against.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -123,6 +141,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// In which order to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run the dependencies one after another, stopping at the first one that fails.
+    #[default]
+    Sequence,
+    /// Run all dependencies at the same time.
+    Parallel,
+}
+
 /// Which edited buffers to save before running a task.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -160,7 +189,7 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (!self.has_command() && self.depends_on.is_empty()) {
             return None;
         }
 
@@ -239,6 +268,26 @@ impl TaskTemplate {
             &variable_names,
             &mut substituted_variables,
         )?;
+        let problem_matchers = self
+            .problem_matchers
+            .iter()
+            .map(|matcher| {
+                let mut matcher = matcher.clone();
+                if let ProblemMatcher::Custom(CustomProblemMatcher {
+                    file_location: Some(file_location),
+                    ..
+                }) = &mut matcher
+                {
+                    *file_location = substitute_all_template_variables_in_str(
+                        file_location,
+                        &task_variables,
+                        &variable_names,
+                        &mut substituted_variables,
+                    )?;
+                }
+                Some(matcher)
+            })
+            .collect::<Option<Vec<_>>>()?;
 
         let task_hash = to_hex_hash(self)
             .context("hashing task template")
@@ -272,6 +321,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -299,10 +349,16 @@ impl TaskTemplate {
                 show_command: self.show_command,
                 show_rerun: true,
                 save: self.save,
+                problem_matchers,
             },
         })
     }
 
+    /// Whether the task spawns a command, rather than only running the tasks it [depends on](Self::depends_on).
+    pub fn has_command(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+
     /// Validates that all `$ZED_*` variables used in this template are known
     /// variable names, returning a vector with all of the unique unknown
     /// variables.
@@ -550,6 +606,16 @@ mod tests {
                 "should not resolve task with blank label and/or command: {task_with_blank_property:?}"
             );
         }
+
+        let task_with_dependencies_only = TaskTemplate {
+            label: "test_label".to_string(),
+            depends_on: vec!["other_label".to_string()],
+            ..TaskTemplate::default()
+        };
+        let resolved_task = task_with_dependencies_only
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("should resolve a task that only runs its dependencies");
+        assert!(!resolved_task.original_task().has_command());
     }
 
     #[test]
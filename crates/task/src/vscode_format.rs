@@ -3,7 +3,10 @@ use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, EnvVariableReplacer, ProblemMatcher,
+    ProblemPattern, ProblemSeverity, TaskTemplate, TaskTemplates, VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -13,10 +16,28 @@ struct TaskOptions {
     env: HashMap<String, String>,
 }
 
+/// Labels of the tasks a task depends on, either a single label or a list of them.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    #[default]
+    Parallel,
+    Sequence,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 struct VsCodeTaskDefinition {
     label: String,
     command: Option<Command>,
+    depends_on: Option<DependsOn>,
+    depends_order: VsCodeDependsOrder,
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
 }
@@ -33,6 +54,10 @@ impl<'de> serde::Deserialize<'de> for VsCodeTaskDefinition {
             label: Option<String>,
             #[serde(flatten)]
             command: Option<Command>,
+            #[serde(default)]
+            depends_on: Option<DependsOn>,
+            #[serde(default)]
+            depends_order: VsCodeDependsOrder,
             #[serde(flatten)]
             other_attributes: HashMap<String, serde_json_lenient::Value>,
             options: Option<TaskOptions>,
@@ -47,6 +72,8 @@ impl<'de> serde::Deserialize<'de> for VsCodeTaskDefinition {
         Ok(VsCodeTaskDefinition {
             label,
             command: helper.command,
+            depends_on: helper.depends_on,
+            depends_order: helper.depends_order,
             other_attributes: helper.other_attributes,
             options: helper.options,
         })
@@ -86,44 +113,182 @@ fn generate_label(command: &Option<Command>) -> String {
 }
 
 impl VsCodeTaskDefinition {
-    fn into_zed_format(
-        self,
-        replacer: &EnvVariableReplacer,
-    ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+    fn into_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
+        let depends_on = match self.depends_on {
+            Some(DependsOn::Single(label)) => vec![label],
+            Some(DependsOn::Multiple(labels)) => labels,
+            None => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` is usually not set in tasks that only group other tasks with `dependsOn`, hence command is an Option.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
         let args = args.into_iter().map(|arg| replacer.replace(&arg)).collect();
+        let problem_matchers = self
+            .other_attributes
+            .get("problemMatcher")
+            .map(|problem_matcher| problem_matchers_from_vscode(problem_matcher, replacer))
+            .unwrap_or_default();
         let mut template = TaskTemplate {
             label: self.label,
             command,
             args,
+            depends_order: match self.depends_order {
+                VsCodeDependsOrder::Parallel if !depends_on.is_empty() => DependsOrder::Parallel,
+                VsCodeDependsOrder::Parallel | VsCodeDependsOrder::Sequence => {
+                    DependsOrder::Sequence
+                }
+            },
+            depends_on,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
-        Ok(Some(template))
+        Ok(template)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcher {
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    file_location: Option<serde_json_lenient::Value>,
+    #[serde(default)]
+    pattern: Option<serde_json_lenient::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    #[serde(default)]
+    file: Option<usize>,
+    #[serde(default)]
+    line: Option<usize>,
+    #[serde(default)]
+    column: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default)]
+    end_column: Option<usize>,
+    #[serde(default)]
+    severity: Option<usize>,
+    #[serde(default)]
+    code: Option<usize>,
+    #[serde(default)]
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+/// Converts the `problemMatcher` of a task, skipping the matchers Zed does not support,
+/// such as the named patterns and the matchers of extensions other than the built-in ones.
+fn problem_matchers_from_vscode(
+    problem_matcher: &serde_json_lenient::Value,
+    replacer: &EnvVariableReplacer,
+) -> Vec<ProblemMatcher> {
+    use serde_json_lenient::Value;
+
+    match problem_matcher {
+        Value::Array(problem_matchers) => problem_matchers
+            .iter()
+            .flat_map(|problem_matcher| problem_matchers_from_vscode(problem_matcher, replacer))
+            .collect(),
+        Value::String(name) => built_in_problem_matcher(name)
+            .map(ProblemMatcher::BuiltIn)
+            .into_iter()
+            .collect(),
+        Value::Object(_) => {
+            let Some(problem_matcher) =
+                serde_json_lenient::from_value::<VsCodeProblemMatcher>(problem_matcher.clone())
+                    .log_err()
+            else {
+                return Vec::new();
+            };
+            let base = problem_matcher
+                .base
+                .as_deref()
+                .and_then(built_in_problem_matcher);
+            let pattern = match &problem_matcher.pattern {
+                Some(Value::Array(patterns)) => patterns.iter().collect(),
+                Some(pattern @ Value::Object(_)) => vec![pattern],
+                _ => Vec::new(),
+            }
+            .into_iter()
+            .map(|pattern| {
+                serde_json_lenient::from_value::<VsCodeProblemPattern>(pattern.clone())
+                    .map(|pattern| ProblemPattern {
+                        regexp: pattern.regexp,
+                        file: pattern.file,
+                        line: pattern.line,
+                        column: pattern.column,
+                        end_line: pattern.end_line,
+                        end_column: pattern.end_column,
+                        severity: pattern.severity,
+                        code: pattern.code,
+                        message: pattern.message,
+                        repeat: pattern.repeat,
+                    })
+                    .log_err()
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default();
+            if base.is_none() && pattern.is_empty() {
+                log::warn!("Skipping unsupported VS Code problem matcher {problem_matcher:?}");
+                return Vec::new();
+            }
+
+            // `relative`, the default, is relative to the workspace folder, which is the default working directory of Zed tasks.
+            let file_location = match &problem_matcher.file_location {
+                Some(Value::Array(file_location)) => file_location
+                    .get(1)
+                    .and_then(Value::as_str)
+                    .map(|directory| replacer.replace(directory)),
+                _ => None,
+            };
+            vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                base,
+                source: problem_matcher.source.or(problem_matcher.owner),
+                severity: problem_matcher
+                    .severity
+                    .as_deref()
+                    .and_then(ProblemSeverity::parse)
+                    .unwrap_or_default(),
+                pattern,
+                file_location,
+            })]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn built_in_problem_matcher(name: &str) -> Option<BuiltInProblemMatcher> {
+    match name.strip_prefix('$').unwrap_or(name) {
+        "rustc" | "rustc-watch" => Some(BuiltInProblemMatcher::Rustc),
+        "gcc" => Some(BuiltInProblemMatcher::Gcc),
+        "tsc" | "tsc-watch" => Some(BuiltInProblemMatcher::Tsc),
+        "eslint-stylish" => Some(BuiltInProblemMatcher::Eslint),
+        _ => {
+            log::warn!("Skipping unsupported VS Code problem matcher `{name}`");
+            None
+        }
     }
 }
 
@@ -152,12 +317,7 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
         let templates = value
             .tasks
             .into_iter()
-            .filter_map(|vscode_definition| {
-                vscode_definition
-                    .into_zed_format(&replacer)
-                    .log_err()
-                    .flatten()
-            })
+            .filter_map(|vscode_definition| vscode_definition.into_zed_format(&replacer).log_err())
             .collect();
         Ok(Self(templates))
     }
@@ -168,8 +328,9 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        BuiltInProblemMatcher, CustomProblemMatcher, DependsOrder, ProblemMatcher, TaskTemplate,
+        TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, DependsOn, VsCodeTaskDefinition},
     };
 
     use super::{EnvVariableReplacer, generate_label};
@@ -224,6 +385,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -238,6 +401,8 @@ mod tests {
                         "--watch".to_string(),
                     ],
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -246,6 +411,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:compiler".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -254,6 +421,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -271,6 +440,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
@@ -282,18 +452,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Tsc)],
                 ..Default::default()
             },
         ];
@@ -313,6 +486,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "watch".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
@@ -321,6 +496,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
@@ -330,6 +507,8 @@ mod tests {
                     command: "cargo build --package rust-analyzer".to_string(),
                     args: Default::default(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
@@ -339,6 +518,8 @@ mod tests {
                     command: "cargo build --release --package rust-analyzer".to_string(),
                     args: Default::default(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
@@ -347,18 +528,30 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "pretest".to_string(),
                 }),
+                depends_on: None,
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
+                depends_on: Some(DependsOn::Multiple(vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ])),
+                depends_order: Default::default(),
                 options: None,
                 other_attributes: Default::default(),
             },
@@ -369,33 +562,63 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let extension_problem_matcher = || {
+            vec![ProblemMatcher::Custom(CustomProblemMatcher {
+                base: Some(BuiltInProblemMatcher::Tsc),
+                file_location: Some("${ZED_WORKTREE_ROOT}/editors/code/".to_string()),
+                ..CustomProblemMatcher::default()
+            })]
+        };
+        let rustc_problem_matcher = || vec![ProblemMatcher::BuiltIn(BuiltInProblemMatcher::Rustc)];
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: extension_problem_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: extension_problem_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: rustc_problem_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: rustc_problem_matcher(),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: extension_problem_matcher(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: rustc_problem_matcher(),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                depends_order: DependsOrder::Parallel,
+                problem_matchers: rustc_problem_matcher(),
                 ..Default::default()
             },
         ];
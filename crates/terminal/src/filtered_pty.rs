@@ -0,0 +1,129 @@
+use std::{
+    io::{self, Read as _, Write as _},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+};
+use parking_lot::Mutex;
+use polling::{Event as PollingEvent, PollMode, Poller};
+
+use crate::{
+    port_detection::LocalPortScanner, shell_integration::ShellIntegrationScanner,
+    terminal_images::TerminalImages,
+};
+
+/// How much is read from the shell at once. Images are replaced by placeholder cells that can
+/// take up more bytes than the image sequence, so this is well below the size of Alacritty's
+/// read buffer, but anything that doesn't fit is kept for the next read.
+const READ_BUFFER_LEN: usize = 16 * 1024;
+
+/// A PTY that processes the output of the shell before Alacritty reads it: inline images are
+/// replaced by placeholder cells, and the result is scanned for semantic prompt marks and local
+/// addresses.
+pub(crate) struct FilteredPty {
+    pty: Pty,
+    shell_integration: Arc<Mutex<ShellIntegrationScanner>>,
+    images: Arc<Mutex<TerminalImages>>,
+    local_ports: Arc<Mutex<LocalPortScanner>>,
+    read_buffer: Box<[u8]>,
+    filtered: Vec<u8>,
+}
+
+impl FilteredPty {
+    pub fn new(
+        pty: Pty,
+        shell_integration: Arc<Mutex<ShellIntegrationScanner>>,
+        images: Arc<Mutex<TerminalImages>>,
+        local_ports: Arc<Mutex<LocalPortScanner>>,
+    ) -> Self {
+        Self {
+            pty,
+            shell_integration,
+            images,
+            local_ports,
+            read_buffer: vec![0; READ_BUFFER_LEN].into_boxed_slice(),
+            filtered: Vec::new(),
+        }
+    }
+}
+
+impl io::Read for FilteredPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Image data is consumed without output, so keep reading until there is some.
+        while self.filtered.is_empty() {
+            let len = self.pty.reader().read(&mut self.read_buffer)?;
+            if len == 0 {
+                return Ok(0);
+            }
+
+            let responses = {
+                let mut images = self.images.lock();
+                images.filter(&self.read_buffer[..len], &mut self.filtered);
+                images.take_responses()
+            };
+            if !responses.is_empty()
+                && let Err(error) = self.pty.writer().write_all(&responses)
+            {
+                log::warn!("Failed to reply to a kitty graphics command: {error}");
+            }
+            self.shell_integration.lock().scan(&self.filtered);
+            self.local_ports.lock().scan(&self.filtered);
+        }
+
+        let len = self.filtered.len().min(buf.len());
+        buf[..len].copy_from_slice(&self.filtered[..len]);
+        self.filtered.drain(..len);
+        Ok(len)
+    }
+}
+
+impl EventedReadWrite for FilteredPty {
+    type Reader = Self;
+    type Writer = <Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollingEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for FilteredPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for FilteredPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.images.lock().set_window_size(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
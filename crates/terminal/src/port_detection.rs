@@ -0,0 +1,115 @@
+//! Detecting servers started in the terminal from the `localhost:PORT` addresses they print, so
+//! their ports can be offered for forwarding when the terminal runs on a remote host.
+
+use collections::HashSet;
+use regex::bytes::Regex;
+use std::sync::LazyLock;
+
+static LOCAL_ADDRESS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?-u)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):([0-9]{1,5})").unwrap()
+});
+
+/// How much of the end of the previous output is scanned again with the next output, so addresses
+/// split between two reads are found. This fits the longest address matched.
+const CARRY_LEN: usize = 32;
+
+#[derive(Default)]
+pub(crate) struct LocalPortScanner {
+    carry: Vec<u8>,
+    seen: HashSet<u16>,
+    detected: Vec<u16>,
+}
+
+impl LocalPortScanner {
+    /// Scans output of the shell for local addresses. Each port is only detected once.
+    pub fn scan(&mut self, output: &[u8]) {
+        if output.is_empty() {
+            return;
+        }
+        let mut text = std::mem::take(&mut self.carry);
+        let carry_len = text.len();
+        text.extend_from_slice(output);
+
+        for captures in LOCAL_ADDRESS_REGEX.captures_iter(&text) {
+            let (Some(address), Some(port)) = (captures.get(0), captures.get(1)) else {
+                continue;
+            };
+            // Addresses ending before the new output were checked with the previous output. The
+            // port of an address ending with the output might continue in the next one, in which
+            // case the address is checked once the rest of it arrives.
+            if address.end() < carry_len || address.end() == text.len() {
+                continue;
+            }
+            let continues_word = |byte: Option<&u8>| byte.is_some_and(u8::is_ascii_alphanumeric);
+            if continues_word(text.get(address.end()))
+                || (address.start() > 0 && continues_word(text.get(address.start() - 1)))
+            {
+                continue;
+            }
+            let Some(port) = std::str::from_utf8(port.as_bytes())
+                .ok()
+                .and_then(|port| port.parse::<u16>().ok())
+                .filter(|port| *port != 0)
+            else {
+                continue;
+            };
+            if self.seen.insert(port) {
+                self.detected.push(port);
+            }
+        }
+
+        let carry_start = text.len().saturating_sub(CARRY_LEN);
+        text.drain(..carry_start);
+        self.carry = text;
+    }
+
+    /// Returns the ports detected since the last call.
+    pub fn take_ports(&mut self) -> Vec<u16> {
+        std::mem::take(&mut self.detected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(chunks: &[&[u8]]) -> Vec<u16> {
+        let mut scanner = LocalPortScanner::default();
+        for chunk in chunks {
+            scanner.scan(chunk);
+        }
+        scanner.take_ports()
+    }
+
+    #[test]
+    fn test_detect_local_addresses() {
+        assert_eq!(
+            scan(&[
+                b"  VITE ready\r\n  \x1b[32m->\x1b[0m  Local:   http://localhost:5173/\r\n",
+                b"Listening on 127.0.0.1:8000, 0.0.0.0:9000 and [::1]:3000\r\n",
+            ]),
+            vec![5173, 8000, 9000, 3000]
+        );
+    }
+
+    #[test]
+    fn test_detect_address_split_between_reads() {
+        assert_eq!(
+            scan(&[b"Serving on http://local", b"host:80", b"80/ ..."]),
+            vec![8080]
+        );
+        assert_eq!(scan(&[b"http://localhost:3000"]), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn test_ignore_invalid_and_repeated_ports() {
+        assert_eq!(
+            scan(&[
+                b"localhost:0 localhost:70000 localhost:123456 notlocalhost:1234 ",
+                b"localhost:4000 localhost:4000\n",
+                b"http://localhost:4000/api\n",
+            ]),
+            vec![4000]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Serializing the terminal's contents as text with SGR escape sequences, so they can be written
+//! back into a terminal later.
+//!
+//! Every SGR sequence written here starts with a reset (`ESC [ 0`), so each line is styled
+//! independently of the lines before it, and the output can be truncated at any line.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    index::{Column, Line},
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color, NamedColor},
+};
+
+use crate::terminal_images::IMAGE_PLACEHOLDER;
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+#[derive(Clone, Copy, PartialEq)]
+struct CellStyle {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for CellStyle {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl CellStyle {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+
+    fn write_sgr(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        let flags = [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "21"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ];
+        for (flag, parameter) in flags {
+            if self.flags.contains(flag) {
+                output.push(';');
+                output.push_str(parameter);
+            }
+        }
+        write_color(self.fg, 30, output);
+        write_color(self.bg, 40, output);
+        output.push('m');
+    }
+}
+
+/// Writes the SGR parameters for a color, where `base` is 30 for foreground colors and 40 for
+/// background colors. Default colors don't need any.
+fn write_color(color: Color, base: usize, output: &mut String) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            let dim_colors = NamedColor::DimBlack as usize..=NamedColor::DimWhite as usize;
+            let parameter = match index {
+                0..=7 => base + index,
+                8..=15 => base + 60 + index - 8,
+                _ if dim_colors.contains(&index) => base + index - dim_colors.start(),
+                _ => return,
+            };
+            write!(output, ";{parameter}").ok();
+        }
+        Color::Indexed(index) => {
+            write!(output, ";{};5;{index}", base + 8).ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == IMAGE_PLACEHOLDER
+        || (cell.c == ' '
+            && cell.bg == Color::Named(NamedColor::Background)
+            && !cell
+                .flags
+                .intersects(Flags::INVERSE | Flags::ALL_UNDERLINES | Flags::STRIKEOUT))
+}
+
+/// Serializes the grid from the top of the scrollback down to the cursor. Only the newest lines
+/// that fit in `max_len` bytes are kept.
+pub(crate) fn serialize_grid<T>(term: &Term<T>, max_len: usize) -> String {
+    let grid = term.grid();
+    let cursor = grid.cursor.point;
+    let mut lines = Vec::new();
+    let mut len = 0;
+    for line in (term.topmost_line().0..=cursor.line.0).rev() {
+        let row = &grid[Line(line)];
+        let mut text = String::new();
+        let min_columns = if line == cursor.line.0 {
+            cursor.column.0
+        } else {
+            0
+        };
+        serialize_row(
+            (0..term.columns()).map(|column| &row[Column(column)]),
+            min_columns,
+            &mut text,
+        );
+        let wrapped = row[term.last_column()].flags.contains(Flags::WRAPLINE);
+
+        len += text.len() + 2;
+        if len > max_len && !lines.is_empty() {
+            break;
+        }
+        lines.push((text, wrapped));
+    }
+
+    let mut output = String::with_capacity(len);
+    for (text, wrapped) in lines.into_iter().rev() {
+        output.push_str(&text);
+        // Wrapped lines are joined, so they are wrapped again at the width of the terminal
+        // they are written to.
+        if !wrapped {
+            output.push_str("\r\n");
+        }
+    }
+    // The cursor line doesn't end with a line break.
+    if output.ends_with("\r\n") {
+        output.truncate(output.len() - 2);
+    }
+    output
+}
+
+/// Serializes the cells of a row, without trailing blanks past `min_columns`.
+fn serialize_row<'a>(
+    cells: impl Iterator<Item = &'a Cell> + Clone,
+    min_columns: usize,
+    output: &mut String,
+) {
+    let len = cells
+        .clone()
+        .enumerate()
+        .filter(|(_, cell)| !is_blank(cell))
+        .last()
+        .map_or(0, |(column, _)| column + 1)
+        .max(min_columns);
+
+    let mut current_style = CellStyle::default();
+    for cell in cells.take(len) {
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        // Images aren't saved, and their placeholders would show whatever image gets their id
+        // next.
+        let (c, style) = if cell.c == IMAGE_PLACEHOLDER {
+            (' ', CellStyle::default())
+        } else {
+            (cell.c, CellStyle::of(cell))
+        };
+        if style != current_style {
+            style.write_sgr(output);
+            current_style = style;
+        }
+        output.push(c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            output.extend(zerowidth);
+        }
+    }
+    if current_style != CellStyle::default() {
+        output.push_str("\x1b[0m");
+    }
+}
+
+/// Dims serialized scrollback, by adding the dim attribute to every SGR sequence in it.
+pub(crate) fn dim(scrollback: &str) -> String {
+    format!("\x1b[2m{}\x1b[0m", scrollback.replace("\x1b[0", "\x1b[0;2"))
+}
@@ -0,0 +1,526 @@
+//! Shell integration through semantic prompt marks.
+//!
+//! Shells that are configured for it report where prompts, commands and their output begin with
+//! OSC 133 (FinalTerm) or OSC 633 (VS Code) sequences, and their working directory with OSC 7.
+//! Alacritty drops these sequences, so [`ShellIntegrationScanner`] scans everything the shell
+//! writes before Alacritty parses it, and the marks are turned into [`CommandBlock`]s once the
+//! terminal has caught up with the output.
+
+use std::{mem, path::PathBuf};
+
+/// The longest OSC payload that is inspected. Longer sequences (inline images, clipboard
+/// writes) can't be semantic prompt marks and are skipped without buffering.
+const MAX_OSC_LEN: usize = 4096;
+
+/// A prompt, the command run from it and the command's output.
+///
+/// Lines are counted from the first line the terminal ever showed, including lines that have
+/// since dropped off the top of the scrollback, so they stay put while new output scrolls the
+/// screen. Use [`crate::Terminal::grid_line`] to turn them into grid lines.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// The first line of the prompt.
+    pub prompt_line: usize,
+    /// The line the command is typed on, right after the prompt.
+    pub command_line: Option<usize>,
+    /// The column the command starts at, unless the shell wrote more output before the
+    /// terminal could tell.
+    pub command_column: Option<usize>,
+    /// The first line of the command's output, once the command is executed.
+    pub output_start: Option<usize>,
+    /// The line after the command's output, once the command is finished.
+    pub output_end: Option<usize>,
+    /// The command line, if the shell reported it (OSC 633 E).
+    pub command: Option<String>,
+    /// The exit code of a finished command, if the shell reported one.
+    pub exit_code: Option<i32>,
+    /// The shell's working directory when the prompt was shown.
+    pub cwd: Option<PathBuf>,
+}
+
+impl CommandBlock {
+    pub fn is_finished(&self) -> bool {
+        self.output_end.is_some()
+    }
+
+    pub fn failed(&self) -> bool {
+        self.exit_code.is_some_and(|code| code != 0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ShellMarkKind {
+    PromptStart,
+    CommandStart,
+    CommandExecuted,
+    CommandFinished { exit_code: Option<i32> },
+    CommandLine(String),
+    WorkingDirectory(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ShellMark {
+    pub kind: ShellMarkKind,
+    /// Line feeds the shell wrote between the previous mark and this one.
+    pub line_feeds: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Finds semantic prompt marks in the raw output of the shell.
+#[derive(Debug, Default)]
+pub(crate) struct ShellIntegrationScanner {
+    state: ScanState,
+    osc: Vec<u8>,
+    osc_overflowed: bool,
+    marks: Vec<ShellMark>,
+    line_feeds: usize,
+    /// Line feeds written since the terminal last synced its scrollback.
+    unsynced_line_feeds: usize,
+}
+
+impl ShellIntegrationScanner {
+    pub fn scan(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            match self.state {
+                ScanState::Ground => match byte {
+                    0x1b => self.state = ScanState::Escape,
+                    b'\n' => {
+                        self.line_feeds += 1;
+                        self.unsynced_line_feeds += 1;
+                    }
+                    _ => {}
+                },
+                ScanState::Escape => match byte {
+                    b']' => {
+                        self.osc.clear();
+                        self.osc_overflowed = false;
+                        self.state = ScanState::Osc;
+                    }
+                    0x1b => {}
+                    _ => self.state = ScanState::Ground,
+                },
+                ScanState::Osc => match byte {
+                    0x07 => self.finish_osc(),
+                    0x1b => self.state = ScanState::OscEscape,
+                    // CAN and SUB abort the sequence.
+                    0x18 | 0x1a => self.state = ScanState::Ground,
+                    _ if self.osc.len() < MAX_OSC_LEN => self.osc.push(byte),
+                    _ => self.osc_overflowed = true,
+                },
+                ScanState::OscEscape => match byte {
+                    b'\\' => self.finish_osc(),
+                    b']' => {
+                        self.osc.clear();
+                        self.osc_overflowed = false;
+                        self.state = ScanState::Osc;
+                    }
+                    0x1b => self.state = ScanState::Escape,
+                    _ => self.state = ScanState::Ground,
+                },
+            }
+        }
+    }
+
+    /// Takes the marks found so far, along with the number of line feeds written after the
+    /// last of them.
+    pub fn take_marks(&mut self) -> (Vec<ShellMark>, usize) {
+        (mem::take(&mut self.marks), self.line_feeds)
+    }
+
+    /// Takes the number of line feeds written since the last call.
+    pub fn take_unsynced_line_feeds(&mut self) -> usize {
+        mem::take(&mut self.unsynced_line_feeds)
+    }
+
+    fn finish_osc(&mut self) {
+        self.state = ScanState::Ground;
+        if self.osc_overflowed {
+            return;
+        }
+        if let Some(kind) = parse_osc(&self.osc) {
+            self.marks.push(ShellMark {
+                kind,
+                line_feeds: mem::take(&mut self.line_feeds),
+            });
+        }
+    }
+}
+
+fn parse_osc(osc: &[u8]) -> Option<ShellMarkKind> {
+    let mut params = osc.split(|&byte| byte == b';');
+    match params.next()? {
+        b"133" | b"633" => {
+            let vscode = osc.starts_with(b"633");
+            match params.next()? {
+                b"A" => Some(ShellMarkKind::PromptStart),
+                b"B" => Some(ShellMarkKind::CommandStart),
+                b"C" => Some(ShellMarkKind::CommandExecuted),
+                b"D" => Some(ShellMarkKind::CommandFinished {
+                    exit_code: params
+                        .next()
+                        .and_then(|code| std::str::from_utf8(code).ok())
+                        .and_then(|code| code.parse().ok()),
+                }),
+                b"E" if vscode => Some(ShellMarkKind::CommandLine(unescape_vscode_value(
+                    params.next()?,
+                ))),
+                b"P" if vscode => {
+                    let property = params.next()?;
+                    let cwd = property.strip_prefix(b"Cwd=")?;
+                    Some(ShellMarkKind::WorkingDirectory(PathBuf::from(
+                        unescape_vscode_value(cwd),
+                    )))
+                }
+                // Continuation prompts (`633;F`) and properties other than the cwd aren't tracked.
+                _ => None,
+            }
+        }
+        b"7" => {
+            let url = std::str::from_utf8(osc.get(2..)?).ok()?;
+            let path = url.strip_prefix("file://")?;
+            let path = &path[path.find('/')?..];
+            let path = urlencoding::decode(path).ok()?;
+            Some(ShellMarkKind::WorkingDirectory(PathBuf::from(
+                path.into_owned(),
+            )))
+        }
+        b"1337" => {
+            let cwd = params.next()?.strip_prefix(b"CurrentDir=")?;
+            Some(ShellMarkKind::WorkingDirectory(PathBuf::from(
+                String::from_utf8_lossy(cwd).into_owned(),
+            )))
+        }
+        _ => None,
+    }
+}
+
+/// VS Code escapes backslashes as `\\` and semicolons and control characters as `\xAB`.
+fn unescape_vscode_value(value: &[u8]) -> String {
+    let mut unescaped = Vec::with_capacity(value.len());
+    let mut bytes = value.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            unescaped.push(byte);
+            continue;
+        }
+        match bytes.peek() {
+            Some(b'\\') => {
+                bytes.next();
+                unescaped.push(b'\\');
+            }
+            Some(b'x') => {
+                bytes.next();
+                let hex = [bytes.next(), bytes.next()];
+                let decoded = match hex {
+                    [Some(high), Some(low)] => std::str::from_utf8(&[high, low])
+                        .ok()
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                    _ => None,
+                };
+                match decoded {
+                    Some(decoded) => unescaped.push(decoded),
+                    None => {
+                        unescaped.extend(b"\\x");
+                        unescaped.extend(hex.into_iter().flatten());
+                    }
+                }
+            }
+            _ => unescaped.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+/// The command blocks of a terminal, built from the marks of its shell.
+#[derive(Debug, Default)]
+pub(crate) struct CommandBlocks {
+    blocks: Vec<CommandBlock>,
+    cwd: Option<PathBuf>,
+    /// How many lines dropped off the top of the scrollback since the terminal started.
+    lines_dropped: usize,
+    /// The history size and cursor line as of the last sync.
+    last_sync: (usize, usize),
+}
+
+impl CommandBlocks {
+    pub fn blocks(&self) -> &[CommandBlock] {
+        &self.blocks
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    pub fn lines_dropped(&self) -> usize {
+        self.lines_dropped
+    }
+
+    /// Counts the lines that dropped off the top of the scrollback since the last sync.
+    ///
+    /// Alacritty stops growing its history once it's full and drops the oldest lines instead,
+    /// without reporting how many. Every line feed either moves the cursor down or scrolls a
+    /// line into the history, so what the cursor and the history didn't account for was
+    /// dropped. Output that moves the cursor up or clears the screen makes this an estimate.
+    pub fn sync_scrollback(
+        &mut self,
+        line_feeds: usize,
+        history_size: usize,
+        cursor_line: usize,
+        history_full: bool,
+    ) {
+        let (last_history_size, last_cursor_line) =
+            mem::replace(&mut self.last_sync, (history_size, cursor_line));
+        if history_full {
+            let scrolled = line_feeds.saturating_sub(cursor_line.saturating_sub(last_cursor_line));
+            self.lines_dropped +=
+                scrolled.saturating_sub(history_size.saturating_sub(last_history_size));
+        }
+    }
+
+    /// Forgets the blocks that ended in lines which dropped off the top of the scrollback.
+    pub fn remove_scrolled_out(&mut self) {
+        let lines_dropped = self.lines_dropped;
+        self.blocks
+            .retain(|block| block.output_end.is_none_or(|end| end > lines_dropped));
+    }
+
+    /// Applies marks that the terminal has already parsed the output for.
+    ///
+    /// Alacritty doesn't report where the marks were parsed, so the marks are placed by walking
+    /// back from the cursor, one line per line feed the shell wrote. This is exact for the
+    /// output of line-oriented programs, and re-anchors at every batch of marks otherwise.
+    pub fn apply(
+        &mut self,
+        marks: Vec<ShellMark>,
+        trailing_line_feeds: usize,
+        cursor: (usize, usize),
+    ) {
+        let (cursor_line, cursor_column) = cursor;
+        let mut line = cursor_line.saturating_sub(trailing_line_feeds);
+        let mut lines = vec![0; marks.len()];
+        for (mark, mark_line) in marks.iter().zip(&mut lines).rev() {
+            *mark_line = line;
+            line = line.saturating_sub(mark.line_feeds);
+        }
+
+        let last_mark = marks.len().saturating_sub(1);
+        for (ix, (mark, line)) in marks.into_iter().zip(lines).enumerate() {
+            // The cursor only tells where a mark is if nothing was written after it.
+            let column = (ix == last_mark && trailing_line_feeds == 0).then_some(cursor_column);
+            self.apply_mark(mark.kind, line, column);
+        }
+    }
+
+    fn apply_mark(&mut self, kind: ShellMarkKind, line: usize, column: Option<usize>) {
+        match kind {
+            ShellMarkKind::PromptStart => {
+                if let Some(last) = self.blocks.last_mut() {
+                    if last.output_start.is_none() {
+                        // The prompt was abandoned, e.g. with ctrl-c.
+                        self.blocks.pop();
+                    } else if last.output_end.is_none() {
+                        last.output_end = Some(line);
+                    }
+                }
+                self.blocks.push(CommandBlock {
+                    prompt_line: line,
+                    cwd: self.cwd.clone(),
+                    ..CommandBlock::default()
+                });
+            }
+            ShellMarkKind::CommandStart => {
+                let block = self.current_block(line);
+                block.command_line = Some(line);
+                block.command_column = column;
+            }
+            ShellMarkKind::CommandExecuted => {
+                self.current_block(line).output_start = Some(line);
+            }
+            ShellMarkKind::CommandFinished { exit_code } => {
+                let block = self.current_block(line);
+                if block.output_start.is_some() && block.output_end.is_none() {
+                    block.output_end = Some(line);
+                    block.exit_code = exit_code;
+                }
+            }
+            ShellMarkKind::CommandLine(command) => {
+                self.current_block(line).command = Some(command);
+            }
+            ShellMarkKind::WorkingDirectory(cwd) => {
+                if let Some(block) = self
+                    .blocks
+                    .last_mut()
+                    .filter(|block| block.output_start.is_none())
+                {
+                    block.cwd = Some(cwd.clone());
+                }
+                self.cwd = Some(cwd);
+            }
+        }
+    }
+
+    /// The block that is being prompted for or run, starting one for shells that
+    /// don't mark their prompts.
+    fn current_block(&mut self, line: usize) -> &mut CommandBlock {
+        if self
+            .blocks
+            .last()
+            .is_none_or(|block| block.output_end.is_some())
+        {
+            self.blocks.push(CommandBlock {
+                prompt_line: line,
+                cwd: self.cwd.clone(),
+                ..CommandBlock::default()
+            });
+        }
+        self.blocks.last_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(chunks: &[&[u8]]) -> (Vec<ShellMark>, usize) {
+        let mut scanner = ShellIntegrationScanner::default();
+        for chunk in chunks {
+            scanner.scan(chunk);
+        }
+        scanner.take_marks()
+    }
+
+    fn mark(kind: ShellMarkKind, line_feeds: usize) -> ShellMark {
+        ShellMark { kind, line_feeds }
+    }
+
+    #[test]
+    fn test_scan_final_term_marks() {
+        let (marks, trailing_line_feeds) = scan(&[
+            b"\x1b]133;A\x07user@host\n$ \x1b]133;B\x07",
+            b"ls\r\n\x1b]1",
+            b"33;C\x1b\\a\r\nb\r\n\x1b]133;D;2\x07\x1b]133;A;k=i\x07$ ",
+        ]);
+        assert_eq!(
+            marks,
+            [
+                mark(ShellMarkKind::PromptStart, 0),
+                mark(ShellMarkKind::CommandStart, 1),
+                mark(ShellMarkKind::CommandExecuted, 1),
+                mark(ShellMarkKind::CommandFinished { exit_code: Some(2) }, 2),
+                mark(ShellMarkKind::PromptStart, 0),
+            ]
+        );
+        assert_eq!(trailing_line_feeds, 0);
+    }
+
+    #[test]
+    fn test_scan_vscode_marks() {
+        let (marks, _) = scan(&[
+            b"\x1b]633;P;Cwd=/home/user/my\\x3bdir\x1b\\",
+            b"\x1b]633;E;echo a\\x3b echo \\\\b;nonce\x07\x1b]633;D\x07",
+            b"\x1b]7;file://host/tmp/a%20b\x07\x1b]0;title\x07\x1b]133;X\x07",
+        ]);
+        assert_eq!(
+            marks,
+            [
+                mark(
+                    ShellMarkKind::WorkingDirectory("/home/user/my;dir".into()),
+                    0
+                ),
+                mark(ShellMarkKind::CommandLine("echo a; echo \\b".into()), 0),
+                mark(ShellMarkKind::CommandFinished { exit_code: None }, 0),
+                mark(ShellMarkKind::WorkingDirectory("/tmp/a b".into()), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_blocks() {
+        let mut blocks = CommandBlocks::default();
+        // A two line prompt on line 10, with the cursor after it.
+        blocks.apply(
+            vec![
+                mark(ShellMarkKind::PromptStart, 0),
+                mark(ShellMarkKind::CommandStart, 1),
+            ],
+            0,
+            (11, 2),
+        );
+        // The command printed two lines and exited with 1, then the next prompt was shown.
+        blocks.apply(
+            vec![
+                mark(ShellMarkKind::CommandLine("make".into()), 0),
+                mark(ShellMarkKind::CommandExecuted, 1),
+                mark(ShellMarkKind::CommandFinished { exit_code: Some(1) }, 2),
+                mark(ShellMarkKind::PromptStart, 0),
+                mark(ShellMarkKind::CommandStart, 1),
+            ],
+            0,
+            (15, 2),
+        );
+        assert_eq!(
+            blocks.blocks(),
+            [
+                CommandBlock {
+                    prompt_line: 10,
+                    command_line: Some(11),
+                    command_column: Some(2),
+                    output_start: Some(12),
+                    output_end: Some(14),
+                    command: Some("make".into()),
+                    exit_code: Some(1),
+                    cwd: None,
+                },
+                CommandBlock {
+                    prompt_line: 14,
+                    command_line: Some(15),
+                    command_column: Some(2),
+                    ..CommandBlock::default()
+                },
+            ]
+        );
+        assert!(blocks.blocks()[0].failed());
+
+        // Abandoned prompts don't leave a block behind.
+        blocks.apply(vec![mark(ShellMarkKind::PromptStart, 1)], 0, (16, 0));
+        assert_eq!(blocks.blocks().len(), 2);
+        assert_eq!(blocks.blocks()[1].prompt_line, 16);
+    }
+
+    #[test]
+    fn test_sync_scrollback() {
+        let mut blocks = CommandBlocks::default();
+        // The history fills up partway: 10 line feeds move the cursor down 5 lines and push 4
+        // lines into the history, so the last one is dropped.
+        blocks.sync_scrollback(10, 4, 5, true);
+        assert_eq!(blocks.lines_dropped(), 1);
+        blocks.sync_scrollback(3, 4, 5, true);
+        assert_eq!(blocks.lines_dropped(), 4);
+        // Nothing drops while there's room left in the history.
+        blocks.sync_scrollback(3, 2, 5, false);
+        assert_eq!(blocks.lines_dropped(), 4);
+
+        blocks.apply(
+            vec![
+                mark(ShellMarkKind::PromptStart, 0),
+                mark(ShellMarkKind::CommandStart, 0),
+                mark(ShellMarkKind::CommandExecuted, 1),
+                mark(ShellMarkKind::CommandFinished { exit_code: Some(0) }, 2),
+                mark(ShellMarkKind::PromptStart, 0),
+            ],
+            0,
+            (4, 0),
+        );
+        blocks.remove_scrolled_out();
+        assert_eq!(blocks.blocks().len(), 1);
+        assert_eq!(blocks.blocks()[0].prompt_line, 4);
+    }
+}
@@ -2,8 +2,13 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod filtered_pty;
+mod port_detection;
 mod pty_info;
+mod scrollback;
+mod shell_integration;
 mod terminal_hyperlinks;
+mod terminal_images;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -40,13 +45,18 @@ use mappings::mouse::{
 };
 
 use collections::{HashMap, VecDeque};
+use filtered_pty::FilteredPty;
 use futures::StreamExt;
+use parking_lot::Mutex;
+use port_detection::LocalPortScanner;
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{CommandBlocks, ShellIntegrationScanner};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, SpawnInTerminal};
 use terminal_hyperlinks::RegexSearches;
+use terminal_images::TerminalImages;
 use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use urlencoding;
@@ -74,6 +84,9 @@ use gpui::{
 
 use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
+pub use shell_integration::CommandBlock;
+pub use terminal_images::{ImageCell, TerminalImage};
+
 actions!(
     terminal,
     [
@@ -107,6 +120,14 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls to the previous shell prompt.
+        ScrollToPreviousPrompt,
+        /// Scrolls to the next shell prompt.
+        ScrollToNextPrompt,
+        /// Copies the output of the last finished command to the clipboard.
+        CopyLastCommandOutput,
+        /// Runs the last finished command again.
+        RerunLastCommand,
     ]
 );
 
@@ -140,6 +161,8 @@ pub enum Event {
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
     Open(MaybeNavigationTarget),
+    /// The output contained a `localhost:PORT` address with a port that wasn't seen before.
+    LocalhostPortDetected(u16),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -387,6 +410,10 @@ impl TerminalBuilder {
             term,
             term_config: config,
             title_override: None,
+            shell_integration: Arc::default(),
+            command_blocks: CommandBlocks::default(),
+            images: Arc::default(),
+            local_ports: Arc::default(),
             events: VecDeque::with_capacity(10),
             last_content: Default::default(),
             last_mouse: None,
@@ -593,12 +620,20 @@ impl TerminalBuilder {
             let term = Arc::new(FairMutex::new(term));
 
             let pty_info = PtyProcessInfo::new(&pty);
+            let shell_integration = Arc::new(Mutex::new(ShellIntegrationScanner::default()));
+            let images = Arc::new(Mutex::new(TerminalImages::default()));
+            let local_ports = Arc::new(Mutex::new(LocalPortScanner::default()));
 
             //And connect them together
             let event_loop = EventLoop::new(
                 term.clone(),
                 ZedListener(events_tx),
-                pty,
+                FilteredPty::new(
+                    pty,
+                    shell_integration.clone(),
+                    images.clone(),
+                    local_ports.clone(),
+                ),
                 pty_options.drain_on_exit,
                 false,
             )
@@ -618,6 +653,10 @@ impl TerminalBuilder {
                 term,
                 term_config: config,
                 title_override: terminal_title_override,
+                shell_integration,
+                command_blocks: CommandBlocks::default(),
+                images,
+                local_ports,
                 events: VecDeque::with_capacity(10), //Should never get this high.
                 last_content: Default::default(),
                 last_mouse: None,
@@ -805,6 +844,7 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    pub history_size: usize,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -831,6 +871,7 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            history_size: 0,
         }
     }
 }
@@ -854,6 +895,10 @@ pub struct Terminal {
     completion_tx: Option<Sender<Option<ExitStatus>>>,
     term: Arc<FairMutex<Term<ZedListener>>>,
     term_config: Config,
+    shell_integration: Arc<Mutex<ShellIntegrationScanner>>,
+    command_blocks: CommandBlocks,
+    images: Arc<Mutex<TerminalImages>>,
+    local_ports: Arc<Mutex<LocalPortScanner>>,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
     last_mouse: Option<(AlacPoint, AlacDirection)>,
@@ -987,7 +1032,9 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                self.update_command_blocks();
                 cx.emit(Event::Wakeup);
+                self.emit_detected_ports(cx);
 
                 if let TerminalType::Pty { info, .. } = &self.terminal_type {
                     info.emit_title_changed_if_changed(cx);
@@ -1046,6 +1093,7 @@ impl Terminal {
             }
             InternalEvent::Clear => {
                 trace!("Clearing");
+                self.command_blocks.clear();
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1318,14 +1366,33 @@ impl Terminal {
             prev_byte = byte;
         }
 
+        let mut filtered = Vec::with_capacity(converted.len());
+        {
+            let mut images = self.images.lock();
+            images.set_window_size(self.last_content.terminal_bounds.into());
+            images.filter(&converted, &mut filtered);
+            // There is no process to reply to.
+            images.take_responses();
+        }
+
+        self.shell_integration.lock().scan(&filtered);
+        self.local_ports.lock().scan(&filtered);
         let mut processor = alacritty_terminal::vte::ansi::Processor::<
             alacritty_terminal::vte::ansi::StdSyncHandler,
         >::new();
         {
             let mut term = self.term.lock();
-            processor.advance(&mut *term, &converted);
+            processor.advance(&mut *term, &filtered);
         }
+        self.update_command_blocks();
         cx.emit(Event::Wakeup);
+        self.emit_detected_ports(cx);
+    }
+
+    fn emit_detected_ports(&self, cx: &mut Context<Self>) {
+        for port in self.local_ports.lock().take_ports() {
+            cx.emit(Event::LocalhostPortDetected(port));
+        }
     }
 
     pub fn total_lines(&self) -> usize {
@@ -1437,6 +1504,200 @@ impl Terminal {
         self.last_content.scrolled_to_bottom
     }
 
+    /// The terminal's contents as text with SGR styling, for [`Self::restore_scrollback`].
+    /// Only the newest lines that fit in `max_len` bytes are kept.
+    pub fn scrollback(&self, max_len: usize) -> String {
+        scrollback::serialize_grid(&self.term.lock_unfair(), max_len)
+    }
+
+    /// Writes scrollback saved with [`Self::scrollback`] above everything the shell printed
+    /// so far, dimmed to set it apart from the new session.
+    pub fn restore_scrollback(&mut self, scrollback: &str, cx: &mut Context<Self>) {
+        let mut processor = alacritty_terminal::vte::ansi::Processor::<
+            alacritty_terminal::vte::ansi::StdSyncHandler,
+        >::new();
+        {
+            let mut term = self.term.lock();
+            let new_output = scrollback::serialize_grid(&term, usize::MAX);
+            let mut bytes = String::from("\x1b[H\x1b[2J\x1b[3J");
+            bytes.push_str(&scrollback::dim(scrollback));
+            bytes.push_str("\r\n");
+            bytes.push_str(&new_output);
+            processor.advance(&mut *term, bytes.as_bytes());
+        }
+        self.command_blocks.clear();
+        cx.emit(Event::Wakeup);
+    }
+
+    /// The inline image shown by [`ImageCell`]s with this id, unless it has been deleted.
+    pub fn image(&self, id: u32) -> Option<Arc<TerminalImage>> {
+        self.images.lock().image(id)
+    }
+
+    /// The commands run in this terminal, oldest first, as far as the shell reported them
+    /// with semantic prompt marks.
+    pub fn command_blocks(&self) -> &[CommandBlock] {
+        self.command_blocks.blocks()
+    }
+
+    /// Converts a line of a [`CommandBlock`] into a line of the grid, as of the last sync.
+    pub fn grid_line(&self, line: usize) -> Line {
+        self.block_line_to_grid(line, self.last_content.history_size)
+    }
+
+    fn block_line_to_grid(&self, line: usize, history_size: usize) -> Line {
+        Line(line as i32 - (self.command_blocks.lines_dropped() + history_size) as i32)
+    }
+
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let viewport_top = self.viewport_top_line();
+        if let Some(block) = self
+            .command_blocks()
+            .iter()
+            .rev()
+            .find(|block| block.prompt_line < viewport_top)
+        {
+            self.scroll_line_to_top(block.prompt_line);
+        }
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        let viewport_top = self.viewport_top_line();
+        match self
+            .command_blocks()
+            .iter()
+            .find(|block| block.prompt_line > viewport_top)
+        {
+            Some(block) => self.scroll_line_to_top(block.prompt_line),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    fn viewport_top_line(&self) -> usize {
+        let term = self.term.lock_unfair();
+        (self.command_blocks.lines_dropped() + term.history_size())
+            .saturating_sub(term.grid().display_offset())
+    }
+
+    fn scroll_line_to_top(&mut self, line: usize) {
+        let term = self.term.lock_unfair();
+        let display_offset = (self.command_blocks.lines_dropped() + term.history_size())
+            .saturating_sub(line)
+            .min(term.history_size());
+        let delta = display_offset as i32 - term.grid().display_offset() as i32;
+        drop(term);
+        self.events
+            .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+    }
+
+    /// The text a finished command printed, as far as it is still in the scrollback.
+    pub fn command_output(&self, block: &CommandBlock) -> Option<String> {
+        let (start, end) = (block.output_start?, block.output_end?);
+        let term = self.term.lock_unfair();
+        let history_size = term.history_size();
+        let start = self
+            .block_line_to_grid(start, history_size)
+            .max(term.topmost_line());
+        let end = (self.block_line_to_grid(end, history_size) - 1).min(term.bottommost_line());
+        if start > end {
+            return Some(String::new());
+        }
+        let output = term.bounds_to_string(
+            AlacPoint::new(start, Column(0)),
+            AlacPoint::new(end, term.last_column()),
+        );
+        Some(output.trim_end().to_string())
+    }
+
+    pub fn last_command_output(&self) -> Option<String> {
+        let block = self
+            .command_blocks()
+            .iter()
+            .rev()
+            .find(|block| block.is_finished())?;
+        self.command_output(block)
+    }
+
+    /// The command line of a block, either as reported by the shell or as shown after the prompt.
+    pub fn command_text(&self, block: &CommandBlock) -> Option<String> {
+        if let Some(command) = &block.command {
+            return Some(command.clone());
+        }
+        let (line, column) = (block.command_line?, block.command_column?);
+        let last_line = block.output_start.map_or(line, |output_start| {
+            output_start.saturating_sub(1).max(line)
+        });
+        let term = self.term.lock_unfair();
+        let history_size = term.history_size();
+        let start = self.block_line_to_grid(line, history_size);
+        let end = self
+            .block_line_to_grid(last_line, history_size)
+            .min(term.bottommost_line());
+        if start < term.topmost_line() || start > end {
+            return None;
+        }
+        let command = term.bounds_to_string(
+            AlacPoint::new(start, Column(column)),
+            AlacPoint::new(end, term.last_column()),
+        );
+        let command = command.trim();
+        (!command.is_empty()).then(|| command.to_string())
+    }
+
+    pub fn rerun_last_command(&mut self) {
+        let Some(command) = self
+            .command_blocks()
+            .iter()
+            .rev()
+            .filter(|block| block.is_finished())
+            .find_map(|block| self.command_text(block))
+        else {
+            return;
+        };
+        self.input(format!("{command}\r").into_bytes());
+    }
+
+    /// Places the marks the shell wrote since the last update, now that the terminal has
+    /// parsed the output around them.
+    fn update_command_blocks(&mut self) {
+        let ((marks, trailing_line_feeds), line_feeds) = {
+            let mut shell_integration = self.shell_integration.lock();
+            (
+                shell_integration.take_marks(),
+                shell_integration.take_unsynced_line_feeds(),
+            )
+        };
+        let (history_size, cursor_line, cursor_column, alt_screen) = {
+            let term = self.term.lock_unfair();
+            let point = term.grid().cursor.point;
+            (
+                term.history_size(),
+                point.line.0.max(0) as usize,
+                point.column.0,
+                term.mode().contains(TermMode::ALT_SCREEN),
+            )
+        };
+        // The alternate screen has no scrollback, so nothing drops off while it's shown.
+        if !alt_screen {
+            let history_full = history_size >= self.term_config.scrolling_history;
+            self.command_blocks.sync_scrollback(
+                line_feeds,
+                history_size,
+                cursor_line,
+                history_full,
+            );
+        }
+        if !marks.is_empty() {
+            let cursor = (
+                self.command_blocks.lines_dropped() + history_size + cursor_line,
+                cursor_column,
+            );
+            self.command_blocks
+                .apply(marks, trailing_line_feeds, cursor);
+        }
+        self.command_blocks.remove_scrolled_out();
+    }
+
     ///Resize the terminal and the PTY.
     pub fn set_size(&mut self, new_bounds: TerminalBounds) {
         if self.last_content.terminal_bounds != new_bounds {
@@ -1659,6 +1920,7 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            history_size: term.history_size(),
         }
     }
 
@@ -3207,6 +3469,218 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_command_blocks_from_semantic_prompts(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                None,
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\x1b]133;A\x07$ \x1b]133;B\x07", cx);
+            terminal.write_output(
+                b"echo hi\r\n\x1b]133;C\x07hi\nthere\n\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07",
+                cx,
+            );
+            terminal.write_output(
+                b"false\r\n\x1b]133;C\x07\x1b]133;D;1\x07\x1b]133;A\x07$ \x1b]133;B\x07",
+                cx,
+            );
+        });
+
+        terminal.update(cx, |terminal, _| {
+            let blocks = terminal.command_blocks().to_vec();
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| (block.prompt_line, block.output_start, block.output_end))
+                    .collect::<Vec<_>>(),
+                [
+                    (0, Some(1), Some(3)),
+                    (3, Some(4), Some(4)),
+                    (4, None, None)
+                ]
+            );
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| block.exit_code)
+                    .collect::<Vec<_>>(),
+                [Some(0), Some(1), None]
+            );
+            assert_eq!(
+                terminal.command_output(&blocks[0]).as_deref(),
+                Some("hi\nthere")
+            );
+            assert_eq!(
+                terminal.command_text(&blocks[0]).as_deref(),
+                Some("echo hi")
+            );
+            assert_eq!(terminal.command_text(&blocks[1]).as_deref(), Some("false"));
+            assert_eq!(terminal.last_command_output().as_deref(), Some(""));
+
+            terminal.rerun_last_command();
+            assert_eq!(terminal.take_input_log(), [b"false\r".to_vec()]);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_command_blocks_with_full_scrollback(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(4),
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+
+        let mut output =
+            b"\x1b]133;A\x07$ \x1b]133;B\x07echo hi\r\n\x1b]133;C\x07hi\n\x1b]133;D;0\x07".to_vec();
+        output.extend_from_slice(b"\x1b]133;A\x07$ \x1b]133;B\x07seq 20\r\n\x1b]133;C\x07");
+        for i in 1..=20 {
+            output.extend_from_slice(format!("{i}\n").as_bytes());
+        }
+        output.extend_from_slice(b"\x1b]133;D;0\x07\x1b]133;A\x07$ \x1b]133;B\x07true");
+        terminal.update(cx, |terminal, cx| terminal.write_output(&output, cx));
+
+        terminal.update(cx, |terminal, _| {
+            // 24 lines on a 6 line screen with 4 lines of history leaves 14 dropped lines, which
+            // take the first block with them.
+            let blocks = terminal.command_blocks().to_vec();
+            assert_eq!(
+                blocks
+                    .iter()
+                    .map(|block| (block.prompt_line, block.output_start, block.output_end))
+                    .collect::<Vec<_>>(),
+                [(2, Some(3), Some(23)), (23, None, None)]
+            );
+            let expected_output = (12..=20).map(|i| i.to_string()).collect::<Vec<_>>();
+            assert_eq!(
+                terminal.command_output(&blocks[0]),
+                Some(expected_output.join("\n"))
+            );
+            assert_eq!(terminal.command_text(&blocks[1]).as_deref(), Some("true"));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_scrollback_round_trip(cx: &mut TestAppContext) {
+        let new_terminal = |cx: &mut TestAppContext| {
+            cx.new(|cx| {
+                TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                    cx.background_executor(),
+                    PathStyle::local(),
+                )
+                .unwrap()
+                .subscribe(cx)
+            })
+        };
+        let old_terminal = new_terminal(cx);
+        let restored_terminal = new_terminal(cx);
+
+        let scrollback = old_terminal.update(cx, |terminal, cx| {
+            terminal.write_output(
+                b"plain\r\n\x1b[1;31mbold red\x1b[0m and \x1b[48;5;100mbg\x1b[0m\r\n$ ",
+                cx,
+            );
+            assert_eq!(terminal.scrollback(20), "$ ");
+            terminal.scrollback(usize::MAX)
+        });
+        assert_eq!(
+            scrollback,
+            "plain\r\n\x1b[0;1;31mbold red\x1b[0m and \x1b[0;48;5;100mbg\x1b[0m\r\n$ "
+        );
+
+        restored_terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"% ", cx);
+            terminal.restore_scrollback(&scrollback, cx);
+
+            let term = terminal.term.lock();
+            let grid = term.grid();
+            let line_text = |line: i32| {
+                let text = (0..term.columns())
+                    .map(|column| grid[Line(line)][Column(column)].c)
+                    .collect::<String>();
+                text.trim_end().to_string()
+            };
+            assert_eq!(
+                (0..4).map(line_text).collect::<Vec<_>>(),
+                ["plain", "bold red and bg", "$", "%"]
+            );
+            assert!(grid[Line(0)][Column(0)].flags.contains(Flags::DIM));
+            assert!(
+                grid[Line(1)][Column(0)]
+                    .flags
+                    .contains(Flags::DIM | Flags::BOLD)
+            );
+            assert!(!grid[Line(3)][Column(0)].flags.contains(Flags::DIM));
+            assert_eq!(grid.cursor.point, AlacPoint::new(Line(3), Column(2)));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_inline_images_become_placeholder_cells(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                None,
+                0,
+                cx.background_executor(),
+                PathStyle::local(),
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+
+        // A 10x12 sixel image takes up 2x3 of the 5x5 cells of the test terminal.
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"$ \x1bPq#1;2;100;0;0!10~-!10~\x1b\\done", cx);
+        });
+
+        terminal.update(cx, |terminal, _| {
+            let image = terminal.image(1).unwrap();
+            assert_eq!((image.width, image.height), (10, 12));
+            assert_eq!((image.columns, image.rows), (2, 3));
+
+            let term = terminal.term.lock();
+            let grid = term.grid();
+            for row in 0..3 {
+                for column in 0..2 {
+                    assert_eq!(
+                        ImageCell::from_cell(&grid[Line(row)][Column(column + 2)]),
+                        Some(ImageCell {
+                            image_id: 1,
+                            row: row as usize,
+                            column,
+                        })
+                    );
+                }
+                assert_eq!(ImageCell::from_cell(&grid[Line(row)][Column(4)]), None);
+            }
+            assert_eq!(grid[Line(0)][Column(0)].c, '$');
+            // The cursor moves below the image, at the column it started at.
+            assert_eq!(grid[Line(3)][Column(2)].c, 'd');
+        });
+    }
+
     #[gpui::test]
     async fn test_hyperlink_ctrl_click_same_position(cx: &mut TestAppContext) {
         let terminal = init_ctrl_click_hyperlink_test(cx, b"Visit https://zed.dev/ for more\r\n");
@@ -0,0 +1,1128 @@
+//! Inline images drawn with sixel (`ESC P q ... ESC \`) and the kitty graphics protocol
+//! (`ESC _ G ... ESC \`).
+//!
+//! Alacritty ignores both, so [`TerminalImages`] takes the image sequences out of the output
+//! before Alacritty parses it. Every decoded image is replaced by a rectangle of
+//! [`IMAGE_PLACEHOLDER`] cells whose colors encode the image and the part of it the cell shows.
+//! The placeholders are ordinary grid content: they scroll into the scrollback, get overwritten
+//! and cleared like text, and the terminal element paints the images on top of them.
+
+use std::{
+    collections::BTreeMap,
+    io::{Read as _, Write as _},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::WindowSize,
+    term::cell::Cell,
+    vte::ansi::{Color, Rgb},
+};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::{HashMap, VecDeque};
+use gpui::{Pixels, RenderImage, Size, px, size};
+use image::{Frame, ImageFormat, RgbaImage};
+
+/// The character written to the cells covered by an image. It is the one kitty uses for its
+/// Unicode placeholders, from a private use plane, so it never clashes with real text.
+pub const IMAGE_PLACEHOLDER: char = '\u{10EEEE}';
+
+/// The longest image sequence that is decoded. Longer ones are dropped.
+const MAX_SEQUENCE_LEN: usize = 32 * 1024 * 1024;
+/// The memory images may take up before the oldest ones are evicted, the same quota kitty has.
+const MAX_STORED_BYTES: usize = 320 * 1024 * 1024;
+/// Sixel images are decoded into memory, so their dimensions are capped.
+const MAX_SIXEL_DIMENSION: usize = 10_000;
+/// Image ids are stored in the 24 bits of a truecolor foreground.
+const MAX_IMAGE_ID: u32 = 0xFF_FFFF;
+/// Rows and columns each get 12 bits of a truecolor background.
+const MAX_IMAGE_CELLS: usize = 0xFFF;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image placed in the terminal.
+pub struct TerminalImage {
+    pub image: Arc<RenderImage>,
+    /// The size of the image in pixels.
+    pub width: u32,
+    pub height: u32,
+    /// The cells reserved for the image.
+    pub columns: usize,
+    pub rows: usize,
+    /// Whether the image is scaled up to the reserved cells, rather than only scaled down when
+    /// it doesn't fit.
+    pub fit_to_cells: bool,
+    /// The kitty image this is a placement of.
+    kitty_id: Option<u32>,
+}
+
+impl TerminalImage {
+    /// The size to paint the image at, for the given cell size.
+    pub fn paint_size(&self, cell_width: Pixels, line_height: Pixels) -> Size<Pixels> {
+        let width = f32::from(cell_width) * self.columns as f32;
+        let height = f32::from(line_height) * self.rows as f32;
+        let mut scale = (width / self.width as f32).min(height / self.height as f32);
+        if !self.fit_to_cells {
+            scale = scale.min(1.);
+        }
+        size(
+            px(self.width as f32 * scale),
+            px(self.height as f32 * scale),
+        )
+    }
+
+    fn byte_len(&self) -> usize {
+        self.width as usize * self.height as usize * 4
+    }
+}
+
+/// A placeholder cell, showing one cell of an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageCell {
+    pub image_id: u32,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl ImageCell {
+    pub fn from_cell(cell: &Cell) -> Option<Self> {
+        if cell.c != IMAGE_PLACEHOLDER {
+            return None;
+        }
+        let (Color::Spec(id), Color::Spec(position)) = (cell.fg, cell.bg) else {
+            return None;
+        };
+        Some(Self {
+            image_id: u32::from_be_bytes([0, id.r, id.g, id.b]),
+            row: ((position.r as usize) << 4) | ((position.g as usize) >> 4),
+            column: (((position.g as usize) & 0xF) << 8) | position.b as usize,
+        })
+    }
+
+    fn background(row: usize, column: usize) -> Rgb {
+        Rgb {
+            r: (row >> 4) as u8,
+            g: (((row & 0xF) << 4) | (column >> 8)) as u8,
+            b: column as u8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum FilterState {
+    #[default]
+    Ground,
+    Escape,
+    DcsParams,
+    Apc,
+    Sixel,
+    SixelEscape,
+    Kitty,
+    KittyEscape,
+}
+
+/// Where the cursor ends up after an image is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// At the start column, on the line below the image, like sixel scrolling mode.
+    BelowImage,
+    /// After the last cell of the image's bottom row, as kitty does by default.
+    AfterImage,
+    /// Back where the image was placed.
+    Unchanged,
+}
+
+/// Takes image sequences out of the shell's output and keeps the decoded images.
+#[derive(Default)]
+pub(crate) struct TerminalImages {
+    state: FilterState,
+    sequence: Vec<u8>,
+    sequence_overflowed: bool,
+    dcs_params: Vec<u8>,
+    window_size: Option<WindowSize>,
+    placements: BTreeMap<u32, Arc<TerminalImage>>,
+    placement_bytes: usize,
+    next_placement_id: u32,
+    kitty_images: HashMap<u32, KittyImage>,
+    kitty_image_order: VecDeque<u32>,
+    kitty_image_bytes: usize,
+    kitty_image_numbers: HashMap<u32, u32>,
+    next_kitty_image_id: u32,
+    kitty_transfer: Option<(KittyCommand, Vec<u8>)>,
+    responses: Vec<u8>,
+}
+
+impl TerminalImages {
+    pub fn set_window_size(&mut self, window_size: WindowSize) {
+        self.window_size = Some(window_size);
+    }
+
+    pub fn image(&self, id: u32) -> Option<Arc<TerminalImage>> {
+        self.placements.get(&id).cloned()
+    }
+
+    /// Replies to kitty graphics commands, to be written back to the shell.
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Copies `bytes` to `output`, replacing image sequences with placeholder cells.
+    pub fn filter(&mut self, bytes: &[u8], output: &mut Vec<u8>) {
+        for &byte in bytes {
+            self.filter_byte(byte, output);
+        }
+    }
+
+    fn filter_byte(&mut self, byte: u8, output: &mut Vec<u8>) {
+        match self.state {
+            FilterState::Ground => {
+                if byte == 0x1b {
+                    self.state = FilterState::Escape;
+                } else {
+                    output.push(byte);
+                }
+            }
+            FilterState::Escape => match byte {
+                b'P' => {
+                    self.dcs_params.clear();
+                    self.state = FilterState::DcsParams;
+                }
+                b'_' => self.state = FilterState::Apc,
+                0x1b => output.push(0x1b),
+                _ => {
+                    output.extend_from_slice(&[0x1b, byte]);
+                    self.state = FilterState::Ground;
+                }
+            },
+            FilterState::DcsParams => match byte {
+                b'0'..=b'9' | b';' if self.dcs_params.len() < 32 => self.dcs_params.push(byte),
+                b'q' => self.start_sequence(FilterState::Sixel),
+                // Any other device control string is left to Alacritty.
+                _ => {
+                    output.extend_from_slice(b"\x1bP");
+                    output.extend_from_slice(&self.dcs_params);
+                    self.state = FilterState::Ground;
+                    self.filter_byte(byte, output);
+                }
+            },
+            FilterState::Apc => {
+                if byte == b'G' {
+                    self.start_sequence(FilterState::Kitty);
+                } else {
+                    output.extend_from_slice(b"\x1b_");
+                    self.state = FilterState::Ground;
+                    self.filter_byte(byte, output);
+                }
+            }
+            FilterState::Sixel | FilterState::Kitty => match byte {
+                0x1b => {
+                    self.state = if self.state == FilterState::Sixel {
+                        FilterState::SixelEscape
+                    } else {
+                        FilterState::KittyEscape
+                    };
+                }
+                // CAN and SUB abort the sequence.
+                0x18 | 0x1a => self.state = FilterState::Ground,
+                _ if self.sequence.len() < MAX_SEQUENCE_LEN => self.sequence.push(byte),
+                _ => self.sequence_overflowed = true,
+            },
+            FilterState::SixelEscape | FilterState::KittyEscape => {
+                let state = self.state;
+                self.state = FilterState::Ground;
+                if byte != b'\\' {
+                    // A new escape sequence cuts the image short.
+                    self.filter_byte(0x1b, output);
+                    self.filter_byte(byte, output);
+                } else if self.sequence_overflowed {
+                    log::warn!("Dropping terminal image larger than {MAX_SEQUENCE_LEN} bytes");
+                } else {
+                    let sequence = std::mem::take(&mut self.sequence);
+                    if state == FilterState::SixelEscape {
+                        self.place_sixel(&sequence, output);
+                    } else {
+                        self.handle_kitty_command(&sequence, output);
+                    }
+                }
+                self.sequence.clear();
+            }
+        }
+    }
+
+    fn start_sequence(&mut self, state: FilterState) {
+        self.sequence.clear();
+        self.sequence_overflowed = false;
+        self.state = state;
+    }
+
+    fn cell_size(&self) -> (u32, u32) {
+        self.window_size.map_or((8, 16), |size| {
+            (
+                size.cell_width.max(1) as u32,
+                size.cell_height.max(1) as u32,
+            )
+        })
+    }
+
+    /// The columns and lines images can take up at most.
+    fn screen_size(&self) -> (usize, usize) {
+        let (columns, lines) = self.window_size.map_or((80, 24), |size| {
+            (size.num_cols as usize, size.num_lines as usize)
+        });
+        (
+            columns.clamp(1, MAX_IMAGE_CELLS),
+            lines.clamp(1, MAX_IMAGE_CELLS),
+        )
+    }
+
+    fn place_sixel(&mut self, data: &[u8], output: &mut Vec<u8>) {
+        let Some(image) = decode_sixel(data) else {
+            log::debug!("Ignoring empty sixel image");
+            return;
+        };
+        let (cell_width, cell_height) = self.cell_size();
+        let columns = image.width().div_ceil(cell_width) as usize;
+        let rows = image.height().div_ceil(cell_height) as usize;
+        self.place(
+            render_image(image),
+            columns,
+            rows,
+            false,
+            None,
+            CursorMovement::BelowImage,
+            output,
+        );
+    }
+
+    fn place(
+        &mut self,
+        (image, width, height): (Arc<RenderImage>, u32, u32),
+        columns: usize,
+        rows: usize,
+        fit_to_cells: bool,
+        kitty_id: Option<u32>,
+        cursor_movement: CursorMovement,
+        output: &mut Vec<u8>,
+    ) {
+        let (screen_columns, screen_lines) = self.screen_size();
+        let columns = columns.clamp(1, screen_columns);
+        let rows = rows.clamp(1, screen_lines);
+
+        self.next_placement_id = self.next_placement_id % MAX_IMAGE_ID + 1;
+        let id = self.next_placement_id;
+        let placement = TerminalImage {
+            image,
+            width,
+            height,
+            columns,
+            rows,
+            fit_to_cells,
+            kitty_id,
+        };
+        self.placement_bytes += placement.byte_len();
+        if let Some(replaced) = self.placements.insert(id, Arc::new(placement)) {
+            self.placement_bytes -= replaced.byte_len();
+        }
+        while self.placement_bytes > MAX_STORED_BYTES
+            && let Some((_, evicted)) = self.placements.pop_first()
+        {
+            self.placement_bytes -= evicted.byte_len();
+        }
+
+        write_placeholders(id, columns, rows, cursor_movement, output);
+    }
+
+    fn handle_kitty_command(&mut self, sequence: &[u8], output: &mut Vec<u8>) {
+        let (control, payload) = match sequence.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&sequence[..ix], &sequence[ix + 1..]),
+            None => (sequence, &[][..]),
+        };
+        let command = KittyCommand::parse(control);
+
+        // Only the first chunk of a transmission carries the command, the others just say
+        // whether more data follows.
+        let (command, payload) = match self.kitty_transfer.take() {
+            Some((first, mut data)) => {
+                data.extend_from_slice(payload);
+                if command.more {
+                    self.kitty_transfer = Some((first, data));
+                    return;
+                }
+                (first, data)
+            }
+            None if command.more => {
+                self.kitty_transfer = Some((command, payload.to_vec()));
+                return;
+            }
+            None => (command, payload.to_vec()),
+        };
+
+        let result = self.run_kitty_command(&command, &payload, output);
+        self.respond_to_kitty_command(&command, result);
+    }
+
+    fn run_kitty_command(
+        &mut self,
+        command: &KittyCommand,
+        payload: &[u8],
+        output: &mut Vec<u8>,
+    ) -> Result<u32, KittyError> {
+        match command.action {
+            b't' | b'T' | b'q' => {
+                let image = command.decode_image(payload)?;
+                if command.action == b'q' {
+                    return Ok(command.id);
+                }
+                let id = self.kitty_image_id(command);
+                self.store_kitty_image(id, image);
+                if command.action == b'T' {
+                    self.place_kitty_image(id, command, output)?;
+                }
+                Ok(id)
+            }
+            b'p' => {
+                let id = match command.id {
+                    0 => self
+                        .kitty_image_numbers
+                        .get(&command.number)
+                        .copied()
+                        .ok_or(KittyError::NotFound)?,
+                    id => id,
+                };
+                self.place_kitty_image(id, command, output)?;
+                Ok(id)
+            }
+            b'd' => {
+                self.delete_kitty_images(command);
+                Ok(command.id)
+            }
+            _ => Err(KittyError::Invalid("unsupported action")),
+        }
+    }
+
+    fn kitty_image_id(&mut self, command: &KittyCommand) -> u32 {
+        if command.id != 0 {
+            return command.id;
+        }
+        // Images transmitted with just a number get an id picked by the terminal.
+        self.next_kitty_image_id = self.next_kitty_image_id.wrapping_sub(1).max(1);
+        let id = self.next_kitty_image_id;
+        if command.number != 0 {
+            self.kitty_image_numbers.insert(command.number, id);
+        }
+        id
+    }
+
+    fn store_kitty_image(&mut self, id: u32, image: RgbaImage) {
+        let image = KittyImage::new(image);
+        self.kitty_image_bytes += image.byte_len();
+        if let Some(replaced) = self.kitty_images.insert(id, image) {
+            self.kitty_image_bytes -= replaced.byte_len();
+            self.kitty_image_order.retain(|stored| *stored != id);
+        }
+        self.kitty_image_order.push_back(id);
+        while self.kitty_image_bytes > MAX_STORED_BYTES
+            && let Some(evicted) = self.kitty_image_order.pop_front()
+        {
+            if let Some(evicted) = self.kitty_images.remove(&evicted) {
+                self.kitty_image_bytes -= evicted.byte_len();
+            }
+        }
+    }
+
+    fn place_kitty_image(
+        &mut self,
+        id: u32,
+        command: &KittyCommand,
+        output: &mut Vec<u8>,
+    ) -> Result<(), KittyError> {
+        let image = self.kitty_images.get(&id).ok_or(KittyError::NotFound)?;
+        let render_image = (image.image.clone(), image.width, image.height);
+        let (cell_width, cell_height) = self.cell_size();
+        let (cell_width, cell_height) = (cell_width as u64, cell_height as u64);
+        let (width, height) = (image.width as u64, image.height as u64);
+        // The requested size comes from the program, so it's kept within the screen before
+        // the other side is derived from it.
+        let (screen_columns, screen_lines) = self.screen_size();
+        let requested_columns = (command.columns as u64).min(screen_columns as u64);
+        let requested_rows = (command.rows as u64).min(screen_lines as u64);
+        let (columns, rows) = match (requested_columns, requested_rows) {
+            (0, 0) => (width.div_ceil(cell_width), height.div_ceil(cell_height)),
+            (0, rows) => (
+                (rows.saturating_mul(cell_height).saturating_mul(width) / height.max(1))
+                    .div_ceil(cell_width),
+                rows,
+            ),
+            (columns, 0) => (
+                columns,
+                (columns.saturating_mul(cell_width).saturating_mul(height) / width.max(1))
+                    .div_ceil(cell_height),
+            ),
+            size => size,
+        };
+        let columns = columns.min(screen_columns as u64) as usize;
+        let rows = rows.min(screen_lines as u64) as usize;
+        let fit_to_cells = command.columns != 0 || command.rows != 0;
+        let cursor_movement = if command.cursor_movement == 1 {
+            CursorMovement::Unchanged
+        } else {
+            CursorMovement::AfterImage
+        };
+        self.place(
+            render_image,
+            columns,
+            rows,
+            fit_to_cells,
+            Some(id),
+            cursor_movement,
+            output,
+        );
+        Ok(())
+    }
+
+    fn delete_kitty_images(&mut self, command: &KittyCommand) {
+        match command.delete.to_ascii_lowercase() {
+            b'a' => {
+                self.placements.clear();
+                self.placement_bytes = 0;
+            }
+            b'i' | b'n' => {
+                let id = if command.delete.eq_ignore_ascii_case(&b'n') {
+                    self.kitty_image_numbers.get(&command.number).copied()
+                } else {
+                    Some(command.id)
+                };
+                let Some(id) = id else {
+                    return;
+                };
+                self.placements.retain(|_, placement| {
+                    let retain = placement.kitty_id != Some(id);
+                    if !retain {
+                        self.placement_bytes -= placement.byte_len();
+                    }
+                    retain
+                });
+                // Upper case deletions free the image data too.
+                if command.delete.is_ascii_uppercase()
+                    && let Some(image) = self.kitty_images.remove(&id)
+                {
+                    self.kitty_image_bytes -= image.byte_len();
+                    self.kitty_image_order.retain(|stored| *stored != id);
+                }
+            }
+            _ => log::debug!(
+                "Ignoring unsupported kitty graphics deletion {:?}",
+                command.delete as char
+            ),
+        }
+    }
+
+    fn respond_to_kitty_command(
+        &mut self,
+        command: &KittyCommand,
+        result: Result<u32, KittyError>,
+    ) {
+        // Commands without an id or number don't get a reply.
+        if (command.id == 0 && command.number == 0) || command.action == b'd' {
+            return;
+        }
+        let message = match &result {
+            Ok(_) if command.quiet >= 1 => return,
+            Err(_) if command.quiet >= 2 => return,
+            Ok(_) => "OK".to_string(),
+            Err(error) => error.to_string(),
+        };
+        let id = result.unwrap_or(command.id);
+        let response = &mut self.responses;
+        response.extend_from_slice(b"\x1b_G");
+        let mut keys = Vec::new();
+        if id != 0 {
+            keys.push(format!("i={id}"));
+        }
+        if command.number != 0 {
+            keys.push(format!("I={}", command.number));
+        }
+        if command.placement != 0 {
+            keys.push(format!("p={}", command.placement));
+        }
+        response.extend_from_slice(keys.join(",").as_bytes());
+        response.push(b';');
+        response.extend_from_slice(message.as_bytes());
+        response.extend_from_slice(b"\x1b\\");
+    }
+}
+
+/// Writes the placeholder cells of an image at the cursor, which is left as `cursor_movement`
+/// says.
+fn write_placeholders(
+    id: u32,
+    columns: usize,
+    rows: usize,
+    cursor_movement: CursorMovement,
+    output: &mut Vec<u8>,
+) {
+    let [_, r, g, b] = id.to_be_bytes();
+    // Scroll first so the image fits below the cursor, and disable auto-wrap so an image
+    // placed near the right edge is cropped instead of wrapping onto the next line.
+    output.extend_from_slice(b"\x1b[?7l");
+    if rows > 1 {
+        output.extend(std::iter::repeat_n(b'\n', rows - 1));
+        write!(output, "\x1b[{}A", rows - 1).ok();
+    }
+    for row in 0..rows {
+        if row > 0 {
+            write!(output, "\x1b[{columns}D\x1b[B").ok();
+        }
+        write!(output, "\x1b[38;2;{r};{g};{b}m").ok();
+        for column in 0..columns {
+            let position = ImageCell::background(row, column);
+            write!(
+                output,
+                "\x1b[48;2;{};{};{}m{IMAGE_PLACEHOLDER}",
+                position.r, position.g, position.b
+            )
+            .ok();
+        }
+    }
+    output.extend_from_slice(b"\x1b[39;49m\x1b[?7h");
+    match cursor_movement {
+        CursorMovement::BelowImage => write!(output, "\x1b[{columns}D\n"),
+        CursorMovement::AfterImage => Ok(()),
+        CursorMovement::Unchanged if rows > 1 => {
+            write!(output, "\x1b[{}A\x1b[{columns}D", rows - 1)
+        }
+        CursorMovement::Unchanged => write!(output, "\x1b[{columns}D"),
+    }
+    .ok();
+}
+
+fn render_image(mut image: RgbaImage) -> (Arc<RenderImage>, u32, u32) {
+    let (width, height) = image.dimensions();
+    // GPUI expects BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    (
+        Arc::new(RenderImage::new(vec![Frame::new(image)])),
+        width,
+        height,
+    )
+}
+
+struct KittyImage {
+    image: Arc<RenderImage>,
+    width: u32,
+    height: u32,
+}
+
+impl KittyImage {
+    fn new(image: RgbaImage) -> Self {
+        let (image, width, height) = render_image(image);
+        Self {
+            image,
+            width,
+            height,
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        self.width as usize * self.height as usize * 4
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum KittyError {
+    #[error("ENOENT:image not found")]
+    NotFound,
+    #[error("EINVAL:{0}")]
+    Invalid(&'static str),
+}
+
+/// The control data of a kitty graphics command. Only the keys that affect how images are
+/// shown in Zed are kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct KittyCommand {
+    action: u8,
+    quiet: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    id: u32,
+    number: u32,
+    placement: u32,
+    columns: u32,
+    rows: u32,
+    cursor_movement: u32,
+    more: bool,
+    delete: u8,
+}
+
+impl Default for KittyCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            quiet: 0,
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            id: 0,
+            number: 0,
+            placement: 0,
+            columns: 0,
+            rows: 0,
+            cursor_movement: 0,
+            more: false,
+            delete: b'a',
+        }
+    }
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self::default();
+        for pair in control.split(|&byte| byte == b',') {
+            let [key, b'=', value @ ..] = pair else {
+                continue;
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(0)
+            };
+            let character = value.first().copied().unwrap_or(0);
+            match key {
+                b'a' => command.action = character,
+                b'q' => command.quiet = number() as u8,
+                b'f' => command.format = number(),
+                b't' => command.medium = character,
+                b'o' => command.compressed = character == b'z',
+                b's' => command.width = number(),
+                b'v' => command.height = number(),
+                b'i' => command.id = number(),
+                b'I' => command.number = number(),
+                b'p' => command.placement = number(),
+                b'c' => command.columns = number(),
+                b'r' => command.rows = number(),
+                b'C' => command.cursor_movement = number(),
+                b'm' => command.more = number() == 1,
+                b'd' => command.delete = character,
+                _ => {}
+            }
+        }
+        command
+    }
+
+    fn decode_image(&self, payload: &[u8]) -> Result<RgbaImage, KittyError> {
+        // Only data sent in the escape sequence itself is accepted. Files and shared memory
+        // named by the output could be anything on this machine, even when the program
+        // printing them runs on another one.
+        if self.medium != b'd' {
+            return Err(KittyError::Invalid("unsupported transmission medium"));
+        }
+        let mut data = BASE64
+            .decode(payload)
+            .map_err(|_| KittyError::Invalid("payload is not valid base64"))?;
+        if self.compressed {
+            let mut decompressed = Vec::new();
+            flate2::read::ZlibDecoder::new(data.as_slice())
+                .take(MAX_SEQUENCE_LEN as u64 * 4)
+                .read_to_end(&mut decompressed)
+                .map_err(|_| KittyError::Invalid("payload is not valid zlib data"))?;
+            data = decompressed;
+        }
+
+        match self.format {
+            100 => image::load_from_memory_with_format(&data, ImageFormat::Png)
+                .map(|image| image.into_rgba8())
+                .map_err(|_| KittyError::Invalid("payload is not a valid PNG")),
+            24 | 32 => {
+                let (width, height) = (self.width as usize, self.height as usize);
+                let bytes_per_pixel = self.format as usize / 8;
+                if width == 0 || height == 0 || data.len() < width * height * bytes_per_pixel {
+                    return Err(KittyError::Invalid("payload is smaller than the image"));
+                }
+                let pixels = if bytes_per_pixel == 4 {
+                    data.truncate(width * height * 4);
+                    data
+                } else {
+                    data.chunks_exact(3)
+                        .take(width * height)
+                        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 0xFF])
+                        .collect()
+                };
+                RgbaImage::from_raw(self.width, self.height, pixels)
+                    .ok_or(KittyError::Invalid("payload is smaller than the image"))
+            }
+            _ => Err(KittyError::Invalid("unsupported format")),
+        }
+    }
+}
+
+/// The 16 colors a VT340 starts with, in percent. The other registers start out black.
+const SIXEL_DEFAULT_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+/// Decodes the data of a sixel sequence, after the `q`. Pixels that aren't drawn are
+/// transparent.
+fn decode_sixel(data: &[u8]) -> Option<RgbaImage> {
+    let mut palette = [[0, 0, 0, 0xFF]; 256];
+    for (color, percent) in palette.iter_mut().zip(SIXEL_DEFAULT_PALETTE) {
+        *color = [
+            percent_to_u8(percent[0]),
+            percent_to_u8(percent[1]),
+            percent_to_u8(percent[2]),
+            0xFF,
+        ];
+    }
+
+    let mut rows: Vec<Vec<[u8; 4]>> = Vec::new();
+    let (mut width, mut height) = (0, 0);
+    let (mut x, mut band) = (0, 0);
+    let mut color = 0;
+    let mut ix = 0;
+
+    let parse_numbers = |ix: &mut usize| {
+        let mut numbers = Vec::new();
+        let mut current: Option<usize> = None;
+        while let Some(&byte) = data.get(*ix) {
+            match byte {
+                b'0'..=b'9' => {
+                    let digit = (byte - b'0') as usize;
+                    current = Some(
+                        current
+                            .unwrap_or(0)
+                            .saturating_mul(10)
+                            .saturating_add(digit),
+                    );
+                }
+                b';' => numbers.push(current.take().unwrap_or(0)),
+                _ => break,
+            }
+            *ix += 1;
+        }
+        numbers.push(current.unwrap_or(0));
+        numbers
+    };
+
+    while let Some(&byte) = data.get(ix) {
+        ix += 1;
+        match byte {
+            b'"' => {
+                // Raster attributes: aspect ratio numerator and denominator, then the size.
+                let numbers = parse_numbers(&mut ix);
+                if let [_, _, raster_width, raster_height, ..] = numbers[..] {
+                    width = width.max(raster_width.min(MAX_SIXEL_DIMENSION));
+                    height = height.max(raster_height.min(MAX_SIXEL_DIMENSION));
+                }
+            }
+            b'#' => {
+                let numbers = parse_numbers(&mut ix);
+                color = numbers[0].min(255);
+                match numbers[..] {
+                    [_, 1, hue, lightness, saturation] => {
+                        palette[color] = hls_to_rgb(hue, lightness, saturation);
+                    }
+                    [_, 2, red, green, blue] => {
+                        palette[color] = [
+                            percent_to_u8(red.min(100) as u8),
+                            percent_to_u8(green.min(100) as u8),
+                            percent_to_u8(blue.min(100) as u8),
+                            0xFF,
+                        ];
+                    }
+                    _ => {}
+                }
+            }
+            b'!' => {
+                let count = parse_numbers(&mut ix)[0].max(1);
+                if let Some(&sixel @ b'?'..=b'~') = data.get(ix) {
+                    ix += 1;
+                    let count = count.min(MAX_SIXEL_DIMENSION.saturating_sub(x));
+                    draw_sixel(&mut rows, x, band, count, sixel, palette[color]);
+                    x += count;
+                }
+            }
+            b'$' => x = 0,
+            b'-' => {
+                x = 0;
+                band += 1;
+                if band * 6 >= MAX_SIXEL_DIMENSION {
+                    break;
+                }
+            }
+            b'?'..=b'~' => {
+                if x < MAX_SIXEL_DIMENSION {
+                    draw_sixel(&mut rows, x, band, 1, byte, palette[color]);
+                    x += 1;
+                }
+            }
+            _ => {}
+        }
+        width = width.max(x);
+    }
+
+    let height = height.max(rows.len());
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut image = RgbaImage::new(width as u32, height as u32);
+    for (y, row) in rows.iter().enumerate().take(height) {
+        for (x, pixel) in row.iter().enumerate().take(width) {
+            image.put_pixel(x as u32, y as u32, image::Rgba(*pixel));
+        }
+    }
+    Some(image)
+}
+
+/// Draws a sixel, a column of six pixels given by the low bits of `sixel - '?'`, `count`
+/// times from `x` on.
+fn draw_sixel(
+    rows: &mut Vec<Vec<[u8; 4]>>,
+    x: usize,
+    band: usize,
+    count: usize,
+    sixel: u8,
+    color: [u8; 4],
+) {
+    let bits = sixel - b'?';
+    for bit in 0..6 {
+        if bits & (1 << bit) == 0 {
+            continue;
+        }
+        let y = band * 6 + bit;
+        if rows.len() <= y {
+            rows.resize_with(y + 1, Vec::new);
+        }
+        let row = &mut rows[y];
+        if row.len() < x + count {
+            row.resize(x + count, [0; 4]);
+        }
+        row[x..x + count].fill(color);
+    }
+}
+
+fn percent_to_u8(percent: u8) -> u8 {
+    ((percent as u32 * 255 + 50) / 100) as u8
+}
+
+/// Converts a sixel HLS color, where a hue of 0° is blue rather than red, to RGB.
+fn hls_to_rgb(hue: usize, lightness: usize, saturation: usize) -> [u8; 4] {
+    let hue = ((hue + 240) % 360) as f32;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let x = chroma * (1. - ((hue / 60.) % 2. - 1.).abs());
+    let (red, green, blue) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let offset = lightness - chroma / 2.;
+    let channel = |value: f32| ((value + offset) * 255.).round().clamp(0., 255.) as u8;
+    [channel(red), channel(green), channel(blue), 0xFF]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_size() -> WindowSize {
+        WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 2,
+            cell_height: 4,
+        }
+    }
+
+    /// The placeholder cells in `output`, as (image id, row, column) in the order written.
+    fn placeholders(output: &[u8]) -> Vec<(u32, usize, usize)> {
+        let output = String::from_utf8_lossy(output);
+        let mut fg = (0, 0, 0);
+        let mut cells = Vec::new();
+        for part in output.split("\x1b[").skip(1) {
+            let Some((params, rest)) = part.split_once('m') else {
+                continue;
+            };
+            let numbers: Vec<u32> = params.split(';').filter_map(|n| n.parse().ok()).collect();
+            match numbers[..] {
+                [38, 2, r, g, b] => fg = (r, g, b),
+                [48, 2, r, g, b] if rest.starts_with(IMAGE_PLACEHOLDER) => {
+                    let cell = Cell {
+                        c: IMAGE_PLACEHOLDER,
+                        fg: Color::Spec(Rgb {
+                            r: fg.0 as u8,
+                            g: fg.1 as u8,
+                            b: fg.2 as u8,
+                        }),
+                        bg: Color::Spec(Rgb {
+                            r: r as u8,
+                            g: g as u8,
+                            b: b as u8,
+                        }),
+                        ..Cell::default()
+                    };
+                    let cell = ImageCell::from_cell(&cell).unwrap();
+                    cells.push((cell.image_id, cell.row, cell.column));
+                }
+                _ => {}
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_decode_sixel() {
+        // A red 3x7 rectangle on a 4x7 raster, with a blue pixel below its top-left corner.
+        let image = decode_sixel(b"\"1;1;4;7#1;2;100;0;0#2;2;0;0;100#1!3~$#2A-#1!3@").unwrap();
+        assert_eq!(image.dimensions(), (4, 7));
+        let red = image::Rgba([255, 0, 0, 255]);
+        let blue = image::Rgba([0, 0, 255, 255]);
+        let transparent = image::Rgba([0, 0, 0, 0]);
+        assert_eq!(*image.get_pixel(0, 0), red);
+        assert_eq!(*image.get_pixel(0, 1), blue);
+        assert_eq!(*image.get_pixel(1, 1), red);
+        assert_eq!(*image.get_pixel(2, 5), red);
+        assert_eq!(*image.get_pixel(0, 6), red);
+        assert_eq!(*image.get_pixel(3, 0), transparent);
+        assert_eq!(*image.get_pixel(1, 5), red);
+
+        // Hue 120 is red in the sixel color wheel.
+        assert_eq!(hls_to_rgb(120, 50, 100), [255, 0, 0, 255]);
+        assert_eq!(hls_to_rgb(0, 50, 100), [0, 0, 255, 255]);
+
+        assert!(decode_sixel(b"#1;2;100;0;0").is_none());
+    }
+
+    #[test]
+    fn test_filter_sixel() {
+        let mut images = TerminalImages::default();
+        images.set_window_size(window_size());
+
+        let mut output = Vec::new();
+        // A 5x6 image covers three columns and two rows of 2x4 cells.
+        images.filter(b"before\x1bPq#0;2;0;100;0!5~\x1b", &mut output);
+        images.filter(b"\\after\x1bP1$r\x1b\\\x1b_Xignored\x1b\\", &mut output);
+
+        let output_text = String::from_utf8_lossy(&output);
+        assert!(output_text.starts_with("before\x1b[?7l\n\x1b[1A"));
+        assert!(output_text.ends_with("\x1b[3D\nafter\x1bP1$r\x1b\\\x1b_Xignored\x1b\\"));
+        assert_eq!(
+            placeholders(&output),
+            [
+                (1, 0, 0),
+                (1, 0, 1),
+                (1, 0, 2),
+                (1, 1, 0),
+                (1, 1, 1),
+                (1, 1, 2)
+            ]
+        );
+
+        let image = images.image(1).unwrap();
+        assert_eq!((image.width, image.height), (5, 6));
+        assert_eq!((image.columns, image.rows), (3, 2));
+        assert_eq!(image.paint_size(px(2.), px(4.)), size(px(5.), px(6.)));
+        assert!(images.take_responses().is_empty());
+    }
+
+    #[test]
+    fn test_filter_kitty_graphics() {
+        let mut images = TerminalImages::default();
+        images.set_window_size(window_size());
+
+        // A 2x2 RGBA image in two chunks, transmitted and displayed in 4x1 cells.
+        let pixels = BASE64.encode([0xFFu8; 16]);
+        let (first, second) = pixels.split_at(12);
+        let mut output = Vec::new();
+        images.filter(
+            format!("\x1b_Ga=T,f=32,s=2,v=2,i=7,c=4,r=1,m=1;{first}\x1b\\").as_bytes(),
+            &mut output,
+        );
+        assert!(output.is_empty());
+        images.filter(format!("\x1b_Gm=0;{second}\x1b\\").as_bytes(), &mut output);
+        assert_eq!(
+            placeholders(&output),
+            [(1, 0, 0), (1, 0, 1), (1, 0, 2), (1, 0, 3)]
+        );
+        assert_eq!(images.take_responses(), b"\x1b_Gi=7;OK\x1b\\");
+
+        let image = images.image(1).unwrap();
+        assert_eq!((image.columns, image.rows), (4, 1));
+        assert_eq!(image.paint_size(px(2.), px(4.)), size(px(4.), px(4.)));
+
+        // Placing the stored image again, quietly, and then an unknown image.
+        output.clear();
+        images.filter(b"\x1b_Ga=p,i=7,q=1\x1b\\\x1b_Ga=p,i=8\x1b\\", &mut output);
+        assert_eq!(placeholders(&output), [(2, 0, 0)]);
+        assert!(images.image(2).is_some());
+        assert_eq!(
+            images.take_responses(),
+            b"\x1b_Gi=8;ENOENT:image not found\x1b\\"
+        );
+
+        // Queries check the image without storing it.
+        images.filter(b"\x1b_Ga=q,i=31,s=1,v=1,f=24;AAAA\x1b\\", &mut output);
+        images.filter(b"\x1b_Ga=q,i=32,s=2,v=2,f=24;AAAA\x1b\\", &mut output);
+        assert_eq!(
+            images.take_responses(),
+            b"\x1b_Gi=31;OK\x1b\\\x1b_Gi=32;EINVAL:payload is smaller than the image\x1b\\"
+        );
+
+        // Sizes far beyond the screen are clamped to it rather than overflowing.
+        output.clear();
+        images.filter(b"\x1b_Ga=p,i=7,q=2,r=4000000\x1b\\", &mut output);
+        images.filter(b"\x1b_Ga=p,i=7,q=2,c=4294967295\x1b\\", &mut output);
+        let tall = images.image(3).unwrap();
+        assert_eq!((tall.columns, tall.rows), (48, 24));
+        let wide = images.image(4).unwrap();
+        assert_eq!((wide.columns, wide.rows), (80, 24));
+
+        // Images are only accepted inline, never read from files named by the output.
+        images.filter(
+            b"\x1b_Ga=t,i=40,t=f,f=100;L2V0Yy9wYXNzd2Q=\x1b\\",
+            &mut output,
+        );
+        images.filter(b"\x1b_Ga=q,i=41,t=t,f=100;L3RtcC94\x1b\\", &mut output);
+        assert_eq!(
+            images.take_responses(),
+            b"\x1b_Gi=40;EINVAL:unsupported transmission medium\x1b\\\x1b_Gi=41;EINVAL:unsupported transmission medium\x1b\\"
+        );
+
+        // Deleting the image removes its placements.
+        images.filter(b"\x1b_Ga=d,d=I,i=7\x1b\\", &mut output);
+        assert!(images.image(1).is_none());
+        assert!(images.image(2).is_none());
+        assert!(images.take_responses().is_empty());
+    }
+}
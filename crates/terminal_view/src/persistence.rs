@@ -415,6 +415,9 @@ impl Domain for TerminalDb {
         sql! (
             ALTER TABLE terminals ADD COLUMN custom_title TEXT;
         ),
+        sql! (
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
+        ),
     ];
 }
 
@@ -506,4 +509,36 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    pub async fn save_scrollback(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        scrollback: String,
+    ) -> Result<()> {
+        log::debug!(
+            "Saving {} bytes of scrollback for item {item_id} in workspace {workspace_id:?}",
+            scrollback.len()
+        );
+        self.write(move |conn| {
+            let query = "INSERT INTO terminals (item_id, workspace_id, scrollback)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT (workspace_id, item_id) DO UPDATE SET
+                    scrollback = excluded.scrollback";
+            let mut statement = Statement::prepare(conn, query)?;
+            let mut next_index = statement.bind(&item_id, 1)?;
+            next_index = statement.bind(&workspace_id, next_index)?;
+            statement.bind(&scrollback, next_index)?;
+            statement.exec()
+        })
+        .await
+    }
+
+    query! {
+        pub fn get_scrollback(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT scrollback
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
 }
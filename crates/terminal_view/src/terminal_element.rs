@@ -1,19 +1,19 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun,
+    TextStyle, UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point,
+    px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    ImageCell, IndexedCell, Terminal, TerminalBounds, TerminalContent, TerminalImage,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -51,11 +51,67 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// Viewport lines of prompts whose command failed, marked in the gutter.
+    failed_command_lines: Vec<i32>,
+    failed_command_color: Hsla,
+    image_slices: Vec<ImageSlice>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
 }
 
+/// A run of placeholder cells on one viewport line, showing part of an inline image.
+struct ImageSlice {
+    line: i32,
+    column: usize,
+    len: usize,
+    image_id: u32,
+    image_row: usize,
+    image_column: usize,
+    image: Arc<TerminalImage>,
+}
+
+impl ImageSlice {
+    fn paint(&self, origin: Point<Pixels>, dimensions: &TerminalBounds, window: &mut Window) {
+        let cell_width = dimensions.cell_width;
+        let line_height = dimensions.line_height;
+        let cells_origin = origin
+            + point(
+                cell_width * self.column as f32,
+                line_height * self.line as f32,
+            );
+        let cells_bounds = Bounds::new(
+            cells_origin,
+            size(cell_width * self.len as f32, line_height),
+        );
+        let image_origin = cells_origin
+            - point(
+                cell_width * self.image_column as f32,
+                line_height * self.image_row as f32,
+            );
+        let image_bounds =
+            Bounds::new(image_origin, self.image.paint_size(cell_width, line_height));
+        // Only the part of the image covered by this run of cells is painted, so text that
+        // overwrote the rest of the image stays visible.
+        window.with_content_mask(
+            Some(ContentMask {
+                bounds: cells_bounds,
+            }),
+            |window| {
+                window
+                    .paint_image(
+                        image_bounds,
+                        Corners::default(),
+                        self.image.image.clone(),
+                        0,
+                        false,
+                    )
+                    .log_err();
+            },
+        );
+    }
+}
+
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
 #[derive(Copy, Clone)]
 struct DisplayCursor {
@@ -365,6 +421,11 @@ impl TerminalElement {
             let mut previous_cell_had_extras = false;
 
             for cell in line {
+                // Inline images are painted separately.
+                if ImageCell::from_cell(&cell).is_some() {
+                    continue;
+                }
+
                 let mut fg = cell.fg;
                 let mut bg = cell.bg;
                 if cell.flags.contains(Flags::INVERSE) {
@@ -1038,6 +1099,52 @@ impl Element for TerminalElement {
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let failed_command_lines = {
+                    let terminal = self.terminal.read(cx);
+                    let viewport_lines = 0..dimensions.num_lines() as i32;
+                    terminal
+                        .command_blocks()
+                        .iter()
+                        .filter(|block| block.failed())
+                        .map(|block| {
+                            terminal.grid_line(block.prompt_line).0 + display_offset as i32
+                        })
+                        .filter(|line| viewport_lines.contains(line))
+                        .collect::<Vec<_>>()
+                };
+
+                let image_slices = {
+                    let terminal = self.terminal.read(cx);
+                    let mut slices = Vec::<ImageSlice>::new();
+                    for cell in cells {
+                        let Some(image_cell) = ImageCell::from_cell(cell) else {
+                            continue;
+                        };
+                        let line = cell.point.line.0 + display_offset as i32;
+                        let column = cell.point.column.0;
+                        if let Some(slice) = slices.last_mut()
+                            && slice.line == line
+                            && slice.image_id == image_cell.image_id
+                            && slice.image_row == image_cell.row
+                            && slice.column + slice.len == column
+                            && slice.image_column + slice.len == image_cell.column
+                        {
+                            slice.len += 1;
+                        } else if let Some(image) = terminal.image(image_cell.image_id) {
+                            slices.push(ImageSlice {
+                                line,
+                                column,
+                                len: 1,
+                                image_id: image_cell.image_id,
+                                image_row: image_cell.row,
+                                image_column: image_cell.column,
+                                image,
+                            });
+                        }
+                    }
+                    slices
+                };
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -1222,6 +1329,9 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    failed_command_lines,
+                    failed_command_color: theme.status().error,
+                    image_slices,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1305,6 +1415,22 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for image_slice in &layout.image_slices {
+                        image_slice.paint(origin, &layout.dimensions, window);
+                    }
+
+                    for line in &layout.failed_command_lines {
+                        let line_height = layout.dimensions.line_height;
+                        let mark_bounds = Bounds::new(
+                            point(
+                                bounds.origin.x + layout.gutter / 3.,
+                                origin.y + *line as f32 * line_height,
+                            ),
+                            size(layout.gutter / 3., line_height),
+                        );
+                        window.paint_quad(fill(mark_bounds, layout.failed_command_color));
+                    }
+
                     for (relative_highlighted_range, color) in &layout.relative_highlighted_ranges {
                         if let Some((start_y, highlighted_range_lines)) =
                             to_highlighted_range_lines(relative_highlighted_range, layout, origin)
@@ -625,6 +625,56 @@ impl TerminalPanel {
         cx.spawn(async move |_, _| rx.await?)
     }
 
+    /// Shows the problems that the task's problem matchers find in its output as diagnostics,
+    /// replacing the ones found during the task's previous run.
+    fn update_task_diagnostics(
+        &self,
+        task: &SpawnInTerminal,
+        terminal: &WeakEntity<Terminal>,
+        cx: &mut Context<Self>,
+    ) {
+        if task.problem_matchers.is_empty() {
+            return;
+        }
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        let Some(cwd) = task.cwd.clone().or_else(|| {
+            project
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        }) else {
+            return;
+        };
+        let Ok(output) = terminal.read_with(cx, |terminal, _| terminal.get_content()) else {
+            return;
+        };
+
+        let task_id = task.id.clone();
+        let problem_matchers = task.problem_matchers.clone();
+        cx.spawn(async move |_, cx| {
+            let problems = cx
+                .background_spawn(async move {
+                    problem_matchers
+                        .iter()
+                        .flat_map(|problem_matcher| problem_matcher.match_output(&output))
+                        .collect::<Vec<_>>()
+                })
+                .await;
+            project
+                .update(cx, |project, cx| {
+                    project.lsp_store().update(cx, |lsp_store, cx| {
+                        lsp_store.update_task_diagnostics(task_id, &cwd, problems, cx)
+                    })
+                })
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn spawn_in_new_terminal(
         &mut self,
         spawn_task: SpawnInTerminal,
@@ -1695,6 +1745,9 @@ impl workspace::TerminalProvider for TerminalProvider {
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
                         .ok()?
                         .await?;
+                    terminal_panel.update(cx, |terminal_panel, cx| {
+                        terminal_panel.update_task_diagnostics(&task, &terminal, cx);
+                    });
                     Some(Ok(exit_status))
                 }
                 Err(e) => Some(Err(e)),
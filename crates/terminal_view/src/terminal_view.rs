@@ -9,10 +9,10 @@ use editor::{
     ui_scrollbar_settings_from_raw,
 };
 use gpui::{
-    Action, AnyElement, App, ClipboardEntry, DismissEvent, Entity, EventEmitter, ExternalPaths,
-    FocusHandle, Focusable, Font, KeyContext, KeyDownEvent, Keystroke, MouseButton, MouseDownEvent,
-    Pixels, Point, Render, ScrollWheelEvent, Styled, Subscription, Task, WeakEntity, actions,
-    anchored, deferred, div,
+    Action, AnyElement, App, ClipboardEntry, ClipboardItem, DismissEvent, Entity, EventEmitter,
+    ExternalPaths, FocusHandle, Focusable, Font, KeyContext, KeyDownEvent, Keystroke, MouseButton,
+    MouseDownEvent, Pixels, Point, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    WeakEntity, actions, anchored, deferred, div,
 };
 use itertools::Itertools;
 use menu;
@@ -32,8 +32,9 @@ use std::{
 };
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyLastCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste,
+    RerunLastCommand, ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom,
+    ScrollToNextPrompt, ScrollToPreviousPrompt, ScrollToTop, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point as AlacPoint,
@@ -69,6 +70,10 @@ struct ImeState {
 }
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+/// How long after new output the scrollback is saved, so it survives a crash.
+const SCROLLBACK_SERIALIZATION_DELAY: Duration = Duration::from_secs(5);
+/// The most scrollback that is saved per terminal, in bytes of text and SGR sequences.
+const MAX_SERIALIZED_SCROLLBACK_LEN: usize = 1024 * 1024;
 
 /// Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -133,6 +138,7 @@ pub struct TerminalView {
     mode: TerminalMode,
     blinking_terminal_enabled: bool,
     needs_serialize: bool,
+    scrollback_serialization: Option<Task<()>>,
     custom_title: Option<String>,
     hover: Option<HoverTarget>,
     hover_tooltip_update: Task<()>,
@@ -286,6 +292,7 @@ impl TerminalView {
             scroll_top: Pixels::ZERO,
             scroll_handle,
             needs_serialize: false,
+            scrollback_serialization: None,
             custom_title: None,
             ime_state: None,
             self_handle: cx.entity().downgrade(),
@@ -390,6 +397,27 @@ impl TerminalView {
         self.custom_title.as_deref()
     }
 
+    fn schedule_scrollback_serialization(&mut self, cx: &mut Context<Self>) {
+        if self.scrollback_serialization.is_some()
+            || self.workspace_id.is_none()
+            || self.terminal.read(cx).task().is_some()
+        {
+            return;
+        }
+        self.scrollback_serialization = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(SCROLLBACK_SERIALIZATION_DELAY)
+                .await;
+            this.update(cx, |this, cx| {
+                this.scrollback_serialization = None;
+                this.needs_serialize = true;
+                // Item events are what get the workspace to serialize the item.
+                cx.emit(ItemEvent::UpdateTab);
+            })
+            .ok();
+        }));
+    }
+
     pub fn set_custom_title(&mut self, label: Option<String>, cx: &mut Context<Self>) {
         let label = label.filter(|l| !l.trim().is_empty());
         if self.custom_title != label {
@@ -504,6 +532,12 @@ impl TerminalView {
             .selection_text
             .as_ref()
             .is_some_and(|text| !text.is_empty());
+        let has_finished_command = self
+            .terminal
+            .read(cx)
+            .command_blocks()
+            .iter()
+            .any(|block| block.is_finished());
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal::default()))
@@ -512,6 +546,11 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .when(has_finished_command, |menu| {
+                    menu.separator()
+                        .action("Copy Last Command Output", Box::new(CopyLastCommandOutput))
+                        .action("Rerun Last Command", Box::new(RerunLastCommand))
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -746,6 +785,45 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(
+        &mut self,
+        _: &CopyLastCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(output) = self.terminal.read(cx).last_command_output() {
+            cx.write_to_clipboard(ClipboardItem::new_string(output));
+        }
+    }
+
+    fn rerun_last_command(&mut self, _: &RerunLastCommand, _: &mut Window, cx: &mut Context<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.rerun_last_command());
+        cx.notify();
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -1010,6 +1088,7 @@ fn subscribe_for_terminal_events(
                     cx.emit(Event::Wakeup);
                     cx.emit(ItemEvent::UpdateTab);
                     cx.emit(SearchEvent::MatchesInvalidated);
+                    terminal_view.schedule_scrollback_serialization(cx);
                 }
 
                 Event::Bell => {
@@ -1101,6 +1180,7 @@ fn subscribe_for_terminal_events(
                     window.invalidate_character_coordinates();
                     cx.emit(SearchEvent::ActiveMatchChanged)
                 }
+                Event::LocalhostPortDetected(_) => {}
             }
         },
     );
@@ -1230,6 +1310,10 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
+            .on_action(cx.listener(TerminalView::rerun_last_command))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
@@ -1733,6 +1817,7 @@ impl SerializableItem for TerminalView {
         let workspace_id = self.workspace_id?;
         let cwd = terminal.working_directory();
         let custom_title = self.custom_title.clone();
+        let scrollback = terminal.scrollback(MAX_SERIALIZED_SCROLLBACK_LEN);
         self.needs_serialize = false;
 
         let db = TerminalDb::global(cx);
@@ -1743,6 +1828,8 @@ impl SerializableItem for TerminalView {
             }
             db.save_custom_title(item_id, workspace_id, custom_title)
                 .await?;
+            db.save_scrollback(item_id, workspace_id, scrollback)
+                .await?;
             Ok(())
         }))
     }
@@ -1760,7 +1847,7 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
-            let (cwd, custom_title) = cx
+            let (cwd, custom_title, scrollback) = cx
                 .update(|_window, cx| {
                     let db = TerminalDb::global(cx);
                     let from_db = db
@@ -1782,14 +1869,24 @@ impl SerializableItem for TerminalView {
                         .log_err()
                         .flatten()
                         .filter(|title| !title.trim().is_empty());
-                    (cwd, custom_title)
+                    let scrollback = db
+                        .get_scrollback(item_id, workspace_id)
+                        .log_err()
+                        .flatten()
+                        .filter(|scrollback| !scrollback.trim().is_empty());
+                    (cwd, custom_title, scrollback)
                 })
                 .ok()
-                .unwrap_or((None, None));
+                .unwrap_or((None, None, None));
 
             let terminal = project
                 .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))
                 .await?;
+            if let Some(scrollback) = scrollback {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.restore_scrollback(&scrollback, cx)
+                });
+            }
             cx.update(|window, cx| {
                 cx.new(|cx| {
                     let mut view = TerminalView::new(
@@ -32,7 +32,10 @@ use gpui::{
     Subscription, WeakEntity, Window, actions, div,
 };
 use onboarding_banner::OnboardingBanner;
-use project::{Project, git_store::GitStoreEvent, trusted_worktrees::TrustedWorktrees};
+use project::{
+    Project, ProjectPath, UnsyncedBufferState, git_store::GitStoreEvent,
+    trusted_worktrees::TrustedWorktrees,
+};
 use remote::RemoteConnectionOptions;
 use settings::Settings;
 use settings::WorktreeId;
@@ -225,6 +228,7 @@ impl Render for TitleBar {
                                 .when(title_bar_settings.show_project_items, |title_bar| {
                                     title_bar
                                         .children(self.render_project_host(cx))
+                                        .children(self.render_unsynced_buffers(cx))
                                         .child(self.render_project_name(project_name, window, cx))
                                 })
                                 .when_some(
@@ -353,16 +357,19 @@ impl TitleBar {
                 cx.notify()
             }),
         );
-        subscriptions.push(
-            cx.subscribe(&project, |this, _, event: &project::Event, cx| {
-                if let project::Event::BufferEdited = event {
+        subscriptions.push(cx.subscribe(
+            &project,
+            |this, _, event: &project::Event, cx| match event {
+                project::Event::BufferEdited => {
                     // Clear override when user types in any editor,
                     // so the title bar reflects the project they're actually working in
                     this.clear_active_worktree_override(cx);
                     cx.notify();
                 }
-            }),
-        );
+                project::Event::UnsyncedBuffersChanged => cx.notify(),
+                _ => {}
+            },
+        ));
         subscriptions.push(cx.observe(&active_call, |this, _, cx| this.active_call_changed(cx)));
         subscriptions.push(cx.observe_window_activation(window, Self::window_activation_changed));
         subscriptions.push(
@@ -514,6 +521,7 @@ impl TitleBar {
             RemoteConnectionOptions::Docker(_dev_container_connection) => {
                 (None, "Dev Container", IconName::Box)
             }
+            RemoteConnectionOptions::Command(_) => (None, "Remote Project", IconName::Terminal),
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(_) => (None, "Mock Remote Project", IconName::Server),
         };
@@ -595,6 +603,83 @@ impl TitleBar {
         )
     }
 
+    /// Lists the buffers that were saved while disconnected from the remote server, and haven't
+    /// been written there yet.
+    fn render_unsynced_buffers(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let project = self.project.read(cx);
+        let unsynced_buffers = project.unsynced_buffers(cx);
+        if unsynced_buffers.is_empty() {
+            return None;
+        }
+
+        let path_style = project.path_style(cx);
+        let entries = unsynced_buffers
+            .into_iter()
+            .filter_map(|(buffer, state)| {
+                let file = buffer.read(cx).file()?;
+                let project_path = ProjectPath::from_file(file.as_ref(), cx);
+                let label = match state {
+                    UnsyncedBufferState::Pending => file.path().display(path_style).to_string(),
+                    UnsyncedBufferState::Conflict => {
+                        format!("{} (changed on disk)", file.path().display(path_style))
+                    }
+                    UnsyncedBufferState::Failed => {
+                        format!("{} (failed to save)", file.path().display(path_style))
+                    }
+                };
+                Some((project_path, label, state))
+            })
+            .collect::<Vec<_>>();
+        let (color, tint) = if entries
+            .iter()
+            .all(|(_, _, state)| *state == UnsyncedBufferState::Pending)
+        {
+            (Color::Warning, TintColor::Warning)
+        } else {
+            (Color::Error, TintColor::Error)
+        };
+        let label = format!("{} Unsynced", entries.len());
+        let workspace = self.workspace.clone();
+
+        Some(
+            PopoverMenu::new("unsynced-buffers-menu")
+                .menu(move |window, cx| {
+                    let entries = entries.clone();
+                    let workspace = workspace.clone();
+                    Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                        menu = menu.header("Saved While Disconnected");
+                        for (project_path, label, _) in entries {
+                            let workspace = workspace.clone();
+                            menu = menu.entry(label, None, move |window, cx| {
+                                workspace
+                                    .update(cx, |workspace, cx| {
+                                        workspace
+                                            .open_path(project_path.clone(), None, true, window, cx)
+                                            .detach_and_log_err(cx);
+                                    })
+                                    .ok();
+                            });
+                        }
+                        menu
+                    }))
+                })
+                .trigger_with_tooltip(
+                    Button::new("unsynced-buffers", label)
+                        .style(ButtonStyle::Tinted(tint))
+                        .label_size(LabelSize::Small)
+                        .color(color)
+                        .start_icon(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(color),
+                        ),
+                    Tooltip::text("Saves that haven't been written on the remote server yet"),
+                )
+                .anchor(gpui::Corner::TopLeft)
+                .into_any_element(),
+        )
+    }
+
     pub fn render_restricted_mode(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
         let has_restricted_worktrees = TrustedWorktrees::try_get_global(cx)
             .map(|trusted_worktrees| {
@@ -2498,6 +2498,7 @@ impl ShellExec {
                     show_command: false,
                     show_rerun: false,
                     save: SaveStrategy::default(),
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
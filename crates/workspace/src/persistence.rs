@@ -27,7 +27,8 @@ use project::{
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use remote::{
-    DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
+    CommandConnectionOptions, DockerConnectionOptions, RemoteConnectionOptions,
+    SshConnectionOptions, WslConnectionOptions,
 };
 use serde::{Deserialize, Serialize};
 use sqlez::{
@@ -974,6 +975,11 @@ impl Domain for WorkspaceDb {
         sql!(
             ALTER TABLE remote_connections ADD COLUMN remote_env TEXT;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN args TEXT;
+            ALTER TABLE remote_connections ADD COLUMN terminal_args TEXT;
+            ALTER TABLE remote_connections ADD COLUMN upload_binary BOOLEAN;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1504,6 +1510,9 @@ impl WorkspaceDb {
         let mut container_id = None;
         let mut use_podman = None;
         let mut remote_env = None;
+        let mut args = None;
+        let mut terminal_args = None;
+        let mut upload_binary = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1524,6 +1533,19 @@ impl WorkspaceDb {
                 user = Some(options.remote_user);
                 remote_env = serde_json::to_string(&options.remote_env).ok();
             }
+            RemoteConnectionOptions::Command(options) => {
+                kind = RemoteConnectionKind::Command;
+                name = Some(options.nickname);
+                host = Some(options.program);
+                args = Some(serde_json::to_string(&options.args)?);
+                terminal_args = options
+                    .terminal_args
+                    .map(|terminal_args| serde_json::to_string(&terminal_args))
+                    .transpose()?;
+                upload_binary = Some(options.upload_binary_over_command);
+                remote_env = serde_json::to_string(&options.remote_env).ok();
+                user = None;
+            }
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1542,6 +1564,9 @@ impl WorkspaceDb {
             container_id,
             use_podman,
             remote_env,
+            args,
+            terminal_args,
+            upload_binary,
         )
     }
 
@@ -1556,6 +1581,9 @@ impl WorkspaceDb {
         container_id: Option<String>,
         use_podman: Option<bool>,
         remote_env: Option<String>,
+        args: Option<String>,
+        terminal_args: Option<String>,
+        upload_binary: Option<bool>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1567,7 +1595,9 @@ impl WorkspaceDb {
                 user IS ? AND
                 distro IS ? AND
                 name IS ? AND
-                container_id IS ?
+                container_id IS ? AND
+                args IS ? AND
+                terminal_args IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1577,6 +1607,8 @@ impl WorkspaceDb {
             distro.clone(),
             name.clone(),
             container_id.clone(),
+            args.clone(),
+            terminal_args.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1590,19 +1622,22 @@ impl WorkspaceDb {
                     name,
                     container_id,
                     use_podman,
-                    remote_env
-                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                    remote_env,
+                    args,
+                    terminal_args,
+                    upload_binary
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
                 RETURNING id
             ))?((
-                kind.serialize(),
-                host,
-                port,
-                user,
-                distro,
-                name,
-                container_id,
-                use_podman,
-                remote_env,
+                (kind.serialize(), host, port, user, distro, name),
+                (
+                    container_id,
+                    use_podman,
+                    remote_env,
+                    args,
+                    terminal_args,
+                    upload_binary,
+                ),
             ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
@@ -1704,13 +1739,17 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro, container_id, name, use_podman, remote_env
+                id, kind, host, port, user, distro, container_id, name, use_podman, remote_env,
+                args, terminal_args, upload_binary
             FROM
                 remote_connections
         ))?()?
         .into_iter()
         .filter_map(
-            |(id, kind, host, port, user, distro, container_id, name, use_podman, remote_env)| {
+            |(
+                (id, kind, host, port, user, distro, container_id, name, use_podman, remote_env),
+                (args, terminal_args, upload_binary),
+            )| {
                 Some((
                     RemoteConnectionId(id),
                     Self::remote_connection_from_row(
@@ -1723,6 +1762,9 @@ impl WorkspaceDb {
                         name,
                         use_podman,
                         remote_env,
+                        args,
+                        terminal_args,
+                        upload_binary,
                     )?,
                 ))
             },
@@ -1734,13 +1776,17 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro, container_id, name, use_podman, remote_env) =
-            self.select_row_bound(sql!(
-                SELECT kind, host, port, user, distro, container_id, name, use_podman, remote_env
-                FROM remote_connections
-                WHERE id = ?
-            ))?(id.0)?
-            .context("no such remote connection")?;
+        let (
+            (kind, host, port, user, distro, container_id, name, use_podman, remote_env),
+            (args, terminal_args, upload_binary),
+        ) = self.select_row_bound(sql!(
+            SELECT
+                kind, host, port, user, distro, container_id, name, use_podman, remote_env,
+                args, terminal_args, upload_binary
+            FROM remote_connections
+            WHERE id = ?
+        ))?(id.0)?
+        .context("no such remote connection")?;
         Self::remote_connection_from_row(
             kind,
             host,
@@ -1751,6 +1797,9 @@ impl WorkspaceDb {
             name,
             use_podman,
             remote_env,
+            args,
+            terminal_args,
+            upload_binary,
         )
         .context("invalid remote_connection row")
     }
@@ -1765,6 +1814,9 @@ impl WorkspaceDb {
         name: Option<String>,
         use_podman: Option<bool>,
         remote_env: Option<String>,
+        args: Option<String>,
+        terminal_args: Option<String>,
+        upload_binary: Option<bool>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1789,6 +1841,19 @@ impl WorkspaceDb {
                     remote_env,
                 }))
             }
+            RemoteConnectionKind::Command => {
+                Some(RemoteConnectionOptions::Command(CommandConnectionOptions {
+                    nickname: name?,
+                    program: host?,
+                    args: serde_json::from_str(&args?).ok()?,
+                    terminal_args: terminal_args
+                        .map(|terminal_args| serde_json::from_str(&terminal_args))
+                        .transpose()
+                        .ok()?,
+                    remote_env: serde_json::from_str(&remote_env?).ok()?,
+                    upload_binary_over_command: upload_binary?,
+                }))
+            }
         }
     }
 
@@ -3617,6 +3682,61 @@ mod tests {
         assert_eq!(connection_id, same_connection_id);
     }
 
+    #[gpui::test]
+    async fn test_get_or_create_command_connection() {
+        let db = WorkspaceDb::open_test_db("test_get_or_create_command_connection").await;
+
+        let options = CommandConnectionOptions {
+            nickname: "Dev Pod".to_string(),
+            program: "kubectl".to_string(),
+            args: vec!["exec".into(), "-i".into(), "dev-0".into(), "--".into()],
+            terminal_args: Some(vec![
+                "exec".into(),
+                "-it".into(),
+                "dev-0".into(),
+                "--".into(),
+            ]),
+            remote_env: BTreeMap::from_iter([("RUST_LOG".to_string(), "info".to_string())]),
+            upload_binary_over_command: true,
+        };
+        let connection_id = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Command(options.clone()))
+            .await
+            .unwrap();
+        let same_connection_id = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Command(options.clone()))
+            .await
+            .unwrap();
+        assert_eq!(connection_id, same_connection_id);
+
+        let other_pod_id = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Command(
+                CommandConnectionOptions {
+                    args: vec!["exec".into(), "-i".into(), "dev-1".into(), "--".into()],
+                    ..options.clone()
+                },
+            ))
+            .await
+            .unwrap();
+        assert_ne!(connection_id, other_pod_id);
+
+        // The environment may hold secrets, so it's kept out of the host column.
+        let (host, remote_env) = db
+            .select_row_bound::<u64, (String, String)>(sql!(
+                SELECT host, remote_env FROM remote_connections WHERE id = ?
+            ))
+            .unwrap()(connection_id.0)
+        .unwrap()
+        .unwrap();
+        assert_eq!(host, "kubectl");
+        assert_eq!(remote_env, r#"{"RUST_LOG":"info"}"#);
+
+        assert_eq!(
+            db.remote_connection(connection_id).unwrap(),
+            RemoteConnectionOptions::Command(options)
+        );
+    }
+
     #[gpui::test]
     async fn test_get_remote_connections() {
         let db = WorkspaceDb::open_test_db("test_get_remote_connections").await;
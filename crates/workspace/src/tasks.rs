@@ -2,13 +2,14 @@ use std::process::ExitStatus;
 
 use anyhow::Result;
 use collections::HashSet;
-use gpui::{AppContext, Context, Entity, Task};
+use futures::{FutureExt as _, future::LocalBoxFuture};
+use gpui::{AppContext, AsyncWindowContext, Context, Entity, Task, WeakEntity};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskNode, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
 use task::{
-    DebugScenario, ResolvedTask, SaveStrategy, SharedTaskContext, SpawnInTerminal, TaskContext,
-    TaskHook, TaskTemplate, TaskVariables, VariableName,
+    DebugScenario, DependsOrder, ResolvedTask, SaveStrategy, SharedTaskContext, SpawnInTerminal,
+    TaskContext, TaskHook, TaskTemplate, TaskVariables, VariableName,
 };
 use ui::Window;
 use util::TryFutureExt;
@@ -59,7 +60,6 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
-        let spawn_in_terminal = resolved_task.resolved.clone();
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -70,68 +70,63 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task.clone());
                     })
                 }
             });
         }
 
         if self.terminal_provider.is_some() {
-            let task = cx.spawn_in(window, async move |workspace, cx| {
-                let save_action = match spawn_in_terminal.save {
-                    SaveStrategy::All => {
-                        let save_all = workspace.update_in(cx, |workspace, window, cx| {
-                            let task = workspace.save_all_internal(SaveIntent::SaveAll, window, cx);
-                            // Match the type of the other arm by ignoring the bool value returned
-                            cx.background_spawn(async { task.await.map(|_| ()) })
-                        });
-                        save_all.ok()
-                    }
-                    SaveStrategy::Current => {
-                        let save_current = workspace.update_in(cx, |workspace, window, cx| {
-                            workspace.save_active_item(SaveIntent::SaveAll, window, cx)
-                        });
-                        save_current.ok()
-                    }
-                    SaveStrategy::None => None,
-                };
-                if let Some(save_action) = save_action {
-                    save_action.log_err().await;
-                }
-
-                let spawn_task = workspace.update_in(cx, |workspace, window, cx| {
-                    workspace
-                        .terminal_provider
-                        .as_ref()
-                        .map(|terminal_provider| {
-                            terminal_provider.spawn(spawn_in_terminal, window, cx)
-                        })
-                });
-                if let Some(spawn_task) = spawn_task.ok().flatten() {
-                    let res = cx.background_spawn(spawn_task).await;
-                    match res {
-                        Some(Ok(status)) => {
-                            if status.success() {
-                                log::debug!("Task spawn succeeded");
-                            } else {
-                                log::debug!("Task spawn failed, code: {:?}", status.code());
-                            }
-                        }
-                        Some(Err(e)) => {
-                            log::error!("Task spawn failed: {e:#}");
-                            _ = workspace.update(cx, |w, cx| {
-                                let id = NotificationId::unique::<ResolvedTask>();
-                                w.show_toast(Toast::new(id, format!("Task spawn failed: {e}")), cx);
-                            })
-                        }
-                        None => log::debug!("Task spawn got cancelled"),
-                    };
+            let dependencies =
+                self.resolve_task_dependencies(&task_source_kind, &resolved_task, cx);
+            let dependencies = match dependencies {
+                Ok(dependencies) => dependencies,
+                Err(e) => {
+                    log::error!("Failed to resolve task dependencies: {e:#}");
+                    let id = NotificationId::unique::<TaskNode>();
+                    self.show_toast(Toast::new(id, format!("Cannot run task: {e}")), cx);
+                    return;
                 }
+            };
+            let node = TaskNode {
+                source_kind: task_source_kind,
+                task: resolved_task,
+                dependencies,
+            };
+            let task = cx.spawn_in(window, async move |workspace, cx| {
+                run_task_node(workspace, node, cx.clone()).await;
             });
             self.scheduled_tasks.push(task);
         }
     }
 
+    fn resolve_task_dependencies(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        resolved_task: &ResolvedTask,
+        cx: &Context<Self>,
+    ) -> Result<Vec<TaskNode>> {
+        if resolved_task.original_task().depends_on.is_empty() {
+            return Ok(Vec::new());
+        }
+        let project = self.project.read(cx);
+        let Some(inventory) = project.task_store().read(cx).task_inventory() else {
+            return Ok(Vec::new());
+        };
+        let worktree_id = match task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => resolved_task
+                .context()
+                .cwd
+                .as_deref()
+                .and_then(|cwd| project.find_worktree(cwd, cx))
+                .map(|(worktree, _)| worktree.read(cx).id()),
+        };
+        inventory
+            .read(cx)
+            .resolve_dependencies(resolved_task, worktree_id)
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -272,6 +267,115 @@ impl Workspace {
     }
 }
 
+/// Runs the dependencies of a task, then the task itself, returning whether all of them succeeded.
+/// A dependency that fails prevents the tasks depending on it from running.
+fn run_task_node(
+    workspace: WeakEntity<Workspace>,
+    node: TaskNode,
+    mut cx: AsyncWindowContext,
+) -> LocalBoxFuture<'static, bool> {
+    async move {
+        let label = node.task.resolved_label.clone();
+        let dependencies = node
+            .dependencies
+            .into_iter()
+            .map(|dependency| run_task_node(workspace.clone(), dependency, cx.clone()));
+        let dependencies_succeeded = match node.task.original_task().depends_order {
+            DependsOrder::Sequence => {
+                let mut succeeded = true;
+                for dependency in dependencies {
+                    if !dependency.await {
+                        succeeded = false;
+                        break;
+                    }
+                }
+                succeeded
+            }
+            DependsOrder::Parallel => futures::future::join_all(dependencies)
+                .await
+                .into_iter()
+                .all(|succeeded| succeeded),
+        };
+        if !dependencies_succeeded {
+            log::debug!("Not running task `{label}` as one of its dependencies failed");
+            return false;
+        }
+
+        let has_command = node.task.original_task().has_command();
+        let succeeded = save_and_spawn(&workspace, node.task.resolved, has_command, &mut cx).await;
+        if !succeeded {
+            log::debug!("Task `{label}` failed");
+        }
+        succeeded
+    }
+    .boxed_local()
+}
+
+async fn save_and_spawn(
+    workspace: &WeakEntity<Workspace>,
+    spawn_in_terminal: SpawnInTerminal,
+    has_command: bool,
+    cx: &mut AsyncWindowContext,
+) -> bool {
+    let save_action = match spawn_in_terminal.save {
+        SaveStrategy::All => {
+            let save_all = workspace.update_in(cx, |workspace, window, cx| {
+                let task = workspace.save_all_internal(SaveIntent::SaveAll, window, cx);
+                // Match the type of the other arm by ignoring the bool value returned
+                cx.background_spawn(async { task.await.map(|_| ()) })
+            });
+            save_all.ok()
+        }
+        SaveStrategy::Current => {
+            let save_current = workspace.update_in(cx, |workspace, window, cx| {
+                workspace.save_active_item(SaveIntent::SaveAll, window, cx)
+            });
+            save_current.ok()
+        }
+        SaveStrategy::None => None,
+    };
+    if let Some(save_action) = save_action {
+        save_action.log_err().await;
+    }
+    // Tasks that only group their dependencies have nothing to spawn.
+    if !has_command {
+        return true;
+    }
+
+    let spawn_task = workspace.update_in(cx, |workspace, window, cx| {
+        workspace
+            .terminal_provider
+            .as_ref()
+            .map(|terminal_provider| terminal_provider.spawn(spawn_in_terminal, window, cx))
+    });
+    let Some(spawn_task) = spawn_task.ok().flatten() else {
+        return false;
+    };
+    let res = cx.background_spawn(spawn_task).await;
+    match res {
+        Some(Ok(status)) => {
+            if status.success() {
+                log::debug!("Task spawn succeeded");
+            } else {
+                log::debug!("Task spawn failed, code: {:?}", status.code());
+            }
+            status.success()
+        }
+        Some(Err(e)) => {
+            log::error!("Task spawn failed: {e:#}");
+            _ = workspace.update(cx, |w, cx| {
+                let id = NotificationId::unique::<ResolvedTask>();
+                w.show_toast(Toast::new(id, format!("Task spawn failed: {e}")), cx);
+            });
+            false
+        }
+        None => {
+            log::debug!("Task spawn got cancelled");
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -282,10 +386,11 @@ mod tests {
     };
     use gpui::{App, TestAppContext};
     use parking_lot::Mutex;
-    use project::{FakeFs, Project, TaskSourceKind};
+    use project::{FakeFs, Project, TaskSourceKind, task_store::TaskSettingsLocation};
     use serde_json::json;
-    use std::sync::Arc;
+    use std::{path::Path, sync::Arc};
     use task::TaskTemplate;
+    use util::path;
 
     struct Fixture {
         workspace: Entity<Workspace>,
@@ -353,6 +458,88 @@ mod tests {
         assert!(cx.read(|cx| fixture.item.read(cx).is_dirty));
     }
 
+    #[gpui::test]
+    async fn test_schedule_resolved_task_with_dependencies(cx: &mut TestAppContext) {
+        let (fixture, cx) = create_fixture(cx, SaveStrategy::None).await;
+        let spawned_labels: Arc<Mutex<Vec<String>>> = Arc::default();
+        let inventory = fixture.workspace.update(cx, |workspace, cx| {
+            workspace.terminal_provider = Some(Box::new(RecordingTerminalProvider {
+                spawned_labels: spawned_labels.clone(),
+            }));
+            workspace
+                .project()
+                .read(cx)
+                .task_store()
+                .read(cx)
+                .task_inventory()
+                .cloned()
+                .unwrap()
+        });
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(Path::new(path!("/tasks.json"))),
+                    Some(
+                        &json!([
+                            { "label": "build", "command": "true" },
+                            { "label": "lint", "command": "false" },
+                            { "label": "test", "command": "true", "depends_on": ["build"] },
+                            { "label": "all", "depends_on": ["test", "lint"] },
+                            {
+                                "label": "failing",
+                                "command": "true",
+                                "depends_on": ["lint", "build"],
+                            },
+                            {
+                                "label": "failing_parallel",
+                                "command": "true",
+                                "depends_on": ["lint", "build"],
+                                "depends_order": "parallel",
+                            },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        let mut run_task = |label: &str| {
+            spawned_labels.lock().clear();
+            let template = inventory.update(cx, |inventory, cx| {
+                inventory.task_template_by_label(None, None, label, cx)
+            });
+            let template = cx.executor().block(template).unwrap();
+            let task = template
+                .resolve_task("test", &TaskContext::default())
+                .unwrap();
+            fixture.workspace.update_in(cx, |workspace, window, cx| {
+                workspace.schedule_resolved_task(
+                    TaskSourceKind::UserInput,
+                    task,
+                    false,
+                    window,
+                    cx,
+                );
+            });
+            cx.executor().run_until_parked();
+            spawned_labels.lock().clone()
+        };
+
+        assert_eq!(run_task("all"), ["build", "test", "lint"]);
+        assert_eq!(
+            run_task("failing"),
+            ["lint"],
+            "A failed dependency should stop the remaining ones and the dependent task"
+        );
+        let mut spawned = run_task("failing_parallel");
+        spawned.sort();
+        assert_eq!(
+            spawned,
+            ["build", "lint"],
+            "Parallel dependencies should all run, but not the dependent task"
+        );
+    }
+
     async fn create_fixture(
         cx: &mut TestAppContext,
         save_strategy: SaveStrategy,
@@ -433,4 +620,26 @@ mod tests {
             Task::ready(Some(Ok(ExitStatus::default())))
         }
     }
+
+    /// Records the labels of the spawned tasks, failing the ones whose command is `false`.
+    struct RecordingTerminalProvider {
+        spawned_labels: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl TerminalProvider for RecordingTerminalProvider {
+        fn spawn(
+            &self,
+            task: task::SpawnInTerminal,
+            _window: &mut ui::Window,
+            _cx: &mut App,
+        ) -> Task<Option<Result<ExitStatus>>> {
+            let failed = task.command.as_deref() == Some("false");
+            self.spawned_labels.lock().push(task.label);
+            if failed {
+                Task::ready(Some(Err(anyhow::anyhow!("task failed"))))
+            } else {
+                Task::ready(Some(Ok(ExitStatus::default())))
+            }
+        }
+    }
 }
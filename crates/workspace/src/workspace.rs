@@ -9105,6 +9105,9 @@ pub fn workspace_windows_for_location(
                 (RemoteConnectionOptions::Docker(a), RemoteConnectionOptions::Docker(b)) => {
                     a.container_id == b.container_id
                 }
+                (RemoteConnectionOptions::Command(a), RemoteConnectionOptions::Command(b)) => {
+                    (&a.program, &a.args) == (&b.program, &b.args)
+                }
                 #[cfg(any(test, feature = "test-support"))]
                 (RemoteConnectionOptions::Mock(a), RemoteConnectionOptions::Mock(b)) => {
                     a.id == b.id
@@ -39,6 +39,7 @@ use gpui::{
     UpdateGlobal, WeakEntity, Window, WindowBounds, WindowHandle, WindowKind, WindowOptions,
     actions, image_cache, img, point, px, retain_all,
 };
+use hierarchy_panel::HierarchyPanel;
 use image_viewer::ImageInfo;
 use language::Capability;
 use language_onboarding::BasedPyrightBanner;
@@ -55,6 +56,7 @@ use paths::{
     local_debug_file_relative_path, local_settings_file_relative_path,
     local_tasks_file_relative_path,
 };
+use ports_panel::PortsPanel;
 use project::{DirectoryLister, DisableAiSettings, ProjectItem};
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
@@ -653,6 +655,8 @@ fn initialize_panels(window: &mut Window, cx: &mut Context<Workspace>) -> Task<a
             workspace_handle.clone(),
             cx.clone(),
         );
+        let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
+        let ports_panel = PortsPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         async fn add_panel_when_ready(
@@ -677,6 +681,8 @@ fn initialize_panels(window: &mut Window, cx: &mut Context<Workspace>) -> Task<a
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(notification_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(hierarchy_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(ports_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(debug_panel, workspace_handle.clone(), cx.clone()),
             initialize_agent_panel(workspace_handle, cx.clone()).map(|r| r.log_err()),
         );
@@ -4947,6 +4953,7 @@ mod tests {
                 "git_panel",
                 "git_picker",
                 "go_to_line",
+                "hierarchy_panel",
                 "highlights_tree_view",
                 "icon_theme_selector",
                 "image_viewer",
@@ -4970,10 +4977,12 @@ mod tests {
                 "pane",
                 "panel",
                 "picker",
+                "ports_panel",
                 "project_panel",
                 "project_search",
                 "project_symbols",
                 "projects",
+                "rebase_editor",
                 "recent_projects",
                 "remote_debug",
                 "repl",
@@ -4986,6 +4995,7 @@ mod tests {
                 "svg",
                 "syntax_tree_view",
                 "tab_switcher",
+                "tag_picker",
                 "task",
                 "terminal",
                 "terminal_panel",
@@ -5181,6 +5191,8 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            hierarchy_panel::init(cx);
+            ports_panel::init(cx);
             terminal_view::init(cx);
             copilot_chat::init(
                 app_state.fs.clone(),
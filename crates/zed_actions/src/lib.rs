@@ -70,6 +70,8 @@ actions!(
         OpenTelemetryLog,
         /// Opens the performance profiler.
         OpenPerformanceProfiler,
+        /// Starts recording tracing spans, or stops and saves them as a trace file.
+        ToggleTraceRecording,
         /// Opens the onboarding view.
         OpenOnboarding,
     ]
@@ -270,6 +272,8 @@ pub mod git {
             Branch,
             /// Opens the git stash selector.
             ViewStash,
+            /// Opens the git tag selector.
+            ViewTags,
             /// Opens the git worktree selector.
             Worktree,
             /// Creates a pull request for the current branch.
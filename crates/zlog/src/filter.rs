@@ -11,6 +11,11 @@ use log;
 
 static ENV_FILTER: OnceLock<env_config::EnvFilter> = OnceLock::new();
 static SCOPE_MAP: RwLock<ScopeMap> = RwLock::new(ScopeMap::empty());
+/// The scope levels from the settings, kept to rebuild the scope map when runtime levels change.
+static SETTINGS_LEVELS: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+/// Scope levels changed while running, e.g. from the log viewer. They take precedence over the
+/// settings and are forgotten on restart.
+static RUNTIME_LEVELS: RwLock<Vec<(String, log::LevelFilter)>> = RwLock::new(Vec::new());
 
 pub const LEVEL_ENABLED_MAX_DEFAULT: log::LevelFilter = log::LevelFilter::Info;
 /// The maximum log level of verbosity that is enabled by default.
@@ -89,8 +94,63 @@ pub fn is_scope_enabled(
 }
 
 pub fn refresh_from_settings(settings: &HashMap<String, String>) {
+    *SETTINGS_LEVELS.write().unwrap_or_else(|err| {
+        SETTINGS_LEVELS.clear_poison();
+        err.into_inner()
+    }) = Some(settings.clone());
+    refresh_scope_map(settings);
+}
+
+/// Sets the level of a scope or module path until Zed quits, or goes back to the configured level
+/// when `level` is `None`.
+pub fn set_runtime_level(scope: &str, level: Option<log::LevelFilter>) {
+    {
+        let mut runtime_levels = RUNTIME_LEVELS.write().unwrap_or_else(|err| {
+            RUNTIME_LEVELS.clear_poison();
+            err.into_inner()
+        });
+        runtime_levels.retain(|(existing, _)| existing != scope);
+        if let Some(level) = level {
+            runtime_levels.push((scope.to_string(), level));
+        }
+    }
+    let settings = SETTINGS_LEVELS
+        .read()
+        .unwrap_or_else(|err| {
+            SETTINGS_LEVELS.clear_poison();
+            err.into_inner()
+        })
+        .clone()
+        .unwrap_or_default();
+    refresh_scope_map(&settings);
+}
+
+/// The levels set with [`set_runtime_level`], in the order they were set.
+pub fn runtime_levels() -> Vec<(String, log::LevelFilter)> {
+    RUNTIME_LEVELS
+        .read()
+        .unwrap_or_else(|err| {
+            RUNTIME_LEVELS.clear_poison();
+            err.into_inner()
+        })
+        .clone()
+}
+
+fn with_runtime_levels(
+    settings: &HashMap<String, String>,
+    runtime_levels: &[(String, log::LevelFilter)],
+) -> HashMap<String, String> {
+    let mut levels = settings.clone();
+    for (scope, level) in runtime_levels {
+        levels.insert(scope.clone(), level.as_str().to_ascii_lowercase());
+    }
+    levels
+}
+
+fn refresh_scope_map(settings: &HashMap<String, String>) {
+    let levels = with_runtime_levels(settings, &runtime_levels());
     let env_config = ENV_FILTER.get();
-    let map_new = ScopeMap::new_from_settings_and_env(settings, env_config, DEFAULT_FILTERS);
+    let map_new = ScopeMap::new_from_settings_and_env(&levels, env_config, DEFAULT_FILTERS);
     let mut level_enabled_max = LEVEL_ENABLED_MAX_STATIC.load(Ordering::Acquire);
     for entry in &map_new.entries {
         if let Some(level) = entry.enabled {
@@ -419,6 +479,38 @@ mod tests {
         ScopeMap::new_from_settings_and_env(&hash_map, None, &[])
     }
 
+    #[test]
+    fn test_runtime_levels_override_settings() {
+        let settings = HashMap::from_iter([
+            ("project".to_string(), "warn".to_string()),
+            ("gpui".to_string(), "error".to_string()),
+        ]);
+        let levels = with_runtime_levels(
+            &settings,
+            &[
+                ("project".to_string(), log::LevelFilter::Trace),
+                ("editor::display_map".to_string(), log::LevelFilter::Debug),
+            ],
+        );
+        let map = ScopeMap::new_from_settings_and_env(&levels, None, &[]);
+        assert_eq!(
+            map.is_enabled(&scope_from_scope_str("project"), None, log::Level::Trace),
+            EnabledStatus::Enabled
+        );
+        assert_eq!(
+            map.is_enabled(&scope_from_scope_str("gpui"), None, log::Level::Warn),
+            EnabledStatus::Disabled
+        );
+        assert_eq!(
+            map.is_enabled(
+                &scope_from_scope_str("editor"),
+                Some("editor::display_map"),
+                log::Level::Debug
+            ),
+            EnabledStatus::Enabled
+        );
+    }
+
     #[test]
     fn test_initialization() {
         let map = scope_map_from_keys(&[("a.b.c.d", "trace")]);
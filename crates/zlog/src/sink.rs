@@ -29,6 +29,8 @@ static SINK_FILE_PATH_ROTATE: OnceLock<&'static PathBuf> = OnceLock::new();
 static ENABLED_SINKS_STDOUT: AtomicBool = AtomicBool::new(false);
 /// Whether stderr output is enabled.
 static ENABLED_SINKS_STDERR: AtomicBool = AtomicBool::new(false);
+/// Whether records are written as JSON lines instead of text.
+static SINK_FORMAT_JSON: AtomicBool = AtomicBool::new(false);
 /// Atomic counter for the size of the log file in bytes.
 static SINK_FILE_SIZE_BYTES: AtomicU64 = AtomicU64::new(0);
 /// Maximum size of the log file before it will be rotated, in bytes.
@@ -40,6 +42,18 @@ pub struct Record<'a> {
     pub message: &'a std::fmt::Arguments<'a>,
     pub module_path: Option<&'a str>,
     pub line: Option<u32>,
+    /// Structured fields of records logged through the `log` crate, such as
+    /// `log::info!(path:?; "opened")`. Only written in the JSON format.
+    pub key_values: Option<&'a dyn log::kv::Source>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `{timestamp} {LEVEL} [{scope}] {message}` lines.
+    Text,
+    /// One JSON object per line, with the timestamp, level, scope path, message and key-values
+    /// of the record.
+    Json,
 }
 
 pub fn init_output_stdout() {
@@ -52,6 +66,10 @@ pub fn init_output_stderr() {
     ENABLED_SINKS_STDERR.store(true, Ordering::Release);
 }
 
+pub fn init_output_format(format: OutputFormat) {
+    SINK_FORMAT_JSON.store(format == OutputFormat::Json, Ordering::Release);
+}
+
 pub fn init_output_file(
     path: &'static PathBuf,
     path_rotate: Option<&'static PathBuf>,
@@ -118,38 +136,47 @@ pub fn submit(mut record: Record) {
         // Only render line numbers for actual rust files emitted by `log_err` and friends
         record.line.take();
     }
+    let json = SINK_FORMAT_JSON.load(Ordering::Acquire);
     if ENABLED_SINKS_STDOUT.load(Ordering::Acquire) {
         let mut stdout = std::io::stdout().lock();
-        _ = writeln!(
-            &mut stdout,
-            "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
-            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
-            LEVEL_ANSI_COLORS[record.level as usize],
-            LEVEL_OUTPUT_STRINGS[record.level as usize],
-            SourceFmt {
-                scope: record.scope,
-                module_path: record.module_path,
-                line: record.line,
-                ansi: true,
-            },
-            record.message
-        );
+        if json {
+            _ = write_json(&mut stdout, &record);
+        } else {
+            _ = writeln!(
+                &mut stdout,
+                "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
+                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
+                LEVEL_ANSI_COLORS[record.level as usize],
+                LEVEL_OUTPUT_STRINGS[record.level as usize],
+                SourceFmt {
+                    scope: record.scope,
+                    module_path: record.module_path,
+                    line: record.line,
+                    ansi: true,
+                },
+                record.message
+            );
+        }
     } else if ENABLED_SINKS_STDERR.load(Ordering::Acquire) {
         let mut stdout = std::io::stderr().lock();
-        _ = writeln!(
-            &mut stdout,
-            "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
-            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
-            LEVEL_ANSI_COLORS[record.level as usize],
-            LEVEL_OUTPUT_STRINGS[record.level as usize],
-            SourceFmt {
-                scope: record.scope,
-                module_path: record.module_path,
-                line: record.line,
-                ansi: true,
-            },
-            record.message
-        );
+        if json {
+            _ = write_json(&mut stdout, &record);
+        } else {
+            _ = writeln!(
+                &mut stdout,
+                "{} {ANSI_BOLD}{}{}{ANSI_RESET} {} {}",
+                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
+                LEVEL_ANSI_COLORS[record.level as usize],
+                LEVEL_OUTPUT_STRINGS[record.level as usize],
+                SourceFmt {
+                    scope: record.scope,
+                    module_path: record.module_path,
+                    line: record.line,
+                    ansi: true,
+                },
+                record.message
+            );
+        }
     }
     let mut file_guard = ENABLED_SINKS_FILE.lock().unwrap_or_else(|handle| {
         ENABLED_SINKS_FILE.clear_poison();
@@ -173,19 +200,23 @@ pub fn submit(mut record: Record) {
         }
         let file_size_bytes = {
             let mut writer = SizedWriter { file, written: 0 };
-            _ = writeln!(
-                &mut writer,
-                "{} {} {} {}",
-                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
-                LEVEL_OUTPUT_STRINGS[record.level as usize],
-                SourceFmt {
-                    scope: record.scope,
-                    module_path: record.module_path,
-                    line: record.line,
-                    ansi: false,
-                },
-                record.message
-            );
+            if json {
+                _ = write_json(&mut writer, &record);
+            } else {
+                _ = writeln!(
+                    &mut writer,
+                    "{} {} {} {}",
+                    chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z"),
+                    LEVEL_OUTPUT_STRINGS[record.level as usize],
+                    SourceFmt {
+                        scope: record.scope,
+                        module_path: record.module_path,
+                        line: record.line,
+                        ansi: false,
+                    },
+                    record.message
+                );
+            }
             SINK_FILE_SIZE_BYTES.fetch_add(writer.written, Ordering::AcqRel) + writer.written
         };
         if file_size_bytes > SINK_FILE_SIZE_BYTES_MAX {
@@ -218,20 +249,101 @@ pub fn flush() {
     }
 }
 
-struct SourceFmt<'a> {
+/// Writes the record as a single line JSON object.
+fn write_json(writer: &mut impl Write, record: &Record) -> io::Result<()> {
+    struct JsonFields<'a> {
+        line: &'a mut String,
+        first: bool,
+    }
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for JsonFields<'_> {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            if !self.first {
+                self.line.push(',');
+            }
+            self.first = false;
+            push_json_string(self.line, key.as_str());
+            self.line.push(':');
+            push_json_string(self.line, &value.to_string());
+            Ok(())
+        }
+    }
+
+    let mut line = String::with_capacity(128);
+    line.push_str("{\"timestamp\":");
+    push_json_string(
+        &mut line,
+        &chrono::Local::now()
+            .format("%Y-%m-%dT%H:%M:%S%.3f%:z")
+            .to_string(),
+    );
+    line.push_str(",\"level\":");
+    push_json_string(&mut line, record.level.as_str());
+    line.push_str(",\"scope\":");
+    push_json_string(
+        &mut line,
+        &ScopeFmt {
+            scope: record.scope,
+            module_path: record.module_path,
+        }
+        .to_string(),
+    );
+    if let Some(module_path) = record.module_path {
+        line.push_str(",\"module\":");
+        push_json_string(&mut line, module_path);
+    }
+    if let Some(line_number) = record.line {
+        line.push_str(",\"line\":");
+        line.push_str(&line_number.to_string());
+    }
+    line.push_str(",\"message\":");
+    push_json_string(&mut line, &record.message.to_string());
+    if let Some(key_values) = record.key_values
+        && key_values.count() > 0
+    {
+        line.push_str(",\"fields\":{");
+        _ = key_values.visit(&mut JsonFields {
+            line: &mut line,
+            first: true,
+        });
+        line.push('}');
+    }
+    line.push_str("}\n");
+    writer.write_all(line.as_bytes())
+}
+
+fn push_json_string(buffer: &mut String, value: &str) {
+    use std::fmt::Write as _;
+    buffer.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            char if char.is_control() => {
+                _ = write!(buffer, "\\u{:04x}", char as u32);
+            }
+            char => buffer.push(char),
+        }
+    }
+    buffer.push('"');
+}
+
+/// The dot separated scope of a record, or its module path if it was logged without a scope.
+struct ScopeFmt<'a> {
     scope: ScopeRef<'a>,
     module_path: Option<&'a str>,
-    line: Option<u32>,
-    ansi: bool,
 }
 
-impl std::fmt::Display for SourceFmt<'_> {
+impl std::fmt::Display for ScopeFmt<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use std::fmt::Write;
-        f.write_char('[')?;
-        if self.ansi {
-            f.write_str(ANSI_BOLD)?;
-        }
         // NOTE: if no longer prefixing scopes with their crate name, check if scope[0] is empty
         if (self.scope[1].is_empty() && self.module_path.is_some()) || self.scope[0].is_empty() {
             f.write_str(self.module_path.unwrap_or("?"))?;
@@ -245,6 +357,29 @@ impl std::fmt::Display for SourceFmt<'_> {
                 f.write_str(subscope)?;
             }
         }
+        Ok(())
+    }
+}
+
+struct SourceFmt<'a> {
+    scope: ScopeRef<'a>,
+    module_path: Option<&'a str>,
+    line: Option<u32>,
+    ansi: bool,
+}
+
+impl std::fmt::Display for SourceFmt<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        f.write_char('[')?;
+        if self.ansi {
+            f.write_str(ANSI_BOLD)?;
+        }
+        ScopeFmt {
+            scope: self.scope,
+            module_path: self.module_path,
+        }
+        .fmt(f)?;
         if let Some(line) = self.line {
             f.write_char(':')?;
             line.fmt(f)?;
@@ -324,6 +459,35 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&log_file_path).unwrap(), contents);
     }
 
+    #[test]
+    fn test_write_json() {
+        let fields = [("path", "src/\"main\".rs")];
+        let mut output = Vec::new();
+        write_json(
+            &mut output,
+            &Record {
+                scope: ["project", "lsp_store", "", ""],
+                level: log::Level::Warn,
+                message: &format_args!("slow\tresponse from {}", "rust-analyzer"),
+                module_path: Some("project::lsp_store"),
+                line: None,
+                key_values: Some(&fields),
+            },
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("{\"timestamp\":\""));
+        assert_eq!(
+            &output[output.find(",\"level\"").unwrap()..],
+            concat!(
+                r#","level":"WARN","scope":"project.lsp_store","module":"project::lsp_store","#,
+                r#""message":"slow\tresponse from rust-analyzer","#,
+                r#""fields":{"path":"src/\"main\".rs"}}"#,
+                "\n"
+            )
+        );
+    }
+
     /// Regression test, ensuring that if log level values change we are made aware
     #[test]
     fn test_log_level_names() {
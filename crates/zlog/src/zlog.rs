@@ -5,7 +5,10 @@ mod env_config;
 pub mod filter;
 pub mod sink;
 
-pub use sink::{flush, init_output_file, init_output_stderr, init_output_stdout};
+pub use sink::{
+    OutputFormat, flush, init_output_file, init_output_format, init_output_stderr,
+    init_output_stdout,
+};
 
 pub const SCOPE_DEPTH_MAX: usize = 4;
 
@@ -21,6 +24,9 @@ pub fn try_init(filter: Option<String>) -> anyhow::Result<()> {
     log::set_max_level(log::LevelFilter::max());
     process_env(filter);
     filter::refresh_from_settings(&std::collections::HashMap::default());
+    if std::env::var("ZED_LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json")) {
+        init_output_format(OutputFormat::Json);
+    }
     Ok(())
 }
 
@@ -94,6 +100,7 @@ impl log::Log for Zlog {
             // PERF(batching): store non-static paths in a cache + leak them and pass static str here
             module_path,
             line: record.line(),
+            key_values: Some(record.key_values()),
         });
     }
 
@@ -115,6 +122,7 @@ macro_rules! log {
                 message: &format_args!($($arg)+),
                 module_path: Some(module_path!()),
                 line: Some(line!()),
+                key_values: None,
             });
         }
     }
@@ -311,6 +319,7 @@ impl log::Log for Logger {
             message: record.args(),
             module_path: record.module_path(),
             line: record.line(),
+            key_values: Some(record.key_values()),
         });
     }
 
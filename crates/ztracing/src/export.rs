@@ -0,0 +1,528 @@
+//! Recording spans to share them as a trace file.
+//!
+//! While recording, every time a span is entered and exited becomes one slice of the trace,
+//! attributed to the thread it ran on. Threads are named after the executor threads they belong
+//! to, like `main` for the foreground executor and `Worker-N` for the background executor. The
+//! trace can be saved as Chrome trace event JSON, which Perfetto and `chrome://tracing` open as a
+//! flame chart, or as OTLP/JSON for OpenTelemetry tooling.
+
+use serde_json::{Value, json};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::Path,
+    sync::{
+        Arc, LazyLock, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tracing::{
+    Subscriber,
+    field::{Field, Visit},
+    span,
+};
+use tracing_subscriber::{Layer, layer::Context, registry::LookupSpan};
+
+/// Recording stops keeping spans after this many, so a forgotten recording can't use up memory.
+const MAX_RECORDED_SPANS: usize = 1_000_000;
+
+/// Setting this environment variable to a file path records from startup and writes the trace to
+/// that path when Zed quits.
+pub const EXPORT_PATH_ENV_VAR: &str = "ZTRACING_EXPORT";
+
+pub(crate) static RECORDER: LazyLock<Arc<Recorder>> = LazyLock::new(Default::default);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// The Chrome trace event format, as opened by Perfetto.
+    Chrome,
+    /// OpenTelemetry spans encoded as OTLP/JSON.
+    Otlp,
+}
+
+impl TraceFormat {
+    /// Files named `*.otlp.json` are written as OTLP, all others as Chrome traces.
+    pub fn for_path(path: &Path) -> Self {
+        let is_otlp = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.to_ascii_lowercase().ends_with(".otlp.json"));
+        if is_otlp { Self::Otlp } else { Self::Chrome }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpanRecord {
+    /// Unique within the trace, and never zero.
+    pub id: u64,
+    /// The record of the span this one was entered in.
+    pub parent_id: Option<u64>,
+    pub name: &'static str,
+    pub target: &'static str,
+    pub thread_id: u64,
+    /// Time since the recording started.
+    pub start: Duration,
+    pub duration: Duration,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordedTrace {
+    pub started_at: SystemTime,
+    pub spans: Vec<SpanRecord>,
+    pub thread_names: BTreeMap<u64, String>,
+    /// Spans left out after reaching the limit of recorded spans.
+    pub dropped_spans: usize,
+}
+
+impl RecordedTrace {
+    pub fn serialize(&self, format: TraceFormat) -> String {
+        match format {
+            TraceFormat::Chrome => self.to_chrome_json(),
+            TraceFormat::Otlp => self.to_otlp_json(),
+        }
+        .to_string()
+    }
+
+    /// Writes the trace in the format matching the file name, see [`TraceFormat::for_path`].
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.serialize(TraceFormat::for_path(path)))
+    }
+
+    fn to_chrome_json(&self) -> Value {
+        let pid = std::process::id();
+        let thread_names = self.thread_names.iter().map(|(thread_id, name)| {
+            json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": pid,
+                "tid": thread_id,
+                "args": { "name": name },
+            })
+        });
+        let spans = self.spans.iter().map(|span| {
+            json!({
+                "name": span.name,
+                "cat": span.target,
+                "ph": "X",
+                "ts": micros(span.start),
+                "dur": micros(span.duration),
+                "pid": pid,
+                "tid": span.thread_id,
+                "args": span
+                    .fields
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), Value::from(value.as_str())))
+                    .collect::<serde_json::Map<_, _>>(),
+            })
+        });
+        json!({
+            "traceEvents": thread_names.chain(spans).collect::<Vec<_>>(),
+            "displayTimeUnit": "ms",
+            "otherData": { "droppedSpans": self.dropped_spans },
+        })
+    }
+
+    fn to_otlp_json(&self) -> Value {
+        let started_at = self
+            .started_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        // One recording is one trace. Its start time keeps ids of separate recordings apart.
+        let trace_id = format!("{:032x}", started_at.as_nanos());
+        let unix_nanos = |offset: Duration| (started_at + offset).as_nanos().to_string();
+        let string_attribute =
+            |key: &str, value: &str| json!({ "key": key, "value": { "stringValue": value } });
+
+        let spans = self
+            .spans
+            .iter()
+            .map(|span| {
+                let mut attributes = vec![
+                    json!({ "key": "thread.id", "value": { "intValue": span.thread_id.to_string() } }),
+                    string_attribute("code.namespace", span.target),
+                ];
+                if let Some(thread_name) = self.thread_names.get(&span.thread_id) {
+                    attributes.push(string_attribute("thread.name", thread_name));
+                }
+                attributes.extend(
+                    span.fields
+                        .iter()
+                        .map(|(key, value)| string_attribute(key, value)),
+                );
+                json!({
+                    "traceId": trace_id,
+                    "spanId": format!("{:016x}", span.id),
+                    "parentSpanId": span
+                        .parent_id
+                        .map(|id| format!("{id:016x}"))
+                        .unwrap_or_default(),
+                    "name": span.name,
+                    // SPAN_KIND_INTERNAL
+                    "kind": 1,
+                    "startTimeUnixNano": unix_nanos(span.start),
+                    "endTimeUnixNano": unix_nanos(span.start + span.duration),
+                    "attributes": attributes,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        string_attribute("service.name", "zed"),
+                        json!({ "key": "process.pid", "value": { "intValue": std::process::id().to_string() } }),
+                    ],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "ztracing" },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+struct Recording {
+    started_at: Instant,
+    started_at_system: SystemTime,
+    spans: Vec<SpanRecord>,
+    thread_names: BTreeMap<u64, String>,
+    dropped_spans: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct Recorder {
+    is_recording: AtomicBool,
+    next_span_id: AtomicU64,
+    recording: Mutex<Option<Recording>>,
+}
+
+impl Recorder {
+    pub fn start(&self) {
+        let mut recording = self
+            .recording
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        *recording = Some(Recording {
+            started_at: Instant::now(),
+            started_at_system: SystemTime::now(),
+            spans: Vec::new(),
+            thread_names: BTreeMap::new(),
+            dropped_spans: 0,
+        });
+        self.is_recording.store(true, Ordering::Release);
+    }
+
+    pub fn stop(&self) -> Option<RecordedTrace> {
+        self.is_recording.store(false, Ordering::Release);
+        let recording = self
+            .recording
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .take()?;
+        Some(RecordedTrace {
+            started_at: recording.started_at_system,
+            spans: recording.spans,
+            thread_names: recording.thread_names,
+            dropped_spans: recording.dropped_spans,
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.is_recording.load(Ordering::Acquire)
+    }
+
+    fn next_span_id(&self) -> u64 {
+        self.next_span_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn push(
+        &self,
+        entry: ActiveEntry,
+        name: &'static str,
+        target: &'static str,
+        fields: Vec<(&'static str, String)>,
+    ) {
+        let end = Instant::now();
+        let mut recording = self
+            .recording
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let Some(recording) = recording.as_mut() else {
+            return;
+        };
+        if recording.spans.len() >= MAX_RECORDED_SPANS {
+            recording.dropped_spans += 1;
+            return;
+        }
+        let (thread_id, thread_name) = current_thread();
+        if let Some(thread_name) = thread_name {
+            recording
+                .thread_names
+                .entry(thread_id)
+                .or_insert_with(|| thread_name.to_string());
+        }
+        let start = entry.start.max(recording.started_at);
+        recording.spans.push(SpanRecord {
+            id: entry.id,
+            parent_id: entry.parent_id,
+            name,
+            target,
+            thread_id,
+            start: start - recording.started_at,
+            duration: end.saturating_duration_since(start),
+            fields,
+        });
+    }
+}
+
+fn current_thread() -> (u64, Option<Arc<str>>) {
+    static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static THREAD: (u64, Option<Arc<str>>) = (
+            NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed),
+            std::thread::current().name().map(Arc::from),
+        );
+    }
+    THREAD.with(|thread| thread.clone())
+}
+
+/// Records spans into a [`Recorder`] while it is recording.
+#[cfg_attr(not(ztracing), allow(dead_code))]
+pub(crate) struct ExportLayer {
+    recorder: Arc<Recorder>,
+}
+
+#[cfg_attr(not(ztracing), allow(dead_code))]
+impl ExportLayer {
+    pub fn new(recorder: Arc<Recorder>) -> Self {
+        Self { recorder }
+    }
+}
+
+#[derive(Default)]
+struct SpanFields(Vec<(&'static str, String)>);
+
+impl Visit for SpanFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+/// The entries of a span that is currently entered, innermost last. A span can be entered on
+/// several threads at once.
+#[derive(Default)]
+struct ActiveEntries(Vec<ActiveEntry>);
+
+struct ActiveEntry {
+    id: u64,
+    parent_id: Option<u64>,
+    thread_id: u64,
+    start: Instant,
+}
+
+impl<S> Layer<S> for ExportLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(fields) = extensions.get_mut::<SpanFields>() {
+            values.record(fields);
+        } else {
+            let mut fields = SpanFields::default();
+            values.record(&mut fields);
+            extensions.insert(fields);
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let thread_id = current_thread().0;
+        let parent_id = span.parent().and_then(|parent| {
+            let extensions = parent.extensions();
+            let entries = extensions.get::<ActiveEntries>()?;
+            entries
+                .0
+                .iter()
+                .rev()
+                .find(|entry| entry.thread_id == thread_id)
+                .or_else(|| entries.0.last())
+                .map(|entry| entry.id)
+        });
+        let entry = ActiveEntry {
+            id: self.recorder.next_span_id(),
+            parent_id,
+            thread_id,
+            start: Instant::now(),
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(entries) = extensions.get_mut::<ActiveEntries>() {
+            entries.0.push(entry);
+        } else {
+            extensions.insert(ActiveEntries(vec![entry]));
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let thread_id = current_thread().0;
+        let mut extensions = span.extensions_mut();
+        let Some(entry) = extensions.get_mut::<ActiveEntries>().and_then(|entries| {
+            let position = entries
+                .0
+                .iter()
+                .rposition(|entry| entry.thread_id == thread_id)?;
+            Some(entries.0.remove(position))
+        }) else {
+            return;
+        };
+        let fields = extensions
+            .get::<SpanFields>()
+            .map(|fields| fields.0.clone())
+            .unwrap_or_default();
+        drop(extensions);
+        self.recorder
+            .push(entry, span.name(), span.metadata().target(), fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    fn record(f: impl FnOnce()) -> RecordedTrace {
+        let recorder = Arc::new(Recorder::default());
+        let subscriber = tracing_subscriber::registry().with(ExportLayer::new(recorder.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("before recording").in_scope(|| {});
+            recorder.start();
+            f();
+        });
+        recorder.stop().unwrap()
+    }
+
+    #[test]
+    fn test_record_nested_spans() {
+        let trace = record(|| {
+            let outer = tracing::info_span!("outer", path = "a.rs", line = 3);
+            outer.in_scope(|| {
+                tracing::debug_span!("inner").in_scope(|| {});
+                tracing::debug_span!("inner").in_scope(|| {});
+            });
+            outer.in_scope(|| {});
+        });
+
+        let spans = trace
+            .spans
+            .iter()
+            .map(|span| (span.name, span.id, span.parent_id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                ("inner", 2, Some(1)),
+                ("inner", 3, Some(1)),
+                ("outer", 1, None),
+                ("outer", 4, None),
+            ]
+        );
+        assert_eq!(
+            trace.spans[2].fields,
+            vec![("path", "a.rs".to_string()), ("line", "3".to_string())]
+        );
+        assert!(trace.spans[2].duration >= trace.spans[0].duration);
+        assert!(trace.spans[0].start >= trace.spans[2].start);
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let trace = std::thread::Builder::new()
+            .name("Worker-0".to_string())
+            .spawn(|| record(|| tracing::info_span!("work", items = 2).in_scope(|| {})))
+            .unwrap()
+            .join()
+            .unwrap();
+        let json: Value = serde_json::from_str(&trace.serialize(TraceFormat::Chrome)).unwrap();
+        let events = json["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["args"]["name"], "Worker-0");
+        assert_eq!(events[1]["name"], "work");
+        assert_eq!(events[1]["ph"], "X");
+        assert_eq!(events[1]["tid"], events[0]["tid"]);
+        assert_eq!(events[1]["args"], json!({ "items": "2" }));
+    }
+
+    #[test]
+    fn test_otlp_trace() {
+        let trace = record(|| {
+            tracing::info_span!("outer").in_scope(|| tracing::info_span!("inner").in_scope(|| {}))
+        });
+        let json: Value = serde_json::from_str(&trace.serialize(TraceFormat::Otlp)).unwrap();
+        let spans = json["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+        let (inner, outer) = (&spans[0], &spans[1]);
+        assert_eq!(inner["name"], "inner");
+        assert_eq!(inner["parentSpanId"], outer["spanId"]);
+        assert_eq!(outer["parentSpanId"], "");
+        assert_eq!(inner["traceId"], outer["traceId"]);
+        assert_eq!(outer["spanId"].as_str().unwrap().len(), 16);
+        let start = |span: &Value| span["startTimeUnixNano"].as_str().unwrap().parse::<u128>();
+        assert!(start(inner).unwrap() >= start(outer).unwrap());
+    }
+
+    #[test]
+    fn test_trace_format_for_path() {
+        assert_eq!(
+            TraceFormat::for_path(Path::new("/tmp/zed.json")),
+            TraceFormat::Chrome
+        );
+        assert_eq!(
+            TraceFormat::for_path(Path::new("/tmp/zed.OTLP.json")),
+            TraceFormat::Otlp
+        );
+    }
+}
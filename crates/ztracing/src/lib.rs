@@ -1,3 +1,6 @@
+mod export;
+
+pub use export::{EXPORT_PATH_ENV_VAR, RecordedTrace, SpanRecord, TraceFormat};
 pub use tracing::{Level, field};
 
 #[cfg(ztracing)]
@@ -86,10 +89,45 @@ pub fn init() {
     zlog::info!("Starting tracy subscriber, you can now connect the profiler");
     tracing::subscriber::set_global_default(
         tracing_subscriber::registry()
-            .with(tracing_tracy::TracyLayer::new(TracyLayerConfig::default())),
+            .with(tracing_tracy::TracyLayer::new(TracyLayerConfig::default()))
+            .with(export::ExportLayer::new(export::RECORDER.clone())),
     )
     .expect("setup tracy layer");
+
+    if let Some(path) = export_path_from_env() {
+        zlog::info!("Recording spans to {}", path.display());
+        start_recording();
+    }
 }
 
 #[cfg(not(ztracing))]
 pub fn init() {}
+
+/// Whether spans are compiled in, which requires building with `ZTRACING=1`.
+pub const fn is_available() -> bool {
+    cfg!(ztracing)
+}
+
+/// Starts recording spans, discarding any recording in progress.
+pub fn start_recording() {
+    export::RECORDER.start();
+}
+
+/// Stops recording and returns the spans recorded since [`start_recording`].
+pub fn stop_recording() -> Option<RecordedTrace> {
+    export::RECORDER.stop()
+}
+
+pub fn is_recording() -> bool {
+    export::RECORDER.is_recording()
+}
+
+/// The path to write the trace recorded from startup to, see [`EXPORT_PATH_ENV_VAR`].
+pub fn export_path_from_env() -> Option<std::path::PathBuf> {
+    if !is_available() {
+        return None;
+    }
+    std::env::var_os(EXPORT_PATH_ENV_VAR)
+        .filter(|path| !path.is_empty())
+        .map(Into::into)
+}